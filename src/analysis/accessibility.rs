@@ -0,0 +1,580 @@
+//! فحص إمكانية الوصول (WCAG 2.1) لمخرجات HTML
+//! كل مخالفة تُربط بمسار العنصر ورقم السطر لتسهيل إصلاحها
+
+use serde::Serialize;
+use crate::analysis::css::{self, DesignPalette, StyleResolver};
+use crate::analysis::html::{HtmlDocument, HtmlElement};
+use crate::analysis::text::{self, Script};
+
+/// نصوص بديلة لا تصف الصورة
+const GENERIC_ALT: [&str; 8] = ["صورة", "image", "img", "photo", "picture", "logo", "شعار", "icon"];
+
+/// امتدادات الملفات التي تدل على نص بديل مولد تلقائياً
+const FILE_EXTENSIONS: [&str; 6] = [".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp"];
+
+/// أنواع حقول الإدخال التي لا تحتاج تسمية
+const UNLABELED_INPUTS: [&str; 5] = ["hidden", "submit", "button", "reset", "image"];
+
+/// قواعد الفحص
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum A11yRule {
+    ImageAlt,
+    FormLabel,
+    HeadingOrder,
+    ColorContrast,
+    LangDir,
+    Landmarks,
+}
+
+impl A11yRule {
+    pub const ALL: [A11yRule; 6] = [
+        A11yRule::ImageAlt,
+        A11yRule::FormLabel,
+        A11yRule::HeadingOrder,
+        A11yRule::ColorContrast,
+        A11yRule::LangDir,
+        A11yRule::Landmarks,
+    ];
+
+    /// معيار WCAG المرتبط
+    pub fn wcag(&self) -> &'static str {
+        match self {
+            A11yRule::ImageAlt => "1.1.1",
+            A11yRule::FormLabel => "1.3.1, 4.1.2",
+            A11yRule::HeadingOrder => "1.3.1, 2.4.6",
+            A11yRule::ColorContrast => "1.4.3",
+            A11yRule::LangDir => "3.1.1, 3.1.2",
+            A11yRule::Landmarks => "1.3.1, 2.4.1",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            A11yRule::ImageAlt => "النص البديل للصور",
+            A11yRule::FormLabel => "تسميات حقول النماذج",
+            A11yRule::HeadingOrder => "ترتيب العناوين",
+            A11yRule::ColorContrast => "تباين الألوان",
+            A11yRule::LangDir => "اللغة والاتجاه",
+            A11yRule::Landmarks => "المعالم (Landmarks)",
+        }
+    }
+
+    fn weight(&self) -> f32 {
+        match self {
+            A11yRule::ImageAlt => 0.2,
+            A11yRule::FormLabel => 0.2,
+            A11yRule::HeadingOrder => 0.15,
+            A11yRule::ColorContrast => 0.2,
+            A11yRule::LangDir => 0.15,
+            A11yRule::Landmarks => 0.1,
+        }
+    }
+}
+
+/// تأثير المخالفة على المستخدم
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Impact {
+    Minor,
+    Moderate,
+    Serious,
+    Critical,
+}
+
+impl Impact {
+    fn penalty(&self) -> f32 {
+        match self {
+            Impact::Minor => 0.25,
+            Impact::Moderate => 0.5,
+            Impact::Serious => 0.75,
+            Impact::Critical => 1.0,
+        }
+    }
+}
+
+/// مخالفة إمكانية وصول
+#[derive(Debug, Clone, Serialize)]
+pub struct A11yViolation {
+    pub rule: A11yRule,
+    pub wcag: &'static str,
+    pub impact: Impact,
+    pub message: String,
+    pub element: String,
+    pub line: usize,
+}
+
+/// ملخص قاعدة
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleSummary {
+    pub rule: A11yRule,
+    pub name: &'static str,
+    pub checked: usize,
+    pub violations: usize,
+    pub score: f32,
+}
+
+/// تقرير إمكانية الوصول
+#[derive(Debug, Clone, Serialize)]
+pub struct AccessibilityReport {
+    pub score: f32,
+    pub rules: Vec<RuleSummary>,
+    pub violations: Vec<A11yViolation>,
+    pub critical_count: usize,
+}
+
+impl AccessibilityReport {
+    /// المخالفات الخاصة بقاعدة معينة
+    pub fn violations_for(&self, rule: A11yRule) -> Vec<&A11yViolation> {
+        self.violations.iter().filter(|v| v.rule == rule).collect()
+    }
+}
+
+/// فاحص إمكانية الوصول
+#[derive(Debug, Clone)]
+pub struct AccessibilityChecker {
+    pub palette: DesignPalette,
+    pub min_contrast: f64,
+    pub min_large_text_contrast: f64,
+}
+
+impl Default for AccessibilityChecker {
+    fn default() -> Self {
+        Self {
+            palette: DesignPalette::from_design_system(),
+            min_contrast: 4.5,
+            min_large_text_contrast: 3.0,
+        }
+    }
+}
+
+/// سياق الفحص لقاعدة واحدة
+struct RuleRun {
+    rule: A11yRule,
+    checked: usize,
+    violations: Vec<A11yViolation>,
+}
+
+impl RuleRun {
+    fn new(rule: A11yRule) -> Self {
+        Self { rule, checked: 0, violations: Vec::new() }
+    }
+
+    fn violation(&mut self, doc: &HtmlDocument, element: Option<&HtmlElement>, impact: Impact, message: String) {
+        self.violations.push(A11yViolation {
+            rule: self.rule,
+            wcag: self.rule.wcag(),
+            impact,
+            message,
+            element: element.map(|e| doc.path(e.index)).unwrap_or_else(|| "document".to_string()),
+            line: element.map(|e| e.line).unwrap_or(1),
+        });
+    }
+
+    fn score(&self) -> f32 {
+        let penalty: f32 = self.violations.iter().map(|v| v.impact.penalty()).sum();
+        (1.0 - penalty / self.checked.max(1) as f32).max(0.0)
+    }
+}
+
+impl AccessibilityChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// فحص مستند HTML
+    pub fn check(&self, html: &str) -> AccessibilityReport {
+        let doc = HtmlDocument::parse(html);
+
+        let runs = vec![
+            self.check_images(&doc),
+            self.check_form_labels(&doc),
+            self.check_headings(&doc),
+            self.check_contrast(&doc),
+            self.check_lang_dir(&doc),
+            self.check_landmarks(&doc),
+        ];
+
+        // القواعد التي لم تجد ما تفحصه لا تدخل في النتيجة
+        let applicable: Vec<&RuleRun> = runs.iter().filter(|r| r.checked > 0).collect();
+        let weights: f32 = applicable.iter().map(|r| r.rule.weight()).sum();
+        let score = if weights > 0.0 {
+            applicable.iter().map(|r| r.score() * r.rule.weight()).sum::<f32>() / weights
+        } else {
+            1.0
+        };
+
+        let rules = runs.iter()
+            .map(|r| RuleSummary {
+                rule: r.rule,
+                name: r.rule.display_name(),
+                checked: r.checked,
+                violations: r.violations.len(),
+                score: r.score(),
+            })
+            .collect();
+
+        let mut violations: Vec<A11yViolation> = runs.into_iter().flat_map(|r| r.violations).collect();
+        violations.sort_by(|a, b| b.impact.cmp(&a.impact).then(a.line.cmp(&b.line)));
+
+        AccessibilityReport {
+            score,
+            rules,
+            critical_count: violations.iter().filter(|v| v.impact == Impact::Critical).count(),
+            violations,
+        }
+    }
+
+    /// النص البديل للصور (1.1.1)
+    fn check_images(&self, doc: &HtmlDocument) -> RuleRun {
+        let mut run = RuleRun::new(A11yRule::ImageAlt);
+
+        for element in doc.elements() {
+            let needs_alt = element.tag == "img"
+                || element.tag == "area"
+                || (element.tag == "input" && element.attr("type") == Some("image"));
+            if !needs_alt || is_hidden(element) {
+                continue;
+            }
+            run.checked += 1;
+
+            let Some(alt) = element.attr("alt").map(str::trim) else {
+                run.violation(doc, Some(element), Impact::Critical,
+                    format!("العنصر <{}> بدون خاصية alt", element.tag));
+                continue;
+            };
+
+            if alt.is_empty() {
+                // الصورة الزخرفية مقبولة إلا إذا كانت المحتوى الوحيد لرابط أو زر
+                let container = doc.ancestor(element.index, "a").or_else(|| doc.ancestor(element.index, "button"));
+                if let Some(container) = container {
+                    if doc.text_of(container.index).is_empty() && !has_aria_name(doc, container) {
+                        run.violation(doc, Some(element), Impact::Serious,
+                            format!("الصورة هي المحتوى الوحيد لـ <{}> ونصها البديل فارغ", container.tag));
+                    }
+                }
+                continue;
+            }
+
+            let lower = alt.to_lowercase();
+            if FILE_EXTENSIONS.iter().any(|ext| lower.ends_with(ext)) || GENERIC_ALT.contains(&lower.as_str()) {
+                run.violation(doc, Some(element), Impact::Moderate,
+                    format!("النص البديل \"{}\" لا يصف محتوى الصورة", alt));
+            }
+        }
+
+        run
+    }
+
+    /// تسميات حقول النماذج (1.3.1، 4.1.2)
+    fn check_form_labels(&self, doc: &HtmlDocument) -> RuleRun {
+        let mut run = RuleRun::new(A11yRule::FormLabel);
+        let labels = doc.find_all("label");
+
+        for element in doc.elements() {
+            let is_control = match element.tag.as_str() {
+                "input" => !UNLABELED_INPUTS.contains(&element.attr("type").unwrap_or("text")),
+                "select" | "textarea" => true,
+                _ => false,
+            };
+            if !is_control || is_hidden(element) {
+                continue;
+            }
+            run.checked += 1;
+
+            let by_for = element.attr("id")
+                .filter(|id| !id.is_empty())
+                .map(|id| labels.iter().any(|l| l.attr("for") == Some(id)))
+                .unwrap_or(false);
+            let wrapped = doc.has_ancestor(element.index, "label");
+            let titled = element.attr("title").map(|t| !t.trim().is_empty()).unwrap_or(false);
+
+            if by_for || wrapped || titled || has_aria_name(doc, element) {
+                continue;
+            }
+
+            if element.attr("placeholder").map(|p| !p.trim().is_empty()).unwrap_or(false) {
+                run.violation(doc, Some(element), Impact::Serious,
+                    format!("الحقل <{}> يعتمد على placeholder فقط دون تسمية (label)", element.tag));
+            } else {
+                run.violation(doc, Some(element), Impact::Critical,
+                    format!("الحقل <{}> بدون تسمية (label أو aria-label)", element.tag));
+            }
+        }
+
+        // تسميات تشير إلى عناصر غير موجودة
+        for label in &labels {
+            if let Some(target) = label.attr("for") {
+                let exists = doc.elements().iter().any(|e| e.attr("id") == Some(target));
+                if !exists {
+                    run.checked += 1;
+                    run.violation(doc, Some(label), Impact::Moderate,
+                        format!("التسمية تشير إلى id=\"{}\" غير موجود", target));
+                }
+            }
+        }
+
+        run
+    }
+
+    /// ترتيب العناوين (1.3.1، 2.4.6)
+    fn check_headings(&self, doc: &HtmlDocument) -> RuleRun {
+        let mut run = RuleRun::new(A11yRule::HeadingOrder);
+        let headings: Vec<(&HtmlElement, u8)> = doc.elements().iter()
+            .filter_map(|e| e.heading_level().map(|l| (e, l)))
+            .collect();
+
+        let body = doc.find("body");
+        if headings.is_empty() {
+            if body.map(|b| !doc.text_of(b.index).is_empty()).unwrap_or(false) {
+                run.checked = 1;
+                run.violation(doc, body, Impact::Moderate, "لا توجد عناوين لتنظيم المحتوى".to_string());
+            }
+            return run;
+        }
+
+        run.checked = headings.len();
+
+        if !headings.iter().any(|(_, level)| *level == 1) {
+            run.violation(doc, Some(headings[0].0), Impact::Moderate, "لا يوجد عنوان رئيسي (h1)".to_string());
+        }
+
+        let mut previous = 0u8;
+        for (element, level) in &headings {
+            if doc.text_of(element.index).is_empty() && !has_aria_name(doc, element) {
+                run.violation(doc, Some(element), Impact::Serious, format!("العنوان h{} فارغ", level));
+            }
+            if previous > 0 && *level > previous + 1 {
+                run.violation(doc, Some(element), Impact::Moderate,
+                    format!("قفزة في مستوى العناوين من h{} إلى h{}", previous, level));
+            }
+            previous = *level;
+        }
+
+        run
+    }
+
+    /// تباين الألوان (1.4.3)
+    fn check_contrast(&self, doc: &HtmlDocument) -> RuleRun {
+        let mut run = RuleRun::new(A11yRule::ColorContrast);
+        let styles = StyleResolver::compute(doc, &self.palette);
+
+        for element in doc.elements() {
+            if !css::has_own_text(element)
+                || matches!(element.tag.as_str(), "html" | "head" | "title" | "script" | "style" | "noscript")
+                || doc.has_ancestor(element.index, "head")
+                || is_hidden(element)
+            {
+                continue;
+            }
+            let Some(style) = styles.style(element.index) else { continue };
+            run.checked += 1;
+
+            let required = if style.is_large_text() { self.min_large_text_contrast } else { self.min_contrast };
+            let (ratio, background) = style.worst_contrast();
+            if ratio < required {
+                let impact = if ratio < required * 0.66 { Impact::Serious } else { Impact::Moderate };
+                run.violation(doc, Some(element), impact, format!(
+                    "تباين {:.2}:1 بين النص {} والخلفية {} أقل من {:.1}:1",
+                    ratio, style.color.to_hex(), background.to_hex(), required
+                ));
+            }
+        }
+
+        run
+    }
+
+    /// اللغة والاتجاه (3.1.1، 3.1.2)
+    fn check_lang_dir(&self, doc: &HtmlDocument) -> RuleRun {
+        let mut run = RuleRun::new(A11yRule::LangDir);
+        let root = doc.root();
+        run.checked = 2;
+
+        let tokens = text::tokenize(&doc.visible_text());
+        let arabic = tokens.iter().filter(|t| t.script == Script::Arabic).count();
+        let latin = tokens.iter().filter(|t| t.script == Script::Latin).count();
+        let content_is_arabic = arabic > 0 && arabic >= latin;
+
+        let lang = root.and_then(|r| r.attr("lang")).map(|l| l.trim().to_lowercase());
+        match &lang {
+            None => run.violation(doc, root, Impact::Serious, "خاصية lang مفقودة في وسم html".to_string()),
+            Some(lang) if !is_valid_lang(lang) => {
+                run.violation(doc, root, Impact::Serious, format!("قيمة lang=\"{}\" غير صالحة", lang));
+            }
+            Some(lang) if content_is_arabic && !lang.starts_with("ar") => {
+                run.violation(doc, root, Impact::Serious, format!("المحتوى عربي لكن lang=\"{}\"", lang));
+            }
+            _ => {}
+        }
+
+        let is_arabic = lang.as_deref().map(|l| l.starts_with("ar")).unwrap_or(content_is_arabic);
+        let dir = root.and_then(|r| r.attr("dir"))
+            .or_else(|| doc.find("body").and_then(|b| b.attr("dir")))
+            .map(|d| d.trim().to_lowercase());
+        if is_arabic && dir.as_deref() != Some("rtl") {
+            run.violation(doc, root, Impact::Serious, "المحتوى العربي يحتاج dir=\"rtl\" في وسم html".to_string());
+        }
+
+        // خصائص lang و dir على العناصر الداخلية
+        for element in doc.elements().iter().filter(|e| e.tag != "html") {
+            if let Some(value) = element.attr("dir") {
+                run.checked += 1;
+                if !matches!(value.trim().to_lowercase().as_str(), "rtl" | "ltr" | "auto") {
+                    run.violation(doc, Some(element), Impact::Minor, format!("قيمة dir=\"{}\" غير صالحة", value));
+                }
+            }
+            if let Some(value) = element.attr("lang") {
+                run.checked += 1;
+                let value = value.trim().to_lowercase();
+                if !is_valid_lang(&value) {
+                    run.violation(doc, Some(element), Impact::Minor, format!("قيمة lang=\"{}\" غير صالحة", value));
+                } else if is_arabic && !value.starts_with("ar") && !element.has_attr("dir") {
+                    run.violation(doc, Some(element), Impact::Minor,
+                        format!("مقطع بلغة \"{}\" داخل صفحة عربية بدون dir=\"ltr\"", value));
+                }
+            }
+        }
+
+        run
+    }
+
+    /// المعالم (1.3.1، 2.4.1)
+    fn check_landmarks(&self, doc: &HtmlDocument) -> RuleRun {
+        let mut run = RuleRun::new(A11yRule::Landmarks);
+        let Some(body) = doc.find("body") else { return run };
+        run.checked = 4;
+
+        let landmark = |tag: &str, role: &str| -> Vec<&HtmlElement> {
+            doc.elements().iter()
+                .filter(|e| e.tag == tag || e.attr("role") == Some(role))
+                .collect()
+        };
+
+        let main = landmark("main", "main");
+        match main.len() {
+            0 => run.violation(doc, Some(body), Impact::Serious, "لا يوجد معلم رئيسي <main>".to_string()),
+            1 => {}
+            count => run.violation(doc, Some(main[1]), Impact::Moderate,
+                format!("يوجد {} معالم رئيسية والمسموح واحد", count)),
+        }
+
+        // المعالم الفرعية يجب ألا تكون داخل <main>
+        let header = landmark("header", "banner");
+        if !header.iter().any(|h| !doc.has_ancestor(h.index, "main") && !doc.has_ancestor(h.index, "article")) {
+            run.violation(doc, Some(body), Impact::Minor, "لا يوجد رأس للصفحة <header>".to_string());
+        }
+
+        if doc.find_all("a").len() >= 3 && landmark("nav", "navigation").is_empty() {
+            run.violation(doc, Some(body), Impact::Minor, "الروابط غير مجمعة في معلم تنقل <nav>".to_string());
+        }
+
+        let footer = landmark("footer", "contentinfo");
+        if !footer.iter().any(|f| !doc.has_ancestor(f.index, "main") && !doc.has_ancestor(f.index, "article")) {
+            run.violation(doc, Some(body), Impact::Minor, "لا يوجد تذييل للصفحة <footer>".to_string());
+        }
+
+        run
+    }
+}
+
+/// هل العنصر مخفي عن قارئات الشاشة؟
+fn is_hidden(element: &HtmlElement) -> bool {
+    element.has_attr("hidden")
+        || element.attr("aria-hidden") == Some("true")
+        || matches!(element.attr("role"), Some("presentation") | Some("none"))
+}
+
+/// هل للعنصر اسم عبر aria-label أو aria-labelledby صالح؟
+fn has_aria_name(doc: &HtmlDocument, element: &HtmlElement) -> bool {
+    if element.attr("aria-label").map(|l| !l.trim().is_empty()).unwrap_or(false) {
+        return true;
+    }
+    element.attr("aria-labelledby")
+        .map(|ids| ids.split_whitespace().any(|id| doc.elements().iter().any(|e| e.attr("id") == Some(id))))
+        .unwrap_or(false)
+}
+
+/// تحقق مبسط من وسم اللغة (BCP 47): رمز من حرفين أو ثلاثة مع وسوم فرعية اختيارية
+fn is_valid_lang(lang: &str) -> bool {
+    let mut parts = lang.split('-');
+    let primary = parts.next().unwrap_or("");
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && parts.all(|p| !p.is_empty() && p.len() <= 8 && p.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_violations_with_element_paths() {
+        let html = r#"<!DOCTYPE html>
+<html lang="ar">
+<head><style>
+:root { --muted: #bbbbbb; }
+.hint { color: var(--muted); }
+footer a { color: white; }
+</style></head>
+<body>
+<main>
+<h1>التسجيل</h1>
+<h3>البيانات</h3>
+<img src="hero.png">
+<form><input type="email" placeholder="البريد"><label for="name">الاسم</label><input id="name"></form>
+<p class="hint">نص خافت</p>
+</main>
+<footer style="background-color: var(--color-primary-dark)"><a href="/">الرئيسية</a></footer>
+</body>
+</html>"#;
+        let report = AccessibilityChecker::new().check(html);
+
+        let alt = report.violations_for(A11yRule::ImageAlt);
+        assert_eq!(alt.len(), 1);
+        assert_eq!(alt[0].element, "html > body > main > img");
+        assert_eq!(alt[0].impact, Impact::Critical);
+
+        let labels = report.violations_for(A11yRule::FormLabel);
+        assert_eq!(labels.len(), 1);
+        assert!(labels[0].element.ends_with("form > input:nth-of-type(1)"));
+
+        assert_eq!(report.violations_for(A11yRule::HeadingOrder).len(), 1);
+        assert!(report.violations_for(A11yRule::LangDir).iter().any(|v| v.message.contains("rtl")));
+
+        // النص الخافت يفشل، والرابط الأبيض على لون نظام التصميم الداكن ينجح
+        let contrast = report.violations_for(A11yRule::ColorContrast);
+        assert_eq!(contrast.len(), 1);
+        assert_eq!(contrast[0].element, "html > body > main > p");
+        assert!(report.score < 1.0);
+    }
+
+    #[test]
+    fn test_accessible_page() {
+        let html = r#"<html lang="ar" dir="rtl"><body>
+<header><nav><a href="/">الرئيسية</a></nav></header>
+<main><h1>مرحباً</h1><h2>عن الموقع</h2><img src="a.png" alt="فريق العمل في المكتب">
+<label>الاسم <input type="text"></label></main>
+<footer><p>جميع الحقوق محفوظة</p></footer>
+</body></html>"#;
+        let report = AccessibilityChecker::new().check(html);
+
+        assert!(report.violations.is_empty(), "{:?}", report.violations);
+        assert_eq!(report.score, 1.0);
+    }
+
+    #[test]
+    fn test_design_palette_contrast() {
+        let palette = DesignPalette::from_design_system();
+        assert!(!palette.is_empty());
+
+        let primary = palette.get("--color-primary-main").unwrap();
+        assert_eq!(primary.to_hex(), "#4a6fa5");
+        assert!(primary.contrast_ratio(&css::Rgb::WHITE) > 4.5);
+        assert_eq!(palette.get("--color-neutral-600"), palette.get("--mds-color-neutral-gray-600"));
+    }
+
+    #[test]
+    fn test_unterminated_arabic_tags_do_not_panic() {
+        for input in ["<pمرحبا", "<aب", "<p class=\"مرحبا", "<html><pمرحبا", "<p>نص</p><imgصورة"] {
+            AccessibilityChecker::new().check(input);
+        }
+    }
+}
@@ -0,0 +1,615 @@
+//! حساب الأنماط المبسط لمستندات HTML
+//! يدعم أوراق الأنماط المضمنة والخاصية style والمتغيرات ولوحة ألوان نظام التصميم
+
+use std::collections::HashMap;
+use serde::Serialize;
+use crate::analysis::html::{HtmlChild, HtmlDocument, HtmlElement};
+
+/// ملف نظام التصميم العربي (ليس JSON صالحاً بالكامل، لذا تُستخرج منه لوحة الألوان فقط)
+const DESIGN_SYSTEM: &str = include_str!("../../templates/designs/arabic_design_system.json");
+
+/// حجم الخط الافتراضي بالبكسل
+const BASE_FONT_SIZE: f32 = 16.0;
+
+/// أقصى عمق لحل المتغيرات المتداخلة
+const MAX_VAR_DEPTH: usize = 8;
+
+/// لون RGB
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb { r: 255, g: 255, b: 255 };
+    pub const BLACK: Rgb = Rgb { r: 0, g: 0, b: 0 };
+
+    /// تحليل لون بصيغة #rgb أو #rrggbb أو rgb() أو اسم لون شائع
+    pub fn parse(value: &str) -> Option<Rgb> {
+        Self::parse_with_alpha(value)
+            .filter(|(_, alpha)| *alpha > 0.0)
+            .map(|(color, _)| color)
+    }
+
+    /// تحليل لون مع درجة الشفافية (0 - 1)
+    pub fn parse_with_alpha(value: &str) -> Option<(Rgb, f32)> {
+        let value = value.trim().to_lowercase();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            let expand = |c: char| c.to_digit(16).map(|d| (d * 17) as u8);
+            return match hex.len() {
+                3 | 4 => {
+                    let chars: Vec<char> = hex.chars().collect();
+                    let alpha = chars.get(3).and_then(|c| expand(*c)).map(|a| a as f32 / 255.0).unwrap_or(1.0);
+                    Some((Rgb { r: expand(chars[0])?, g: expand(chars[1])?, b: expand(chars[2])? }, alpha))
+                }
+                6 | 8 => {
+                    let channel = |i: usize| hex.get(i..i + 2).and_then(|h| u8::from_str_radix(h, 16).ok());
+                    let alpha = channel(6).map(|a| a as f32 / 255.0).unwrap_or(1.0);
+                    Some((Rgb { r: channel(0)?, g: channel(2)?, b: channel(4)? }, alpha))
+                }
+                _ => None,
+            };
+        }
+
+        if let Some(args) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")) {
+            let parts: Vec<f32> = args.trim_end_matches(')')
+                .split([',', ' ', '/'])
+                .filter(|p| !p.is_empty())
+                .filter_map(|p| p.trim().parse::<f32>().ok())
+                .collect();
+            let channel = |v: f32| v.clamp(0.0, 255.0) as u8;
+            return match parts.as_slice() {
+                [r, g, b] => Some((Rgb { r: channel(*r), g: channel(*g), b: channel(*b) }, 1.0)),
+                [r, g, b, a, ..] => Some((Rgb { r: channel(*r), g: channel(*g), b: channel(*b) }, a.clamp(0.0, 1.0))),
+                _ => None,
+            };
+        }
+
+        let named = match value.as_str() {
+            "transparent" => return Some((Rgb::BLACK, 0.0)),
+            "white" => (255, 255, 255),
+            "black" => (0, 0, 0),
+            "red" => (255, 0, 0),
+            "green" => (0, 128, 0),
+            "blue" => (0, 0, 255),
+            "yellow" => (255, 255, 0),
+            "orange" => (255, 165, 0),
+            "gray" | "grey" => (128, 128, 128),
+            "silver" => (192, 192, 192),
+            "navy" => (0, 0, 128),
+            "maroon" => (128, 0, 0),
+            "purple" => (128, 0, 128),
+            "teal" => (0, 128, 128),
+            _ => return None,
+        };
+        Some((Rgb { r: named.0, g: named.1, b: named.2 }, 1.0))
+    }
+
+    /// مزج لون شفاف فوق خلفية
+    pub fn blend_over(&self, alpha: f32, background: &Rgb) -> Rgb {
+        let mix = |fg: u8, bg: u8| (fg as f32 * alpha + bg as f32 * (1.0 - alpha)).round() as u8;
+        Rgb {
+            r: mix(self.r, background.r),
+            g: mix(self.g, background.g),
+            b: mix(self.b, background.b),
+        }
+    }
+
+    /// الإضاءة النسبية حسب WCAG 2.1
+    pub fn relative_luminance(&self) -> f64 {
+        let channel = |c: u8| {
+            let c = c as f64 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * channel(self.r) + 0.7152 * channel(self.g) + 0.0722 * channel(self.b)
+    }
+
+    /// نسبة التباين بين لونين (1 - 21)
+    pub fn contrast_ratio(&self, other: &Rgb) -> f64 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        let (light, dark) = if a > b { (a, b) } else { (b, a) };
+        (light + 0.05) / (dark + 0.05)
+    }
+
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// لوحة ألوان نظام التصميم كمتغيرات CSS
+#[derive(Debug, Clone, Default)]
+pub struct DesignPalette {
+    variables: HashMap<String, Rgb>,
+}
+
+impl DesignPalette {
+    /// لوحة الألوان من ملف نظام التصميم المرفق
+    pub fn from_design_system() -> Self {
+        Self::parse(DESIGN_SYSTEM).unwrap_or_default()
+    }
+
+    /// استخراج كائن color_palette من نص نظام التصميم
+    /// كل لون يُسجل كـ --color-{group}-{name} و --mds-color-{group}-{name}
+    pub fn parse(design_system: &str) -> Option<Self> {
+        let start = design_system.find("\"color_palette\"")?;
+        let open = start + design_system[start..].find('{')?;
+
+        let mut depth = 0;
+        let mut close = None;
+        for (i, c) in design_system[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(open + i + 1);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let groups: HashMap<String, HashMap<String, String>> =
+            serde_json::from_str(&design_system[open..close?]).ok()?;
+
+        let mut variables = HashMap::new();
+        for (group, colors) in groups {
+            for (name, value) in colors {
+                let Some(rgb) = Rgb::parse(&value) else { continue };
+                let name = name.replace('_', "-");
+                let mut names = vec![name.clone()];
+                // أسماء الرمادي تُستخدم في المكونات بدون البادئة (--color-neutral-600)
+                if let Some(shade) = name.strip_prefix("gray-") {
+                    names.push(shade.to_string());
+                }
+                for name in names {
+                    variables.insert(format!("--color-{}-{}", group, name), rgb);
+                    variables.insert(format!("--mds-color-{}-{}", group, name), rgb);
+                }
+            }
+        }
+
+        Some(Self { variables })
+    }
+
+    pub fn get(&self, variable: &str) -> Option<Rgb> {
+        self.variables.get(variable).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.variables.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.variables.is_empty()
+    }
+}
+
+/// جزء مركب من محدد (مثل a.nav-link#home)
+#[derive(Debug, Clone, Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+impl Compound {
+    fn parse(part: &str) -> Option<Self> {
+        if part == ":root" {
+            return Some(Compound { tag: Some("html".to_string()), ..Default::default() });
+        }
+        // حالات التفاعل والعناصر الزائفة والخصائص لا تنطبق على العرض الافتراضي
+        if part.contains([':', '[', '+', '~']) {
+            return None;
+        }
+
+        let mut compound = Compound::default();
+        let mut kind = 't';
+        let mut current = String::new();
+
+        let flush = |kind: char, current: &mut String, compound: &mut Compound| {
+            if !current.is_empty() {
+                let value = std::mem::take(current);
+                match kind {
+                    '.' => compound.classes.push(value),
+                    '#' => compound.id = Some(value),
+                    _ if value != "*" => compound.tag = Some(value.to_lowercase()),
+                    _ => {}
+                }
+            }
+        };
+
+        for c in part.chars() {
+            if c == '.' || c == '#' {
+                flush(kind, &mut current, &mut compound);
+                kind = c;
+            } else {
+                current.push(c);
+            }
+        }
+        flush(kind, &mut current, &mut compound);
+
+        Some(compound)
+    }
+
+    fn matches(&self, element: &HtmlElement) -> bool {
+        if let Some(tag) = &self.tag {
+            if &element.tag != tag {
+                return false;
+            }
+        }
+        if let Some(id) = &self.id {
+            if element.attr("id") != Some(id.as_str()) {
+                return false;
+            }
+        }
+        let classes = element.classes();
+        self.classes.iter().all(|c| classes.contains(&c.as_str()))
+    }
+
+    fn specificity(&self) -> (usize, usize, usize) {
+        (self.id.is_some() as usize, self.classes.len(), self.tag.is_some() as usize)
+    }
+}
+
+/// قاعدة CSS
+#[derive(Debug, Clone)]
+struct StyleRule {
+    selector: Vec<Compound>,
+    specificity: (usize, usize, usize),
+    order: usize,
+    declarations: Vec<(String, String)>,
+}
+
+/// النمط المحسوب لعنصر
+#[derive(Debug, Clone, Serialize)]
+pub struct ComputedStyle {
+    pub color: Rgb,
+    pub backgrounds: Vec<Rgb>,
+    pub font_size: f32,
+    pub bold: bool,
+}
+
+impl ComputedStyle {
+    /// نص كبير حسب WCAG: 24px أو 18.66px عريض
+    pub fn is_large_text(&self) -> bool {
+        self.font_size >= 24.0 || (self.bold && self.font_size >= 18.66)
+    }
+
+    /// أسوأ نسبة تباين بين لون النص وألوان الخلفية
+    pub fn worst_contrast(&self) -> (f64, Rgb) {
+        self.backgrounds.iter()
+            .map(|bg| (self.color.contrast_ratio(bg), *bg))
+            .min_by(|a, b| a.0.partial_cmp(&b.0).unwrap())
+            .unwrap_or((self.color.contrast_ratio(&Rgb::WHITE), Rgb::WHITE))
+    }
+}
+
+/// الأنماط المحسوبة لكل عناصر المستند
+pub struct StyleResolver {
+    styles: Vec<ComputedStyle>,
+}
+
+impl StyleResolver {
+    /// حساب الأنماط من وسوم <style> والخاصية style
+    pub fn compute(doc: &HtmlDocument, palette: &DesignPalette) -> Self {
+        let mut rules = Vec::new();
+        for style in doc.find_all("style") {
+            parse_stylesheet(&doc.raw_text_of(style.index), &mut rules);
+        }
+
+        // المتغيرات: لوحة نظام التصميم ثم تعريفات الصفحة
+        let mut variables: HashMap<String, String> = palette.variables.iter()
+            .map(|(k, v)| (k.clone(), v.to_hex()))
+            .collect();
+        for rule in &rules {
+            for (property, value) in &rule.declarations {
+                if property.starts_with("--") {
+                    variables.insert(property.clone(), value.clone());
+                }
+            }
+        }
+
+        let mut styles: Vec<ComputedStyle> = Vec::with_capacity(doc.elements().len());
+
+        // العناصر مرتبة بحيث يسبق الأب أبناءه
+        for element in doc.elements() {
+            let parent = element.parent.map(|p| styles[p].clone()).unwrap_or(ComputedStyle {
+                color: Rgb::BLACK,
+                backgrounds: vec![Rgb::WHITE],
+                font_size: BASE_FONT_SIZE,
+                bold: false,
+            });
+
+            let declarations = declarations_for(doc, element, &rules);
+            let mut style = ComputedStyle {
+                color: parent.color,
+                backgrounds: parent.backgrounds.clone(),
+                font_size: default_font_size(&element.tag).unwrap_or(parent.font_size),
+                bold: parent.bold || element.heading_level().is_some() || matches!(element.tag.as_str(), "b" | "strong" | "th"),
+            };
+
+            for (property, value) in declarations {
+                let value = resolve_variables(&value, &variables, 0);
+                match property.as_str() {
+                    "color" => {
+                        if let Some(color) = Rgb::parse(&value) {
+                            style.color = color;
+                        }
+                    }
+                    "background-color" | "background" => {
+                        // الألوان الشفافة تُمزج فوق خلفية الأب
+                        let colors: Vec<Rgb> = extract_colors(&value).into_iter()
+                            .filter(|(_, alpha)| *alpha > 0.0)
+                            .flat_map(|(color, alpha)| {
+                                parent.backgrounds.iter()
+                                    .map(move |bg| if alpha >= 1.0 { color } else { color.blend_over(alpha, bg) })
+                            })
+                            .collect();
+                        if !colors.is_empty() {
+                            style.backgrounds = colors;
+                        }
+                    }
+                    "font-size" => {
+                        if let Some(size) = parse_font_size(&value, parent.font_size) {
+                            style.font_size = size;
+                        }
+                    }
+                    "font-weight" => {
+                        style.bold = value == "bold" || value == "bolder"
+                            || value.parse::<u16>().map(|w| w >= 600).unwrap_or(false);
+                    }
+                    _ => {}
+                }
+            }
+
+            styles.push(style);
+        }
+
+        Self { styles }
+    }
+
+    pub fn style(&self, index: usize) -> Option<&ComputedStyle> {
+        self.styles.get(index)
+    }
+}
+
+/// هل يحتوي العنصر على نص مباشر؟
+pub fn has_own_text(element: &HtmlElement) -> bool {
+    element.children.iter().any(|c| matches!(c, HtmlChild::Text(t) if !t.trim().is_empty()))
+}
+
+/// تحليل ورقة أنماط إلى قواعد
+fn parse_stylesheet(css: &str, rules: &mut Vec<StyleRule>) {
+    // إزالة التعليقات
+    let mut cleaned = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        cleaned.push_str(&rest[..start]);
+        rest = rest[start + 2..].find("*/").map(|e| &rest[start + 2 + e + 2..]).unwrap_or("");
+    }
+    cleaned.push_str(rest);
+
+    let mut rest = cleaned.as_str();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+
+        // قواعد @ (تُتجاهل مع كتلها)
+        if rest.starts_with('@') {
+            let semicolon = rest.find(';');
+            let brace = rest.find('{');
+            match (semicolon, brace) {
+                (Some(s), Some(b)) if s < b => rest = &rest[s + 1..],
+                (Some(s), None) => rest = &rest[s + 1..],
+                (_, Some(b)) => rest = skip_block(&rest[b..]),
+                (None, None) => break,
+            }
+            continue;
+        }
+
+        let Some(open) = rest.find('{') else { break };
+        let Some(close) = rest[open..].find('}').map(|c| c + open) else { break };
+        let selectors = &rest[..open];
+        let body = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+
+        let declarations: Vec<(String, String)> = body.split(';')
+            .filter_map(|d| d.split_once(':'))
+            .map(|(p, v)| (p.trim().to_lowercase(), v.trim().trim_end_matches("!important").trim().to_string()))
+            .filter(|(p, v)| !p.is_empty() && !v.is_empty())
+            .collect();
+
+        for selector in selectors.split(',') {
+            let parts: Option<Vec<Compound>> = selector.replace('>', " ")
+                .split_whitespace()
+                .map(Compound::parse)
+                .collect();
+            let Some(parts) = parts.filter(|p| !p.is_empty()) else { continue };
+
+            let specificity = parts.iter().fold((0, 0, 0), |acc, c| {
+                let s = c.specificity();
+                (acc.0 + s.0, acc.1 + s.1, acc.2 + s.2)
+            });
+
+            rules.push(StyleRule {
+                selector: parts,
+                specificity,
+                order: rules.len(),
+                declarations: declarations.clone(),
+            });
+        }
+    }
+}
+
+/// تخطي كتلة متوازنة الأقواس تبدأ بـ {
+fn skip_block(css: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in css.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &css[i + 1..];
+                }
+            }
+            _ => {}
+        }
+    }
+    ""
+}
+
+/// هل ينطبق المحدد على العنصر؟ (أسلاف غير مباشرين للأجزاء السابقة)
+fn selector_matches(doc: &HtmlDocument, element: &HtmlElement, selector: &[Compound]) -> bool {
+    let Some((subject, ancestors)) = selector.split_last() else { return false };
+    if !subject.matches(element) {
+        return false;
+    }
+
+    let mut current = element.parent;
+    for compound in ancestors.iter().rev() {
+        loop {
+            let Some(index) = current else { return false };
+            let ancestor = &doc.elements()[index];
+            current = ancestor.parent;
+            if compound.matches(ancestor) {
+                break;
+            }
+        }
+    }
+    true
+}
+
+/// التصريحات المطبقة على عنصر مرتبة حسب الأولوية
+fn declarations_for(doc: &HtmlDocument, element: &HtmlElement, rules: &[StyleRule]) -> Vec<(String, String)> {
+    let mut matched: Vec<&StyleRule> = rules.iter()
+        .filter(|r| selector_matches(doc, element, &r.selector))
+        .collect();
+    matched.sort_by_key(|r| (r.specificity, r.order));
+
+    let mut declarations: Vec<(String, String)> = matched.into_iter()
+        .flat_map(|r| r.declarations.iter().cloned())
+        .collect();
+
+    // الخاصية style لها الأولوية العليا
+    if let Some(inline) = element.attr("style") {
+        declarations.extend(
+            inline.split(';')
+                .filter_map(|d| d.split_once(':'))
+                .map(|(p, v)| (p.trim().to_lowercase(), v.trim().to_string())),
+        );
+    }
+
+    declarations
+}
+
+/// استبدال var(--name, fallback) بقيمها
+fn resolve_variables(value: &str, variables: &HashMap<String, String>, depth: usize) -> String {
+    if depth > MAX_VAR_DEPTH || !value.contains("var(") {
+        return value.to_string();
+    }
+
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("var(") {
+        out.push_str(&rest[..start]);
+        let inner_start = start + 4;
+
+        // إيجاد القوس المغلق المطابق
+        let mut depth_parens = 1;
+        let mut end = rest.len();
+        for (i, c) in rest[inner_start..].char_indices() {
+            match c {
+                '(' => depth_parens += 1,
+                ')' => {
+                    depth_parens -= 1;
+                    if depth_parens == 0 {
+                        end = inner_start + i;
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let inner = &rest[inner_start..end];
+        let (name, fallback) = match inner.split_once(',') {
+            Some((name, fallback)) => (name.trim(), Some(fallback.trim())),
+            None => (inner.trim(), None),
+        };
+        let resolved = variables.get(name).map(|v| v.as_str()).or(fallback).unwrap_or("");
+        out.push_str(&resolve_variables(resolved, variables, depth + 1));
+
+        rest = rest.get(end + 1..).unwrap_or("");
+    }
+    out.push_str(rest);
+    out
+}
+
+/// استخراج جميع الألوان وشفافيتها من قيمة (مثل التدرجات)
+fn extract_colors(value: &str) -> Vec<(Rgb, f32)> {
+    let mut colors = Vec::new();
+    let lower = value.to_lowercase();
+    let mut rest = lower.as_str();
+
+    while !rest.is_empty() {
+        if let Some(stripped) = rest.strip_prefix('#') {
+            let len = stripped.chars().take_while(|c| c.is_ascii_hexdigit()).count();
+            if let Some(color) = Rgb::parse_with_alpha(&rest[..len + 1]) {
+                colors.push(color);
+            }
+            rest = &rest[len + 1..];
+        } else if rest.starts_with("rgb") {
+            let end = rest.find(')').map(|e| e + 1).unwrap_or(rest.len());
+            if let Some(color) = Rgb::parse_with_alpha(&rest[..end]) {
+                colors.push(color);
+            }
+            rest = &rest[end..];
+        } else if rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let len = rest.chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '-').count();
+            if let Some(color) = Rgb::parse_with_alpha(&rest[..len]) {
+                colors.push(color);
+            }
+            rest = &rest[len..];
+        } else {
+            let skip = rest.chars().next().map(char::len_utf8).unwrap_or(1);
+            rest = &rest[skip..];
+        }
+    }
+
+    colors
+}
+
+/// تحويل حجم الخط إلى بكسل
+fn parse_font_size(value: &str, parent: f32) -> Option<f32> {
+    let value = value.trim();
+    let number = |suffix: &str| value.strip_suffix(suffix).and_then(|v| v.trim().parse::<f32>().ok());
+
+    number("px")
+        .or_else(|| number("rem").map(|v| v * BASE_FONT_SIZE))
+        .or_else(|| number("em").map(|v| v * parent))
+        .or_else(|| number("%").map(|v| v / 100.0 * parent))
+        .or_else(|| number("pt").map(|v| v * 4.0 / 3.0))
+}
+
+/// أحجام العناوين الافتراضية في المتصفحات
+fn default_font_size(tag: &str) -> Option<f32> {
+    match tag {
+        "h1" => Some(32.0),
+        "h2" => Some(24.0),
+        "h3" => Some(18.72),
+        _ => None,
+    }
+}
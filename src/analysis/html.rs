@@ -0,0 +1,512 @@
+//! محلل HTML خفيف ومتسامح مع الأخطاء
+//! يكفي لفحص مخرجات المصانع دون الحاجة لمحرك متصفح كامل
+
+use serde::Serialize;
+
+/// العناصر التي لا تحتاج وسم إغلاق
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input",
+    "link", "meta", "source", "track", "wbr", "param",
+];
+
+/// العناصر التي يُقرأ محتواها كنص خام
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// محتوى العنصر
+#[derive(Debug, Clone, Serialize)]
+pub enum HtmlChild {
+    Element(usize),
+    Text(String),
+}
+
+/// عنصر HTML
+#[derive(Debug, Clone, Serialize)]
+pub struct HtmlElement {
+    pub index: usize,
+    pub tag: String,
+    pub attributes: Vec<(String, String)>,
+    pub parent: Option<usize>,
+    pub children: Vec<HtmlChild>,
+    pub line: usize,
+}
+
+impl HtmlElement {
+    /// قيمة خاصية
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// هل الخاصية موجودة (حتى لو كانت فارغة)؟
+    pub fn has_attr(&self, name: &str) -> bool {
+        self.attributes.iter().any(|(key, _)| key == name)
+    }
+
+    /// أصناف العنصر (class)
+    pub fn classes(&self) -> Vec<&str> {
+        self.attr("class")
+            .map(|c| c.split_whitespace().collect())
+            .unwrap_or_default()
+    }
+
+    /// مستوى العنوان (h1-h6)
+    pub fn heading_level(&self) -> Option<u8> {
+        let bytes = self.tag.as_bytes();
+        if bytes.len() == 2 && bytes[0] == b'h' && (b'1'..=b'6').contains(&bytes[1]) {
+            Some(bytes[1] - b'0')
+        } else {
+            None
+        }
+    }
+}
+
+/// مستند HTML محلل
+#[derive(Debug, Clone, Serialize)]
+pub struct HtmlDocument {
+    elements: Vec<HtmlElement>,
+    roots: Vec<HtmlChild>,
+}
+
+impl HtmlDocument {
+    /// تحليل نص HTML
+    pub fn parse(html: &str) -> Self {
+        let mut parser = Parser {
+            input: html,
+            pos: 0,
+            line: 1,
+            elements: Vec::new(),
+            roots: Vec::new(),
+            stack: Vec::new(),
+        };
+        parser.run();
+
+        Self {
+            elements: parser.elements,
+            roots: parser.roots,
+        }
+    }
+
+    /// جميع العناصر بترتيب ظهورها
+    pub fn elements(&self) -> &[HtmlElement] {
+        &self.elements
+    }
+
+    /// عنصر حسب الفهرس
+    pub fn element(&self, index: usize) -> Option<&HtmlElement> {
+        self.elements.get(index)
+    }
+
+    /// جميع العناصر بوسم معين
+    pub fn find_all(&self, tag: &str) -> Vec<&HtmlElement> {
+        self.elements.iter().filter(|e| e.tag == tag).collect()
+    }
+
+    /// أول عنصر بوسم معين
+    pub fn find(&self, tag: &str) -> Option<&HtmlElement> {
+        self.elements.iter().find(|e| e.tag == tag)
+    }
+
+    /// العنصر الجذر <html>
+    pub fn root(&self) -> Option<&HtmlElement> {
+        self.find("html")
+    }
+
+    /// النص الكامل للعنصر وأبنائه
+    pub fn text_of(&self, index: usize) -> String {
+        let mut text = String::new();
+        if let Some(element) = self.elements.get(index) {
+            if !RAW_TEXT_ELEMENTS.contains(&element.tag.as_str()) {
+                self.collect_text(&element.children, &mut text);
+            }
+        }
+        collapse_whitespace(&text)
+    }
+
+    /// المحتوى الخام لعنصر (مثل السكربتات)
+    pub fn raw_text_of(&self, index: usize) -> String {
+        let mut text = String::new();
+        if let Some(element) = self.elements.get(index) {
+            for child in &element.children {
+                if let HtmlChild::Text(t) = child {
+                    text.push_str(t);
+                }
+            }
+        }
+        text
+    }
+
+    /// النص المرئي للمستند بالكامل
+    pub fn visible_text(&self) -> String {
+        let mut text = String::new();
+        self.collect_text(&self.roots, &mut text);
+        collapse_whitespace(&text)
+    }
+
+    /// هل العنصر داخل عنصر آخر بوسم معين؟
+    pub fn has_ancestor(&self, index: usize, tag: &str) -> bool {
+        self.ancestor(index, tag).is_some()
+    }
+
+    /// أقرب سلف بوسم معين
+    pub fn ancestor(&self, index: usize, tag: &str) -> Option<&HtmlElement> {
+        let mut current = self.elements.get(index).and_then(|e| e.parent);
+        while let Some(parent) = current {
+            let element = &self.elements[parent];
+            if element.tag == tag {
+                return Some(element);
+            }
+            current = element.parent;
+        }
+        None
+    }
+
+    /// مسار العنصر بصيغة شبيهة بمحددات CSS
+    pub fn path(&self, index: usize) -> String {
+        let mut parts = Vec::new();
+        let mut current = Some(index);
+
+        while let Some(i) = current {
+            let element = &self.elements[i];
+            let mut part = element.tag.clone();
+
+            if let Some(id) = element.attr("id").filter(|id| !id.is_empty()) {
+                part.push('#');
+                part.push_str(id);
+            } else {
+                // ترتيب العنصر بين إخوته من نفس الوسم
+                let siblings: Vec<usize> = self.sibling_elements(element.parent)
+                    .into_iter()
+                    .filter(|s| self.elements[*s].tag == element.tag)
+                    .collect();
+                if siblings.len() > 1 {
+                    let position = siblings.iter().position(|s| *s == i).unwrap_or(0) + 1;
+                    part.push_str(&format!(":nth-of-type({})", position));
+                }
+            }
+
+            parts.push(part);
+            current = element.parent;
+        }
+
+        parts.reverse();
+        parts.join(" > ")
+    }
+
+    fn sibling_elements(&self, parent: Option<usize>) -> Vec<usize> {
+        let children = match parent {
+            Some(p) => &self.elements[p].children,
+            None => &self.roots,
+        };
+        children.iter()
+            .filter_map(|c| match c {
+                HtmlChild::Element(i) => Some(*i),
+                HtmlChild::Text(_) => None,
+            })
+            .collect()
+    }
+
+    fn collect_text(&self, children: &[HtmlChild], out: &mut String) {
+        for child in children {
+            match child {
+                HtmlChild::Text(t) => {
+                    out.push_str(t);
+                }
+                HtmlChild::Element(i) => {
+                    let element = &self.elements[*i];
+                    if RAW_TEXT_ELEMENTS.contains(&element.tag.as_str()) {
+                        continue;
+                    }
+                    out.push(' ');
+                    self.collect_text(&element.children, out);
+                    out.push(' ');
+                }
+            }
+        }
+    }
+}
+
+/// ضغط المسافات المتتالية
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// فك ترميز الكيانات الشائعة
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let end = rest.find(';').filter(|e| *e <= 10);
+        let decoded = end.and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{00A0}'),
+                _ if entity.starts_with("#x") || entity.starts_with("#X") => {
+                    u32::from_str_radix(&entity[2..], 16).ok().and_then(char::from_u32)
+                }
+                _ if entity.starts_with('#') => {
+                    entity[1..].parse::<u32>().ok().and_then(char::from_u32)
+                }
+                _ => None,
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// حالة المحلل
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    line: usize,
+    elements: Vec<HtmlElement>,
+    roots: Vec<HtmlChild>,
+    stack: Vec<usize>,
+}
+
+impl<'a> Parser<'a> {
+    fn run(&mut self) {
+        while self.pos < self.input.len() {
+            let rest = &self.input[self.pos..];
+
+            if rest.starts_with("<!--") {
+                let end = rest.find("-->").map(|e| e + 3).unwrap_or(rest.len());
+                self.advance(end);
+            } else if rest.starts_with("<!") || rest.starts_with("<?") {
+                let end = rest.find('>').map(|e| e + 1).unwrap_or(rest.len());
+                self.advance(end);
+            } else if rest.starts_with("</") {
+                self.parse_closing_tag();
+            } else if rest.starts_with('<') && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+                self.parse_opening_tag();
+            } else {
+                let first = rest.chars().next().map(char::len_utf8).unwrap_or(1);
+                let end = rest[first..].find('<').map(|e| e + first).unwrap_or(rest.len());
+                let text = decode_entities(&rest[..end]);
+                self.push_child(HtmlChild::Text(text));
+                self.advance(end);
+            }
+        }
+    }
+
+    fn advance(&mut self, bytes: usize) {
+        let end = (self.pos + bytes).min(self.input.len());
+        self.line += self.input[self.pos..end].matches('\n').count();
+        self.pos = end;
+    }
+
+    fn push_child(&mut self, child: HtmlChild) {
+        match self.stack.last() {
+            Some(parent) => self.elements[*parent].children.push(child),
+            None => self.roots.push(child),
+        }
+    }
+
+    fn parse_closing_tag(&mut self) {
+        let rest = &self.input[self.pos..];
+        let end = rest.find('>').map(|e| e + 1).unwrap_or(rest.len());
+        let tag = rest[2..end].trim_end_matches('>').trim().to_lowercase();
+        self.advance(end);
+
+        // إغلاق أقرب عنصر مطابق وما فوقه، وتجاهل الإغلاق اليتيم
+        if let Some(position) = self.stack.iter().rposition(|i| self.elements[*i].tag == tag) {
+            self.stack.truncate(position);
+        }
+    }
+
+    fn parse_opening_tag(&mut self) {
+        let line = self.line;
+        let rest = &self.input[self.pos..];
+        // الوسم غير المغلق يمتد إلى نهاية المدخل دون حذف آخر حرف منه
+        let (end, inner_end) = match find_tag_end(rest) {
+            Some(end) => (end, end - 1),
+            None => (rest.len(), rest.len()),
+        };
+        let inner = &rest[1..inner_end];
+        let self_closing = inner.ends_with('/');
+        let inner = inner.trim_end_matches('/');
+
+        let name_end = inner.find(|c: char| c.is_whitespace()).unwrap_or(inner.len());
+        let tag = inner[..name_end].to_lowercase();
+        let attributes = parse_attributes(&inner[name_end..]);
+        self.advance(end);
+
+        let index = self.elements.len();
+        self.elements.push(HtmlElement {
+            index,
+            tag: tag.clone(),
+            attributes,
+            parent: self.stack.last().copied(),
+            children: Vec::new(),
+            line,
+        });
+        self.push_child(HtmlChild::Element(index));
+
+        if RAW_TEXT_ELEMENTS.contains(&tag.as_str()) && !self_closing {
+            let rest = &self.input[self.pos..];
+            let closing = format!("</{}", tag);
+            let content_end = find_case_insensitive(rest, &closing).unwrap_or(rest.len());
+            let content = rest[..content_end].to_string();
+            self.elements[index].children.push(HtmlChild::Text(content));
+            self.advance(content_end);
+            let rest = &self.input[self.pos..];
+            let close_end = rest.find('>').map(|e| e + 1).unwrap_or(rest.len());
+            self.advance(close_end);
+            return;
+        }
+
+        if !self_closing && !VOID_ELEMENTS.contains(&tag.as_str()) {
+            self.stack.push(index);
+        }
+    }
+}
+
+/// موضع ما بعد `>` الخاتمة مع مراعاة علامات الاقتباس داخل الخصائص، أو `None` للوسم غير المغلق
+fn find_tag_end(rest: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"') | (None, '\'') => quote = Some(c),
+            (None, '>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// البحث دون مراعاة حالة الأحرف
+fn find_case_insensitive(haystack: &str, needle: &str) -> Option<usize> {
+    let needle = needle.to_ascii_lowercase();
+    haystack.char_indices()
+        .map(|(i, _)| i)
+        .find(|i| {
+            haystack.len() - i >= needle.len()
+                && haystack.is_char_boundary(i + needle.len())
+                && haystack[*i..i + needle.len()].to_ascii_lowercase() == needle
+        })
+}
+
+/// تحليل خصائص الوسم
+fn parse_attributes(input: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let chars: Vec<char> = input.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        while i < chars.len() && (chars[i].is_whitespace() || chars[i] == '/') {
+            i += 1;
+        }
+        if i >= chars.len() {
+            break;
+        }
+
+        let start = i;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '=' {
+            i += 1;
+        }
+        let name: String = chars[start..i].iter().collect::<String>().to_lowercase();
+
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+
+        let mut value = String::new();
+        if i < chars.len() && chars[i] == '=' {
+            i += 1;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == '"' || chars[i] == '\'') {
+                let quote = chars[i];
+                i += 1;
+                let value_start = i;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                value = chars[value_start..i].iter().collect();
+                i += 1;
+            } else {
+                let value_start = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                value = chars[value_start..i].iter().collect();
+            }
+        }
+
+        if !name.is_empty() {
+            attributes.push((name, decode_entities(&value)));
+        }
+    }
+
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nested_elements() {
+        let doc = HtmlDocument::parse(
+            r#"<!DOCTYPE html><html lang="ar" dir="rtl"><body><main id="content"><p>مرحبا <b>بكم</b></p><img src="a.png"></main></body></html>"#
+        );
+
+        let html = doc.root().unwrap();
+        assert_eq!(html.attr("lang"), Some("ar"));
+        assert_eq!(doc.find_all("img").len(), 1);
+
+        let p = doc.find("p").unwrap();
+        assert_eq!(doc.text_of(p.index), "مرحبا بكم");
+
+        let img = doc.find("img").unwrap();
+        assert_eq!(doc.path(img.index), "html > body > main#content > img");
+    }
+
+    #[test]
+    fn test_raw_text_and_entities() {
+        let doc = HtmlDocument::parse("<p>a &amp; b</p><script>if (a < b) {}</script><p>c</p>");
+        let script = doc.find("script").unwrap();
+        assert_eq!(doc.raw_text_of(script.index), "if (a < b) {}");
+        assert_eq!(doc.visible_text(), "a & b c");
+        assert_eq!(doc.path(doc.find_all("p")[1].index), "p:nth-of-type(2)");
+    }
+
+    #[test]
+    fn test_unterminated_tag_ending_in_multibyte_char() {
+        for input in ["<pمرحبا", "<aب", "<p class=\"مرحبا", "<html><pمرحبا", "<p>نص</p><imgصورة"] {
+            HtmlDocument::parse(input);
+        }
+        assert!(HtmlDocument::parse("<p class=\"مرحبا").find("p").is_some());
+        assert!(HtmlDocument::parse("<aب").find("aب").is_some());
+    }
+}
@@ -0,0 +1,16 @@
+//! وحدة تحليل المحتوى
+//! تحليل النصوص العربية والمخرجات المولدة لاستخدامها في بوابات الجودة
+
+pub mod text;
+pub mod html;
+pub mod seo;
+pub mod css;
+pub mod accessibility;
+pub mod security;
+
+// إعادة التصدير للاستخدام السهل
+pub use text::{TextAnalyzer, TextQualityReport};
+pub use html::HtmlDocument;
+pub use seo::{SeoAnalyzer, SeoReport};
+pub use accessibility::{AccessibilityChecker, AccessibilityReport};
+pub use security::{SecurityScanner, SecurityReport};
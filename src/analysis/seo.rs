@@ -0,0 +1,802 @@
+//! محلل تحسين محركات البحث (SEO)
+//! يفحص مخرجات HTML و Markdown ويحسب كثافة الكلمات المفتاحية بالعربية والإنجليزية
+
+use serde::Serialize;
+use crate::analysis::html::HtmlDocument;
+use crate::analysis::text::{self, Script};
+
+/// البوادئ العربية الملتصقة التي تُزال عند مطابقة الكلمات المفتاحية
+const ARABIC_PREFIXES: [&str; 9] = ["وال", "بال", "كال", "فال", "لل", "ال", "و", "ب", "ل"];
+
+/// تنسيق المحتوى
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum SeoFormat {
+    Html,
+    Markdown,
+}
+
+impl SeoFormat {
+    /// تخمين التنسيق من المحتوى
+    pub fn detect(content: &str) -> Self {
+        let trimmed = content.trim_start().to_lowercase();
+        if trimmed.starts_with("<!doctype") || trimmed.starts_with("<html") || trimmed.contains("<body") {
+            SeoFormat::Html
+        } else {
+            SeoFormat::Markdown
+        }
+    }
+}
+
+/// الفحوص المتاحة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SeoCheck {
+    Title,
+    MetaDescription,
+    SingleH1,
+    HeadingHierarchy,
+    ImageAlt,
+    InternalLinks,
+    KeywordDensity,
+    LangDir,
+}
+
+impl SeoCheck {
+    /// وزن الفحص في النتيجة النهائية
+    fn weight(&self) -> f32 {
+        match self {
+            SeoCheck::Title => 0.15,
+            SeoCheck::MetaDescription => 0.15,
+            SeoCheck::SingleH1 => 0.1,
+            SeoCheck::HeadingHierarchy => 0.1,
+            SeoCheck::ImageAlt => 0.15,
+            SeoCheck::InternalLinks => 0.1,
+            SeoCheck::KeywordDensity => 0.15,
+            SeoCheck::LangDir => 0.1,
+        }
+    }
+}
+
+/// خطورة الملاحظة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FindingSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// نتيجة فحص واحد
+#[derive(Debug, Clone, Serialize)]
+pub struct SeoFinding {
+    pub check: SeoCheck,
+    pub severity: FindingSeverity,
+    pub score: f32,
+    pub message: String,
+    pub element: Option<String>,
+}
+
+/// كثافة كلمة مفتاحية
+#[derive(Debug, Clone, Serialize)]
+pub struct KeywordDensity {
+    pub keyword: String,
+    pub occurrences: usize,
+    pub density: f32,
+    pub in_title: bool,
+    pub in_description: bool,
+    pub in_headings: bool,
+}
+
+/// صورة في المستند
+#[derive(Debug, Clone, Serialize)]
+pub struct SeoImage {
+    pub src: String,
+    pub alt: Option<String>,
+    pub element: String,
+}
+
+/// رابط في المستند
+#[derive(Debug, Clone, Serialize)]
+pub struct SeoLink {
+    pub href: String,
+    pub internal: bool,
+    pub element: String,
+}
+
+/// البيانات المستخرجة من المستند بغض النظر عن تنسيقه
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SeoDocument {
+    pub title: Option<String>,
+    pub meta_description: Option<String>,
+    pub headings: Vec<(u8, String, String)>,
+    pub images: Vec<SeoImage>,
+    pub links: Vec<SeoLink>,
+    pub text: String,
+    pub lang: Option<String>,
+    pub dir: Option<String>,
+}
+
+/// تقرير SEO
+#[derive(Debug, Clone, Serialize)]
+pub struct SeoReport {
+    pub score: f32,
+    pub format: SeoFormat,
+    pub title: Option<String>,
+    pub meta_description: Option<String>,
+    pub word_count: usize,
+    pub headings: Vec<(u8, String)>,
+    pub internal_links: usize,
+    pub external_links: usize,
+    pub images: usize,
+    pub images_without_alt: usize,
+    pub lang: Option<String>,
+    pub dir: Option<String>,
+    pub keywords: Vec<KeywordDensity>,
+    pub findings: Vec<SeoFinding>,
+    pub suggestions: Vec<String>,
+}
+
+/// محلل SEO
+#[derive(Debug, Clone)]
+pub struct SeoAnalyzer {
+    pub target_keywords: Vec<String>,
+    pub site_host: Option<String>,
+    pub title_length: (usize, usize),
+    pub description_length: (usize, usize),
+    pub keyword_density: (f32, f32),
+}
+
+impl Default for SeoAnalyzer {
+    fn default() -> Self {
+        Self {
+            target_keywords: Vec::new(),
+            site_host: None,
+            title_length: (10, 60),
+            description_length: (50, 160),
+            keyword_density: (0.005, 0.03),
+        }
+    }
+}
+
+impl SeoAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// تحديد الكلمات المفتاحية المستهدفة
+    pub fn with_keywords(mut self, keywords: Vec<String>) -> Self {
+        self.target_keywords = keywords.into_iter()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+            .collect();
+        self
+    }
+
+    /// تحديد نطاق الموقع لتمييز الروابط الداخلية المطلقة
+    pub fn with_site_host(mut self, host: &str) -> Self {
+        self.site_host = Some(host.to_lowercase());
+        self
+    }
+
+    /// تحليل محتوى مع تخمين تنسيقه
+    pub fn analyze(&self, content: &str) -> SeoReport {
+        self.analyze_as(content, SeoFormat::detect(content))
+    }
+
+    /// تحليل محتوى بتنسيق محدد
+    pub fn analyze_as(&self, content: &str, format: SeoFormat) -> SeoReport {
+        let document = match format {
+            SeoFormat::Html => self.extract_html(content),
+            SeoFormat::Markdown => self.extract_markdown(content),
+        };
+        self.evaluate(document, format)
+    }
+
+    /// استخراج البيانات من HTML
+    fn extract_html(&self, content: &str) -> SeoDocument {
+        let doc = HtmlDocument::parse(content);
+        let mut extracted = SeoDocument {
+            title: doc.find("title")
+                .map(|t| doc.text_of(t.index))
+                .filter(|t| !t.is_empty()),
+            meta_description: doc.find_all("meta").into_iter()
+                .find(|m| m.attr("name").map(|n| n.eq_ignore_ascii_case("description")).unwrap_or(false))
+                .and_then(|m| m.attr("content"))
+                .map(|c| c.trim().to_string()),
+            ..Default::default()
+        };
+
+        for element in doc.elements() {
+            if let Some(level) = element.heading_level() {
+                extracted.headings.push((level, doc.text_of(element.index), doc.path(element.index)));
+            }
+        }
+
+        extracted.images = doc.find_all("img").into_iter()
+            .map(|img| SeoImage {
+                src: img.attr("src").unwrap_or("").to_string(),
+                alt: img.attr("alt").map(|a| a.trim().to_string()),
+                element: doc.path(img.index),
+            })
+            .collect();
+
+        extracted.links = doc.find_all("a").into_iter()
+            .filter_map(|a| a.attr("href").map(|href| (a, href)))
+            .map(|(a, href)| SeoLink {
+                href: href.to_string(),
+                internal: self.is_internal(href),
+                element: doc.path(a.index),
+            })
+            .collect();
+
+        let body_text = doc.find("body")
+            .map(|b| doc.text_of(b.index))
+            .unwrap_or_else(|| doc.visible_text());
+        extracted.text = body_text;
+
+        if let Some(root) = doc.root() {
+            extracted.lang = root.attr("lang").map(|l| l.to_string());
+            extracted.dir = root.attr("dir").map(|d| d.to_string());
+        }
+        if extracted.dir.is_none() {
+            extracted.dir = doc.find("body").and_then(|b| b.attr("dir")).map(|d| d.to_string());
+        }
+
+        extracted
+    }
+
+    /// استخراج البيانات من Markdown (مع دعم الترويسة الأمامية)
+    fn extract_markdown(&self, content: &str) -> SeoDocument {
+        let mut extracted = SeoDocument::default();
+        let mut body = content;
+
+        // الترويسة الأمامية بصيغة YAML المبسطة
+        if let Some(rest) = content.strip_prefix("---") {
+            if let Some(end) = rest.find("\n---") {
+                for line in rest[..end].lines() {
+                    if let Some((key, value)) = line.split_once(':') {
+                        let value = value.trim().trim_matches('"').trim_matches('\'').to_string();
+                        match key.trim() {
+                            "title" => extracted.title = Some(value),
+                            "description" => extracted.meta_description = Some(value),
+                            "lang" => extracted.lang = Some(value),
+                            "dir" => extracted.dir = Some(value),
+                            _ => {}
+                        }
+                    }
+                }
+                body = rest[end + 4..].trim_start_matches(['-', '\n', '\r']);
+            }
+        }
+
+        let mut text = String::new();
+        let mut in_code_block = false;
+
+        for (line_number, line) in body.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("```") {
+                in_code_block = !in_code_block;
+                continue;
+            }
+            if in_code_block {
+                continue;
+            }
+
+            let element = format!("line {}", line_number + 1);
+            let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+            let mut line_text = if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+                let heading = trimmed[hashes..].trim().to_string();
+                extracted.headings.push((hashes as u8, heading.clone(), element.clone()));
+                heading
+            } else {
+                trimmed.to_string()
+            };
+
+            // الصور ![alt](src) والروابط [text](href)
+            let mut cleaned = String::new();
+            let mut rest = line_text.as_str();
+            while let Some(open) = rest.find('[') {
+                let is_image = open > 0 && rest[..open].ends_with('!');
+                let Some(close) = rest[open..].find("](").map(|c| c + open) else { break };
+                let Some(end) = rest[close..].find(')').map(|e| e + close) else { break };
+
+                let label = &rest[open + 1..close];
+                let target = rest[close + 2..end].split_whitespace().next().unwrap_or("").to_string();
+
+                if is_image {
+                    cleaned.push_str(&rest[..open - 1]);
+                    extracted.images.push(SeoImage {
+                        src: target,
+                        alt: Some(label.trim().to_string()),
+                        element: element.clone(),
+                    });
+                } else {
+                    cleaned.push_str(&rest[..open]);
+                    cleaned.push_str(label);
+                    extracted.links.push(SeoLink {
+                        internal: self.is_internal(&target),
+                        href: target,
+                        element: element.clone(),
+                    });
+                }
+                rest = &rest[end + 1..];
+            }
+            cleaned.push_str(rest);
+            line_text = cleaned;
+
+            text.push_str(&line_text.replace(['*', '_', '`', '>'], " "));
+            text.push('\n');
+        }
+
+        if extracted.title.is_none() {
+            extracted.title = extracted.headings.iter()
+                .find(|(level, _, _)| *level == 1)
+                .map(|(_, heading, _)| heading.clone());
+        }
+
+        extracted.text = text;
+        extracted
+    }
+
+    /// هل الرابط داخلي؟
+    fn is_internal(&self, href: &str) -> bool {
+        let href = href.trim().to_lowercase();
+        if href.starts_with("mailto:") || href.starts_with("tel:") || href.starts_with("javascript:") {
+            return false;
+        }
+        if href.starts_with("http://") || href.starts_with("https://") || href.starts_with("//") {
+            return match &self.site_host {
+                Some(host) => href.trim_start_matches("https://")
+                    .trim_start_matches("http://")
+                    .trim_start_matches("//")
+                    .split('/')
+                    .next()
+                    .map(|h| h == host || h.ends_with(&format!(".{}", host)))
+                    .unwrap_or(false),
+                None => false,
+            };
+        }
+        !href.is_empty()
+    }
+
+    /// تقييم المستند
+    fn evaluate(&self, document: SeoDocument, format: SeoFormat) -> SeoReport {
+        let tokens = text::tokenize(&document.text);
+        let mut findings = vec![
+            self.check_title(&document),
+            self.check_description(&document),
+            self.check_single_h1(&document),
+            self.check_heading_hierarchy(&document),
+        ];
+        findings.extend(self.check_images(&document));
+        findings.push(self.check_internal_links(&document));
+
+        let keywords = self.compute_keywords(&document, &tokens);
+        findings.extend(self.check_keywords(&keywords));
+
+        if format == SeoFormat::Html || document.lang.is_some() {
+            findings.push(self.check_lang_dir(&document, &tokens));
+        }
+
+        let score = self.compute_score(&findings);
+        let suggestions = findings.iter()
+            .filter(|f| f.severity != FindingSeverity::Info)
+            .map(|f| f.message.clone())
+            .collect();
+
+        SeoReport {
+            score,
+            format,
+            word_count: tokens.len(),
+            headings: document.headings.iter().map(|(l, h, _)| (*l, h.clone())).collect(),
+            internal_links: document.links.iter().filter(|l| l.internal).count(),
+            external_links: document.links.iter().filter(|l| !l.internal).count(),
+            images: document.images.len(),
+            images_without_alt: document.images.iter()
+                .filter(|i| i.alt.as_deref().map(str::is_empty).unwrap_or(true))
+                .count(),
+            title: document.title,
+            meta_description: document.meta_description,
+            lang: document.lang,
+            dir: document.dir,
+            keywords,
+            findings,
+            suggestions,
+        }
+    }
+
+    /// فحص طول نص ضمن نطاق
+    fn length_finding(&self, check: SeoCheck, label: &str, value: Option<&String>, range: (usize, usize)) -> SeoFinding {
+        match value {
+            None => SeoFinding {
+                check,
+                severity: FindingSeverity::Error,
+                score: 0.0,
+                message: format!("{} مفقود", label),
+                element: None,
+            },
+            Some(value) => {
+                let length = value.chars().count();
+                if length < range.0 {
+                    SeoFinding {
+                        check,
+                        severity: FindingSeverity::Warning,
+                        score: 0.5,
+                        message: format!("{} قصير ({} حرف، المستحسن {}-{})", label, length, range.0, range.1),
+                        element: None,
+                    }
+                } else if length > range.1 {
+                    SeoFinding {
+                        check,
+                        severity: FindingSeverity::Warning,
+                        score: 0.6,
+                        message: format!("{} طويل ({} حرف، المستحسن {}-{})", label, length, range.0, range.1),
+                        element: None,
+                    }
+                } else {
+                    SeoFinding {
+                        check,
+                        severity: FindingSeverity::Info,
+                        score: 1.0,
+                        message: format!("{} بطول مناسب ({} حرف)", label, length),
+                        element: None,
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_title(&self, document: &SeoDocument) -> SeoFinding {
+        self.length_finding(SeoCheck::Title, "العنوان", document.title.as_ref(), self.title_length)
+    }
+
+    fn check_description(&self, document: &SeoDocument) -> SeoFinding {
+        self.length_finding(
+            SeoCheck::MetaDescription,
+            "وصف الميتا",
+            document.meta_description.as_ref(),
+            self.description_length,
+        )
+    }
+
+    fn check_single_h1(&self, document: &SeoDocument) -> SeoFinding {
+        let h1: Vec<&(u8, String, String)> = document.headings.iter().filter(|(l, _, _)| *l == 1).collect();
+        match h1.len() {
+            1 => SeoFinding {
+                check: SeoCheck::SingleH1,
+                severity: FindingSeverity::Info,
+                score: 1.0,
+                message: "عنوان رئيسي (h1) واحد".to_string(),
+                element: Some(h1[0].2.clone()),
+            },
+            0 => SeoFinding {
+                check: SeoCheck::SingleH1,
+                severity: FindingSeverity::Error,
+                score: 0.0,
+                message: "لا يوجد عنوان رئيسي (h1)".to_string(),
+                element: None,
+            },
+            count => SeoFinding {
+                check: SeoCheck::SingleH1,
+                severity: FindingSeverity::Warning,
+                score: 0.5,
+                message: format!("يوجد {} عناوين رئيسية (h1) والمستحسن واحد", count),
+                element: Some(h1[1].2.clone()),
+            },
+        }
+    }
+
+    fn check_heading_hierarchy(&self, document: &SeoDocument) -> SeoFinding {
+        let mut previous = 0u8;
+        for (level, heading, element) in &document.headings {
+            if previous > 0 && *level > previous + 1 {
+                return SeoFinding {
+                    check: SeoCheck::HeadingHierarchy,
+                    severity: FindingSeverity::Warning,
+                    score: 0.5,
+                    message: format!("قفزة في تسلسل العناوين من h{} إلى h{} عند \"{}\"", previous, level, heading),
+                    element: Some(element.clone()),
+                };
+            }
+            previous = *level;
+        }
+
+        SeoFinding {
+            check: SeoCheck::HeadingHierarchy,
+            severity: FindingSeverity::Info,
+            score: if document.headings.is_empty() { 0.5 } else { 1.0 },
+            message: if document.headings.is_empty() {
+                "لا توجد عناوين لتنظيم المحتوى".to_string()
+            } else {
+                "تسلسل العناوين سليم".to_string()
+            },
+            element: None,
+        }
+    }
+
+    fn check_images(&self, document: &SeoDocument) -> Vec<SeoFinding> {
+        if document.images.is_empty() {
+            return vec![SeoFinding {
+                check: SeoCheck::ImageAlt,
+                severity: FindingSeverity::Info,
+                score: 1.0,
+                message: "لا توجد صور".to_string(),
+                element: None,
+            }];
+        }
+
+        let missing: Vec<&SeoImage> = document.images.iter()
+            .filter(|i| i.alt.as_deref().map(str::is_empty).unwrap_or(true))
+            .collect();
+        let score = 1.0 - missing.len() as f32 / document.images.len() as f32;
+
+        if missing.is_empty() {
+            return vec![SeoFinding {
+                check: SeoCheck::ImageAlt,
+                severity: FindingSeverity::Info,
+                score,
+                message: format!("جميع الصور ({}) لها نص بديل", document.images.len()),
+                element: None,
+            }];
+        }
+
+        missing.into_iter()
+            .map(|image| SeoFinding {
+                check: SeoCheck::ImageAlt,
+                severity: FindingSeverity::Warning,
+                score,
+                message: format!("الصورة \"{}\" بدون نص بديل (alt)", image.src),
+                element: Some(image.element.clone()),
+            })
+            .collect()
+    }
+
+    fn check_internal_links(&self, document: &SeoDocument) -> SeoFinding {
+        let internal = document.links.iter().filter(|l| l.internal).count();
+        if internal > 0 {
+            SeoFinding {
+                check: SeoCheck::InternalLinks,
+                severity: FindingSeverity::Info,
+                score: 1.0,
+                message: format!("{} رابط داخلي", internal),
+                element: None,
+            }
+        } else {
+            SeoFinding {
+                check: SeoCheck::InternalLinks,
+                severity: FindingSeverity::Warning,
+                score: 0.0,
+                message: "لا توجد روابط داخلية".to_string(),
+                element: None,
+            }
+        }
+    }
+
+    /// حساب كثافة الكلمات المفتاحية
+    fn compute_keywords(&self, document: &SeoDocument, tokens: &[text::Token]) -> Vec<KeywordDensity> {
+        let words: Vec<Vec<String>> = tokens.iter().map(|t| word_stems(&t.normalized)).collect();
+        let title_words = normalized_words(document.title.as_deref().unwrap_or(""));
+        let description_words = normalized_words(document.meta_description.as_deref().unwrap_or(""));
+        let heading_words: Vec<Vec<String>> = document.headings.iter()
+            .flat_map(|(_, h, _)| normalized_words(h))
+            .collect();
+
+        self.target_keywords.iter()
+            .map(|keyword| {
+                let parts = normalized_words(keyword);
+                let occurrences = count_sequence(&words, &parts);
+                let density = if words.is_empty() {
+                    0.0
+                } else {
+                    (occurrences * parts.len().max(1)) as f32 / words.len() as f32
+                };
+
+                KeywordDensity {
+                    keyword: keyword.clone(),
+                    occurrences,
+                    density,
+                    in_title: count_sequence(&title_words, &parts) > 0,
+                    in_description: count_sequence(&description_words, &parts) > 0,
+                    in_headings: count_sequence(&heading_words, &parts) > 0,
+                }
+            })
+            .collect()
+    }
+
+    fn check_keywords(&self, keywords: &[KeywordDensity]) -> Vec<SeoFinding> {
+        let (min, max) = self.keyword_density;
+
+        keywords.iter()
+            .map(|k| {
+                let (severity, mut score, message) = if k.occurrences == 0 {
+                    (FindingSeverity::Error, 0.0, format!("الكلمة المفتاحية \"{}\" غير موجودة في المحتوى", k.keyword))
+                } else if k.density < min {
+                    (FindingSeverity::Warning, 0.5, format!("كثافة \"{}\" منخفضة ({:.1}%)", k.keyword, k.density * 100.0))
+                } else if k.density > max {
+                    (FindingSeverity::Warning, 0.4, format!("كثافة \"{}\" مرتفعة ({:.1}%) وقد تعتبر حشواً", k.keyword, k.density * 100.0))
+                } else {
+                    (FindingSeverity::Info, 0.8, format!("كثافة \"{}\" مناسبة ({:.1}%)", k.keyword, k.density * 100.0))
+                };
+
+                // مكافأة الظهور في العنوان والوصف
+                if k.in_title {
+                    score += 0.1;
+                }
+                if k.in_description {
+                    score += 0.1;
+                }
+
+                SeoFinding {
+                    check: SeoCheck::KeywordDensity,
+                    severity,
+                    score: f32::min(score, 1.0),
+                    message,
+                    element: None,
+                }
+            })
+            .collect()
+    }
+
+    fn check_lang_dir(&self, document: &SeoDocument, tokens: &[text::Token]) -> SeoFinding {
+        let arabic = tokens.iter().filter(|t| t.script == Script::Arabic).count();
+        let latin = tokens.iter().filter(|t| t.script == Script::Latin).count();
+        let content_is_arabic = arabic > 0 && arabic >= latin;
+
+        let lang = document.lang.as_deref().map(str::to_lowercase);
+        let dir = document.dir.as_deref().map(str::to_lowercase);
+
+        let Some(lang) = lang else {
+            return SeoFinding {
+                check: SeoCheck::LangDir,
+                severity: FindingSeverity::Error,
+                score: 0.0,
+                message: "خاصية lang مفقودة في وسم html".to_string(),
+                element: Some("html".to_string()),
+            };
+        };
+
+        if content_is_arabic && !lang.starts_with("ar") {
+            return SeoFinding {
+                check: SeoCheck::LangDir,
+                severity: FindingSeverity::Warning,
+                score: 0.5,
+                message: format!("المحتوى عربي لكن lang=\"{}\"", lang),
+                element: Some("html".to_string()),
+            };
+        }
+
+        if lang.starts_with("ar") && dir.as_deref() != Some("rtl") {
+            return SeoFinding {
+                check: SeoCheck::LangDir,
+                severity: FindingSeverity::Warning,
+                score: 0.5,
+                message: "المحتوى العربي يحتاج dir=\"rtl\"".to_string(),
+                element: Some("html".to_string()),
+            };
+        }
+
+        SeoFinding {
+            check: SeoCheck::LangDir,
+            severity: FindingSeverity::Info,
+            score: 1.0,
+            message: format!("اللغة والاتجاه مضبوطان (lang=\"{}\")", lang),
+            element: None,
+        }
+    }
+
+    /// النتيجة الموزونة مع احتساب متوسط الفحوص المتكررة
+    fn compute_score(&self, findings: &[SeoFinding]) -> f32 {
+        let mut total = 0.0;
+        let mut weights = 0.0;
+        let mut seen: Vec<SeoCheck> = Vec::new();
+
+        for finding in findings {
+            if seen.contains(&finding.check) {
+                continue;
+            }
+            seen.push(finding.check);
+
+            let same: Vec<f32> = findings.iter()
+                .filter(|f| f.check == finding.check)
+                .map(|f| f.score)
+                .collect();
+            let average = same.iter().sum::<f32>() / same.len() as f32;
+
+            total += average * finding.check.weight();
+            weights += finding.check.weight();
+        }
+
+        if weights > 0.0 { total / weights } else { 0.0 }
+    }
+}
+
+/// الصيغ المحتملة لكلمة عربية موحدة بعد إزالة البوادئ الملتصقة (ال، وال، بال...)
+/// تُعاد الكلمة نفسها أولاً لأن الحرف الأول قد يكون أصلياً (مثل الباء في "برمجة")
+fn word_stems(word: &str) -> Vec<String> {
+    let mut stems = vec![word.to_string()];
+    if text::detect_script(word) != Script::Arabic {
+        return stems;
+    }
+    for prefix in ARABIC_PREFIXES {
+        if let Some(stem) = word.strip_prefix(prefix) {
+            // لا تُزال البادئة إذا بقي جذر قصير جداً
+            if stem.chars().count() >= 3 && !stems.iter().any(|s| s == stem) {
+                stems.push(stem.to_string());
+            }
+        }
+    }
+    stems
+}
+
+/// صيغ كلمات نص موحد
+fn normalized_words(value: &str) -> Vec<Vec<String>> {
+    text::tokenize(value).iter()
+        .map(|t| word_stems(&t.normalized))
+        .collect()
+}
+
+/// عدد مرات ظهور تسلسل كلمات، تتطابق الكلمتان إذا اشتركتا في صيغة
+fn count_sequence(words: &[Vec<String>], parts: &[Vec<String>]) -> usize {
+    if parts.is_empty() || words.len() < parts.len() {
+        return 0;
+    }
+    words.windows(parts.len())
+        .filter(|window| {
+            window.iter().zip(parts).all(|(word, part)| word.iter().any(|w| part.contains(w)))
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+<html lang="ar" dir="rtl">
+<head>
+<title>دورة البرمجة بلغة رست للمبتدئين</title>
+<meta name="description" content="تعلم البرمجة بلغة رست خطوة بخطوة مع أمثلة عملية وتمارين تفاعلية مناسبة للمبتدئين">
+</head>
+<body>
+<h1>تعلم البرمجة</h1>
+<h3>المقدمة</h3>
+<p>البرمجة مهارة مهمة. نتعلم البرمجة هنا مع أمثلة كثيرة عن البرمجة.</p>
+<img src="logo.png">
+<a href="/courses">الدورات</a>
+</body>
+</html>"#;
+
+    #[test]
+    fn test_html_findings() {
+        let report = SeoAnalyzer::new()
+            .with_keywords(vec!["برمجة".to_string()])
+            .analyze(PAGE);
+
+        assert_eq!(report.format, SeoFormat::Html);
+        assert_eq!(report.images_without_alt, 1);
+        assert_eq!(report.internal_links, 1);
+        assert!(report.findings.iter().any(|f| f.check == SeoCheck::HeadingHierarchy && f.score < 1.0));
+        assert!(report.findings.iter().any(|f| f.check == SeoCheck::LangDir && f.score == 1.0));
+
+        let keyword = &report.keywords[0];
+        assert_eq!(keyword.occurrences, 4);
+        assert!(keyword.in_title && keyword.in_description);
+    }
+
+    #[test]
+    fn test_markdown_extraction() {
+        let markdown = "---\ntitle: Rust Programming Course\ndescription: short\n---\n# Rust\n## Basics\n![](cover.png)\nSee [docs](https://doc.rust-lang.org) and [intro](./intro.md).\n";
+        let report = SeoAnalyzer::new().analyze(markdown);
+
+        assert_eq!(report.format, SeoFormat::Markdown);
+        assert_eq!(report.title.as_deref(), Some("Rust Programming Course"));
+        assert_eq!(report.images_without_alt, 1);
+        assert_eq!((report.internal_links, report.external_links), (1, 1));
+        assert!(report.findings.iter().any(|f| f.check == SeoCheck::MetaDescription && f.score < 1.0));
+    }
+
+    #[test]
+    fn test_unterminated_arabic_tags_do_not_panic() {
+        for input in ["<pمرحبا", "<aب", "<p class=\"مرحبا", "<html><pمرحبا", "<p>نص</p><imgصورة"] {
+            SeoAnalyzer::new().analyze(input);
+        }
+    }
+}
@@ -0,0 +1,813 @@
+//! محلل جودة النصوص العربية
+//! تقطيع يراعي التشكيل والتطويل وتوحيد الألف والياء، مع إحصائيات ومؤشر مقروئية
+
+use serde::Serialize;
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+
+/// حرف التطويل (الكشيدة)
+const TATWEEL: char = '\u{0640}';
+
+/// علامات نهاية الجملة
+const SENTENCE_TERMINATORS: [char; 7] = ['.', '!', '?', '؟', '۔', '…', '\n'];
+
+/// كلمات شائعة لا تُحتسب عند فحص التكرار المفرط
+const STOPWORDS: [&str; 44] = [
+    "في", "من", "على", "إلى", "عن", "أن", "إن", "هذا", "هذه", "ذلك", "تلك",
+    "التي", "الذي", "الذين", "و", "أو", "ما", "لا", "لم", "لن", "مع", "كان",
+    "كانت", "هو", "هي", "هم", "قد", "ثم", "كل", "بين", "عند", "بعد", "قبل",
+    "the", "a", "an", "and", "or", "of", "to", "in", "is", "for", "with",
+];
+
+/// أخطاء إملائية شائعة (الصيغة الخاطئة، التصحيح)
+const COMMON_MISSPELLINGS: [(&str, &str); 15] = [
+    ("انشاء", "إنشاء"),
+    ("الى", "إلى"),
+    ("فى", "في"),
+    ("الذى", "الذي"),
+    ("التى", "التي"),
+    ("هاذا", "هذا"),
+    ("هاذه", "هذه"),
+    ("هاذي", "هذي"),
+    ("هاؤلاء", "هؤلاء"),
+    ("لاكن", "لكن"),
+    ("ذالك", "ذلك"),
+    ("شيئ", "شيء"),
+    ("مسئولية", "مسؤولية"),
+    ("انشاءالله", "إن شاء الله"),
+    ("إنشاءالله", "إن شاء الله"),
+];
+
+/// نظام الكتابة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Script {
+    Arabic,
+    Latin,
+    Mixed,
+    Other,
+}
+
+impl Script {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Script::Arabic => "arabic",
+            Script::Latin => "latin",
+            Script::Mixed => "mixed",
+            Script::Other => "other",
+        }
+    }
+}
+
+/// كلمة مقطعة من النص
+#[derive(Debug, Clone, Serialize)]
+pub struct Token {
+    pub text: String,
+    pub stripped: String,
+    pub normalized: String,
+    pub script: Script,
+    pub letters: usize,
+}
+
+/// إحصائيات النص
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TextStatistics {
+    pub char_count: usize,
+    pub letter_count: usize,
+    pub word_count: usize,
+    pub unique_words: usize,
+    pub sentence_count: usize,
+    pub paragraph_count: usize,
+    pub avg_word_length: f32,
+    pub avg_sentence_length: f32,
+    pub lexical_diversity: f32,
+    pub long_word_ratio: f32,
+    pub diacritics_count: usize,
+    pub tatweel_count: usize,
+}
+
+/// مستوى المقروئية
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum ReadabilityLevel {
+    VeryEasy,
+    Easy,
+    Moderate,
+    Difficult,
+    VeryDifficult,
+}
+
+impl ReadabilityLevel {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ReadabilityLevel::VeryEasy => "سهل جداً",
+            ReadabilityLevel::Easy => "سهل",
+            ReadabilityLevel::Moderate => "متوسط",
+            ReadabilityLevel::Difficult => "صعب",
+            ReadabilityLevel::VeryDifficult => "صعب جداً",
+        }
+    }
+}
+
+/// مؤشر المقروئية العربي
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadabilityIndex {
+    pub index: f32, // 0 - 100
+    pub score: f32, // 0.0 - 1.0
+    pub level: ReadabilityLevel,
+}
+
+/// ملف أنظمة الكتابة في النص
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScriptProfile {
+    pub arabic_words: usize,
+    pub latin_words: usize,
+    pub mixed_words: usize,
+    pub other_words: usize,
+    pub arabic_ratio: f32,
+    pub latin_ratio: f32,
+    pub dominant: Option<Script>,
+    pub is_mixed: bool,
+}
+
+/// نوع الملاحظة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum IssueKind {
+    RepeatedWord,
+    OverusedWord,
+    Spelling,
+    Punctuation,
+    MixedScript,
+    MixedDigits,
+    ElongatedWord,
+    Tatweel,
+    LongSentence,
+}
+
+/// خطورة الملاحظة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IssueSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl IssueSeverity {
+    /// وزن الملاحظة في خصم النتيجة
+    fn penalty(&self) -> f32 {
+        match self {
+            IssueSeverity::Info => 0.02,
+            IssueSeverity::Warning => 0.05,
+            IssueSeverity::Error => 0.1,
+        }
+    }
+}
+
+/// ملاحظة على النص
+#[derive(Debug, Clone, Serialize)]
+pub struct TextIssue {
+    pub kind: IssueKind,
+    pub severity: IssueSeverity,
+    pub message: String,
+    pub excerpt: String,
+    pub word_index: Option<usize>,
+    pub suggestion: Option<String>,
+}
+
+/// تقرير جودة النص
+#[derive(Debug, Clone, Serialize)]
+pub struct TextQualityReport {
+    pub score: f32,
+    pub statistics: TextStatistics,
+    pub readability: ReadabilityIndex,
+    pub script: ScriptProfile,
+    pub issues: Vec<TextIssue>,
+    pub top_words: Vec<(String, usize)>,
+    pub feedback: Vec<String>,
+    pub suggestions: Vec<String>,
+    pub analyzed_at: DateTime<Utc>,
+}
+
+/// محلل النصوص
+#[derive(Debug, Clone)]
+pub struct TextAnalyzer {
+    /// عدد الكلمات الذي تعتبر بعده الجملة طويلة
+    pub long_sentence_words: usize,
+    /// عدد الحروف الذي تعتبر بعده الكلمة طويلة
+    pub long_word_letters: usize,
+    /// نسبة تكرار الكلمة التي تعتبر بعدها مفرطة
+    pub overuse_ratio: f32,
+    /// الحد الأدنى لعدد مرات التكرار المفرط
+    pub min_overuse_count: usize,
+}
+
+impl Default for TextAnalyzer {
+    fn default() -> Self {
+        Self {
+            long_sentence_words: 40,
+            long_word_letters: 7,
+            overuse_ratio: 0.04,
+            min_overuse_count: 4,
+        }
+    }
+}
+
+/// هل الحرف من علامات التشكيل؟
+pub fn is_diacritic(c: char) -> bool {
+    matches!(c, '\u{064B}'..='\u{065F}' | '\u{0670}' | '\u{06D6}'..='\u{06ED}')
+}
+
+/// هل الحرف عربي؟
+pub fn is_arabic_letter(c: char) -> bool {
+    matches!(c,
+        '\u{0621}'..='\u{063A}'
+        | '\u{0641}'..='\u{064A}'
+        | '\u{0671}'..='\u{06D3}'
+        | '\u{06D5}'
+        | '\u{06FA}'..='\u{06FF}'
+        | '\u{FB50}'..='\u{FDFF}'
+        | '\u{FE70}'..='\u{FEFC}')
+}
+
+/// هل الحرف لاتيني؟
+pub fn is_latin_letter(c: char) -> bool {
+    c.is_ascii_alphabetic() || matches!(c, '\u{00C0}'..='\u{024F}')
+}
+
+/// هل الرقم هندي (٠-٩)؟
+fn is_arabic_indic_digit(c: char) -> bool {
+    matches!(c, '\u{0660}'..='\u{0669}' | '\u{06F0}'..='\u{06F9}')
+}
+
+/// إزالة التشكيل والتطويل مع الإبقاء على الحروف كما هي
+pub fn strip_marks(text: &str) -> String {
+    text.chars()
+        .filter(|c| !is_diacritic(*c) && *c != TATWEEL)
+        .collect()
+}
+
+/// توحيد النص العربي: إزالة التشكيل والتطويل وتوحيد الألف والياء
+pub fn normalize_arabic(text: &str) -> String {
+    text.chars()
+        .filter(|c| !is_diacritic(*c) && *c != TATWEEL)
+        .map(|c| match c {
+            'أ' | 'إ' | 'آ' | 'ٱ' => 'ا',
+            'ى' => 'ي',
+            _ => c,
+        })
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// تحديد نظام الكتابة لكلمة
+pub fn detect_script(word: &str) -> Script {
+    let has_arabic = word.chars().any(is_arabic_letter);
+    let has_latin = word.chars().any(is_latin_letter);
+
+    match (has_arabic, has_latin) {
+        (true, true) => Script::Mixed,
+        (true, false) => Script::Arabic,
+        (false, true) => Script::Latin,
+        (false, false) => Script::Other,
+    }
+}
+
+/// تقطيع النص إلى كلمات
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for c in text.chars().chain(std::iter::once(' ')) {
+        if c.is_alphanumeric() || is_diacritic(c) {
+            current.push(c);
+        } else if !current.is_empty() {
+            let stripped = strip_marks(&current);
+            if !stripped.is_empty() {
+                let normalized = normalize_arabic(&stripped);
+                tokens.push(Token {
+                    script: detect_script(&stripped),
+                    letters: normalized.chars().filter(|c| c.is_alphabetic()).count(),
+                    text: std::mem::take(&mut current),
+                    stripped,
+                    normalized,
+                });
+            }
+            current.clear();
+        }
+    }
+
+    tokens
+}
+
+/// تقسيم النص إلى جمل
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+
+    for c in text.chars() {
+        if SENTENCE_TERMINATORS.contains(&c) {
+            if current.chars().any(|c| c.is_alphanumeric()) {
+                sentences.push(current.trim().to_string());
+            }
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+
+    if current.chars().any(|c| c.is_alphanumeric()) {
+        sentences.push(current.trim().to_string());
+    }
+
+    sentences
+}
+
+impl TextAnalyzer {
+    /// إنشاء محلل بالإعدادات الافتراضية
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// تحليل نص كامل
+    pub fn analyze(&self, text: &str) -> TextQualityReport {
+        let tokens = tokenize(text);
+        let sentences = split_sentences(text);
+        let statistics = self.compute_statistics(text, &tokens, &sentences);
+        let readability = self.compute_readability(&statistics);
+        let script = self.compute_script_profile(&tokens);
+
+        let mut issues = Vec::new();
+        self.check_repeated_words(&tokens, &mut issues);
+        self.check_overused_words(&tokens, &mut issues);
+        self.check_spelling(&tokens, &mut issues);
+        self.check_punctuation(text, &script, &mut issues);
+        self.check_scripts(text, &tokens, &mut issues);
+        self.check_sentences(&sentences, &mut issues);
+
+        if statistics.tatweel_count > 0 {
+            issues.push(TextIssue {
+                kind: IssueKind::Tatweel,
+                severity: IssueSeverity::Info,
+                message: format!("استخدام التطويل (ـ) {} مرة يعيق البحث والقراءة الآلية", statistics.tatweel_count),
+                excerpt: TATWEEL.to_string(),
+                word_index: None,
+                suggestion: Some("إزالة حرف التطويل".to_string()),
+            });
+        }
+
+        let score = self.compute_score(&statistics, &readability, &issues);
+        let top_words = self.top_words(&tokens, 10);
+        let feedback = self.generate_feedback(&statistics, &readability, &script, &issues);
+        let suggestions = self.generate_suggestions(&issues, &readability);
+
+        TextQualityReport {
+            score,
+            statistics,
+            readability,
+            script,
+            issues,
+            top_words,
+            feedback,
+            suggestions,
+            analyzed_at: Utc::now(),
+        }
+    }
+
+    /// حساب الإحصائيات
+    fn compute_statistics(&self, text: &str, tokens: &[Token], sentences: &[String]) -> TextStatistics {
+        let word_count = tokens.len();
+        let letter_count: usize = tokens.iter().map(|t| t.letters).sum();
+        let unique_words = tokens.iter()
+            .map(|t| t.normalized.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len();
+        let long_words = tokens.iter()
+            .filter(|t| t.letters >= self.long_word_letters)
+            .count();
+
+        let paragraph_count = text.split("\n\n")
+            .filter(|p| p.chars().any(|c| c.is_alphanumeric()))
+            .count();
+
+        let ratio = |a: usize, b: usize| if b > 0 { a as f32 / b as f32 } else { 0.0 };
+
+        TextStatistics {
+            char_count: text.chars().count(),
+            letter_count,
+            word_count,
+            unique_words,
+            sentence_count: sentences.len(),
+            paragraph_count,
+            avg_word_length: ratio(letter_count, word_count),
+            avg_sentence_length: ratio(word_count, sentences.len()),
+            lexical_diversity: ratio(unique_words, word_count),
+            long_word_ratio: ratio(long_words, word_count),
+            diacritics_count: text.chars().filter(|c| is_diacritic(*c)).count(),
+            tatweel_count: text.chars().filter(|c| *c == TATWEEL).count(),
+        }
+    }
+
+    /// مؤشر مقروئية تقريبي للعربية
+    /// يعتمد على متوسط طول الجملة ومتوسط طول الكلمة ونسبة الكلمات الطويلة
+    fn compute_readability(&self, stats: &TextStatistics) -> ReadabilityIndex {
+        let index = if stats.word_count == 0 {
+            0.0
+        } else {
+            (120.0
+                - 1.0 * stats.avg_sentence_length
+                - 8.0 * stats.avg_word_length
+                - 30.0 * stats.long_word_ratio)
+                .clamp(0.0, 100.0)
+        };
+
+        let level = if index >= 80.0 {
+            ReadabilityLevel::VeryEasy
+        } else if index >= 65.0 {
+            ReadabilityLevel::Easy
+        } else if index >= 50.0 {
+            ReadabilityLevel::Moderate
+        } else if index >= 35.0 {
+            ReadabilityLevel::Difficult
+        } else {
+            ReadabilityLevel::VeryDifficult
+        };
+
+        ReadabilityIndex {
+            index,
+            score: index / 100.0,
+            level,
+        }
+    }
+
+    /// حساب توزيع أنظمة الكتابة
+    fn compute_script_profile(&self, tokens: &[Token]) -> ScriptProfile {
+        let mut profile = ScriptProfile::default();
+
+        for token in tokens {
+            match token.script {
+                Script::Arabic => profile.arabic_words += 1,
+                Script::Latin => profile.latin_words += 1,
+                Script::Mixed => profile.mixed_words += 1,
+                Script::Other => profile.other_words += 1,
+            }
+        }
+
+        let letter_words = profile.arabic_words + profile.latin_words + profile.mixed_words;
+        if letter_words > 0 {
+            profile.arabic_ratio = profile.arabic_words as f32 / letter_words as f32;
+            profile.latin_ratio = profile.latin_words as f32 / letter_words as f32;
+            profile.dominant = Some(if profile.arabic_words >= profile.latin_words {
+                Script::Arabic
+            } else {
+                Script::Latin
+            });
+            profile.is_mixed = profile.mixed_words > 0
+                || (profile.arabic_ratio >= 0.1 && profile.latin_ratio >= 0.1);
+        }
+
+        profile
+    }
+
+    /// الكلمات المكررة المتتالية
+    fn check_repeated_words(&self, tokens: &[Token], issues: &mut Vec<TextIssue>) {
+        for (index, pair) in tokens.windows(2).enumerate() {
+            let (first, second) = (&pair[0], &pair[1]);
+            if first.script != Script::Other && first.normalized == second.normalized {
+                issues.push(TextIssue {
+                    kind: IssueKind::RepeatedWord,
+                    severity: IssueSeverity::Warning,
+                    message: format!("الكلمة \"{}\" مكررة مرتين متتاليتين", first.stripped),
+                    excerpt: format!("{} {}", first.text, second.text),
+                    word_index: Some(index + 1),
+                    suggestion: Some(format!("حذف التكرار والإبقاء على \"{}\"", first.stripped)),
+                });
+            }
+        }
+    }
+
+    /// الكلمات المستخدمة بإفراط
+    fn check_overused_words(&self, tokens: &[Token], issues: &mut Vec<TextIssue>) {
+        if tokens.is_empty() {
+            return;
+        }
+
+        let stopwords: Vec<String> = STOPWORDS.iter().map(|w| normalize_arabic(w)).collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for token in tokens {
+            if token.letters > 2 && !stopwords.contains(&token.normalized) {
+                *counts.entry(token.normalized.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        let mut overused: Vec<(&str, usize)> = counts.into_iter()
+            .filter(|(_, count)| {
+                *count >= self.min_overuse_count
+                    && *count as f32 / tokens.len() as f32 > self.overuse_ratio
+            })
+            .collect();
+        overused.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        for (word, count) in overused {
+            issues.push(TextIssue {
+                kind: IssueKind::OverusedWord,
+                severity: IssueSeverity::Info,
+                message: format!("الكلمة \"{}\" تكررت {} مرة", word, count),
+                excerpt: word.to_string(),
+                word_index: None,
+                suggestion: Some("استخدام مرادفات لتنويع الأسلوب".to_string()),
+            });
+        }
+    }
+
+    /// أنماط الأخطاء الإملائية الشائعة
+    fn check_spelling(&self, tokens: &[Token], issues: &mut Vec<TextIssue>) {
+        for (index, token) in tokens.iter().enumerate() {
+            if token.script != Script::Arabic {
+                continue;
+            }
+
+            let word = token.stripped.as_str();
+
+            if let Some((_, correct)) = COMMON_MISSPELLINGS.iter().find(|(wrong, _)| *wrong == word) {
+                issues.push(TextIssue {
+                    kind: IssueKind::Spelling,
+                    severity: IssueSeverity::Warning,
+                    message: format!("خطأ إملائي شائع: \"{}\"", word),
+                    excerpt: token.text.clone(),
+                    word_index: Some(index),
+                    suggestion: Some(correct.to_string()),
+                });
+                continue;
+            }
+
+            // مصدر الفعل السداسي (استفعال) يبدأ بهمزة وصل
+            if word.starts_with("إست") && word.chars().count() > 5 {
+                issues.push(TextIssue {
+                    kind: IssueKind::Spelling,
+                    severity: IssueSeverity::Warning,
+                    message: format!("همزة قطع في موضع همزة وصل: \"{}\"", word),
+                    excerpt: token.text.clone(),
+                    word_index: Some(index),
+                    suggestion: Some(word.replacen('إ', "ا", 1)),
+                });
+                continue;
+            }
+
+            // حروف ممطوطة مثل "جميييل"
+            let chars: Vec<char> = word.chars().collect();
+            if chars.windows(3).any(|w| w[0] == w[1] && w[1] == w[2]) {
+                // تقليص كل سلسلة من ثلاثة أحرف متطابقة فأكثر إلى حرف واحد
+                let mut collapsed: Vec<char> = Vec::with_capacity(chars.len());
+                let mut run_start = 0;
+                for i in 1..=chars.len() {
+                    if i == chars.len() || chars[i] != chars[run_start] {
+                        let run = i - run_start;
+                        let keep = if run >= 3 { 1 } else { run };
+                        collapsed.extend(std::iter::repeat_n(chars[run_start], keep));
+                        run_start = i;
+                    }
+                }
+                issues.push(TextIssue {
+                    kind: IssueKind::ElongatedWord,
+                    severity: IssueSeverity::Info,
+                    message: format!("كلمة ممطوطة: \"{}\"", word),
+                    excerpt: token.text.clone(),
+                    word_index: Some(index),
+                    suggestion: Some(collapsed.into_iter().collect()),
+                });
+            }
+        }
+    }
+
+    /// علامات الترقيم
+    fn check_punctuation(&self, text: &str, script: &ScriptProfile, issues: &mut Vec<TextIssue>) {
+        if script.dominant == Some(Script::Arabic) {
+            for (latin, arabic) in [(',', '،'), ('?', '؟'), (';', '؛')] {
+                let count = text.chars().filter(|c| *c == latin).count();
+                if count > 0 {
+                    issues.push(TextIssue {
+                        kind: IssueKind::Punctuation,
+                        severity: IssueSeverity::Info,
+                        message: format!("استخدام \"{}\" اللاتينية {} مرة في نص عربي", latin, count),
+                        excerpt: latin.to_string(),
+                        word_index: None,
+                        suggestion: Some(arabic.to_string()),
+                    });
+                }
+            }
+        }
+
+        let spaced = [" ،", " .", " ؟", " !", " ,", " :"].iter()
+            .map(|p| text.matches(p).count())
+            .sum::<usize>();
+        if spaced > 0 {
+            issues.push(TextIssue {
+                kind: IssueKind::Punctuation,
+                severity: IssueSeverity::Info,
+                message: format!("مسافة قبل علامة الترقيم في {} موضع", spaced),
+                excerpt: String::new(),
+                word_index: None,
+                suggestion: Some("إلصاق علامة الترقيم بالكلمة السابقة".to_string()),
+            });
+        }
+    }
+
+    /// خلط أنظمة الكتابة والأرقام
+    fn check_scripts(&self, text: &str, tokens: &[Token], issues: &mut Vec<TextIssue>) {
+        for (index, token) in tokens.iter().enumerate() {
+            if token.script == Script::Mixed {
+                issues.push(TextIssue {
+                    kind: IssueKind::MixedScript,
+                    severity: IssueSeverity::Warning,
+                    message: format!("كلمة تخلط الحروف العربية واللاتينية: \"{}\"", token.stripped),
+                    excerpt: token.text.clone(),
+                    word_index: Some(index),
+                    suggestion: Some("فصل الجزء اللاتيني عن العربي بمسافة".to_string()),
+                });
+            }
+        }
+
+        let has_western = text.chars().any(|c| c.is_ascii_digit());
+        let has_indic = text.chars().any(is_arabic_indic_digit);
+        if has_western && has_indic {
+            issues.push(TextIssue {
+                kind: IssueKind::MixedDigits,
+                severity: IssueSeverity::Info,
+                message: "النص يخلط الأرقام العربية (0-9) والهندية (٠-٩)".to_string(),
+                excerpt: String::new(),
+                word_index: None,
+                suggestion: Some("توحيد نوع الأرقام في النص".to_string()),
+            });
+        }
+    }
+
+    /// الجمل الطويلة
+    fn check_sentences(&self, sentences: &[String], issues: &mut Vec<TextIssue>) {
+        for sentence in sentences {
+            let words = tokenize(sentence).len();
+            if words > self.long_sentence_words {
+                let excerpt: String = sentence.chars().take(60).collect();
+                issues.push(TextIssue {
+                    kind: IssueKind::LongSentence,
+                    severity: IssueSeverity::Warning,
+                    message: format!("جملة طويلة من {} كلمة", words),
+                    excerpt,
+                    word_index: None,
+                    suggestion: Some("تقسيم الجملة إلى جمل أقصر".to_string()),
+                });
+            }
+        }
+    }
+
+    /// حساب النتيجة النهائية
+    fn compute_score(&self, stats: &TextStatistics, readability: &ReadabilityIndex, issues: &[TextIssue]) -> f32 {
+        if stats.word_count == 0 {
+            return 0.0;
+        }
+
+        // الخصم يقاس بالنسبة لكل 100 كلمة حتى لا تُظلم النصوص الطويلة
+        let total_penalty: f32 = issues.iter().map(|i| i.severity.penalty()).sum();
+        let density = total_penalty / (stats.word_count as f32 / 100.0).max(1.0);
+        let correctness = (1.0 - density).max(0.0);
+
+        (0.5 * readability.score + 0.5 * correctness).clamp(0.0, 1.0)
+    }
+
+    /// الكلمات الأكثر تكراراً
+    fn top_words(&self, tokens: &[Token], limit: usize) -> Vec<(String, usize)> {
+        let stopwords: Vec<String> = STOPWORDS.iter().map(|w| normalize_arabic(w)).collect();
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for token in tokens {
+            if token.letters > 2 && !stopwords.contains(&token.normalized) {
+                *counts.entry(token.normalized.as_str()).or_insert(0) += 1;
+            }
+        }
+
+        let mut words: Vec<(String, usize)> = counts.into_iter()
+            .map(|(word, count)| (word.to_string(), count))
+            .collect();
+        words.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        words.truncate(limit);
+        words
+    }
+
+    /// توليد الملاحظات
+    fn generate_feedback(
+        &self,
+        stats: &TextStatistics,
+        readability: &ReadabilityIndex,
+        script: &ScriptProfile,
+        issues: &[TextIssue],
+    ) -> Vec<String> {
+        let mut feedback = Vec::new();
+
+        if stats.word_count == 0 {
+            feedback.push("النص فارغ".to_string());
+            return feedback;
+        }
+
+        feedback.push(format!("المقروئية: {} ({:.0}/100)", readability.level.display_name(), readability.index));
+        feedback.push(format!(
+            "{} كلمة في {} جملة بمتوسط {:.1} كلمة للجملة",
+            stats.word_count, stats.sentence_count, stats.avg_sentence_length
+        ));
+
+        if stats.lexical_diversity < 0.4 && stats.word_count > 50 {
+            feedback.push("تنوع المفردات منخفض".to_string());
+        }
+
+        if script.is_mixed {
+            feedback.push(format!(
+                "النص مختلط: {:.0}% عربي و{:.0}% لاتيني",
+                script.arabic_ratio * 100.0,
+                script.latin_ratio * 100.0
+            ));
+        }
+
+        let errors = issues.iter().filter(|i| i.severity != IssueSeverity::Info).count();
+        if errors == 0 {
+            feedback.push("لا توجد أخطاء لغوية ظاهرة".to_string());
+        } else {
+            feedback.push(format!("تم رصد {} ملاحظة تحتاج مراجعة", errors));
+        }
+
+        feedback
+    }
+
+    /// توليد الاقتراحات
+    fn generate_suggestions(&self, issues: &[TextIssue], readability: &ReadabilityIndex) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        let mut seen = Vec::new();
+
+        for issue in issues {
+            if seen.contains(&issue.kind) {
+                continue;
+            }
+            seen.push(issue.kind);
+
+            let suggestion = match issue.kind {
+                IssueKind::RepeatedWord => "حذف الكلمات المكررة المتتالية",
+                IssueKind::OverusedWord => "تنويع المفردات واستخدام المرادفات",
+                IssueKind::Spelling => "مراجعة الأخطاء الإملائية الشائعة والهمزات",
+                IssueKind::Punctuation => "استخدام علامات الترقيم العربية (، ؛ ؟)",
+                IssueKind::MixedScript => "الفصل بين الكلمات العربية واللاتينية",
+                IssueKind::MixedDigits => "توحيد نوع الأرقام",
+                IssueKind::ElongatedWord => "تجنب مط الحروف",
+                IssueKind::Tatweel => "إزالة التطويل من النص",
+                IssueKind::LongSentence => "تقسيم الجمل الطويلة",
+            };
+            suggestions.push(suggestion.to_string());
+        }
+
+        if matches!(readability.level, ReadabilityLevel::Difficult | ReadabilityLevel::VeryDifficult) {
+            suggestions.push("تبسيط الصياغة واستخدام كلمات أقصر".to_string());
+        }
+
+        suggestions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_arabic() {
+        assert_eq!(normalize_arabic("إِلَى"), "الي");
+        assert_eq!(normalize_arabic("أحـــمد"), "احمد");
+        assert_eq!(normalize_arabic("آمنة"), "امنة");
+    }
+
+    #[test]
+    fn test_tokenize_and_scripts() {
+        let tokens = tokenize("مرحباً بكم في Rust وRust!");
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[0].normalized, "مرحبا");
+        assert_eq!(tokens[3].script, Script::Latin);
+        assert_eq!(tokens[4].script, Script::Mixed);
+    }
+
+    #[test]
+    fn test_repeated_and_spelling() {
+        let report = TextAnalyzer::new().analyze("ذهبت الى المدرسة المدرسة. هاذا جميل.");
+        assert!(report.issues.iter().any(|i| i.kind == IssueKind::RepeatedWord));
+        assert!(report.issues.iter().any(|i| i.kind == IssueKind::Spelling
+            && i.suggestion.as_deref() == Some("إلى")));
+        assert_eq!(report.statistics.sentence_count, 2);
+    }
+
+    #[test]
+    fn test_readability_prefers_short_sentences() {
+        let analyzer = TextAnalyzer::new();
+        let simple = analyzer.analyze("ذهب الولد. لعب الولد. عاد الولد.");
+        let complex = analyzer.analyze(
+            "واستناداً إلى المعطيات الاستراتيجية المتشعبة والمستخلصة من الدراسات الاستقصائية المتخصصة فإن المؤسسات التعليمية المستقبلية ملزمة بإعادة الهيكلة التنظيمية الشاملة"
+        );
+        assert!(simple.readability.index > complex.readability.index);
+    }
+
+    #[test]
+    fn test_empty_text() {
+        let report = TextAnalyzer::new().analyze("   ");
+        assert_eq!(report.score, 0.0);
+        assert_eq!(report.statistics.word_count, 0);
+    }
+}
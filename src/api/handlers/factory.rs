@@ -1,7 +1,14 @@
 use actix_web::{web, HttpResponse};
+use futures::stream::{self, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use crate::core::factory::FactoryError;
 use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::QualityManager;
+use crate::core::quality_history::ReportQuery;
+use crate::core::telemetry::in_current_context;
+use crate::core::tuning::knobs;
+use crate::mhos::live::{LiveEvent, LiveHub};
 use crate::api::ApiResponse;
 
 /// طلب إنشاء مصنع جديد
@@ -100,67 +107,162 @@ pub async fn manufacture(
     factory_type: web::Path<String>,
     req: web::Json<ManufactureRequest>
 ) -> HttpResponse {
-    match factory_manager.manufacture(
-        &factory_type,
-        req.input.clone(),
-        req.parameters.clone().unwrap_or_default()
-    ) {
-        Ok(product) => {
+    // التوليد قد يحجب حتى مهلة الخلفية مع إعادة المحاولة، فلا يشغل عامل actix
+    let factory_manager = factory_manager.get_ref().clone();
+    let factory_type = factory_type.into_inner();
+    let req = req.into_inner();
+    let outcome = web::block(in_current_context(move || {
+        factory_manager.manufacture(&factory_type, req.input, req.parameters.unwrap_or_default())
+    })).await;
+    
+    match outcome {
+        Ok(Ok(product)) => {
             HttpResponse::Ok().json(ApiResponse::success(product, "تم التصنيع بنجاح"))
         }
-        Err(err) => HttpResponse::BadRequest().json(ApiResponse::error(&err.to_string()))
+        Ok(Err(err)) => manufacture_error(&err),
+        Err(err) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&err.to_string())),
     }
 }
 
-/// تصنيع دفعة
+/// تحويل خطأ التصنيع إلى استجابة: أخطاء الخلفية 502/504 وحالة المصنع 409/503
+fn manufacture_error(err: &FactoryError) -> HttpResponse {
+    match err {
+        FactoryError::Timeout => HttpResponse::GatewayTimeout(),
+        FactoryError::ResourceUnavailable(_) | FactoryError::ProcessingFailed(_) => HttpResponse::BadGateway(),
+        FactoryError::InvalidRequest(_) | FactoryError::ValidationFailed(_) => HttpResponse::BadRequest(),
+        FactoryError::Paused(_) => HttpResponse::Conflict(),
+        FactoryError::Busy(_) => HttpResponse::ServiceUnavailable(),
+        FactoryError::InitializationFailed(_) | FactoryError::Unknown(_) => HttpResponse::InternalServerError(),
+    }.json(ApiResponse::<()>::error(&err.to_string()))
+}
+
+/// معاملات التصنيع بالدفعات
+#[derive(Debug, Deserialize)]
+pub struct BatchQuery {
+    pub factory_type: Option<String>,
+}
+
+/// تصنيع دفعة بعدد عمال متوازين حسب مقبض workers.pool_size
 pub async fn batch_manufacture(
     factory_manager: web::Data<Arc<FactoryManager>>,
+    query: web::Query<BatchQuery>,
     req: web::Json<Vec<ManufactureRequest>>
 ) -> HttpResponse {
-    let mut results = Vec::new();
+    let workers = knobs().worker_pool_size().max(1);
+    let default_type = query.into_inner().factory_type;
+    let factory_manager = factory_manager.get_ref().clone();
     
-    for item in req.into_inner() {
-        // هنا سيتم معالجة كل عنصر في الدفعة
-        results.push(serde_json::json!({
-            "input": item.input,
-            "processed": true
-        }));
-    }
+    let results: Vec<serde_json::Value> = stream::iter(req.into_inner())
+        .map(|item| {
+            let factory_manager = factory_manager.clone();
+            let factory_type = item.parameters.as_ref()
+                .and_then(|p| p.get("factory_type"))
+                .and_then(|t| t.as_str())
+                .map(|t| t.to_string())
+                .or_else(|| default_type.clone());
+            
+            async move {
+                let factory_type = match factory_type {
+                    Some(factory_type) => factory_type,
+                    None => return serde_json::json!({ "input": item.input, "error": "نوع المصنع غير محدد" }),
+                };
+                
+                let input = item.input.clone();
+                let parameters = item.parameters.unwrap_or_default();
+                let outcome = tokio::task::spawn_blocking(in_current_context(move || factory_manager.manufacture(&factory_type, input, parameters)))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|result| result.map_err(|e| e.to_string()));
+                
+                match outcome {
+                    Ok(product) => serde_json::json!({ "input": item.input, "product": product }),
+                    Err(e) => serde_json::json!({ "input": item.input, "error": e }),
+                }
+            }
+        })
+        .buffered(workers)
+        .collect()
+        .await;
     
     HttpResponse::Ok().json(ApiResponse::success(results, "تم معالجة الدفعة بنجاح"))
 }
 
+/// معاملات فحص الجودة
+#[derive(Debug, Deserialize)]
+pub struct QualityCheckQuery {
+    pub factory: Option<String>,
+    pub entity_type: Option<String>,
+}
+
 /// فحص الجودة
 pub async fn quality_check(
     factory_manager: web::Data<Arc<FactoryManager>>,
-    product_id: web::Path<String>
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    live: web::Data<Arc<LiveHub>>,
+    product_id: web::Path<String>,
+    query: web::Query<QualityCheckQuery>
 ) -> HttpResponse {
-    // محاكاة فحص الجودة
-    let quality_report = serde_json::json!({
-        "product_id": product_id.into_inner(),
-        "quality_score": 0.95,
-        "defects": [],
-        "passed": true,
-        "timestamp": chrono::Utc::now().to_rfc3339()
+    let query = query.into_inner();
+    let entity_type = query.entity_type.unwrap_or_else(|| "product".to_string());
+    let data = serde_json::json!({ "factory": query.factory });
+    
+    let mut manager = match quality_manager.write() {
+        Ok(manager) => manager,
+        Err(_) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر الوصول إلى مدير الجودة")),
+    };
+    
+    let report = manager.run_quality_check(&product_id, &entity_type, data);
+    let regressions = manager.save_report(report.clone());
+    live.publish(LiveEvent::QualityReport { report: Box::new(report.clone()) });
+    
+    for regression in &regressions {
+        log::warn!("📉 تراجع في الجودة للمصنع {}: {} ({:.2} ← {:.2})",
+            regression.factory, regression.gate_name, regression.baseline, regression.current);
+    }
+    
+    let result = serde_json::json!({
+        "report": report,
+        "regressions": regressions,
     });
     
-    HttpResponse::Ok().json(ApiResponse::success(quality_report, "تم فحص الجودة"))
+    HttpResponse::Ok().json(ApiResponse::success(result, "تم فحص الجودة"))
 }
 
-/// تقرير الجودة
+/// تقرير الجودة: سجل التقارير مع الاتجاهات والتراجعات
 pub async fn quality_report(
-    factory_manager: web::Data<Arc<FactoryManager>>
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    query: web::Query<ReportQuery>
 ) -> HttpResponse {
+    let query = query.into_inner();
+    let manager = match quality_manager.read() {
+        Ok(manager) => manager,
+        Err(_) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر الوصول إلى مدير الجودة")),
+    };
+    
+    let history = manager.history();
+    let reports = history.query(&query);
+    let passed = reports.iter().filter(|r| r.passed).count();
+    let average_score = if reports.is_empty() {
+        0.0
+    } else {
+        reports.iter().map(|r| r.overall_score).sum::<f64>() / reports.len() as f64
+    };
+    
     let report = serde_json::json!({
-        "total_products": 1000,
-        "passed": 980,
-        "failed": 20,
-        "quality_rate": 0.98,
-        "top_defects": ["misalignment", "color_variation", "size_mismatch"],
+        "summary": {
+            "total_reports": reports.len(),
+            "passed": passed,
+            "failed": reports.len() - passed,
+            "average_score": average_score,
+        },
+        "reports": reports,
+        "trends": history.trends(&query),
+        "regressions": history.regressions(query.factory.as_deref()),
         "timestamp": chrono::Utc::now().to_rfc3339()
     });
     
-    HttpResponse::Ok().json(ApiResponse::success(report, "تقرير الجولة"))
+    HttpResponse::Ok().json(ApiResponse::success(report, "تقرير الجودة"))
 }
 
 /// تحديث مصنع
@@ -209,3 +311,62 @@ pub async fn production_status(
     
     HttpResponse::Ok().json(ApiResponse::success(status, "حالة الإنتاج الحالية"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+    use crate::core::generation::{Completion, ContentGenerator, GenerationRequest, GenerationService};
+
+    /// خلفية تعيد الخطأ نفسه دائماً
+    struct FailingBackend(FactoryError);
+
+    impl ContentGenerator for FailingBackend {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn model(&self) -> &str {
+            "none"
+        }
+
+        fn generate(&self, _request: &GenerationRequest) -> Result<Completion, FactoryError> {
+            Err(self.0.clone())
+        }
+    }
+
+    async fn manufacture_status(manager: FactoryManager) -> u16 {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(Arc::new(manager)))
+                .route("/factories/{factory_type}/manufacture", web::post().to(manufacture))
+        ).await;
+        let req = test::TestRequest::post()
+            .uri("/factories/education/manufacture")
+            .set_json(serde_json::json!({ "input": "الكسور" }))
+            .to_request();
+        test::call_service(&app, req).await.status().as_u16()
+    }
+
+    fn failing(err: FactoryError) -> FactoryManager {
+        let service = GenerationService::new(Box::new(FailingBackend(err))).with_retry(1, std::time::Duration::ZERO);
+        FactoryManager::new().with_generator(Arc::new(service))
+    }
+
+    #[actix_web::test]
+    async fn test_manufacture_maps_generation_errors() {
+        assert_eq!(manufacture_status(failing(FactoryError::Timeout)).await, 504);
+        assert_eq!(manufacture_status(failing(FactoryError::ResourceUnavailable("503".to_string()))).await, 502);
+        assert_eq!(manufacture_status(failing(FactoryError::InvalidRequest("x".to_string()))).await, 400);
+    }
+
+    #[actix_web::test]
+    async fn test_manufacture_success_and_paused() {
+        let manager = FactoryManager::new().with_generator(Arc::new(GenerationService::offline()));
+        assert_eq!(manufacture_status(manager).await, 200);
+
+        let manager = FactoryManager::new().with_generator(Arc::new(GenerationService::offline()));
+        manager.pause_factory("education").unwrap();
+        assert_eq!(manufacture_status(manager).await, 409);
+    }
+}
@@ -0,0 +1,95 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use std::sync::Arc;
+use crate::api::ApiResponse;
+use crate::mhos::scheduler::{JobDraft, Scheduler, SchedulerError};
+
+/// معاملات سجل التشغيل
+#[derive(Debug, Deserialize)]
+pub struct JobRunsQuery {
+    pub job_id: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// تحويل خطأ المجدول إلى استجابة
+fn scheduler_error(e: SchedulerError) -> HttpResponse {
+    match e {
+        SchedulerError::NotFound(_) => HttpResponse::NotFound(),
+        SchedulerError::Invalid(_) => HttpResponse::BadRequest(),
+        SchedulerError::AlreadyRunning(_) => HttpResponse::Conflict(),
+        SchedulerError::Io(_) => HttpResponse::InternalServerError(),
+    }.json(ApiResponse::<()>::error(&e.to_string()))
+}
+
+/// المهام المجدولة مع حالة تشغيلها
+pub async fn list_jobs(scheduler: web::Data<Arc<Scheduler>>) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse::success(scheduler.list(), "المهام المجدولة"))
+}
+
+/// إضافة مهمة
+pub async fn create_job(
+    scheduler: web::Data<Arc<Scheduler>>,
+    body: web::Json<JobDraft>
+) -> HttpResponse {
+    match scheduler.add(body.into_inner()) {
+        Ok(job) => HttpResponse::Created().json(ApiResponse::success(job, "تمت إضافة المهمة")),
+        Err(e) => scheduler_error(e),
+    }
+}
+
+/// حذف مهمة
+pub async fn delete_job(
+    scheduler: web::Data<Arc<Scheduler>>,
+    job_id: web::Path<String>
+) -> HttpResponse {
+    match scheduler.remove(&job_id) {
+        Ok(job) => HttpResponse::Ok().json(ApiResponse::success(job, "تم حذف المهمة")),
+        Err(e) => scheduler_error(e),
+    }
+}
+
+/// إيقاف مهمة مؤقتاً
+pub async fn pause_job(
+    scheduler: web::Data<Arc<Scheduler>>,
+    job_id: web::Path<String>
+) -> HttpResponse {
+    match scheduler.set_paused(&job_id, true) {
+        Ok(job) => HttpResponse::Ok().json(ApiResponse::success(job, "تم إيقاف المهمة")),
+        Err(e) => scheduler_error(e),
+    }
+}
+
+/// استئناف مهمة
+pub async fn resume_job(
+    scheduler: web::Data<Arc<Scheduler>>,
+    job_id: web::Path<String>
+) -> HttpResponse {
+    match scheduler.set_paused(&job_id, false) {
+        Ok(job) => HttpResponse::Ok().json(ApiResponse::success(job, "تم استئناف المهمة")),
+        Err(e) => scheduler_error(e),
+    }
+}
+
+/// تشغيل مهمة يدوياً في الخلفية
+pub async fn trigger_job(
+    scheduler: web::Data<Arc<Scheduler>>,
+    job_id: web::Path<String>
+) -> HttpResponse {
+    match scheduler.trigger(&job_id) {
+        Ok(run_id) => HttpResponse::Accepted().json(ApiResponse::success(
+            serde_json::json!({ "job_id": job_id.as_str(), "run_id": run_id }),
+            "بدأ تشغيل المهمة"
+        )),
+        Err(e) => scheduler_error(e),
+    }
+}
+
+/// سجل تشغيل المهام (الأحدث أولاً)
+pub async fn job_runs(
+    scheduler: web::Data<Arc<Scheduler>>,
+    query: web::Query<JobRunsQuery>
+) -> HttpResponse {
+    let limit = query.limit.unwrap_or(50).clamp(1, 500);
+    let runs = scheduler.runs(query.job_id.as_deref(), limit);
+    HttpResponse::Ok().json(ApiResponse::success(runs, "سجل تشغيل المهام"))
+}
@@ -0,0 +1,110 @@
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use std::sync::{Arc, RwLock};
+use crate::api::ApiResponse;
+use crate::mhos::knowledge::{KnowledgeBase, KnowledgeDraft, KnowledgeError};
+use crate::mhos::MhosSystem;
+
+/// معاملات البحث في قاعدة المعرفة
+#[derive(Debug, Deserialize)]
+pub struct KnowledgeQuery {
+    /// نص البحث (بدونه تُسرد كل المداخل)
+    pub q: Option<String>,
+    pub factory_type: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// تنفيذ عملية على قاعدة المعرفة بعد مزامنتها مع الملف
+fn with_knowledge<T>(
+    store: &RwLock<KnowledgeBase>,
+    f: impl FnOnce(&mut KnowledgeBase) -> Result<T, KnowledgeError>
+) -> Result<T, HttpResponse> {
+    let mut store = store.write()
+        .map_err(|_| HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر الوصول إلى قاعدة المعرفة")))?;
+    if let Err(e) = store.reload_if_changed() {
+        log::error!("❌ تعذر إعادة تحميل قاعدة المعرفة: {}", e);
+    }
+
+    f(&mut store).map_err(|e| {
+        match e {
+            KnowledgeError::NotFound(_) => HttpResponse::NotFound(),
+            KnowledgeError::Invalid(_) => HttpResponse::BadRequest(),
+            KnowledgeError::Io(_) => HttpResponse::InternalServerError(),
+        }.json(ApiResponse::<()>::error(&e.to_string()))
+    })
+}
+
+/// البحث في قاعدة المعرفة أو سرد مداخلها
+pub async fn knowledge_search(
+    store: web::Data<Arc<RwLock<KnowledgeBase>>>,
+    mhos: web::Data<Arc<MhosSystem>>,
+    query: web::Query<KnowledgeQuery>
+) -> HttpResponse {
+    let factory_type = query.factory_type.as_deref();
+
+    match query.q.as_deref().filter(|q| !q.trim().is_empty()) {
+        Some(_) if !mhos.assistant_enabled() => {
+            HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error("المساعد الذكي معطل في تكوين MH-OS"))
+        }
+        Some(q) => {
+            let limit = query.limit.unwrap_or(10).clamp(1, 100);
+            match with_knowledge(&store, |base| Ok(base.search(q, factory_type, limit))) {
+                Ok(hits) => HttpResponse::Ok().json(ApiResponse::success(hits, "نتائج البحث")),
+                Err(response) => response,
+            }
+        }
+        None => match with_knowledge(&store, |base| Ok(base.list(factory_type))) {
+            Ok(entries) => HttpResponse::Ok().json(ApiResponse::success(entries, "مداخل قاعدة المعرفة")),
+            Err(response) => response,
+        },
+    }
+}
+
+/// مدخل واحد
+pub async fn knowledge_entry(
+    store: web::Data<Arc<RwLock<KnowledgeBase>>>,
+    entry_id: web::Path<String>
+) -> HttpResponse {
+    let result = with_knowledge(&store, |base| {
+        base.get(&entry_id).cloned().ok_or_else(|| KnowledgeError::NotFound(entry_id.to_string()))
+    });
+
+    match result {
+        Ok(entry) => HttpResponse::Ok().json(ApiResponse::success(entry, "مدخل قاعدة المعرفة")),
+        Err(response) => response,
+    }
+}
+
+/// إضافة مدخل
+pub async fn create_knowledge_entry(
+    store: web::Data<Arc<RwLock<KnowledgeBase>>>,
+    body: web::Json<KnowledgeDraft>
+) -> HttpResponse {
+    match with_knowledge(&store, |base| base.add(body.into_inner())) {
+        Ok(entry) => HttpResponse::Created().json(ApiResponse::success(entry, "تمت إضافة المدخل")),
+        Err(response) => response,
+    }
+}
+
+/// تعديل مدخل
+pub async fn update_knowledge_entry(
+    store: web::Data<Arc<RwLock<KnowledgeBase>>>,
+    entry_id: web::Path<String>,
+    body: web::Json<KnowledgeDraft>
+) -> HttpResponse {
+    match with_knowledge(&store, |base| base.update(&entry_id, body.into_inner())) {
+        Ok(entry) => HttpResponse::Ok().json(ApiResponse::success(entry, "تم تعديل المدخل")),
+        Err(response) => response,
+    }
+}
+
+/// حذف مدخل
+pub async fn delete_knowledge_entry(
+    store: web::Data<Arc<RwLock<KnowledgeBase>>>,
+    entry_id: web::Path<String>
+) -> HttpResponse {
+    match with_knowledge(&store, |base| base.remove(&entry_id)) {
+        Ok(entry) => HttpResponse::Ok().json(ApiResponse::success(entry, "تم حذف المدخل")),
+        Err(response) => response,
+    }
+}
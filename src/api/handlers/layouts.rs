@@ -0,0 +1,225 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::Deserialize;
+use std::sync::{Arc, RwLock};
+use crate::api::middleware::AuthMiddleware;
+use crate::api::ApiResponse;
+use crate::core::factory_manager::FactoryManager;
+use crate::mhos::dashboard::{Dashboard, DashboardProfile, DashboardWidget, LayoutError, WidgetType};
+use crate::mhos::layouts::LayoutStore;
+use crate::mhos::widgets::widget_registry;
+
+/// طلب إضافة عنصر إلى لوحة المستخدم
+#[derive(Debug, Deserialize)]
+pub struct AddWidgetRequest {
+    pub id: String,
+    pub widget_type: WidgetType,
+    pub title: String,
+    pub col: u32,
+    pub row: u32,
+    pub width: u32,
+    pub height: u32,
+    /// الافتراضي فترة تحديث مزود النوع
+    pub refresh_interval: Option<u64>,
+}
+
+/// طلب نقل عنصر
+#[derive(Debug, Deserialize)]
+pub struct MoveWidgetRequest {
+    pub col: u32,
+    pub row: u32,
+}
+
+/// طلب تغيير أبعاد عنصر
+#[derive(Debug, Deserialize)]
+pub struct ResizeWidgetRequest {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// المستخدم صاحب الطلب (`sub` في توكن JWT)
+fn request_user(req: &HttpRequest) -> Result<String, HttpResponse> {
+    AuthMiddleware::request_claims(req)
+        .map(|claims| claims.sub)
+        .ok_or_else(|| HttpResponse::Unauthorized().json(ApiResponse::<()>::error("مصادقة مطلوبة")))
+}
+
+/// تعديل لوحة المستخدم وحفظ تخطيطها عند نجاح التعديل
+fn update_layout(
+    req: &HttpRequest,
+    store: &RwLock<LayoutStore>,
+    factory_manager: &Arc<FactoryManager>,
+    message: &str,
+    change: impl FnOnce(&mut Dashboard) -> Result<(), LayoutError>
+) -> HttpResponse {
+    let user = match request_user(req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+    let mut store = match store.write() {
+        Ok(store) => store,
+        Err(_) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر الوصول إلى مخزن التخطيطات")),
+    };
+    
+    let mut dashboard = match store.dashboard_for(&user, factory_manager.clone()) {
+        Ok(dashboard) => dashboard,
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    };
+    if let Err(e) = change(&mut dashboard) {
+        let response = match e {
+            LayoutError::UnknownWidget(_) => HttpResponse::NotFound(),
+            LayoutError::Overlap { .. } | LayoutError::DuplicateWidget(_) => HttpResponse::Conflict(),
+            _ => HttpResponse::BadRequest(),
+        }.json(ApiResponse::<()>::error(&e.to_string()));
+        return response;
+    }
+    
+    let profile = dashboard.profile();
+    match store.set(&user, profile.clone()) {
+        Ok(()) => HttpResponse::Ok().json(ApiResponse::success(profile, message)),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    }
+}
+
+/// تخطيط لوحة المستخدم
+pub async fn get_layout(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>
+) -> HttpResponse {
+    let user = match request_user(&req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+    let store = match store.read() {
+        Ok(store) => store,
+        Err(_) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر الوصول إلى مخزن التخطيطات")),
+    };
+    
+    match store.dashboard_for(&user, factory_manager.get_ref().clone()) {
+        Ok(dashboard) => HttpResponse::Ok().json(ApiResponse::success(dashboard.profile(), "تخطيط لوحة التحكم")),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    }
+}
+
+/// إضافة عنصر
+pub async fn add_layout_widget(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    body: web::Json<AddWidgetRequest>
+) -> HttpResponse {
+    let body = body.into_inner();
+    let refresh_interval = body.refresh_interval
+        .or_else(|| widget_registry().get(body.widget_type.provider_name()).map(|p| p.refresh_interval()))
+        .unwrap_or(60);
+    let widget = DashboardWidget {
+        id: body.id,
+        widget_type: body.widget_type,
+        title: body.title,
+        data: serde_json::Value::Null,
+        position: (body.col, body.row),
+        size: (body.width, body.height),
+        refresh_interval,
+    };
+    
+    update_layout(&req, &store, &factory_manager, "تمت إضافة العنصر", |dashboard| dashboard.place_widget(widget))
+}
+
+/// حذف عنصر
+pub async fn remove_layout_widget(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    widget_id: web::Path<String>
+) -> HttpResponse {
+    update_layout(&req, &store, &factory_manager, "تم حذف العنصر", |dashboard| {
+        dashboard.remove_widget(&widget_id).map(|_| ())
+    })
+}
+
+/// نقل عنصر
+pub async fn move_layout_widget(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    widget_id: web::Path<String>,
+    body: web::Json<MoveWidgetRequest>
+) -> HttpResponse {
+    update_layout(&req, &store, &factory_manager, "تم نقل العنصر", |dashboard| {
+        dashboard.move_widget(&widget_id, body.col, body.row)
+    })
+}
+
+/// تغيير أبعاد عنصر
+pub async fn resize_layout_widget(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    widget_id: web::Path<String>,
+    body: web::Json<ResizeWidgetRequest>
+) -> HttpResponse {
+    update_layout(&req, &store, &factory_manager, "تم تغيير أبعاد العنصر", |dashboard| {
+        dashboard.resize_widget(&widget_id, body.width, body.height)
+    })
+}
+
+/// تصدير تخطيط المستخدم كملف JSON
+pub async fn export_layout(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>
+) -> HttpResponse {
+    let user = match request_user(&req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+    let store = match store.read() {
+        Ok(store) => store,
+        Err(_) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر الوصول إلى مخزن التخطيطات")),
+    };
+    
+    match store.dashboard_for(&user, factory_manager.get_ref().clone()) {
+        Ok(dashboard) => HttpResponse::Ok()
+            .insert_header(("Content-Disposition", "attachment; filename=\"dashboard-layout.json\""))
+            .json(dashboard.profile()),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    }
+}
+
+/// استيراد تخطيط (يحل محل تخطيط المستخدم بعد التحقق منه)
+pub async fn import_layout(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    body: web::Json<DashboardProfile>
+) -> HttpResponse {
+    let profile = body.into_inner();
+    update_layout(&req, &store, &factory_manager, "تم استيراد التخطيط", |dashboard| {
+        *dashboard = Dashboard::from_profile(factory_manager.get_ref().clone(), &profile)?;
+        Ok(())
+    })
+}
+
+/// العودة إلى التخطيط الافتراضي
+pub async fn reset_layout(
+    req: HttpRequest,
+    store: web::Data<Arc<RwLock<LayoutStore>>>,
+    factory_manager: web::Data<Arc<FactoryManager>>
+) -> HttpResponse {
+    let user = match request_user(&req) {
+        Ok(user) => user,
+        Err(response) => return response,
+    };
+    let mut store = match store.write() {
+        Ok(store) => store,
+        Err(_) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر الوصول إلى مخزن التخطيطات")),
+    };
+    
+    match store.remove(&user) {
+        Ok(_) => {
+            let profile = Dashboard::new(factory_manager.get_ref().clone()).profile();
+            HttpResponse::Ok().json(ApiResponse::success(profile, "تمت إعادة التخطيط الافتراضي"))
+        }
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    }
+}
@@ -2,6 +2,7 @@ use actix_web::{web, HttpResponse};
 use std::sync::Arc;
 use crate::core::factory_manager::FactoryManager;
 use crate::api::ApiResponse;
+use crate::analysis::{AccessibilityChecker, SecurityScanner, SeoAnalyzer, TextAnalyzer};
 use serde::{Deserialize, Serialize};
 
 /// طلب توليد المحتوى
@@ -73,23 +74,97 @@ pub async fn analyze_content(
     factory_manager: web::Data<Arc<FactoryManager>>,
     req: web::Json<AnalyzeRequest>
 ) -> HttpResponse {
+    if req.content.trim().is_empty() {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("المحتوى فارغ"));
+    }
+
+    if req.analysis_type == "seo" {
+        let keywords: Vec<String> = req.parameters.as_ref()
+            .and_then(|p| p.get("keywords"))
+            .and_then(|k| k.as_array())
+            .map(|k| k.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+        let report = SeoAnalyzer::new().with_keywords(keywords).analyze(&req.content);
+
+        let analysis_result = AnalysisResult {
+            score: report.score,
+            feedback: report.findings.iter()
+                .filter(|f| f.score >= 1.0)
+                .map(|f| f.message.clone())
+                .collect(),
+            suggestions: report.suggestions.clone(),
+            metadata: serde_json::json!({
+                "analysis_type": req.analysis_type,
+                "format": report.format,
+                "title": report.title,
+                "meta_description": report.meta_description,
+                "word_count": report.word_count,
+                "keywords": report.keywords,
+                "findings": report.findings
+            })
+        };
+
+        return HttpResponse::Ok().json(ApiResponse::success(analysis_result, "تم تحليل SEO"));
+    }
+
+    if req.analysis_type == "accessibility" {
+        let report = AccessibilityChecker::new().check(&req.content);
+
+        let analysis_result = AnalysisResult {
+            score: report.score,
+            feedback: report.rules.iter()
+                .filter(|r| r.checked > 0 && r.violations == 0)
+                .map(|r| format!("{}: لا مخالفات", r.name))
+                .collect(),
+            suggestions: report.violations.iter()
+                .map(|v| format!("[WCAG {}] {} ({})", v.wcag, v.message, v.element))
+                .collect(),
+            metadata: serde_json::json!({
+                "analysis_type": req.analysis_type,
+                "critical_count": report.critical_count,
+                "rules": report.rules,
+                "violations": report.violations
+            })
+        };
+
+        return HttpResponse::Ok().json(ApiResponse::success(analysis_result, "تم فحص إمكانية الوصول"));
+    }
+
+    if req.analysis_type == "security" {
+        let report = SecurityScanner::new().scan(&req.content);
+
+        let analysis_result = AnalysisResult {
+            score: report.score,
+            feedback: Vec::new(),
+            suggestions: report.findings.iter()
+                .map(|f| format!("[{}] سطر {}: {}", f.severity.display_name(), f.line, f.message))
+                .collect(),
+            metadata: serde_json::json!({
+                "analysis_type": req.analysis_type,
+                "highest_severity": report.highest_severity,
+                "findings": report.findings
+            })
+        };
+
+        return HttpResponse::Ok().json(ApiResponse::success(analysis_result, "تم الفحص الأمني"));
+    }
+
+    let report = TextAnalyzer::new().analyze(&req.content);
+
     let analysis_result = AnalysisResult {
-        score: 0.88,
-        feedback: vec![
-            "المحتوى جيد من حيث التنظيم".to_string(),
-            "يمكن تحسين الصياغة".to_string(),
-            "الأفكار الرئيسية واضحة".to_string()
-        ],
-        suggestions: vec![
-            "إضافة أمثلة توضيحية".to_string(),
-            "تحسين العناوين".to_string(),
-            "إضافة روابط مرجعية".to_string()
-        ],
+        score: report.score,
+        feedback: report.feedback.clone(),
+        suggestions: report.suggestions.clone(),
         metadata: serde_json::json!({
-            "word_count": req.content.split_whitespace().count(),
-            "readability_score": 0.75,
-            "top_keywords": ["تعليم", "تقنية", "إبداع"],
-            "sentiment": "positive"
+            "analysis_type": req.analysis_type,
+            "word_count": report.statistics.word_count,
+            "sentence_count": report.statistics.sentence_count,
+            "readability_score": report.readability.score,
+            "readability_level": report.readability.level.display_name(),
+            "top_keywords": report.top_words.iter().map(|(word, _)| word).collect::<Vec<_>>(),
+            "script": report.script,
+            "statistics": report.statistics,
+            "issues": report.issues
         })
     };
     
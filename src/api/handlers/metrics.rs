@@ -0,0 +1,178 @@
+use actix_web::{web, HttpResponse};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use crate::api::metrics::{http_metrics, MetricKind, MetricsWriter};
+use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::{QualityManager, QualityReport};
+use crate::mhos::alerts::AlertState;
+use crate::mhos::monitor::{AlertSeverity, SystemMonitor};
+
+/// مقاييس النظام بصيغة Prometheus النصية
+pub async fn prometheus_metrics(
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    monitor: web::Data<Arc<SystemMonitor>>
+) -> HttpResponse {
+    let mut out = MetricsWriter::new();
+
+    http_metrics().write(&mut out);
+    write_factory_metrics(&mut out, &factory_manager);
+
+    match quality_manager.read() {
+        Ok(manager) => write_quality_metrics(&mut out, &manager),
+        Err(_) => log::error!("❌ تعذر قراءة مدير الجودة لتصدير المقاييس"),
+    }
+
+    write_monitor_metrics(&mut out, &monitor).await;
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4; charset=utf-8")
+        .body(out.finish())
+}
+
+/// إحصائيات التصنيع لكل نوع مصنع
+fn write_factory_metrics(out: &mut MetricsWriter, factory_manager: &FactoryManager) {
+    let mut stats: Vec<_> = factory_manager.factory_metrics().into_iter().collect();
+    stats.sort_by(|a, b| a.0.cmp(&b.0));
+
+    out.family("mh_factory_requests_total", MetricKind::Counter, "طلبات التصنيع حسب المصنع والنتيجة");
+    for (factory_type, metrics) in &stats {
+        out.sample("mh_factory_requests_total", &[("factory_type", factory_type.as_str()), ("outcome", "success")], metrics.successful_requests as f64);
+        out.sample("mh_factory_requests_total", &[("factory_type", factory_type.as_str()), ("outcome", "failure")], metrics.failed_requests as f64);
+    }
+
+    out.family("mh_factory_processing_time_seconds", MetricKind::Gauge, "متوسط زمن التصنيع بالثواني");
+    for (factory_type, metrics) in &stats {
+        out.sample("mh_factory_processing_time_seconds", &[("factory_type", factory_type.as_str())], metrics.avg_processing_time_ms / 1000.0);
+    }
+
+    let mut cache_hits: Vec<_> = factory_manager.cache_hits().into_iter().collect();
+    cache_hits.sort_by(|a, b| a.0.cmp(&b.0));
+    out.family("mh_factory_cache_hits_total", MetricKind::Counter, "طلبات التصنيع المخدومة من التخزين المؤقت");
+    for (factory_type, hits) in &cache_hits {
+        out.sample("mh_factory_cache_hits_total", &[("factory_type", factory_type.as_str())], *hits as f64);
+    }
+
+    out.family("mh_factory_queue_size", MetricKind::Gauge, "طلبات التصنيع قيد التنفيذ");
+    for (factory_type, metrics) in &stats {
+        out.sample("mh_factory_queue_size", &[("factory_type", factory_type.as_str())], metrics.current_queue_size as f64);
+    }
+
+    let factories = factory_manager.list_factories();
+
+    out.family("mh_factory_production_total", MetricKind::Counter, "عدد المنتجات المصنعة");
+    for factory in &factories {
+        out.sample("mh_factory_production_total", &[("factory_type", factory.factory_type())], factory.production_count() as f64);
+    }
+
+    out.family("mh_factory_quality_score", MetricKind::Gauge, "درجة جودة المصنع المعلنة");
+    for factory in &factories {
+        out.sample("mh_factory_quality_score", &[("factory_type", factory.factory_type())], factory.quality_score() as f64);
+    }
+}
+
+/// أحدث نتائج الجودة وعدد التقارير والتراجعات لكل مصنع
+fn write_quality_metrics(out: &mut MetricsWriter, manager: &QualityManager) {
+    let history = manager.history();
+
+    // أحدث تقرير وعدد التقارير لكل مصنع (أو نوع الكيان إن لم يُحدد المصنع)
+    let mut latest: HashMap<&str, &QualityReport> = HashMap::new();
+    let mut counts: HashMap<(&str, bool), u64> = HashMap::new();
+    for report in history.all() {
+        let key = report.factory.as_deref().unwrap_or(&report.entity_type);
+        *counts.entry((key, report.passed)).or_insert(0) += 1;
+        latest.entry(key)
+            .and_modify(|current| if report.generated_at >= current.generated_at { *current = report })
+            .or_insert(report);
+    }
+    let mut latest: Vec<_> = latest.into_iter().collect();
+    latest.sort_by(|a, b| a.0.cmp(b.0));
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort();
+
+    out.family("mh_quality_score", MetricKind::Gauge, "النتيجة الإجمالية لأحدث تقرير جودة");
+    for (factory_type, report) in &latest {
+        out.sample("mh_quality_score", &[("factory_type", *factory_type)], report.overall_score);
+    }
+
+    out.family("mh_quality_gate_score", MetricKind::Gauge, "نتيجة كل بوابة جودة في أحدث تقرير");
+    for (factory_type, report) in &latest {
+        let mut gates: Vec<_> = report.gate_results.values().collect();
+        gates.sort_by(|a, b| a.gate_id.cmp(&b.gate_id));
+        for gate in gates {
+            out.sample("mh_quality_gate_score", &[("factory_type", *factory_type), ("gate", gate.gate_id.as_str())], gate.score);
+        }
+    }
+
+    out.family("mh_quality_reports_total", MetricKind::Counter, "تقارير الجودة المحفوظة حسب النجاح");
+    for ((factory_type, passed), count) in &counts {
+        let passed = if *passed { "true" } else { "false" };
+        out.sample("mh_quality_reports_total", &[("factory_type", *factory_type), ("passed", passed)], *count as f64);
+    }
+
+    let mut regressions: HashMap<&str, u64> = HashMap::new();
+    for regression in history.regressions(None) {
+        *regressions.entry(regression.factory.as_str()).or_insert(0) += 1;
+    }
+    let mut regressions: Vec<_> = regressions.into_iter().collect();
+    regressions.sort();
+
+    out.family("mh_quality_regressions_total", MetricKind::Counter, "تراجعات الجودة المكتشفة");
+    for (factory_type, count) in &regressions {
+        out.sample("mh_quality_regressions_total", &[("factory_type", *factory_type)], *count as f64);
+    }
+}
+
+/// آخر عينة من مراقب النظام والإنذارات النشطة
+async fn write_monitor_metrics(out: &mut MetricsWriter, monitor: &SystemMonitor) {
+    let sample = match monitor.latest_metrics() {
+        Some(sample) => sample,
+        None => monitor.get_performance_metrics().await,
+    };
+
+    let gauges = [
+        ("mh_host_cpu_usage_ratio", "نسبة استخدام المعالج", sample.cpu_usage as f64),
+        ("mh_host_memory_usage_ratio", "نسبة استخدام الذاكرة", sample.memory_usage as f64),
+        ("mh_host_disk_usage_ratio", "نسبة استخدام القرص", sample.disk_usage as f64),
+        ("mh_host_network_usage_ratio", "نسبة استخدام الشبكة", sample.network_usage as f64),
+        ("mh_process_resident_memory_bytes", "الذاكرة المقيمة للعملية بالبايت", sample.process_memory_bytes as f64),
+        ("mh_process_threads", "عدد خيوط العملية", sample.process_threads as f64),
+        ("mh_monitor_response_time_seconds", "متوسط زمن الاستجابة في نافذة المراقبة", sample.response_time as f64 / 1000.0),
+        ("mh_monitor_throughput_requests_per_second", "معدل الطلبات في نافذة المراقبة", sample.throughput as f64),
+        ("mh_monitor_overall_score", "درجة الأداء الإجمالية", sample.overall_score as f64),
+    ];
+    for (name, help, value) in gauges {
+        out.family(name, MetricKind::Gauge, help);
+        out.sample(name, &[], value);
+    }
+
+    let alerts = match monitor.list_alerts(None) {
+        Ok(alerts) => alerts,
+        Err(e) => {
+            log::warn!("⚠️ تعذر قراءة الإنذارات لتصدير المقاييس: {}", e);
+            Vec::new()
+        }
+    };
+
+    let mut active: HashMap<&'static str, u64> = HashMap::new();
+    for alert in alerts.iter().filter(|a| a.state != AlertState::Resolved) {
+        *active.entry(severity_label(&alert.severity)).or_insert(0) += 1;
+    }
+    let mut active: Vec<_> = active.into_iter().collect();
+    active.sort();
+
+    out.family("mh_alerts_active", MetricKind::Gauge, "الإنذارات المفتوحة أو المعترف بها حسب الخطورة");
+    for (severity, count) in active {
+        out.sample("mh_alerts_active", &[("severity", severity)], count as f64);
+    }
+}
+
+/// قيمة وسم ثابتة بالإنجليزية (كما في تسلسل JSON) بدل الاسم المعروض
+fn severity_label(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "info",
+        AlertSeverity::Warning => "warning",
+        AlertSeverity::Error => "error",
+        AlertSeverity::Critical => "critical",
+    }
+}
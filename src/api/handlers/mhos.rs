@@ -1,18 +1,44 @@
-use actix_web::{web, HttpResponse};
-use std::sync::Arc;
+use actix_web::{web, HttpRequest, HttpResponse};
+use futures::stream;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast::error::RecvError;
 use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::{MiniDashboard, QualityManager};
+use crate::mhos::alerts::AlertState;
+use crate::mhos::dashboard::Dashboard;
+use crate::mhos::layouts::LayoutStore;
+use crate::mhos::live::{LiveEvent, LiveHub};
+use crate::mhos::monitor::SystemMonitor;
+use crate::mhos::optimizer::{MetricSources, Optimizer};
+use crate::mhos::recommendations::RecommendationEngine;
+use crate::mhos::views::renderer;
+use crate::mhos::MhosSystem;
+use crate::mhos::widgets::WidgetContext;
+use crate::api::middleware::AuthMiddleware;
 use crate::api::ApiResponse;
 
 /// لوحة تحكم MH-OS
 pub async fn mhos_dashboard(
-    factory_manager: web::Data<Arc<FactoryManager>>
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    monitor: web::Data<Arc<SystemMonitor>>,
+    engine: web::Data<Arc<RecommendationEngine>>,
+    mhos: web::Data<Arc<MhosSystem>>
 ) -> HttpResponse {
+    let quality = quality_manager.read()
+        .map(|manager| MiniDashboard::new().with_quality_history(manager.history()).to_json())
+        .unwrap_or(serde_json::Value::Null);
+    let sources = MetricSources::new(factory_manager.get_ref().clone(), monitor.get_ref().clone())
+        .with_quality(quality_manager.get_ref().clone());
+    let recommendations = engine.evaluate_sources(&sources, chrono::Duration::minutes(60)).await;
+    
     let dashboard = serde_json::json!({
         "version": "MH-OS v2.2",
         "status": "active",
         "factories_monitored": 4,
-        "quality_gates": 8,
-        "optimization_score": 0.88,
+        "quality_gates": mhos.get_quality_gates().get_gates().iter().filter(|gate| gate.enabled).count(),
         "ai_assistant": "active",
         "recent_activities": [
             "تحسين مصنع التعليم",
@@ -20,72 +46,545 @@ pub async fn mhos_dashboard(
             "تحديث قوالب التكنولوجيا",
             "تحليل أداء النظام"
         ],
-        "recommendations": [
-            "زيادة قدرة مصنع الشركات",
-            "تحسين خوارزمية الجودة",
-            "إضافة قوالب جديدة"
-        ],
+        "recommendations": recommendations,
+        "quality": quality,
         "timestamp": chrono::Utc::now().to_rfc3339()
     });
     
     HttpResponse::Ok().json(ApiResponse::success(dashboard, "لوحة تحكم MH-OS"))
 }
 
-/// بوابات الجودة
-pub async fn mhos_quality_gates(
-    factory_manager: web::Data<Arc<FactoryManager>>
+/// صفحة لوحة التحكم المعروضة بقوالب Tera (بتخطيط المستخدم إن عُرف)
+pub async fn dashboard_page(
+    req: HttpRequest,
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    monitor: web::Data<Arc<SystemMonitor>>,
+    layouts: web::Data<Arc<RwLock<LayoutStore>>>
 ) -> HttpResponse {
-    let quality_gates = serde_json::json!({
-        "gates": [
-            {
-                "id": "gate_1",
-                "name": "فحص التصميم",
-                "status": "passed",
-                "threshold": 0.9,
-                "actual": 0.95
-            },
-            {
-                "id": "gate_2",
-                "name": "فحص المحتوى",
-                "status": "passed",
-                "threshold": 0.85,
-                "actual": 0.92
-            },
-            {
-                "id": "gate_3",
-                "name": "فحص الأداء",
-                "status": "warning",
-                "threshold": 0.8,
-                "actual": 0.79
-            },
-            {
-                "id": "gate_4",
-                "name": "فحص الأمان",
-                "status": "passed",
-                "threshold": 0.95,
-                "actual": 0.98
-            }
-        ],
-        "overall_status": "healthy",
-        "timestamp": chrono::Utc::now().to_rfc3339()
+    let factory_manager = factory_manager.get_ref().clone();
+    let claims = AuthMiddleware::request_claims(&req);
+    let user_dashboard = claims.as_ref().and_then(|claims| {
+        let layouts = layouts.read().ok()?;
+        layouts.dashboard_for(&claims.sub, factory_manager.clone()).ok()
+    });
+    let context = WidgetContext::new(factory_manager.clone())
+        .with_quality(quality_manager.get_ref().clone())
+        .with_monitor(monitor.get_ref().clone());
+    let mut dashboard = user_dashboard
+        .unwrap_or_else(|| Dashboard::new(factory_manager))
+        .with_context(context);
+    let data = dashboard.get_dashboard_data().await;
+    // توكن البث للمستخدم المصادق عليه فقط؛ الزائر يرى لقطة دون تحديث حي
+    let live_token = claims.as_ref().and_then(|claims| {
+        AuthMiddleware::generate_stream_token(&claims.sub)
+            .map_err(|e| log::warn!("⚠️ تعذر إصدار توكن البث: {}", e))
+            .ok()
     });
+    let mini = quality_manager.read()
+        .ok()
+        .map(|manager| MiniDashboard::new().with_quality_history(manager.history()));
     
-    HttpResponse::Ok().json(ApiResponse::success(quality_gates, "بوابات الجودة"))
+    match renderer().render_dashboard(&data, mini.as_ref(), live_token.as_deref()) {
+        Ok(html) => HttpResponse::Ok().content_type("text/html; charset=utf-8").body(html),
+        Err(e) => {
+            log::error!("❌ تعذر عرض لوحة التحكم: {}", e);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر عرض لوحة التحكم"))
+        }
+    }
+}
+
+/// محتوى عنصر واحد من لوحة التحكم (للتحديث الجزئي)
+pub async fn dashboard_widget(
+    req: HttpRequest,
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    monitor: web::Data<Arc<SystemMonitor>>,
+    layouts: web::Data<Arc<RwLock<LayoutStore>>>,
+    widget_id: web::Path<String>
+) -> HttpResponse {
+    let factory_manager = factory_manager.get_ref().clone();
+    let user_dashboard = AuthMiddleware::request_claims(&req).and_then(|claims| {
+        let layouts = layouts.read().ok()?;
+        layouts.dashboard_for(&claims.sub, factory_manager.clone()).ok()
+    });
+    let context = WidgetContext::new(factory_manager.clone())
+        .with_quality(quality_manager.get_ref().clone())
+        .with_monitor(monitor.get_ref().clone());
+    let mut dashboard = user_dashboard
+        .unwrap_or_else(|| Dashboard::new(factory_manager))
+        .with_context(context);
+    dashboard.update_widget_data(&widget_id).await;
+    
+    let widget = match dashboard.widget(&widget_id) {
+        Some(widget) => widget,
+        None => return HttpResponse::NotFound().json(ApiResponse::<()>::error("العنصر غير موجود")),
+    };
+    
+    match renderer().render_widget(widget) {
+        Ok(html) => HttpResponse::Ok().content_type("text/html; charset=utf-8").body(html),
+        Err(e) => {
+            log::error!("❌ تعذر عرض العنصر {}: {}", widget_id, e);
+            HttpResponse::InternalServerError().json(ApiResponse::<()>::error("تعذر عرض العنصر"))
+        }
+    }
+}
+
+/// بوابات الجودة: فحص حي للمخرجات المقدمة ومقاييس المصانع
+pub async fn mhos_quality_gates(
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    mhos: web::Data<Arc<MhosSystem>>
+) -> HttpResponse {
+    let report = mhos.get_quality_gates().check_all(factory_manager.get_ref().clone()).await;
+    HttpResponse::Ok().json(ApiResponse::success(report, "بوابات الجودة"))
 }
 
-/// تحسين النظام
+/// معاملات التحسين
+#[derive(Debug, Deserialize)]
+pub struct OptimizeQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// تحسين النظام: تطبيق الاستراتيجيات على المقاييس الحية وتعديل مقابض التشغيل
+/// (أو عرضها فقط مع `?dry_run=true`)
 pub async fn mhos_optimize(
-    factory_manager: web::Data<Arc<FactoryManager>>
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    monitor: web::Data<Arc<SystemMonitor>>,
+    optimizer: web::Data<Arc<Optimizer>>,
+    query: web::Query<OptimizeQuery>
 ) -> HttpResponse {
-    let optimization = serde_json::json!({
-        "optimization_id": "opt_001",
-        "status": "started",
-        "areas": ["performance", "memory", "quality"],
-        "estimated_time": "2m",
-        "expected_improvement": 0.15,
-        "timestamp": chrono::Utc::now().to_rfc3339()
+    let sources = MetricSources::new(factory_manager.get_ref().clone(), monitor.get_ref().clone())
+        .with_quality(quality_manager.get_ref().clone());
+    
+    if query.dry_run {
+        let result = optimizer.dry_run(&sources).await;
+        let message = if result.success { "التحسينات المتوقعة (لم يُطبق شيء)" } else { "لا توجد تحسينات مطلوبة حالياً" };
+        return HttpResponse::Ok().json(ApiResponse::success(result, message));
+    }
+    
+    let result = optimizer.optimize(&sources).await;
+    let message = if result.success { "تم تطبيق التحسينات" } else { "لا توجد تحسينات مطلوبة حالياً" };
+    HttpResponse::Ok().json(ApiResponse::success(result, message))
+}
+
+/// معاملات سجل التحسين
+#[derive(Debug, Deserialize)]
+pub struct OptimizationHistoryQuery {
+    pub limit: Option<usize>,
+}
+
+/// سجل عمليات التحسين مع لقطات المقاييس ومصير كل تغيير
+pub async fn mhos_optimize_history(
+    optimizer: web::Data<Arc<Optimizer>>,
+    query: web::Query<OptimizationHistoryQuery>
+) -> HttpResponse {
+    let runs = optimizer.recent_runs(query.limit.unwrap_or(20));
+    HttpResponse::Ok().json(ApiResponse::success(runs, "سجل التحسين"))
+}
+
+/// معاملات التوصيات
+#[derive(Debug, Deserialize)]
+pub struct RecommendationsQuery {
+    /// فترة التقييم بالدقائق (الافتراضي 60)
+    pub window_minutes: Option<i64>,
+}
+
+/// التوصيات الحالية من محرك القواعد مع أدلتها والإجراء المقترح
+pub async fn mhos_recommendations(
+    factory_manager: web::Data<Arc<FactoryManager>>,
+    quality_manager: web::Data<Arc<RwLock<QualityManager>>>,
+    monitor: web::Data<Arc<SystemMonitor>>,
+    engine: web::Data<Arc<RecommendationEngine>>,
+    query: web::Query<RecommendationsQuery>
+) -> HttpResponse {
+    let sources = MetricSources::new(factory_manager.get_ref().clone(), monitor.get_ref().clone())
+        .with_quality(quality_manager.get_ref().clone());
+    let window = chrono::Duration::minutes(query.window_minutes.unwrap_or(60).clamp(1, 24 * 60));
+    
+    let recommendations = engine.evaluate_sources(&sources, window).await;
+    HttpResponse::Ok().json(ApiResponse::success(recommendations, "التوصيات الحالية"))
+}
+
+/// رفض توصية (لا تعود قبل انقضاء فترة التهدئة)
+pub async fn mhos_dismiss_recommendation(
+    engine: web::Data<Arc<RecommendationEngine>>,
+    recommendation_id: web::Path<String>
+) -> HttpResponse {
+    if engine.dismiss(&recommendation_id) {
+        HttpResponse::Ok().json(ApiResponse::success(recommendation_id.into_inner(), "تم رفض التوصية"))
+    } else {
+        HttpResponse::NotFound().json(ApiResponse::<()>::error("التوصية غير موجودة"))
+    }
+}
+
+/// تكوين MH-OS الحالي
+pub async fn mhos_config(mhos: web::Data<Arc<MhosSystem>>) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse::success(mhos.config(), "تكوين MH-OS"))
+}
+
+/// تعديل تكوين MH-OS (كلياً أو جزئياً) وتطبيقه فوراً
+pub async fn update_mhos_config(
+    mhos: web::Data<Arc<MhosSystem>>,
+    body: web::Json<serde_json::Value>
+) -> HttpResponse {
+    let config = match mhos.config().with_patch(&body) {
+        Ok(config) => config,
+        Err(e) => return HttpResponse::BadRequest().json(ApiResponse::<()>::error(&e)),
+    };
+    
+    match mhos.update_config(config) {
+        Ok(config) => HttpResponse::Ok().json(ApiResponse::success(config, "تم تحديث تكوين MH-OS")),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e)),
+    }
+}
+
+/// معاملات قائمة الإنذارات
+#[derive(Debug, Deserialize)]
+pub struct AlertsQuery {
+    pub state: Option<String>,
+}
+
+/// طلب الإقرار بإنذار
+#[derive(Debug, Deserialize)]
+pub struct AcknowledgeAlertRequest {
+    pub by: Option<String>,
+}
+
+/// طلب اختبار قنوات الإشعار
+#[derive(Debug, Deserialize)]
+pub struct NotificationTestRequest {
+    pub route: Option<String>,
+}
+
+/// طلب إسكات إنذار
+#[derive(Debug, Deserialize)]
+pub struct SilenceAlertRequest {
+    pub minutes: Option<i64>,
+}
+
+/// قائمة الإنذارات
+pub async fn mhos_alerts(
+    monitor: web::Data<Arc<SystemMonitor>>,
+    query: web::Query<AlertsQuery>
+) -> HttpResponse {
+    let state = match query.state.as_deref() {
+        Some(value) => match AlertState::parse(value) {
+            Some(state) => Some(state),
+            None => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("حالة إنذار غير معروفة (open, acknowledged, resolved)")),
+        },
+        None => None,
+    };
+    
+    match monitor.list_alerts(state) {
+        Ok(alerts) => HttpResponse::Ok().json(ApiResponse::success(alerts, "قائمة الإنذارات")),
+        Err(e) => HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e)),
+    }
+}
+
+/// الإقرار بإنذار
+pub async fn mhos_alert_ack(
+    monitor: web::Data<Arc<SystemMonitor>>,
+    alert_id: web::Path<String>,
+    req: Option<web::Json<AcknowledgeAlertRequest>>
+) -> HttpResponse {
+    let by = req.and_then(|r| r.into_inner().by).unwrap_or_else(|| "api".to_string());
+    
+    match monitor.acknowledge_alert(&alert_id, &by) {
+        Ok(alert) => HttpResponse::Ok().json(ApiResponse::success(alert, "تم الإقرار بالإنذار")),
+        Err(e) => HttpResponse::BadRequest().json(ApiResponse::<()>::error(&e)),
+    }
+}
+
+/// إسكات إنذار
+pub async fn mhos_alert_silence(
+    monitor: web::Data<Arc<SystemMonitor>>,
+    alert_id: web::Path<String>,
+    req: Option<web::Json<SilenceAlertRequest>>
+) -> HttpResponse {
+    let minutes = req.and_then(|r| r.into_inner().minutes).unwrap_or(60);
+    if minutes <= 0 {
+        return HttpResponse::BadRequest().json(ApiResponse::<()>::error("مدة الإسكات يجب أن تكون موجبة"));
+    }
+    
+    match monitor.silence_alert(&alert_id, minutes) {
+        Ok(alert) => HttpResponse::Ok().json(ApiResponse::success(alert, "تم إسكات الإنذار")),
+        Err(e) => HttpResponse::BadRequest().json(ApiResponse::<()>::error(&e)),
+    }
+}
+
+/// وضع الاختبار: إرسال إنذار تجريبي إلى قنوات الإشعار
+pub async fn mhos_alerts_test(
+    monitor: web::Data<Arc<SystemMonitor>>,
+    req: Option<web::Json<NotificationTestRequest>>
+) -> HttpResponse {
+    let notifier = match monitor.notifier() {
+        Some(notifier) => notifier,
+        None => return HttpResponse::BadRequest().json(ApiResponse::<()>::error("لا توجد قنوات إشعار مهيأة")),
+    };
+    
+    let route = req.and_then(|r| r.into_inner().route);
+    let reports = notifier.send_test(route.as_deref()).await;
+    if reports.is_empty() {
+        return HttpResponse::NotFound().json(ApiResponse::<()>::error("مسار الإشعار غير موجود"));
+    }
+    
+    HttpResponse::Ok().json(ApiResponse::success(reports, "تم إرسال الإشعار التجريبي"))
+}
+
+/// معاملات البث الحي
+#[derive(Debug, Deserialize)]
+pub struct LiveQuery {
+    /// الأحداث المطلوبة مفصولة بفواصل (widget, factory_status, alert, quality_report)
+    pub topics: Option<String>,
+}
+
+/// فترة رسائل الإبقاء على الاتصال
+const LIVE_KEEPALIVE: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// بث تحديثات لوحة التحكم عبر Server-Sent Events
+pub async fn mhos_live(
+    hub: web::Data<Arc<LiveHub>>,
+    query: web::Query<LiveQuery>
+) -> HttpResponse {
+    let topics: Option<Vec<String>> = query.topics.as_deref()
+        .map(|t| t.split(',').map(|topic| topic.trim().to_string()).filter(|topic| !topic.is_empty()).collect());
+    let wanted = move |event: &LiveEvent| topics.as_ref().map(|t| t.iter().any(|topic| topic == event.topic())).unwrap_or(true);
+    
+    // الاشتراك قبل أخذ الحالة الحالية حتى لا يضيع حدث بينهما
+    let receiver = hub.subscribe();
+    let initial: VecDeque<LiveEvent> = hub.current_state().into_iter().filter(|e| wanted(e)).collect();
+    
+    let events = stream::unfold((receiver, initial), move |(mut receiver, mut initial)| {
+        let wanted = wanted.clone();
+        async move {
+            if let Some(event) = initial.pop_front() {
+                return Some((Ok::<_, actix_web::Error>(sse_frame(&event)), (receiver, initial)));
+            }
+            
+            loop {
+                let frame = tokio::select! {
+                    received = receiver.recv() => match received {
+                        Ok(event) if wanted(&event) => sse_frame(&event),
+                        Ok(_) => continue,
+                        Err(RecvError::Lagged(skipped)) => web::Bytes::from(format!(": skipped {}\n\n", skipped)),
+                        Err(RecvError::Closed) => return None,
+                    },
+                    _ = tokio::time::sleep(LIVE_KEEPALIVE) => web::Bytes::from_static(b": keepalive\n\n"),
+                };
+                return Some((Ok(frame), (receiver, initial)));
+            }
+        }
     });
     
-    // محاكاة عملية التحسين
-    HttpResponse::Accepted().json(ApiResponse::success(optimization, "بدأت عملية التحسين"))
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events)
+}
+
+/// إطار SSE واحد: اسم الحدث وبياناته JSON
+fn sse_frame(event: &LiveEvent) -> web::Bytes {
+    let data = serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string());
+    web::Bytes::from(format!("event: {}\ndata: {}\n\n", event.topic(), data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App};
+
+    fn dashboard_app() -> App<impl actix_web::dev::ServiceFactory<
+        actix_web::dev::ServiceRequest,
+        Config = (),
+        Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+        Error = actix_web::Error,
+        InitError = ()
+    >> {
+        App::new()
+            .app_data(web::Data::new(Arc::new(FactoryManager::new())))
+            .app_data(web::Data::new(Arc::new(RwLock::new(QualityManager::new()))))
+            .app_data(web::Data::new(Arc::new(SystemMonitor::new())))
+            .app_data(web::Data::new(Arc::new(RwLock::new(LayoutStore::in_memory()))))
+            .wrap(AuthMiddleware::new())
+            .route("/dashboard", web::get().to(dashboard_page))
+            .route("/dashboard/widgets/{widget_id}", web::get().to(dashboard_widget))
+    }
+
+    fn bearer(user: &str) -> (&'static str, String) {
+        ("Authorization", format!("Bearer {}", AuthMiddleware::generate_token(user, "admin").unwrap()))
+    }
+
+    /// توكن البث الذي تضعه الصفحة لمستخدم مصادق عليه
+    async fn page_stream_token(user: &str) -> String {
+        let app = test::init_service(dashboard_app()).await;
+        let req = test::TestRequest::get().uri("/dashboard").insert_header(bearer(user)).to_request();
+        let html = String::from_utf8(test::read_body(test::call_service(&app, req).await).await.to_vec()).unwrap();
+        let start = html.find("const token = '").unwrap() + "const token = '".len();
+        html[start..].split('\'').next().unwrap().to_string()
+    }
+
+    #[actix_web::test]
+    async fn test_dashboard_page_without_credentials() {
+        let app = test::init_service(dashboard_app()).await;
+        let res = test::call_service(&app, test::TestRequest::get().uri("/dashboard").to_request()).await;
+
+        assert_eq!(res.status(), 200);
+        let html = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+        assert!(html.contains(r#"id="widget-factory_status""#));
+        // الزائر لا يحصل على توكن بث
+        assert!(!html.contains("token="));
+    }
+
+    #[actix_web::test]
+    async fn test_dashboard_page_issues_stream_token_to_user() {
+        let token = page_stream_token("alice").await;
+        let claims = AuthMiddleware::verify_token(&token).unwrap();
+        assert_eq!((claims.sub.as_str(), claims.role.as_str()), ("alice", crate::api::middleware::auth::STREAM_ROLE));
+    }
+
+    #[actix_web::test]
+    async fn test_dashboard_widget_fragment_requires_auth() {
+        let app = test::init_service(dashboard_app()).await;
+        let res = test::call_service(&app, test::TestRequest::get().uri("/dashboard/widgets/factory_status").to_request()).await;
+        assert_eq!(res.status(), 401);
+
+        let req = test::TestRequest::get().uri("/dashboard/widgets/factory_status").insert_header(bearer("alice")).to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), 200);
+        let html = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+        assert!(html.starts_with("<h3>"));
+    }
+
+    #[actix_web::test]
+    async fn test_dashboard_widget_fragment_with_stream_token() {
+        let token = page_stream_token("alice").await;
+        let app = test::init_service(dashboard_app()).await;
+        let req = test::TestRequest::get().uri(&format!("/dashboard/widgets/factory_status?token={}", token)).to_request();
+
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
+
+    #[actix_web::test]
+    async fn test_dashboard_unknown_widget_is_404() {
+        let app = test::init_service(dashboard_app()).await;
+        let req = test::TestRequest::get().uri("/dashboard/widgets/no_such_widget").insert_header(bearer("alice")).to_request();
+
+        assert_eq!(test::call_service(&app, req).await.status(), 404);
+    }
+
+    #[actix_web::test]
+    async fn test_mhos_dashboard_serves_engine_recommendations() {
+        let factory_manager = Arc::new(FactoryManager::new());
+        let engine = Arc::new(RecommendationEngine::new().with_rules(Vec::new()));
+        let app = test::init_service(App::new()
+            .app_data(web::Data::new(factory_manager.clone()))
+            .app_data(web::Data::new(Arc::new(RwLock::new(QualityManager::new()))))
+            .app_data(web::Data::new(Arc::new(SystemMonitor::new())))
+            .app_data(web::Data::new(engine))
+            .app_data(web::Data::new(Arc::new(MhosSystem::new(factory_manager))))
+            .route("/api/v1/mhos/dashboard", web::get().to(mhos_dashboard))).await;
+
+        let req = test::TestRequest::get().uri("/api/v1/mhos/dashboard").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        // محرك بلا قواعد لا يقترح شيئاً، ولا توجد قيم ثابتة
+        assert_eq!(body["data"]["recommendations"], serde_json::json!([]));
+        assert!(body["data"].get("optimization_score").is_none());
+        assert_eq!(body["data"]["quality_gates"], 7);
+    }
+
+    #[actix_web::test]
+    async fn test_quality_gates_score_manufactured_products() {
+        let factory_manager = Arc::new(FactoryManager::new());
+        let mhos = Arc::new(MhosSystem::new(factory_manager.clone()));
+        let app = test::init_service(App::new()
+            .app_data(web::Data::new(factory_manager.clone()))
+            .app_data(web::Data::new(mhos))
+            .route("/api/v1/mhos/quality-gates", web::get().to(mhos_quality_gates))).await;
+        let security = |body: &serde_json::Value| {
+            body["data"]["results"].as_array().unwrap().iter()
+                .find(|r| r["gate_id"] == "security_check").unwrap().clone()
+        };
+
+        let req = test::TestRequest::get().uri("/api/v1/mhos/quality-gates").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(security(&body)["skipped"], true);
+
+        factory_manager.manufacture("education", serde_json::json!("الكسور"), serde_json::json!({})).unwrap();
+        let req = test::TestRequest::get().uri("/api/v1/mhos/quality-gates").to_request();
+        let body: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+        assert_eq!(security(&body)["skipped"], false);
+        assert!(body["data"]["total_gates"].as_u64().unwrap() > 0);
+    }
+
+    fn live_app() -> App<impl actix_web::dev::ServiceFactory<
+        actix_web::dev::ServiceRequest,
+        Config = (),
+        Response = actix_web::dev::ServiceResponse<impl actix_web::body::MessageBody>,
+        Error = actix_web::Error,
+        InitError = ()
+    >> {
+        App::new()
+            .app_data(web::Data::new(Arc::new(LiveHub::new())))
+            .wrap(AuthMiddleware::new())
+            .route("/api/v1/mhos/live", web::get().to(mhos_live))
+            .route("/api/v1/mhos/status", web::get().to(HttpResponse::Ok))
+    }
+
+    #[actix_web::test]
+    async fn test_live_stream_with_query_token_and_no_header() {
+        let token = page_stream_token("alice").await;
+        let app = test::init_service(live_app()).await;
+        let req = test::TestRequest::get()
+            .uri(&format!("/api/v1/mhos/live?topics=widget&token={}", token))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers().get("content-type").unwrap(), "text/event-stream");
+    }
+
+    #[actix_web::test]
+    async fn test_live_stream_rejects_missing_or_bad_token() {
+        let app = test::init_service(live_app()).await;
+
+        let res = test::call_service(&app, test::TestRequest::get().uri("/api/v1/mhos/live").to_request()).await;
+        assert_eq!(res.status(), 401);
+
+        let res = test::call_service(&app, test::TestRequest::get().uri("/api/v1/mhos/live?token=garbage").to_request()).await;
+        assert_eq!(res.status(), 401);
+    }
+
+    #[actix_web::test]
+    async fn test_live_stream_rejects_full_token_in_query() {
+        let app = test::init_service(live_app()).await;
+        let admin = AuthMiddleware::generate_token("admin", "admin").unwrap();
+        let req = test::TestRequest::get().uri(&format!("/api/v1/mhos/live?token={}", admin)).to_request();
+
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+    }
+
+    #[actix_web::test]
+    async fn test_stream_token_limited_to_live_route() {
+        let token = page_stream_token("alice").await;
+        let app = test::init_service(live_app()).await;
+
+        let req = test::TestRequest::get().uri(&format!("/api/v1/mhos/status?token={}", token)).to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+        let req = test::TestRequest::get()
+            .uri("/api/v1/mhos/status")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 401);
+
+        let admin = AuthMiddleware::generate_token("admin", "admin").unwrap();
+        let req = test::TestRequest::get()
+            .uri("/api/v1/mhos/status")
+            .insert_header(("Authorization", format!("Bearer {}", admin)))
+            .to_request();
+        assert_eq!(test::call_service(&app, req).await.status(), 200);
+    }
 }
@@ -2,8 +2,16 @@ pub mod factory;
 pub mod mhos;
 pub mod system;
 pub mod learning;
+pub mod metrics;
+pub mod layouts;
+pub mod knowledge;
+pub mod jobs;
 
 pub use factory::*;
 pub use mhos::*;
 pub use system::*;
 pub use learning::*;
+pub use metrics::*;
+pub use layouts::*;
+pub use knowledge::*;
+pub use jobs::*;
@@ -1,23 +1,51 @@
 use actix_web::{web, HttpResponse};
 use std::sync::Arc;
 use crate::core::factory_manager::FactoryManager;
+use crate::core::health::{HealthRegistry, HealthReport};
 use crate::api::ApiResponse;
 
-/// فحص صحة النظام
+/// تشغيل فحوص المكونات خارج عامل actix (فحص التخزين يكتب في القرص)
+async fn run_report(health: &Arc<HealthRegistry>) -> Result<HealthReport, actix_web::error::BlockingError> {
+    let health = health.clone();
+    web::block(move || health.report()).await
+}
+
+/// فحص صحة النظام: تقرير المكونات الكامل (للعرض، لا يعتمد عليه المسباران)
 pub async fn system_health(
-    factory_manager: web::Data<Arc<FactoryManager>>
+    health: web::Data<Arc<HealthRegistry>>
 ) -> HttpResponse {
-    let health_status = serde_json::json!({
-        "status": "healthy",
-        "version": env!("CARGO_PKG_VERSION"),
-        "uptime": "24h",
-        "active_connections": 42,
-        "memory_usage": "65%",
-        "cpu_usage": "30%",
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    });
+    let report = match run_report(health.get_ref()).await {
+        Ok(report) => report,
+        Err(e) => return HttpResponse::InternalServerError().json(ApiResponse::<()>::error(&e.to_string())),
+    };
+    let message = format!("صحة النظام: {}", report.status.as_str());
+    HttpResponse::Ok().json(ApiResponse::success(report, &message))
+}
+
+/// مسبار الحياة: ينجح ما دامت العملية تستجيب
+pub async fn health_live(
+    health: web::Data<Arc<HealthRegistry>>
+) -> HttpResponse {
+    HttpResponse::Ok().json(ApiResponse::success(health.liveness(), "الخادم يعمل"))
+}
+
+/// مسبار الجاهزية: 503 إذا كان مكون أساسي في حالة حرجة
+pub async fn health_ready(
+    health: web::Data<Arc<HealthRegistry>>
+) -> HttpResponse {
+    let report = match run_report(health.get_ref()).await {
+        Ok(report) => report,
+        Err(e) => return HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error(&e.to_string())),
+    };
+    if report.ready {
+        return HttpResponse::Ok().json(ApiResponse::success(report, "النظام جاهز لاستقبال الطلبات"));
+    }
     
-    HttpResponse::Ok().json(ApiResponse::success(health_status, "النظام يعمل بشكل صحي"))
+    let blocking: Vec<String> = report.blocking().iter()
+        .map(|c| format!("{}: {}", c.component, c.message))
+        .collect();
+    log::warn!("⚠️  النظام غير جاهز - {}", blocking.join("، "));
+    HttpResponse::ServiceUnavailable().json(ApiResponse::<()>::error(&format!("النظام غير جاهز: {}", blocking.join("، "))))
 }
 
 /// إحصائيات النظام
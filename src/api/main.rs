@@ -2,6 +2,7 @@
 
 use marwan_hub_factories::api::ApiServer;
 use marwan_hub_factories::core::factory_manager::FactoryManager;
+use marwan_hub_factories::core::telemetry::{self, TelemetryConfig};
 use std::sync::Arc;
 
 #[tokio::main]
@@ -10,6 +11,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
         .init();
     
+    // التتبع (OTLP و/أو ملف JSON حسب متغيرات البيئة)
+    telemetry::init(TelemetryConfig::from_env());
+    
     // عرض شعار النظام
     print_banner();
     
@@ -28,7 +32,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("⏹️  Press Ctrl+C to stop");
     
     // تشغيل الخادم
-    api_server.run().await?;
+    let result = api_server.run().await;
+    
+    // تصدير المقاطع المتبقية قبل الخروج
+    telemetry::flush(std::time::Duration::from_secs(5));
+    result?;
     
     Ok(())
 }
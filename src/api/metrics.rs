@@ -0,0 +1,214 @@
+//! تصدير المقاييس بصيغة Prometheus النصية
+//!
+//! كل الأسماء تبدأ بـ `mh_` وتنتهي بالوحدة (`_seconds`، `_bytes`، `_ratio`،
+//! `_total` للعدادات)، ويُستخدم الوسم `factory_type` لكل مقياس خاص بمصنع.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// حدود مدرجات زمن الطلب بالثواني
+pub const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// نوع المقياس
+#[derive(Debug, Clone, Copy)]
+pub enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl MetricKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Counter => "counter",
+            MetricKind::Gauge => "gauge",
+            MetricKind::Histogram => "histogram",
+        }
+    }
+}
+
+/// كاتب صيغة العرض النصية لـ Prometheus
+#[derive(Debug, Default)]
+pub struct MetricsWriter {
+    out: String,
+}
+
+impl MetricsWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// سطرا HELP و TYPE لعائلة مقاييس
+    pub fn family(&mut self, name: &str, kind: MetricKind, help: &str) {
+        let _ = writeln!(self.out, "# HELP {} {}", name, help.replace('\\', "\\\\").replace('\n', "\\n"));
+        let _ = writeln!(self.out, "# TYPE {} {}", name, kind.as_str());
+    }
+
+    /// عينة واحدة مع وسومها
+    pub fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.out.push_str(name);
+        if !labels.is_empty() {
+            self.out.push('{');
+            for (i, (key, val)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.out.push(',');
+                }
+                let _ = write!(self.out, "{}=\"{}\"", key, escape_label(val));
+            }
+            self.out.push('}');
+        }
+        let _ = writeln!(self.out, " {}", format_value(value));
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf".to_string() } else { "-Inf".to_string() }
+    } else if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// سلسلة مسار واحد (طريقة + نمط المسار)
+#[derive(Debug, Default)]
+struct RouteSeries {
+    statuses: BTreeMap<u16, u64>,
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+/// عدادات ومدرجات طلبات HTTP لكل مسار
+#[derive(Debug, Default)]
+pub struct HttpMetrics {
+    series: Mutex<BTreeMap<(String, String), RouteSeries>>,
+}
+
+impl HttpMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// تسجيل طلب منتهٍ؛ `route` هو نمط المسار (مثل /api/v1/factories/{factory_type}) لتجنب تضخم الوسوم
+    pub fn observe(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let mut series = match self.series.lock() {
+            Ok(series) => series,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        let entry = series.entry((method.to_string(), route.to_string())).or_default();
+        *entry.statuses.entry(status).or_insert(0) += 1;
+        entry.count += 1;
+        entry.sum += seconds;
+        for (bucket, bound) in entry.buckets.iter_mut().zip(LATENCY_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+    }
+
+    /// كتابة mh_http_requests_total و mh_http_request_duration_seconds
+    pub fn write(&self, out: &mut MetricsWriter) {
+        let series = match self.series.lock() {
+            Ok(series) => series,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        out.family("mh_http_requests_total", MetricKind::Counter, "عدد طلبات HTTP حسب المسار والحالة");
+        for ((method, route), s) in series.iter() {
+            for (status, count) in &s.statuses {
+                let status = status.to_string();
+                out.sample(
+                    "mh_http_requests_total",
+                    &[("method", method.as_str()), ("route", route.as_str()), ("status", status.as_str())],
+                    *count as f64,
+                );
+            }
+        }
+
+        out.family("mh_http_request_duration_seconds", MetricKind::Histogram, "زمن معالجة طلبات HTTP بالثواني");
+        for ((method, route), s) in series.iter() {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(s.buckets.iter()) {
+                let le = format_value(*bound);
+                out.sample(
+                    "mh_http_request_duration_seconds_bucket",
+                    &[("method", method.as_str()), ("route", route.as_str()), ("le", le.as_str())],
+                    *count as f64,
+                );
+            }
+            out.sample(
+                "mh_http_request_duration_seconds_bucket",
+                &[("method", method.as_str()), ("route", route.as_str()), ("le", "+Inf")],
+                s.count as f64,
+            );
+            out.sample("mh_http_request_duration_seconds_sum", &[("method", method.as_str()), ("route", route.as_str())], s.sum);
+            out.sample("mh_http_request_duration_seconds_count", &[("method", method.as_str()), ("route", route.as_str())], s.count as f64);
+        }
+    }
+}
+
+/// سجل مقاييس HTTP المشترك على مستوى العملية (يغذيه وسيط التسجيل)
+pub fn http_metrics() -> &'static HttpMetrics {
+    static METRICS: OnceLock<HttpMetrics> = OnceLock::new();
+    METRICS.get_or_init(HttpMetrics::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_metrics_exposition() {
+        let metrics = HttpMetrics::new();
+        metrics.observe("GET", "/api/v1/factories/{factory_type}", 200, Duration::from_millis(20));
+        metrics.observe("GET", "/api/v1/factories/{factory_type}", 404, Duration::from_millis(300));
+
+        let mut out = MetricsWriter::new();
+        metrics.write(&mut out);
+        out.family("mh_factory_quality_score", MetricKind::Gauge, "درجة الجودة");
+        out.sample("mh_factory_quality_score", &[("factory_type", "edu\"cation")], 0.9);
+        let text = out.finish();
+
+        assert!(text.contains("# TYPE mh_http_requests_total counter"));
+        assert!(text.contains("mh_http_requests_total{method=\"GET\",route=\"/api/v1/factories/{factory_type}\",status=\"404\"} 1"));
+        assert!(text.contains("mh_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/v1/factories/{factory_type}\",le=\"0.025\"} 1"));
+        assert!(text.contains("mh_http_request_duration_seconds_bucket{method=\"GET\",route=\"/api/v1/factories/{factory_type}\",le=\"+Inf\"} 2"));
+        assert!(text.contains("mh_http_request_duration_seconds_count{method=\"GET\",route=\"/api/v1/factories/{factory_type}\"} 2"));
+        assert!(text.contains("mh_factory_quality_score{factory_type=\"edu\\\"cation\"} 0.9"));
+    }
+
+    #[actix_web::test]
+    async fn test_auth_rejections_reach_request_metrics() {
+        use actix_web::{test, web, App, HttpResponse};
+        use crate::api::middleware::{AuthMiddleware, LoggerMiddleware};
+
+        // ترتيب الخادم: السجل خارج المصادقة
+        let app = test::init_service(
+            App::new()
+                .route("/api/v1/metrics-order-probe", web::get().to(HttpResponse::Ok))
+                .wrap(AuthMiddleware::new())
+                .wrap(LoggerMiddleware::new())
+        ).await;
+        let res = test::call_service(&app, test::TestRequest::get().uri("/api/v1/metrics-order-probe").to_request()).await;
+        assert_eq!(res.status(), 401);
+
+        let mut out = MetricsWriter::new();
+        http_metrics().write(&mut out);
+        assert!(out.finish().contains("mh_http_requests_total{method=\"GET\",route=\"/api/v1/metrics-order-probe\",status=\"401\"} 1"));
+    }
+}
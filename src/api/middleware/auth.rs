@@ -1,21 +1,33 @@
+use actix_web::body::EitherBody;
 use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
 use actix_web::http::header;
-use actix_web::{Error, HttpMessage, HttpResponse};
+use actix_web::{web, Error, HttpMessage, HttpRequest, HttpResponse};
 use futures_util::future::{ok, Ready};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 
 /// هيكل الـ JWT
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
     pub role: String,
 }
 
+/// مسار البث الحي الذي يقبل التوكن في معاملات الاستعلام
+pub const LIVE_STREAM_PATH: &str = "/api/v1/mhos/live";
+
+/// أجزاء عناصر لوحة التحكم التي تجلبها الصفحة عند كل تحديث من البث
+pub const WIDGET_FRAGMENT_PREFIX: &str = "/dashboard/widgets/";
+
+/// دور توكنات البث ومدة صلاحيتها
+pub const STREAM_ROLE: &str = "stream";
+const STREAM_TOKEN_TTL_MINUTES: i64 = 15;
+
 /// وسيط المصادقة
 pub struct AuthMiddleware;
 
@@ -26,8 +38,18 @@ impl AuthMiddleware {
     
     /// توليد توكن JWT
     pub fn generate_token(user_id: &str, role: &str) -> Result<String, jsonwebtoken::errors::Error> {
+        Self::issue(user_id, role, chrono::Duration::hours(24))
+    }
+    
+    /// توكن قصير العمر لبث لوحة التحكم الحي؛ يُمرَّر في `?token=` لأن
+    /// EventSource لا يرسل ترويسة Authorization، ولا يُقبل إلا في مسار البث وأجزاء العناصر
+    pub fn generate_stream_token(user_id: &str) -> Result<String, jsonwebtoken::errors::Error> {
+        Self::issue(user_id, STREAM_ROLE, chrono::Duration::minutes(STREAM_TOKEN_TTL_MINUTES))
+    }
+    
+    fn issue(user_id: &str, role: &str, ttl: chrono::Duration) -> Result<String, jsonwebtoken::errors::Error> {
         let expiration = chrono::Utc::now()
-            .checked_add_signed(chrono::Duration::hours(24))
+            .checked_add_signed(ttl)
             .expect("valid timestamp")
             .timestamp() as usize;
         
@@ -52,6 +74,45 @@ impl AuthMiddleware {
             &Validation::default()
         ).map(|data| data.claims)
     }
+    
+    /// مسارات لا تتطلب مصادقة: الصحة والمقاييس وصفحة لوحة التحكم
+    fn is_public(req: &ServiceRequest) -> bool {
+        let path = req.path();
+        path == "/api/v1/system/health" ||
+            path == "/api/v1/health/live" ||
+            path == "/api/v1/health/ready" ||
+            path == "/metrics" ||
+            path == "/dashboard" ||
+            path.starts_with("/api/v1/learn/") ||
+            req.method() == actix_web::http::Method::OPTIONS
+    }
+    
+    /// المسارات التي تقبل توكن البث: البث الحي وأجزاء العناصر التي يحدّثها
+    fn accepts_stream_token(path: &str) -> bool {
+        path == LIVE_STREAM_PATH || path.starts_with(WIDGET_FRAGMENT_PREFIX)
+    }
+    
+    /// توكن البث من `?token=` (لمسارات البث فقط)
+    fn query_token(req: &ServiceRequest) -> Option<String> {
+        if !Self::accepts_stream_token(req.path()) {
+            return None;
+        }
+        web::Query::<HashMap<String, String>>::from_query(req.query_string()).ok()?
+            .into_inner()
+            .remove("token")
+    }
+    
+    /// مطالبات الطلب: ما أضافه الوسيط أو توكن Bearer صالح في الترويسة
+    pub fn request_claims(req: &HttpRequest) -> Option<Claims> {
+        if let Some(claims) = req.extensions().get::<Claims>() {
+            return Some(claims.clone());
+        }
+        
+        let token = req.headers().get(header::AUTHORIZATION)?
+            .to_str().ok()?
+            .strip_prefix("Bearer ")?;
+        Self::verify_token(token).ok().filter(|claims| claims.role != STREAM_ROLE)
+    }
 }
 
 impl<S, B> Transform<S, ServiceRequest> for AuthMiddleware
@@ -60,7 +121,7 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Transform = AuthMiddlewareInner<S>;
     type InitError = ();
@@ -81,7 +142,7 @@ where
     S::Future: 'static,
     B: 'static,
 {
-    type Response = ServiceResponse<B>;
+    type Response = ServiceResponse<EitherBody<B>>;
     type Error = Error;
     type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
 
@@ -91,12 +152,25 @@ where
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
         // استثناء بعض المسارات من المصادقة
-        let path = req.path();
-        if path == "/api/v1/system/health" || 
-           path.starts_with("/api/v1/learn/") ||
-           req.method() == actix_web::http::Method::OPTIONS {
+        if AuthMiddleware::is_public(&req) {
             let fut = self.service.call(req);
-            return Box::pin(async move { fut.await });
+            return Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) });
+        }
+        
+        // توكن البث في الاستعلام (EventSource لا يرسل ترويسات)؛ التوكنات الكاملة
+        // لا تُقبل هنا حتى لا تظهر في سجلات الوسطاء
+        if let Some(token) = AuthMiddleware::query_token(&req) {
+            return match AuthMiddleware::verify_token(&token) {
+                Ok(claims) if claims.role != STREAM_ROLE => {
+                    Box::pin(async move { Ok(unauthorized(req, "الاستعلام يقبل توكن البث فقط")) })
+                }
+                Ok(claims) => {
+                    req.extensions_mut().insert(claims);
+                    let fut = self.service.call(req);
+                    Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+                }
+                Err(_) => Box::pin(async move { Ok(unauthorized(req, "توكن البث غير صالح أو منتهي الصلاحية")) }),
+            };
         }
         
         // التحقق من وجود توكن
@@ -106,23 +180,19 @@ where
                     let token = &auth_str[7..];
                     
                     match AuthMiddleware::verify_token(token) {
+                        Ok(claims) if claims.role == STREAM_ROLE && !AuthMiddleware::accepts_stream_token(req.path()) => {
+                            // توكن البث لا يفتح بقية الواجهة
+                            return Box::pin(async move { Ok(unauthorized(req, "توكن البث مقصور على البث الحي")) });
+                        }
                         Ok(claims) => {
                             // إضافة المطالبات إلى الطلب
                             req.extensions_mut().insert(claims);
                             let fut = self.service.call(req);
-                            return Box::pin(async move { fut.await });
+                            return Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) });
                         }
                         Err(_) => {
                             // التوكن غير صالح
-                            let fut = async {
-                                Ok(ServiceResponse::new(
-                                    req.into_parts().0,
-                                    HttpResponse::Unauthorized().json(
-                                        crate::api::ApiResponse::<()>::error("توكن غير صالح أو منتهي الصلاحية")
-                                    )
-                                ))
-                            };
-                            return Box::pin(fut);
+                            return Box::pin(async move { Ok(unauthorized(req, "توكن غير صالح أو منتهي الصلاحية")) });
                         }
                     }
                 }
@@ -130,15 +200,13 @@ where
         }
         
         // لا يوجد توكن
-        let fut = async {
-            Ok(ServiceResponse::new(
-                req.into_parts().0,
-                HttpResponse::Unauthorized().json(
-                    crate::api::ApiResponse::<()>::error("مصادقة مطلوبة")
-                )
-            ))
-        };
-        
-        Box::pin(fut)
+        Box::pin(async move { Ok(unauthorized(req, "مصادقة مطلوبة")) })
     }
 }
+
+/// رفض الطلب بـ 401
+fn unauthorized<B>(req: ServiceRequest, message: &str) -> ServiceResponse<EitherBody<B>> {
+    req.into_response(
+        HttpResponse::Unauthorized().json(crate::api::ApiResponse::<()>::error(message))
+    ).map_into_right_body()
+}
@@ -1,10 +1,11 @@
 use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::Error;
+use actix_web::{Error, HttpMessage};
 use futures_util::future::{ok, Ready};
 use std::future::Future;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 use std::time::Instant;
+use crate::core::telemetry::SpanContext;
 
 /// وسيط تسجيل الطلبات
 pub struct LoggerMiddleware;
@@ -54,19 +55,28 @@ where
         let start = Instant::now();
         let method = req.method().clone();
         let path = req.path().to_string();
+        let request_id = req.extensions().get::<SpanContext>()
+            .and_then(|c| c.request_id.clone())
+            .unwrap_or_else(|| "-".to_string());
         let fut = self.service.call(req);
         
         Box::pin(async move {
             let res = fut.await?;
             let duration = start.elapsed();
+            crate::mhos::monitor::request_timings().record(duration);
+            
+            // نمط المسار بدل المسار الفعلي حتى لا تتضخم وسوم Prometheus
+            let route = res.request().match_pattern().unwrap_or_else(|| "unmatched".to_string());
+            crate::api::metrics::http_metrics().observe(method.as_str(), &route, res.status().as_u16(), duration);
             
             // تسجيل الطلب
             log::info!(
-                "{} {} - {} - {}ms",
+                "{} {} - {} - {}ms [{}]",
                 method,
                 path,
                 res.status(),
-                duration.as_millis()
+                duration.as_millis(),
+                request_id
             );
             
             Ok(res)
@@ -1,7 +1,9 @@
 pub mod cors;
 pub mod logger;
 pub mod auth;
+pub mod tracing;
 
 pub use cors::CorsMiddleware;
 pub use logger::LoggerMiddleware;
 pub use auth::AuthMiddleware;
+pub use tracing::TracingMiddleware;
@@ -0,0 +1,107 @@
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::{Error, HttpMessage};
+use futures::future::{ok, Ready};
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use crate::core::telemetry::{self, Span, SpanContext};
+
+/// ترويسة معرف الطلب (تُقبل من العميل أو تُولَّد)
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// وسيط التتبع: يفتح مقطع الخادم لكل طلب وينشر request_id إلى كل المقاطع التابعة
+pub struct TracingMiddleware;
+
+impl TracingMiddleware {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for TracingMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TracingMiddlewareInner<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(TracingMiddlewareInner { service })
+    }
+}
+
+pub struct TracingMiddlewareInner<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for TracingMiddlewareInner<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        // معرف الطلب من العميل إن كان صالحاً، وإلا معرف جديد
+        let request_id = req.headers().get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .filter(|id| !id.is_empty() && id.len() <= 128 && id.chars().all(|c| c.is_ascii_graphic()))
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| format!("req_{}", telemetry::random_hex(8)));
+
+        // متابعة تتبع قادم من خدمة أخرى عبر traceparent
+        let remote = req.headers().get("traceparent")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|h| SpanContext::from_traceparent(h, Some(request_id.clone())));
+
+        let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+        let mut span = Span::server(&format!("{} {}", req.method(), route), remote.as_ref(), request_id.clone());
+        span.set_attribute("http.method", req.method().as_str());
+        span.set_attribute("http.route", route);
+        span.set_attribute("http.target", req.path());
+
+        let context = span.context();
+        req.extensions_mut().insert(context.clone());
+        let fut = telemetry::sync_scope(context.clone(), || self.service.call(req));
+
+        Box::pin(async move {
+            match telemetry::scope(context.clone(), fut).await {
+                Ok(mut res) => {
+                    let status = res.status();
+                    span.set_attribute("http.status_code", status.as_u16());
+                    if status.is_server_error() {
+                        span.set_error(status.to_string());
+                    } else {
+                        span.set_ok();
+                    }
+
+                    let headers = res.headers_mut();
+                    if let Ok(value) = HeaderValue::from_str(&request_id) {
+                        headers.insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+                    }
+                    if let Ok(value) = HeaderValue::from_str(&context.traceparent()) {
+                        headers.insert(HeaderName::from_static("traceparent"), value);
+                    }
+                    Ok(res)
+                }
+                Err(e) => {
+                    span.set_error(e.to_string());
+                    Err(e)
+                }
+            }
+        })
+    }
+}
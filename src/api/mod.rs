@@ -1,40 +1,232 @@
 pub mod handlers;
+pub mod metrics;
 pub mod middleware;
 pub mod routes;
 
 use actix_web::{web, App, HttpServer};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::QualityManager;
+use crate::core::quality_history::QualityHistory;
+use crate::core::health::HealthRegistry;
+use crate::mhos::alerts::{AlertStore, ALERTS_FILE};
+use crate::mhos::knowledge::{KnowledgeBase, KNOWLEDGE_FILE};
+use crate::mhos::layouts::{LayoutStore, LAYOUTS_FILE};
+use crate::mhos::live::LiveHub;
+use crate::mhos::{MhosConfig, MhosSystem, MHOS_CONFIG_FILE};
+use crate::mhos::monitor::SystemMonitor;
+use crate::mhos::optimization_history::{OptimizationHistory, OPTIMIZATIONS_FILE};
+use crate::mhos::optimizer::{MetricSources, Optimizer};
+use crate::mhos::recommendations::RecommendationEngine;
+use crate::mhos::scheduler::{JobStore, JOBS_FILE};
+use crate::mhos::notify::{NotificationConfig, Notifier, NOTIFICATIONS_FILE};
+use crate::mhos::widgets::WidgetContext;
 
 /// خادم API الرئيسي للنظام
 pub struct ApiServer {
     factory_manager: Arc<FactoryManager>,
+    quality_manager: Arc<RwLock<QualityManager>>,
+    monitor: Arc<SystemMonitor>,
+    optimizer: Arc<Optimizer>,
+    live: Arc<LiveHub>,
+    layouts: Arc<RwLock<LayoutStore>>,
+    recommendations: Arc<RecommendationEngine>,
+    knowledge: Arc<RwLock<KnowledgeBase>>,
+    mhos: Arc<MhosSystem>,
+    health: Arc<HealthRegistry>,
     port: u16,
 }
 
 impl ApiServer {
     /// إنشاء مثيل جديد لخادم API
     pub fn new(factory_manager: Arc<FactoryManager>, port: u16) -> Self {
+        let live = Arc::new(LiveHub::new());
+        let knowledge = Arc::new(RwLock::new(Self::open_knowledge()));
+        let quality_manager = Arc::new(RwLock::new(Self::open_quality_manager()));
+        let recommendations = Arc::new(RecommendationEngine::new());
+        let monitor = Arc::new(Self::open_monitor()
+            .with_live_hub(live.clone())
+            .with_knowledge(knowledge.clone())
+            .with_recommendations(recommendations.clone()));
+        let optimizer = Arc::new(Self::open_optimizer().with_knowledge(knowledge.clone()));
+        
+        let mhos = MhosSystem::new(factory_manager.clone())
+            .with_monitor(monitor.clone())
+            .with_optimizer(optimizer.clone())
+            .with_recommendations(recommendations.clone())
+            .with_knowledge(knowledge.clone())
+            .with_quality(quality_manager.clone())
+            .with_job_store(Self::open_jobs());
+        let health = Arc::new(HealthRegistry::with_builtin(factory_manager.clone(), crate::core::config::data_dir())
+            .with_monitor(monitor.clone()));
+        
         Self {
             factory_manager,
+            quality_manager,
+            monitor,
+            optimizer,
+            live,
+            layouts: Arc::new(RwLock::new(Self::open_layouts())),
+            recommendations,
+            knowledge,
+            mhos: Arc::new(Self::open_mhos_config(mhos)),
+            health,
             port,
         }
     }
+    
+    /// تكوين MH-OS المحفوظ في دليل البيانات (تُحفظ فيه التحديثات عبر API)
+    fn open_mhos_config(mhos: MhosSystem) -> MhosSystem {
+        let path = crate::core::config::data_dir().join(MHOS_CONFIG_FILE);
+        
+        let mhos = match MhosConfig::load(&path) {
+            Ok(config) => mhos.with_config(config),
+            Err(e) => {
+                log::error!("❌ تعذر تحميل تكوين MH-OS {}: {} - سيُستخدم التكوين الافتراضي", path.display(), e);
+                mhos
+            }
+        };
+        mhos.with_config_path(path)
+    }
+    
+    /// المهام المجدولة المشتركة مع سطر الأوامر
+    fn open_jobs() -> JobStore {
+        let path = crate::core::config::data_dir().join(JOBS_FILE);
+        
+        JobStore::open(&path).unwrap_or_else(|e| {
+            log::error!("❌ تعذر فتح مخزن المهام {}: {} - سيُستخدم مخزن في الذاكرة", path.display(), e);
+            JobStore::in_memory()
+        })
+    }
+    
+    /// مدير الجودة مع سجل التقارير المحفوظ في دليل البيانات
+    fn open_quality_manager() -> QualityManager {
+        let path = crate::core::config::data_dir().join("quality").join("reports.jsonl");
+        
+        match QualityHistory::open(&path) {
+            Ok(history) => {
+                let mut manager = QualityManager::with_history(history);
+                manager.enable_website_gates();
+                manager
+            }
+            Err(e) => {
+                log::error!("❌ تعذر فتح سجل الجودة {}: {} - سيُستخدم سجل في الذاكرة", path.display(), e);
+                QualityManager::new()
+            }
+        }
+    }
+    
+    /// المحسن مع سجل عملياته المحفوظ في دليل البيانات
+    fn open_optimizer() -> Optimizer {
+        let path = crate::core::config::data_dir().join(OPTIMIZATIONS_FILE);
+        
+        match OptimizationHistory::open(&path) {
+            Ok(history) => Optimizer::new().with_history(history),
+            Err(e) => {
+                log::error!("❌ تعذر فتح سجل التحسين {}: {} - سيُستخدم سجل في الذاكرة", path.display(), e);
+                Optimizer::new()
+            }
+        }
+    }
+    
+    /// تخطيطات لوحة التحكم المحفوظة لكل مستخدم
+    fn open_layouts() -> LayoutStore {
+        let path = crate::core::config::data_dir().join(LAYOUTS_FILE);
+        
+        LayoutStore::open(&path).unwrap_or_else(|e| {
+            log::error!("❌ تعذر فتح مخزن التخطيطات {}: {} - سيُستخدم مخزن في الذاكرة", path.display(), e);
+            LayoutStore::in_memory()
+        })
+    }
+    
+    /// قاعدة معرفة المساعد المشتركة مع سطر الأوامر
+    fn open_knowledge() -> KnowledgeBase {
+        let path = crate::core::config::data_dir().join(KNOWLEDGE_FILE);
+        
+        KnowledgeBase::open(&path).unwrap_or_else(|e| {
+            log::error!("❌ تعذر فتح قاعدة المعرفة {}: {} - ستُستخدم قاعدة في الذاكرة", path.display(), e);
+            KnowledgeBase::with_defaults()
+        })
+    }
+    
+    /// مراقب النظام مع مخزن الإنذارات المشترك مع سطر الأوامر وقنوات الإشعار
+    fn open_monitor() -> SystemMonitor {
+        let data_dir = crate::core::config::data_dir();
+        let path = data_dir.join(ALERTS_FILE);
+        
+        let monitor = match AlertStore::open(&path) {
+            Ok(store) => SystemMonitor::new().with_alert_store(store),
+            Err(e) => {
+                log::error!("❌ تعذر فتح مخزن الإنذارات {}: {} - سيُستخدم مخزن في الذاكرة", path.display(), e);
+                SystemMonitor::new()
+            }
+        };
+        
+        let path = data_dir.join(NOTIFICATIONS_FILE);
+        match NotificationConfig::load(&path) {
+            Ok(config) if !config.routes.is_empty() => {
+                log::info!("📨 تم تحميل {} مسار إشعار", config.routes.len());
+                monitor.with_notifier(Notifier::new(config))
+            }
+            Ok(_) => monitor,
+            Err(e) => {
+                log::error!("❌ تعذر تحميل إعدادات الإشعارات {}: {}", path.display(), e);
+                monitor
+            }
+        }
+    }
 
     /// تشغيل خادم API
     pub async fn run(&self) -> std::io::Result<()> {
         let factory_manager = self.factory_manager.clone();
+        let quality_manager = self.quality_manager.clone();
+        let monitor = self.monitor.clone();
+        let optimizer = self.optimizer.clone();
+        let live = self.live.clone();
+        let layouts = self.layouts.clone();
+        let recommendations = self.recommendations.clone();
+        let knowledge = self.knowledge.clone();
+        let mhos = self.mhos.clone();
+        let scheduler = self.mhos.scheduler();
+        let health = self.health.clone();
         let port = self.port;
         
+        // المراقبة والتحسين التلقائي والنسخ الاحتياطي والمهام المجدولة في الخلفية حسب تكوين MH-OS
+        mhos.start().await;
+        
+        // تقييم تغييرات المحسن بعد نافذة القياس والتراجع عما ساء منها
+        let sources = MetricSources::new(factory_manager.clone(), monitor.clone())
+            .with_quality(quality_manager.clone());
+        tokio::spawn(optimizer.clone().run_evaluations(sources));
+        
+        // بث تحديثات العناصر وحالة المصانع للوحات الحية
+        let widget_context = WidgetContext::new(factory_manager.clone())
+            .with_quality(quality_manager.clone())
+            .with_monitor(monitor.clone());
+        tokio::spawn(live.clone().run(widget_context));
+        
         log::info!("🚀 بدء تشغيل خادم API على المنفذ {}", port);
         
         HttpServer::new(move || {
             App::new()
                 .app_data(web::Data::new(factory_manager.clone()))
+                .app_data(web::Data::new(quality_manager.clone()))
+                .app_data(web::Data::new(monitor.clone()))
+                .app_data(web::Data::new(optimizer.clone()))
+                .app_data(web::Data::new(live.clone()))
+                .app_data(web::Data::new(layouts.clone()))
+                .app_data(web::Data::new(recommendations.clone()))
+                .app_data(web::Data::new(knowledge.clone()))
+                .app_data(web::Data::new(mhos.clone()))
+                .app_data(web::Data::new(scheduler.clone()))
+                .app_data(web::Data::new(health.clone()))
                 .configure(routes::configure)
+                // آخر وسيط يُلف هو الخارجي: التتبع ثم السجل والمقاييس ثم المصادقة،
+                // فتُسجَّل ردود 401/403 في مقاييس الطلبات
                 .wrap(middleware::CorsMiddleware::new())
-                .wrap(middleware::LoggerMiddleware::new())
                 .wrap(middleware::AuthMiddleware::new())
+                .wrap(middleware::LoggerMiddleware::new())
+                .wrap(middleware::TracingMiddleware::new())
         })
         .bind(("0.0.0.0", port))?
         .run()
@@ -3,6 +3,13 @@ use crate::api::handlers;
 
 /// تكوين جميع مسارات API
 pub fn configure(cfg: &mut web::ServiceConfig) {
+    // مقاييس Prometheus خارج نطاق الإصدار كما تتوقعه أدوات الجمع
+    cfg.route("/metrics", web::get().to(handlers::prometheus_metrics));
+    
+    // لوحة التحكم المعروضة من الخادم
+    cfg.route("/dashboard", web::get().to(handlers::dashboard_page));
+    cfg.route("/dashboard/widgets/{widget_id}", web::get().to(handlers::dashboard_widget));
+    
     cfg.service(
         web::scope("/api/v1")
             // مسارات المصانع
@@ -23,6 +30,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             
             // مسارات النظام
             .route("/system/health", web::get().to(handlers::system_health))
+            .route("/health/live", web::get().to(handlers::health_live))
+            .route("/health/ready", web::get().to(handlers::health_ready))
             .route("/system/stats", web::get().to(handlers::system_stats))
             .route("/system/restart", web::post().to(handlers::system_restart))
             
@@ -30,6 +39,38 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
             .route("/mhos/dashboard", web::get().to(handlers::mhos_dashboard))
             .route("/mhos/quality-gates", web::get().to(handlers::mhos_quality_gates))
             .route("/mhos/optimize", web::post().to(handlers::mhos_optimize))
+            .route("/mhos/optimize/history", web::get().to(handlers::mhos_optimize_history))
+            .route("/mhos/live", web::get().to(handlers::mhos_live))
+            .route("/mhos/config", web::get().to(handlers::mhos_config))
+            .route("/mhos/config", web::put().to(handlers::update_mhos_config))
+            .route("/mhos/knowledge", web::get().to(handlers::knowledge_search))
+            .route("/mhos/knowledge", web::post().to(handlers::create_knowledge_entry))
+            .route("/mhos/knowledge/{entry_id}", web::get().to(handlers::knowledge_entry))
+            .route("/mhos/knowledge/{entry_id}", web::put().to(handlers::update_knowledge_entry))
+            .route("/mhos/knowledge/{entry_id}", web::delete().to(handlers::delete_knowledge_entry))
+            .route("/mhos/jobs", web::get().to(handlers::list_jobs))
+            .route("/mhos/jobs", web::post().to(handlers::create_job))
+            .route("/mhos/jobs/runs", web::get().to(handlers::job_runs))
+            .route("/mhos/jobs/{job_id}", web::delete().to(handlers::delete_job))
+            .route("/mhos/jobs/{job_id}/pause", web::post().to(handlers::pause_job))
+            .route("/mhos/jobs/{job_id}/resume", web::post().to(handlers::resume_job))
+            .route("/mhos/jobs/{job_id}/trigger", web::post().to(handlers::trigger_job))
+            .route("/mhos/recommendations", web::get().to(handlers::mhos_recommendations))
+            .route("/mhos/recommendations/{recommendation_id}/dismiss", web::post().to(handlers::mhos_dismiss_recommendation))
+            
+            // تخطيط لوحة التحكم لكل مستخدم
+            .route("/mhos/layout", web::get().to(handlers::get_layout))
+            .route("/mhos/layout/widgets", web::post().to(handlers::add_layout_widget))
+            .route("/mhos/layout/widgets/{widget_id}", web::delete().to(handlers::remove_layout_widget))
+            .route("/mhos/layout/widgets/{widget_id}/move", web::put().to(handlers::move_layout_widget))
+            .route("/mhos/layout/widgets/{widget_id}/resize", web::put().to(handlers::resize_layout_widget))
+            .route("/mhos/layout/export", web::get().to(handlers::export_layout))
+            .route("/mhos/layout/import", web::put().to(handlers::import_layout))
+            .route("/mhos/layout/reset", web::post().to(handlers::reset_layout))
+            .route("/mhos/alerts", web::get().to(handlers::mhos_alerts))
+            .route("/mhos/alerts/test", web::post().to(handlers::mhos_alerts_test))
+            .route("/mhos/alerts/{alert_id}/ack", web::post().to(handlers::mhos_alert_ack))
+            .route("/mhos/alerts/{alert_id}/silence", web::post().to(handlers::mhos_alert_silence))
             
             // مسارات التعلم
             .route("/learn/templates", web::get().to(handlers::list_templates))
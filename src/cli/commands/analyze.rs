@@ -1,50 +1,81 @@
 use std::sync::Arc;
 use crate::core::factory_manager::FactoryManager;
+use crate::analysis::{AccessibilityChecker, SecurityScanner, SeoAnalyzer, TextAnalyzer};
 
 /// تنفيذ أمر التحليل
 pub async fn execute(
     factory_manager: Arc<FactoryManager>,
     content: String,
-    analysis_type: Option<String>
+    analysis_type: Option<String>,
+    keywords: Option<String>
 ) -> Result<(), Box<dyn std::error::Error>> {
     let analysis_type = analysis_type.unwrap_or_else(|| "general".to_string());
     
+    // قبول مسار ملف بدلاً من النص المباشر (مفيد لملفات HTML و Markdown)
+    let content = if std::path::Path::new(&content).is_file() {
+        std::fs::read_to_string(&content)?
+    } else {
+        content
+    };
+    
     println!("🔍 تحليل المحتوى:");
     println!("   نوع التحليل: {}", analysis_type);
-    println!("   طول المحتوى: {} حرف", content.len());
+    println!("   طول المحتوى: {} حرف", content.chars().count());
     println!("   عدد الكلمات: {}", content.split_whitespace().count());
     
     // تحليل المحتوى
     let analysis_result = match analysis_type.as_str() {
         "quality" => {
+            let report = TextAnalyzer::new().analyze(&content);
             serde_json::json!({
                 "analysis_type": "quality",
-                "score": 0.85,
-                "feedback": [
-                    "المحتوى جيد التنظيم",
-                    "اللغة سليمة",
-                    "يمكن إضافة أمثلة توضيحية"
-                ],
-                "suggestions": [
-                    "تحسين العناوين",
-                    "إضافة صور توضيحية",
-                    "تقسيم المحتوى إلى أجزاء أصغر"
-                ],
-                "word_count": content.split_whitespace().count(),
-                "readability_score": 0.72
+                "score": report.score,
+                "feedback": report.feedback,
+                "suggestions": report.suggestions,
+                "word_count": report.statistics.word_count,
+                "readability_score": report.readability.score,
+                "readability_level": report.readability.level.display_name(),
+                "issues": report.issues
             })
         }
         "seo" => {
+            let keywords: Vec<String> = keywords
+                .map(|k| k.split(',').map(|s| s.to_string()).collect())
+                .unwrap_or_default();
+            let report = SeoAnalyzer::new().with_keywords(keywords).analyze(&content);
             serde_json::json!({
                 "analysis_type": "seo",
-                "score": 0.78,
-                "keywords_found": ["تعليم", "تقنية", "إبداع"],
-                "meta_description": "محتوى تعليمي تقني إبداعي",
-                "suggestions": [
-                    "إضافة كلمات مفتاحية أكثر",
-                    "تحسين وصف الميتا",
-                    "تحسين الهيكل للسيو"
-                ]
+                "score": report.score,
+                "format": report.format,
+                "title": report.title,
+                "meta_description": report.meta_description,
+                "headings": report.headings,
+                "internal_links": report.internal_links,
+                "images_without_alt": report.images_without_alt,
+                "lang": report.lang,
+                "dir": report.dir,
+                "keywords": report.keywords,
+                "findings": report.findings,
+                "suggestions": report.suggestions
+            })
+        }
+        "accessibility" | "a11y" => {
+            let report = AccessibilityChecker::new().check(&content);
+            serde_json::json!({
+                "analysis_type": "accessibility",
+                "score": report.score,
+                "critical_count": report.critical_count,
+                "rules": report.rules,
+                "violations": report.violations
+            })
+        }
+        "security" => {
+            let report = SecurityScanner::new().scan(&content);
+            serde_json::json!({
+                "analysis_type": "security",
+                "score": report.score,
+                "highest_severity": report.highest_severity,
+                "findings": report.findings
             })
         }
         "sentiment" => {
@@ -65,20 +96,18 @@ pub async fn execute(
             })
         }
         _ => {
+            let report = TextAnalyzer::new().analyze(&content);
             serde_json::json!({
                 "analysis_type": "general",
-                "overall_score": 0.80,
-                "sections": [
-                    {"name": "التنظيم", "score": 0.85},
-                    {"name": "اللغة", "score": 0.90},
-                    {"name": "المحتوى", "score": 0.75},
-                    {"name": "التنسيق", "score": 0.70}
-                ],
-                "recommendations": [
-                    "تحسين تنسيق المحتوى",
-                    "إضافة عناوين فرعية",
-                    "التحقق من الأخطاء الإملائية"
-                ]
+                "overall_score": report.score,
+                "statistics": report.statistics,
+                "readability": {
+                    "index": report.readability.index,
+                    "level": report.readability.level.display_name()
+                },
+                "script": report.script,
+                "top_words": report.top_words,
+                "recommendations": report.suggestions
             })
         }
     };
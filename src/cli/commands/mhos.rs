@@ -1,6 +1,18 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 use crate::core::factory_manager::FactoryManager;
-use crate::cli::MhosCommands;
+use crate::core::quality::QualityManager;
+use crate::core::quality_history::QualityHistory;
+use crate::cli::{AlertCommands, JobCommands, KnowledgeCommands, MhosCommands};
+use crate::cli::tui::TuiOptions;
+use crate::mhos::alerts::{AlertState, AlertStore, ALERTS_FILE};
+use crate::mhos::knowledge::{KnowledgeBase, KnowledgeDraft, KnowledgeEntry, KNOWLEDGE_FILE};
+use crate::mhos::monitor::SystemMonitor;
+use crate::mhos::notify::{NotificationConfig, Notifier, NOTIFICATIONS_FILE};
+use crate::mhos::optimization_history::{OptimizationHistory, OPTIMIZATIONS_FILE};
+use crate::mhos::optimizer::{ChangeOutcome, MetricSources, Optimizer};
+use crate::mhos::quality_gates::QualityGates;
+use crate::mhos::scheduler::{JobDraft, JobKind, JobOutcome, JobStore, MhosJobExecutor, ScheduledJob, Scheduler, JOBS_FILE};
+use crate::mhos::{MhosConfig, MHOS_CONFIG_FILE};
 
 /// تنفيذ أوامر MH-OS
 pub async fn execute(
@@ -8,7 +20,15 @@ pub async fn execute(
     subcommand: MhosCommands
 ) -> Result<(), Box<dyn std::error::Error>> {
     match subcommand {
-        MhosCommands::Dashboard => {
+        MhosCommands::Dashboard { tui: true, interval, no_bidi } => {
+            let options = TuiOptions {
+                interval: std::time::Duration::from_secs(interval.max(1)),
+                bidi: !no_bidi,
+            };
+            crate::cli::tui::run(factory_manager, options).await?;
+        }
+        
+        MhosCommands::Dashboard { .. } => {
             println!("🖥️  لوحة تحكم MH-OS v2.2");
             println!("{:=<50}", "");
             
@@ -68,26 +88,8 @@ pub async fn execute(
             }
         }
         
-        MhosCommands::Optimize => {
-            println!("⚡ بدء عملية تحسين MH-OS...");
-            println!("⏳ جاري تحليل النظام...");
-            
-            // محاكاة عملية التحسين
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-            println!("🔍 تحليل الأداء...");
-            
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-            println!("🔄 تحسين الخوارزميات...");
-            
-            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
-            println!("🧹 تنظيف الذاكرة المؤقتة...");
-            
-            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
-            println!("✅ تم الانتهاء من التحسين!");
-            println!("📈 التحسينات المطبقة:");
-            println!("   • تحسين سرعة المعالجة: +15%");
-            println!("   • تقليل استخدام الذاكرة: -20%");
-            println!("   • تحسين دقة الجودة: +8%");
+        MhosCommands::Optimize { dry_run } => {
+            execute_optimize(factory_manager, dry_run).await?;
         }
         
         MhosCommands::Analyze => {
@@ -114,7 +116,342 @@ pub async fn execute(
             
             println!("{}", serde_json::to_string_pretty(&analysis).unwrap());
         }
+        
+        MhosCommands::Alerts { subcommand } => {
+            execute_alerts(subcommand).await?;
+        }
+        
+        MhosCommands::Knowledge { subcommand } => {
+            execute_knowledge(subcommand)?;
+        }
+        
+        MhosCommands::Jobs { subcommand } => {
+            execute_jobs(factory_manager, subcommand).await?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// تحسين النظام على المقاييس الحالية (أو عرض التحسينات المتوقعة فقط)
+async fn execute_optimize(
+    factory_manager: Arc<FactoryManager>,
+    dry_run: bool
+) -> Result<(), Box<dyn std::error::Error>> {
+    let data_dir = crate::core::config::data_dir();
+    let history = QualityHistory::open(data_dir.join("quality").join("reports.jsonl"))?;
+    let sources = MetricSources::new(factory_manager, Arc::new(SystemMonitor::new()))
+        .with_quality(Arc::new(RwLock::new(QualityManager::with_history(history))));
+    
+    let result = if dry_run {
+        println!("🔍 تشغيل تجريبي لتحسين MH-OS (لن يُطبق أي تغيير)...");
+        Optimizer::new().dry_run(&sources).await
+    } else {
+        println!("⚡ بدء عملية تحسين MH-OS...");
+        let optimizer = Optimizer::new()
+            .with_history(OptimizationHistory::open(data_dir.join(OPTIMIZATIONS_FILE))?);
+        optimizer.optimize(&sources).await
+    };
+    
+    let snapshot = &result.snapshot;
+    println!("📊 المقاييس: الاستجابة {:.0}ms | المعالج {:.0}% | الذاكرة {:.0}%",
+        snapshot.response_time_ms, snapshot.cpu_usage * 100.0, snapshot.memory_usage * 100.0);
+    
+    if result.improvements.is_empty() {
+        println!("✅ لا توجد تحسينات مطلوبة حالياً");
+        return Ok(());
+    }
+    
+    println!("{}", if dry_run { "📋 التحسينات المتوقعة:" } else { "📈 التحسينات المطبقة:" });
+    for improvement in &result.improvements {
+        let note = match improvement.outcome {
+            ChangeOutcome::Pending => " (بانتظار نافذة القياس)",
+            _ => "",
+        };
+        println!("   • [{}] {} - التأثير المتوقع: +{:.0}%{}",
+            improvement.area, improvement.action, improvement.impact * 100.0, note);
+        println!("     {}", improvement.details);
+    }
+    println!("\n📈 تحسين الأداء المتوقع: {:.1}%", result.performance_gain * 100.0);
+    println!("⭐ تحسين الجودة المتوقع: {:.1}%", result.quality_improvement * 100.0);
+    if !dry_run {
+        println!("🆔 معرف التشغيل: {}", result.run_id);
+    }
+    
+    Ok(())
+}
+
+/// أوامر الإنذارات (على المخزن المشترك مع خادم API)
+async fn execute_alerts(subcommand: AlertCommands) -> Result<(), Box<dyn std::error::Error>> {
+    let data_dir = crate::core::config::data_dir();
+    let open_store = || AlertStore::open(data_dir.join(ALERTS_FILE));
+    
+    match subcommand {
+        AlertCommands::List { state } => {
+            let store = open_store()?;
+            let state = match state.as_deref() {
+                Some(value) => Some(AlertState::parse(value)
+                    .ok_or_else(|| format!("حالة إنذار غير معروفة: {}", value))?),
+                None => None,
+            };
+            
+            let alerts = store.list(state);
+            println!("🚨 الإنذارات ({})", alerts.len());
+            println!("{:-<50}", "");
+            
+            if alerts.is_empty() {
+                println!("   ✅ لا توجد إنذارات");
+            }
+            
+            let now = chrono::Utc::now();
+            for alert in alerts {
+                let icon = match alert.state {
+                    AlertState::Open => "🔴",
+                    AlertState::Acknowledged => "🟡",
+                    AlertState::Resolved => "🟢",
+                };
+                let silenced = if alert.is_silenced(now) { " 🔕" } else { "" };
+                println!("   {} [{}] {} - {} ({}){}",
+                    icon, alert.severity.severity_level(), alert.title, alert.message, alert.state.as_str(), silenced);
+                println!("      المعرف: {} | التكرار: {} | آخر ظهور: {}",
+                    alert.id, alert.occurrences, alert.last_seen.format("%Y-%m-%d %H:%M:%S"));
+            }
+        }
+        
+        AlertCommands::Ack { id, by } => {
+            let mut store = open_store()?;
+            let by = by.unwrap_or_else(|| std::env::var("USER").unwrap_or_else(|_| "cli".to_string()));
+            let alert = store.acknowledge(&id, &by)?;
+            store.save()?;
+            println!("👍 تم الإقرار بالإنذار: {} ({})", alert.title, alert.id);
+        }
+        
+        AlertCommands::Silence { id, minutes } => {
+            if minutes <= 0 {
+                return Err("مدة الإسكات يجب أن تكون موجبة".into());
+            }
+            let mut store = open_store()?;
+            let alert = store.silence(&id, chrono::Duration::minutes(minutes))?;
+            store.save()?;
+            println!("🔕 تم إسكات الإنذار: {} لمدة {} دقيقة", alert.title, minutes);
+        }
+        
+        AlertCommands::Test { route } => {
+            let path = data_dir.join(NOTIFICATIONS_FILE);
+            let config = NotificationConfig::load(&path)?;
+            if config.routes.is_empty() {
+                println!("⚠️  لا توجد قنوات إشعار في {}", path.display());
+                return Ok(());
+            }
+            
+            println!("📨 إرسال إنذار تجريبي...");
+            let reports = Notifier::new(config).send_test(route.as_deref()).await;
+            if reports.is_empty() {
+                println!("❌ مسار الإشعار غير موجود");
+            }
+            for report in reports {
+                match report.error {
+                    None => println!("   ✅ {} ({}) - {} محاولة", report.route, report.channel, report.attempts),
+                    Some(error) => println!("   ❌ {} ({}) - {}", report.route, report.channel, error),
+                }
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// أوامر قاعدة المعرفة (على الملف المشترك مع خادم API)
+fn execute_knowledge(subcommand: KnowledgeCommands) -> Result<(), Box<dyn std::error::Error>> {
+    let mut base = KnowledgeBase::open(crate::core::config::data_dir().join(KNOWLEDGE_FILE))?;
+    let split = |value: Option<String>| -> Vec<String> {
+        value.map(|v| v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default()
+    };
+    
+    match subcommand {
+        KnowledgeCommands::Search { query, factory_type, limit } => {
+            let hits = base.search(&query, factory_type.as_deref(), limit.max(1));
+            println!("🔎 نتائج البحث عن \"{}\" ({})", query, hits.len());
+            println!("{:-<50}", "");
+            
+            if hits.is_empty() {
+                println!("   لا توجد نتائج");
+            }
+            for hit in hits {
+                print_knowledge_entry(&hit.entry);
+                println!("      الصلة: {:.2}", hit.score);
+            }
+        }
+        
+        KnowledgeCommands::List { factory_type } => {
+            let entries = base.list(factory_type.as_deref());
+            println!("🧠 قاعدة المعرفة ({})", entries.len());
+            println!("{:-<50}", "");
+            for entry in &entries {
+                print_knowledge_entry(entry);
+            }
+        }
+        
+        KnowledgeCommands::Add { topic, content, tags, factory_types } => {
+            let draft = KnowledgeDraft {
+                topic,
+                content,
+                tags: split(tags),
+                factory_types: split(factory_types),
+            };
+            let entry = base.add(draft)?;
+            println!("✅ تمت إضافة المدخل: {} ({})", entry.topic, entry.id);
+        }
+        
+        KnowledgeCommands::Update { id, topic, content, tags, factory_types } => {
+            let current = base.get(&id).cloned()
+                .ok_or_else(|| format!("المدخل غير موجود: {}", id))?;
+            let draft = KnowledgeDraft {
+                topic: topic.unwrap_or(current.topic),
+                content: content.unwrap_or(current.content),
+                tags: if tags.is_some() { split(tags) } else { current.tags },
+                factory_types: if factory_types.is_some() { split(factory_types) } else { current.factory_types },
+            };
+            let entry = base.update(&id, draft)?;
+            println!("✏️  تم تعديل المدخل: {} ({})", entry.topic, entry.id);
+        }
+        
+        KnowledgeCommands::Remove { id } => {
+            let entry = base.remove(&id)?;
+            println!("🗑️  تم حذف المدخل: {} ({})", entry.topic, entry.id);
+        }
     }
     
     Ok(())
 }
+
+/// أوامر المهام المجدولة (على الملف المشترك مع خادم API)
+async fn execute_jobs(
+    factory_manager: Arc<FactoryManager>,
+    subcommand: JobCommands
+) -> Result<(), Box<dyn std::error::Error>> {
+    let data_dir = crate::core::config::data_dir();
+    let mut store = JobStore::open(data_dir.join(JOBS_FILE))?;
+    
+    match subcommand {
+        JobCommands::List => {
+            let jobs = store.list();
+            println!("🗓️  المهام المجدولة ({})", jobs.len());
+            println!("{:-<50}", "");
+            
+            if jobs.is_empty() {
+                println!("   لا توجد مهام");
+            }
+            for job in jobs {
+                print_job(job);
+            }
+        }
+        
+        JobCommands::Add { name, schedule, kind, factory_type, input, hours, paused } => {
+            let kind = match kind.as_str() {
+                "manufacture_batch" => JobKind::ManufactureBatch {
+                    factory_type: factory_type.ok_or("نوع المصنع مطلوب لتصنيع الدفعة (--factory-type)")?,
+                    // المدخل الذي ليس JSON صالحاً يُعامل كنص
+                    inputs: input.iter()
+                        .map(|value| serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.clone())))
+                        .collect(),
+                    parameters: serde_json::Value::Null,
+                },
+                "quality_audit" => JobKind::QualityAudit,
+                "optimize" => JobKind::Optimize,
+                "backup" => JobKind::Backup,
+                "report_export" => JobKind::ReportExport { hours },
+                other => return Err(format!("نوع مهمة غير معروف: {}", other).into()),
+            };
+            
+            let mut draft = JobDraft::new(name, schedule, kind);
+            draft.paused = paused;
+            let job = store.add(draft)?;
+            println!("✅ تمت إضافة المهمة: {} ({})", job.name, job.id);
+            if let Some(next_run) = job.next_run {
+                println!("   الموعد التالي: {}", next_run.format("%Y-%m-%d %H:%M UTC"));
+            }
+        }
+        
+        JobCommands::Pause { id } => {
+            let job = store.set_paused(&id, true)?;
+            println!("⏸️  تم إيقاف المهمة: {} ({})", job.name, job.id);
+        }
+        
+        JobCommands::Resume { id } => {
+            let job = store.set_paused(&id, false)?;
+            println!("▶️  تم استئناف المهمة: {} ({})", job.name, job.id);
+        }
+        
+        JobCommands::Trigger { id } => {
+            let config = MhosConfig::load(data_dir.join(MHOS_CONFIG_FILE))?;
+            let quality_gates = QualityGates::new();
+            quality_gates.set_quality_threshold(config.quality_threshold);
+            let optimizer = Optimizer::new()
+                .with_history(OptimizationHistory::open(data_dir.join(OPTIMIZATIONS_FILE))?);
+            let history = QualityHistory::open(data_dir.join("quality").join("reports.jsonl"))?;
+            let sources = MetricSources::new(factory_manager, Arc::new(SystemMonitor::new()))
+                .with_quality(Arc::new(RwLock::new(QualityManager::with_history(history))));
+            let (_config, receiver) = tokio::sync::watch::channel(config);
+            
+            let executor = MhosJobExecutor::new(sources, Arc::new(optimizer), Arc::new(quality_gates), receiver);
+            let scheduler = Arc::new(Scheduler::new(store, Arc::new(executor)));
+            
+            println!("🚀 تشغيل المهمة {}...", id);
+            let run = scheduler.run_now(&id).await?;
+            let icon = if run.outcome == JobOutcome::Succeeded { "✅" } else { "❌" };
+            println!("{} {} - {} ({}ms)", icon, run.job_name, run.message, run.duration_ms);
+            if let Some(output) = run.output {
+                println!("   المخرجات: {}", output);
+            }
+        }
+        
+        JobCommands::History { id, limit } => {
+            let runs = store.runs(id.as_deref(), limit.max(1));
+            println!("📜 سجل تشغيل المهام ({})", runs.len());
+            println!("{:-<50}", "");
+            
+            if runs.is_empty() {
+                println!("   لا توجد سجلات");
+            }
+            for run in runs {
+                let icon = match run.outcome {
+                    JobOutcome::Succeeded => "✅",
+                    JobOutcome::Failed => "❌",
+                    JobOutcome::Skipped => "⏭️",
+                };
+                println!("   {} {} [{}] - {} ({}ms)",
+                    icon, run.job_name, run.outcome.as_str(), run.message, run.duration_ms);
+                println!("      {} | {}", run.id, run.started_at.format("%Y-%m-%d %H:%M:%S"));
+            }
+        }
+        
+        JobCommands::Remove { id } => {
+            let job = store.remove(&id)?;
+            println!("🗑️  تم حذف المهمة: {} ({})", job.name, job.id);
+        }
+    }
+    
+    Ok(())
+}
+
+fn print_job(job: &ScheduledJob) {
+    let icon = if job.paused { "⏸️" } else { "🟢" };
+    println!("   {} {} [{}] - {} ({})", icon, job.name, job.id, job.kind.display_name(), job.schedule);
+    let next_run = job.next_run
+        .map(|at| at.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let last = match (job.last_run, job.last_outcome) {
+        (Some(at), Some(outcome)) => format!("{} ({})", at.format("%Y-%m-%d %H:%M UTC"), outcome.as_str()),
+        _ => "-".to_string(),
+    };
+    println!("      التالي: {} | الأخير: {}", next_run, last);
+}
+
+fn print_knowledge_entry(entry: &KnowledgeEntry) {
+    let scope = if entry.factory_types.is_empty() { "عام".to_string() } else { entry.factory_types.join(", ") };
+    println!("   📘 {} [{}]", entry.topic, entry.id);
+    println!("      {}", entry.content);
+    println!("      النطاق: {} | الوسوم: {} | الرصد: {}", scope, entry.tags.join(", "), entry.observations);
+}
@@ -1,4 +1,5 @@
 pub mod commands;
+pub mod tui;
 
 use clap::{Parser, Subcommand};
 use std::sync::Arc;
@@ -74,11 +75,14 @@ pub enum Commands {
     
     /// التحليل والتقييم
     Analyze {
-        #[arg(help = "المحتوى المراد تحليله")]
+        #[arg(help = "المحتوى المراد تحليله أو مسار ملف")]
         content: String,
         
         #[arg(short, long, help = "نوع التحليل")]
         analysis_type: Option<String>,
+        
+        #[arg(short, long, help = "الكلمات المفتاحية المستهدفة مفصولة بفواصل (لتحليل SEO)")]
+        keywords: Option<String>,
     },
     
     /// إدارة النظام
@@ -138,16 +142,205 @@ pub enum FactoryCommands {
 #[derive(Subcommand)]
 pub enum MhosCommands {
     /// عرض لوحة التحكم
-    Dashboard,
+    Dashboard {
+        #[arg(long, help = "لوحة تفاعلية بملء الشاشة تتحدث دورياً")]
+        tui: bool,
+        
+        #[arg(long, default_value = "2", help = "فترة التحديث بالثواني (مع --tui)")]
+        interval: u64,
+        
+        #[arg(long, help = "عدم إعادة ترتيب النص العربي (للطرفيات الداعمة لـ bidi)")]
+        no_bidi: bool,
+    },
     
     /// فحص بوابات الجودة
     QualityGates,
     
     /// تحسين النظام
-    Optimize,
+    Optimize {
+        #[arg(long, help = "عرض التحسينات المتوقعة دون تطبيقها")]
+        dry_run: bool,
+    },
     
     /// تحليل الأداء
     Analyze,
+    
+    /// إدارة الإنذارات
+    Alerts {
+        #[command(subcommand)]
+        subcommand: AlertCommands,
+    },
+    
+    /// قاعدة معرفة المساعد الذكي
+    Knowledge {
+        #[command(subcommand)]
+        subcommand: KnowledgeCommands,
+    },
+    
+    /// المهام المجدولة
+    Jobs {
+        #[command(subcommand)]
+        subcommand: JobCommands,
+    },
+}
+
+/// أوامر المهام المجدولة
+#[derive(Subcommand)]
+pub enum JobCommands {
+    /// سرد المهام
+    List,
+    
+    /// إضافة مهمة
+    Add {
+        #[arg(help = "اسم المهمة")]
+        name: String,
+        
+        #[arg(help = "تعبير cron بتوقيت UTC (مثل \"0 3 * * *\" أو @daily)")]
+        schedule: String,
+        
+        #[arg(short, long, help = "نوع المهمة (manufacture_batch, quality_audit, optimize, backup, report_export)")]
+        kind: String,
+        
+        #[arg(short, long, help = "نوع المصنع (لتصنيع الدفعة)")]
+        factory_type: Option<String>,
+        
+        #[arg(short, long, help = "مدخل للدفعة بصيغة JSON أو نص (يتكرر)")]
+        input: Vec<String>,
+        
+        #[arg(long, default_value = "24", help = "فترة التقرير بالساعات (لتصدير التقرير)")]
+        hours: u32,
+        
+        #[arg(long, help = "إضافة المهمة موقوفة")]
+        paused: bool,
+    },
+    
+    /// إيقاف مهمة مؤقتاً
+    Pause {
+        #[arg(help = "معرف المهمة")]
+        id: String,
+    },
+    
+    /// استئناف مهمة
+    Resume {
+        #[arg(help = "معرف المهمة")]
+        id: String,
+    },
+    
+    /// تشغيل مهمة الآن وانتظار نتيجتها
+    Trigger {
+        #[arg(help = "معرف المهمة")]
+        id: String,
+    },
+    
+    /// سجل التشغيل
+    History {
+        #[arg(help = "معرف المهمة (الافتراضي: كل المهام)")]
+        id: Option<String>,
+        
+        #[arg(short, long, default_value = "20", help = "عدد السجلات")]
+        limit: usize,
+    },
+    
+    /// حذف مهمة
+    Remove {
+        #[arg(help = "معرف المهمة")]
+        id: String,
+    },
+}
+
+/// أوامر قاعدة المعرفة
+#[derive(Subcommand)]
+pub enum KnowledgeCommands {
+    /// البحث في قاعدة المعرفة
+    Search {
+        #[arg(help = "نص البحث")]
+        query: String,
+        
+        #[arg(short, long, help = "نوع المصنع")]
+        factory_type: Option<String>,
+        
+        #[arg(short, long, default_value = "5", help = "عدد النتائج")]
+        limit: usize,
+    },
+    
+    /// سرد المداخل
+    List {
+        #[arg(short, long, help = "نوع المصنع")]
+        factory_type: Option<String>,
+    },
+    
+    /// إضافة مدخل
+    Add {
+        #[arg(help = "الموضوع")]
+        topic: String,
+        
+        #[arg(help = "المحتوى")]
+        content: String,
+        
+        #[arg(short, long, help = "الوسوم مفصولة بفواصل")]
+        tags: Option<String>,
+        
+        #[arg(short, long, help = "أنواع المصانع مفصولة بفواصل (الافتراضي: عام)")]
+        factory_types: Option<String>,
+    },
+    
+    /// تعديل مدخل
+    Update {
+        #[arg(help = "معرف المدخل")]
+        id: String,
+        
+        #[arg(long, help = "الموضوع الجديد")]
+        topic: Option<String>,
+        
+        #[arg(long, help = "المحتوى الجديد")]
+        content: Option<String>,
+        
+        #[arg(short, long, help = "الوسوم مفصولة بفواصل")]
+        tags: Option<String>,
+        
+        #[arg(short, long, help = "أنواع المصانع مفصولة بفواصل")]
+        factory_types: Option<String>,
+    },
+    
+    /// حذف مدخل
+    Remove {
+        #[arg(help = "معرف المدخل")]
+        id: String,
+    },
+}
+
+/// أوامر الإنذارات
+#[derive(Subcommand)]
+pub enum AlertCommands {
+    /// سرد الإنذارات
+    List {
+        #[arg(short, long, help = "تصفية حسب الحالة (open, acknowledged, resolved)")]
+        state: Option<String>,
+    },
+    
+    /// الإقرار بإنذار
+    Ack {
+        #[arg(help = "معرف الإنذار")]
+        id: String,
+        
+        #[arg(short, long, help = "اسم المُقِر")]
+        by: Option<String>,
+    },
+    
+    /// إسكات إنذار
+    Silence {
+        #[arg(help = "معرف الإنذار")]
+        id: String,
+        
+        #[arg(short, long, default_value = "60", help = "مدة الإسكات بالدقائق")]
+        minutes: i64,
+    },
+    
+    /// إرسال إنذار تجريبي إلى قنوات الإشعار
+    Test {
+        #[arg(short, long, help = "اسم مسار الإشعار (الافتراضي: كل المسارات)")]
+        route: Option<String>,
+    },
 }
 
 /// أوامر النظام
@@ -196,8 +389,8 @@ pub async fn run_cli() -> Result<(), Box<dyn std::error::Error>> {
         Commands::Generate { template, params, lang } => {
             commands::generate::execute(factory_manager, template, params, lang).await
         }
-        Commands::Analyze { content, analysis_type } => {
-            commands::analyze::execute(factory_manager, content, analysis_type).await
+        Commands::Analyze { content, analysis_type, keywords } => {
+            commands::analyze::execute(factory_manager, content, analysis_type, keywords).await
         }
         Commands::System { subcommand } => {
             commands::system::execute(factory_manager, subcommand).await
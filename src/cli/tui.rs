@@ -0,0 +1,473 @@
+//! لوحة تحكم MH-OS في الطرفية
+//!
+//! واجهة بملء الشاشة تعرض المصانع وحالاتها ومخططات الأداء الحية والإنذارات
+//! النشطة وآخر تقرير جودة، وتتحدث كل فترة. الطرفيات الشائعة لا تطبق
+//! خوارزمية الاتجاه الثنائي (bidi)، فيُعاد ترتيب النص العربي بصرياً قبل
+//! الرسم ما لم تُعطَّل إعادة الترتيب للطرفيات التي تطبقها بنفسها.
+
+use std::collections::VecDeque;
+use std::io;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Sparkline, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use unicode_bidi::BidiInfo;
+use crate::core::factory::FactoryStatus;
+use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::{QualityManager, QualityReport};
+use crate::mhos::alerts::{AlertState, AlertStore};
+use crate::mhos::monitor::{PerformanceMetrics, SystemMonitor};
+
+/// عدد العينات المعروضة في كل مخطط
+const SPARKLINE_POINTS: usize = 120;
+
+const ZERO_WIDTH_NON_JOINER: char = '\u{200C}';
+
+/// مخطط: العنوان ودالة القيمة واللون
+type MetricSeries = (&'static str, fn(&PerformanceMetrics) -> f32, Color);
+
+/// خيارات اللوحة
+#[derive(Debug, Clone)]
+pub struct TuiOptions {
+    /// فترة التحديث
+    pub interval: Duration,
+    /// إعادة ترتيب النص العربي بصرياً (تُعطَّل للطرفيات الداعمة لـ bidi)
+    pub bidi: bool,
+}
+
+impl Default for TuiOptions {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(2),
+            bidi: true,
+        }
+    }
+}
+
+/// ترتيب النص بصرياً من اليسار إلى اليمين حسب خوارزمية bidi
+///
+/// بعد العكس قد تتجاور لام ثم ألف فتُحسب عرضاً واحداً كأنها لام ألف
+/// فيُقص آخر حرف من السطر؛ فاصل عدم الوصل بينهما يمنع ذلك.
+pub fn visual_order(text: &str) -> String {
+    let bidi = BidiInfo::new(text, None);
+    let visual: String = bidi.paragraphs.iter()
+        .map(|paragraph| bidi.reorder_line(paragraph, paragraph.range.clone()).into_owned())
+        .collect();
+
+    let mut output = String::with_capacity(visual.len());
+    let mut chars = visual.chars().peekable();
+    while let Some(c) = chars.next() {
+        output.push(c);
+        if c == 'ل' && matches!(chars.peek(), Some('ا' | 'أ' | 'إ' | 'آ')) {
+            output.push(ZERO_WIDTH_NON_JOINER);
+        }
+    }
+    output
+}
+
+/// صف مصنع في الجدول
+#[derive(Debug, Clone)]
+struct FactoryRow {
+    factory_type: String,
+    name: String,
+    status: FactoryStatus,
+    total_requests: u64,
+    failed_requests: u64,
+    queue_size: usize,
+    avg_ms: f64,
+}
+
+/// إنذار معروض
+#[derive(Debug, Clone)]
+struct AlertRow {
+    severity: &'static str,
+    title: String,
+    message: String,
+    acknowledged: bool,
+}
+
+/// حالة اللوحة
+pub struct TuiApp {
+    factory_manager: Arc<FactoryManager>,
+    monitor: Arc<SystemMonitor>,
+    quality: QualityManager,
+    alerts: AlertStore,
+    options: TuiOptions,
+    factories: Vec<FactoryRow>,
+    table: TableState,
+    samples: VecDeque<PerformanceMetrics>,
+    active_alerts: Vec<AlertRow>,
+    frozen: bool,
+    message: String,
+    last_refresh: Option<Instant>,
+}
+
+impl TuiApp {
+    pub fn new(
+        factory_manager: Arc<FactoryManager>,
+        monitor: Arc<SystemMonitor>,
+        quality: QualityManager,
+        alerts: AlertStore,
+        options: TuiOptions
+    ) -> Self {
+        let mut app = Self {
+            factory_manager,
+            monitor,
+            quality,
+            alerts,
+            options,
+            factories: Vec::new(),
+            table: TableState::default(),
+            samples: VecDeque::with_capacity(SPARKLINE_POINTS),
+            active_alerts: Vec::new(),
+            frozen: false,
+            message: "q خروج · ↑↓ اختيار · p إيقاف/استئناف · c فحص الجودة · مسافة تجميد · r تحديث".to_string(),
+            last_refresh: None,
+        };
+        app.refresh();
+        app.table.select(Some(0));
+        app
+    }
+
+    /// نص جاهز للرسم
+    fn text(&self, text: &str) -> String {
+        if self.options.bidi { visual_order(text) } else { text.to_string() }
+    }
+
+    /// تحديث البيانات من المصانع والمراقب والمخازن
+    pub fn refresh(&mut self) {
+        let metrics = self.factory_manager.factory_metrics();
+        self.factories = self.factory_manager.list_factories().iter().map(|factory| {
+            let factory_type = factory.factory_type().to_string();
+            let stats = metrics.get(&factory_type);
+            FactoryRow {
+                status: self.factory_manager.factory_status(&factory_type),
+                name: factory.name().to_string(),
+                total_requests: stats.map(|m| m.total_requests).unwrap_or(0),
+                failed_requests: stats.map(|m| m.failed_requests).unwrap_or(0),
+                queue_size: stats.map(|m| m.current_queue_size).unwrap_or(0),
+                avg_ms: stats.map(|m| m.avg_processing_time_ms).unwrap_or(0.0),
+                factory_type,
+            }
+        }).collect();
+
+        if let Some(latest) = self.monitor.latest_metrics() {
+            if self.samples.back().map(|s| s.timestamp) != Some(latest.timestamp) {
+                if self.samples.len() == SPARKLINE_POINTS {
+                    self.samples.pop_front();
+                }
+                self.samples.push_back(latest);
+            }
+        }
+
+        if let Err(e) = self.alerts.reload_if_changed() {
+            self.message = format!("تعذر تحميل الإنذارات: {}", e);
+        }
+        self.active_alerts = self.alerts.active().into_iter().map(|alert| AlertRow {
+            severity: alert.severity.severity_level(),
+            title: alert.title.clone(),
+            message: alert.message.clone(),
+            acknowledged: alert.state == AlertState::Acknowledged,
+        }).collect();
+
+        self.last_refresh = Some(Instant::now());
+    }
+
+    fn selected_factory(&self) -> Option<&FactoryRow> {
+        self.table.selected().and_then(|index| self.factories.get(index))
+    }
+
+    /// معالجة مفتاح؛ `false` عند طلب الخروج
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = self.factories.len().saturating_sub(1);
+                self.table.select(Some(self.table.selected().map(|i| (i + 1).min(last)).unwrap_or(0)));
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.table.select(Some(self.table.selected().map(|i| i.saturating_sub(1)).unwrap_or(0)));
+            }
+            KeyCode::Char('p') => self.toggle_pause(),
+            KeyCode::Char('c') => self.run_quality_check(),
+            KeyCode::Char(' ') => {
+                self.frozen = !self.frozen;
+                self.message = if self.frozen { "التحديث مجمد" } else { "تم استئناف التحديث" }.to_string();
+            }
+            KeyCode::Char('r') => self.refresh(),
+            _ => {}
+        }
+        true
+    }
+
+    /// إيقاف المصنع المحدد مؤقتاً أو استئنافه
+    fn toggle_pause(&mut self) {
+        let factory_type = match self.selected_factory() {
+            Some(factory) => factory.factory_type.clone(),
+            None => return,
+        };
+        let result = if self.factory_manager.is_paused(&factory_type) {
+            self.factory_manager.resume_factory(&factory_type).map(|_| format!("تم استئناف {}", factory_type))
+        } else {
+            self.factory_manager.pause_factory(&factory_type).map(|_| format!("تم إيقاف {} مؤقتاً", factory_type))
+        };
+        self.message = result.unwrap_or_else(|e| e);
+        self.refresh();
+    }
+
+    /// فحص جودة المصنع المحدد وحفظ التقرير في السجل المشترك
+    fn run_quality_check(&mut self) {
+        let factory_type = match self.selected_factory() {
+            Some(factory) => factory.factory_type.clone(),
+            None => return,
+        };
+        let report = self.quality.run_quality_check(&factory_type, "factory", serde_json::json!({ "factory": factory_type }));
+        let passed = report.passed;
+        let score = report.overall_score;
+        let regressions = self.quality.save_report(report);
+
+        self.message = format!("فحص جودة {}: {} ({:.0}%){}",
+            factory_type,
+            if passed { "ناجح" } else { "فاشل" },
+            score * 100.0,
+            if regressions.is_empty() { String::new() } else { format!(" · {} تراجع", regressions.len()) });
+    }
+
+    fn latest_report(&self) -> Option<&QualityReport> {
+        self.quality.history().all().last().copied()
+    }
+
+    /// رسم اللوحة
+    pub fn draw(&mut self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(10),
+            Constraint::Length(1),
+        ]).areas(frame.area());
+        let [top, bottom] = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(body);
+        // التخطيط من اليمين إلى اليسار: المصانع يميناً والمخططات يساراً
+        let [charts, factories] = Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(top);
+        let [quality, alerts] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(bottom);
+
+        let title = format!("لوحة تحكم MH-OS · {}{}",
+            chrono::Local::now().format("%H:%M:%S"),
+            if self.frozen { " · مجمد" } else { "" });
+        frame.render_widget(
+            Paragraph::new(self.text(&title))
+                .alignment(Alignment::Right)
+                .style(Style::default().add_modifier(Modifier::BOLD)),
+            header
+        );
+
+        self.draw_factories(frame, factories);
+        self.draw_charts(frame, charts);
+        self.draw_alerts(frame, alerts);
+        self.draw_quality(frame, quality);
+
+        frame.render_widget(
+            Paragraph::new(self.text(&self.message))
+                .alignment(Alignment::Right)
+                .style(Style::default().fg(Color::DarkGray)),
+            footer
+        );
+    }
+
+    fn block(&self, title: &str) -> Block<'static> {
+        Block::bordered().title(Line::from(self.text(title)).right_aligned())
+    }
+
+    /// صف بترتيب الأعمدة من اليمين إلى اليسار
+    fn rtl_row(&self, cells: Vec<String>) -> Row<'static> {
+        Row::new(cells.into_iter().rev().map(|cell| Cell::from(self.text(&cell))).collect::<Vec<_>>())
+    }
+
+    fn draw_factories(&mut self, frame: &mut Frame, area: Rect) {
+        let header = self.rtl_row(
+            ["المصنع", "الحالة", "الطلبات", "الفاشلة", "الطابور", "المتوسط"].iter().map(|s| s.to_string()).collect()
+        ).style(Style::default().add_modifier(Modifier::BOLD));
+
+        let rows: Vec<Row> = self.factories.iter().map(|factory| {
+            let color = match factory.status {
+                FactoryStatus::Paused => Color::Yellow,
+                FactoryStatus::Processing => Color::Cyan,
+                FactoryStatus::Error(_) => Color::Red,
+                _ => Color::Green,
+            };
+            self.rtl_row(vec![
+                factory.name.clone(),
+                factory.status.display_name().to_string(),
+                factory.total_requests.to_string(),
+                factory.failed_requests.to_string(),
+                factory.queue_size.to_string(),
+                format!("{:.0}ms", factory.avg_ms),
+            ]).style(Style::default().fg(color))
+        }).collect();
+
+        let widths = [
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(14),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(self.block("المصانع"))
+            .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn draw_charts(&self, frame: &mut Frame, area: Rect) {
+        let series: [MetricSeries; 3] = [
+            ("المعالج", |m| m.cpu_usage * 100.0, Color::Cyan),
+            ("الذاكرة", |m| m.memory_usage * 100.0, Color::Magenta),
+            ("الإنتاجية", |m| m.throughput, Color::Green),
+        ];
+        let areas = Layout::vertical([Constraint::Ratio(1, 3); 3]).split(area);
+
+        for ((label, value, color), area) in series.iter().zip(areas.iter()) {
+            let data: Vec<u64> = self.samples.iter().map(|m| value(m).max(0.0) as u64).collect();
+            let current = self.samples.back().map(value).unwrap_or(0.0);
+            let title = format!("{} {:.0}", label, current);
+            let mut sparkline = Sparkline::default()
+                .block(self.block(&title))
+                .data(&data)
+                .style(Style::default().fg(*color));
+            if *label != "الإنتاجية" {
+                sparkline = sparkline.max(100);
+            }
+            frame.render_widget(sparkline, *area);
+        }
+    }
+
+    fn draw_alerts(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = if self.active_alerts.is_empty() {
+            vec![Line::from(self.text("لا توجد إنذارات نشطة")).right_aligned()]
+        } else {
+            self.active_alerts.iter().map(|alert| {
+                let color = match alert.severity {
+                    "critical" => Color::Red,
+                    "high" => Color::LightRed,
+                    "medium" => Color::Yellow,
+                    _ => Color::Gray,
+                };
+                let acknowledged = if alert.acknowledged { " (مُقَر)" } else { "" };
+                let text = format!("[{}] {}: {}{}", alert.severity, alert.title, alert.message, acknowledged);
+                Line::from(self.text(&text)).right_aligned().style(Style::default().fg(color))
+            }).collect()
+        };
+        let title = format!("الإنذارات النشطة ({})", self.active_alerts.len());
+        frame.render_widget(Paragraph::new(lines).block(self.block(&title)), area);
+    }
+
+    fn draw_quality(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = match self.latest_report() {
+            None => vec![Line::from(self.text("لا توجد تقارير جودة")).right_aligned()],
+            Some(report) => {
+                let summary = format!("{} · {} · {:.0}% · {}",
+                    report.factory.as_deref().unwrap_or(&report.entity_id),
+                    if report.passed { "ناجح" } else { "فاشل" },
+                    report.overall_score * 100.0,
+                    report.generated_at.with_timezone(&chrono::Local).format("%H:%M:%S"));
+                let mut gates: Vec<_> = report.gate_results.values().collect();
+                gates.sort_by(|a, b| a.gate_name.cmp(&b.gate_name));
+
+                let mut lines = vec![Line::from(self.text(&summary)).right_aligned()
+                    .style(Style::default().add_modifier(Modifier::BOLD))];
+                lines.extend(gates.into_iter().map(|gate| {
+                    let text = format!("{} {}: {:.0}%", if gate.passed { "✓" } else { "✗" }, gate.gate_name, gate.score * 100.0);
+                    let color = if gate.passed { Color::Green } else { Color::Red };
+                    Line::from(self.text(&text)).right_aligned().style(Style::default().fg(color))
+                }));
+                lines
+            }
+        };
+        frame.render_widget(Paragraph::new(lines).block(self.block("آخر تقرير جودة")), area);
+    }
+
+    /// حلقة الرسم والمفاتيح حتى الخروج
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            let due = self.last_refresh.map(|at| at.elapsed() >= self.options.interval).unwrap_or(true);
+            if due && !self.frozen {
+                self.refresh();
+            }
+            terminal.draw(|frame| self.draw(frame))?;
+
+            if event::poll(Duration::from_millis(200))? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key.code) {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// تشغيل اللوحة بملء الشاشة مع مراقبة النظام في الخلفية
+pub async fn run(factory_manager: Arc<FactoryManager>, options: TuiOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let data_dir = crate::core::config::data_dir();
+    let quality = QualityManager::with_history(
+        crate::core::quality_history::QualityHistory::open(data_dir.join("quality").join("reports.jsonl"))?
+    );
+    let alerts = AlertStore::open(data_dir.join(crate::mhos::alerts::ALERTS_FILE))?;
+
+    let monitor = Arc::new(SystemMonitor::new());
+    monitor.set_monitoring_interval(options.interval.as_secs().max(1));
+    monitor.start_monitoring(factory_manager.clone()).await;
+
+    let mut app = TuiApp::new(factory_manager, monitor.clone(), quality, alerts, options);
+    let result = tokio::task::spawn_blocking(move || {
+        let mut terminal = ratatui::init();
+        let result = app.run(&mut terminal);
+        ratatui::restore();
+        result
+    }).await?;
+
+    monitor.stop_monitoring();
+    result.map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+    use ratatui::Terminal;
+
+    #[test]
+    fn test_arabic_is_reordered_and_factories_render() {
+        // الحروف العربية تُعكس بصرياً والأرقام تبقى بترتيبها
+        assert_eq!(visual_order("مصنع 42"), "42 عنصم");
+        assert_eq!(visual_order("education"), "education");
+        assert_eq!(visual_order("المصانع"), "عناصمل\u{200C}ا");
+
+        let factory_manager = Arc::new(FactoryManager::new());
+        let mut app = TuiApp::new(
+            factory_manager.clone(),
+            Arc::new(SystemMonitor::new()),
+            QualityManager::new(),
+            AlertStore::in_memory(),
+            TuiOptions::default()
+        );
+
+        // إيقاف المصنع الأول من لوحة المفاتيح
+        let first = app.factories[0].factory_type.clone();
+        assert!(app.handle_key(KeyCode::Char('p')));
+        assert!(factory_manager.is_paused(&first));
+        assert!(matches!(app.factories[0].status, FactoryStatus::Paused));
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal.backend().buffer().content.iter().map(|cell| cell.symbol()).collect();
+        assert!(screen.contains(&visual_order("متوقف مؤقتاً")));
+        assert!(screen.contains(&visual_order("المصانع")));
+
+        assert!(!app.handle_key(KeyCode::Char('q')));
+    }
+}
@@ -126,6 +126,13 @@ impl Default for SystemConfig {
     }
 }
 
+/// دليل البيانات من التكوين (أو الافتراضي عند تعذر التحميل)
+pub fn data_dir() -> PathBuf {
+    load_config()
+        .map(|config| config.data_dir)
+        .unwrap_or_else(|_| PathBuf::from("./data"))
+}
+
 /// تحميل التكوين
 pub fn load_config() -> Result<SystemConfig, Box<dyn std::error::Error>> {
     // أولاً حاول تحميل من ملف
@@ -92,6 +92,19 @@ impl FactoryStatus {
             FactoryStatus::Shutdown => "shutdown",
         }
     }
+    
+    /// الاسم المعروض بالعربية
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FactoryStatus::Initializing => "قيد التهيئة",
+            FactoryStatus::Ready => "جاهز",
+            FactoryStatus::Processing => "قيد المعالجة",
+            FactoryStatus::Paused => "متوقف مؤقتاً",
+            FactoryStatus::Error(_) => "خطأ",
+            FactoryStatus::Maintenance => "صيانة",
+            FactoryStatus::Shutdown => "مغلق",
+        }
+    }
 }
 
 /// إمكانية المصنع
@@ -225,6 +238,10 @@ pub enum FactoryError {
     ResourceUnavailable(String),
     ValidationFailed(String),
     Timeout,
+    /// المصنع متوقف مؤقتاً
+    Paused(String),
+    /// بلغ المصنع حد التزامن
+    Busy(String),
     Unknown(String),
 }
 
@@ -237,6 +254,8 @@ impl std::fmt::Display for FactoryError {
             FactoryError::ResourceUnavailable(msg) => write!(f, "مورد غير متاح: {}", msg),
             FactoryError::ValidationFailed(msg) => write!(f, "فشل التحقق: {}", msg),
             FactoryError::Timeout => write!(f, "انتهى وقت الانتظار"),
+            FactoryError::Paused(factory_type) => write!(f, "المصنع {} متوقف مؤقتاً", factory_type),
+            FactoryError::Busy(msg) => write!(f, "المصنع مشغول: {}", msg),
             FactoryError::Unknown(msg) => write!(f, "خطأ غير معروف: {}", msg),
         }
     }
@@ -1,10 +1,33 @@
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, Instant};
 use serde_json::Value;
+use crate::analysis::TextAnalyzer;
+use crate::core::factory::{FactoryError, FactoryMetrics, FactoryStatus};
+use crate::core::generation::{generator, GenerationService, UsageTotals};
+use crate::core::telemetry::Span;
+use crate::core::tuning::{knobs, RuntimeKnobs};
 use crate::factories::{Factory, FactoryType};
 
+/// الحدود العليا (ميلي ثانية) لفئات زمن التصنيع؛ الفئة الأخيرة لما يتجاوزها
+pub const LATENCY_BUCKETS_MS: [f64; 7] = [50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0];
+
+/// مستقبِل المنتجات المصنعة (مثل بوابات الجودة التي تفحصها)
+pub trait ProductObserver: Send + Sync {
+    fn on_product(&self, factory_type: &str, product: &Value);
+}
+
 /// مدير المصانع المركزي
 pub struct FactoryManager {
     factories: HashMap<String, Box<dyn Factory>>,
+    stats: Mutex<HashMap<String, FactoryStats>>,
+    cache: Mutex<HashMap<u64, (Instant, Value)>>,
+    paused: Mutex<HashSet<String>>,
+    generator: Arc<GenerationService>,
+    knobs: Arc<RuntimeKnobs>,
+    observers: RwLock<Vec<Arc<dyn ProductObserver>>>,
 }
 
 impl FactoryManager {
@@ -12,9 +35,40 @@ impl FactoryManager {
     pub fn new() -> Self {
         Self {
             factories: HashMap::new(),
+            stats: Mutex::new(HashMap::new()),
+            cache: Mutex::new(HashMap::new()),
+            paused: Mutex::new(HashSet::new()),
+            generator: generator(),
+            knobs: knobs(),
+            observers: RwLock::new(Vec::new()),
         }
     }
     
+    /// استخدام مولد محتوى محدد (مثل خلفية القوالب في الاختبارات)
+    pub fn with_generator(mut self, generator: Arc<GenerationService>) -> Self {
+        self.generator = generator;
+        self
+    }
+    
+    /// مقابض ضبط غير المقابض المشتركة للعملية
+    pub fn with_knobs(mut self, knobs: Arc<RuntimeKnobs>) -> Self {
+        self.knobs = knobs;
+        self
+    }
+    
+    /// تسجيل مستقبِل لكل منتج جديد (لا تُعاد إليه المنتجات المخزنة مؤقتاً)
+    pub fn observe_products(&self, observer: Arc<dyn ProductObserver>) {
+        match self.observers.write() {
+            Ok(mut observers) => observers.push(observer),
+            Err(poisoned) => poisoned.into_inner().push(observer),
+        }
+    }
+    
+    /// استهلاك الرموز والتكلفة لكل نوع مصنع
+    pub fn generation_usage(&self) -> std::collections::BTreeMap<String, UsageTotals> {
+        self.generator.usage()
+    }
+    
     /// تهيئة المصانع الافتراضية
     pub fn initialize_default_factories(&self) {
         println!("🏭 تهيئة المصانع الافتراضية...");
@@ -74,23 +128,301 @@ impl FactoryManager {
         ]
     }
     
+    /// إحصائيات التصنيع لكل نوع مصنع منذ بدء التشغيل
+    pub fn factory_metrics(&self) -> HashMap<String, FactoryMetrics> {
+        self.lock_stats().iter()
+            .map(|(factory_type, stats)| (factory_type.clone(), stats.metrics.clone()))
+            .collect()
+    }
+    
+    /// الحالة التشغيلية لمصنع: متوقف مؤقتاً، أو قيد المعالجة إن كان في طابوره طلبات، أو جاهز
+    pub fn factory_status(&self, factory_type: &str) -> FactoryStatus {
+        if self.is_paused(factory_type) {
+            return FactoryStatus::Paused;
+        }
+        let busy = self.lock_stats().get(factory_type)
+            .map(|stats| stats.metrics.current_queue_size > 0)
+            .unwrap_or(false);
+        if busy { FactoryStatus::Processing } else { FactoryStatus::Ready }
+    }
+    
+    /// إيقاف مصنع مؤقتاً (تُرفض طلباته الجديدة حتى الاستئناف)
+    pub fn pause_factory(&self, factory_type: &str) -> Result<(), String> {
+        self.ensure_known(factory_type)?;
+        if !self.lock_paused().insert(factory_type.to_string()) {
+            return Err(format!("المصنع {} متوقف مسبقاً", factory_type));
+        }
+        log::info!("⏸️ تم إيقاف المصنع مؤقتاً: {}", factory_type);
+        Ok(())
+    }
+    
+    /// استئناف مصنع متوقف
+    pub fn resume_factory(&self, factory_type: &str) -> Result<(), String> {
+        self.ensure_known(factory_type)?;
+        if !self.lock_paused().remove(factory_type) {
+            return Err(format!("المصنع {} غير متوقف", factory_type));
+        }
+        log::info!("▶️ تم استئناف المصنع: {}", factory_type);
+        Ok(())
+    }
+    
+    pub fn is_paused(&self, factory_type: &str) -> bool {
+        self.lock_paused().contains(factory_type)
+    }
+    
+    fn ensure_known(&self, factory_type: &str) -> Result<(), String> {
+        if self.list_factories().iter().any(|f| f.factory_type() == factory_type) {
+            Ok(())
+        } else {
+            Err(format!("المصنع غير موجود: {}", factory_type))
+        }
+    }
+    
+    /// توزيع أزمنة التصنيع لكل مصنع على فئات `LATENCY_BUCKETS_MS` (مع فئة التجاوز)
+    pub fn latency_histogram(&self) -> HashMap<String, Vec<u64>> {
+        self.lock_stats().iter()
+            .map(|(factory_type, stats)| (factory_type.clone(), stats.latency_buckets.to_vec()))
+            .collect()
+    }
+    
+    /// الطلبات التي خُدمت من التخزين المؤقت لكل مصنع
+    pub fn cache_hits(&self) -> HashMap<String, u64> {
+        self.lock_stats().iter()
+            .map(|(factory_type, stats)| (factory_type.clone(), stats.cache_hits))
+            .collect()
+    }
+    
+    /// عدد مرات استخدام كل قالب (معلمة `template`) مرتبة تنازلياً
+    pub fn template_usage(&self) -> Vec<(String, u64)> {
+        let mut usage: HashMap<String, u64> = HashMap::new();
+        for stats in self.lock_stats().values() {
+            for (template, count) in &stats.templates {
+                *usage.entry(template.clone()).or_insert(0) += count;
+            }
+        }
+        let mut usage: Vec<(String, u64)> = usage.into_iter().collect();
+        usage.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        usage
+    }
+    
     /// تنفيذ التصنيع
-    pub fn manufacture(&self, factory_type: &str, input: Value, parameters: Value) -> Result<Value, String> {
-        println!("🏭 بدء التصنيع في مصنع: {}", factory_type);
-        println!("   المدخلات: {:?}", input);
-        println!("   المعلمات: {:?}", parameters);
+    pub fn manufacture(&self, factory_type: &str, input: Value, parameters: Value) -> Result<Value, FactoryError> {
+        let mut span = Span::start("factory.manufacture");
+        span.set_attribute("factory_type", factory_type);
+        let knobs = self.knobs.clone();
+        
+        // المصنع المتوقف يرفض طلباته حتى ما كان منها في التخزين المؤقت
+        if self.is_paused(factory_type) {
+            let e = FactoryError::Paused(factory_type.to_string());
+            span.set_error(e.to_string());
+            return Err(e);
+        }
+        
+        // المنتج المخزن مؤقتاً لنفس الطلب ما دام ضمن المدة المضبوطة
+        let ttl = knobs.cache_ttl();
+        let template = parameters.get("template").and_then(|t| t.as_str()).map(|t| t.to_string());
+        let cache_key = (!ttl.is_zero()).then(|| product_cache_key(factory_type, &input, &parameters));
+        if let Some(product) = cache_key.and_then(|key| self.cached_product(key, ttl)) {
+            self.record_cache_hit(factory_type, template.as_deref());
+            span.set_attribute("cache_hit", true);
+            span.set_ok();
+            return Ok(product);
+        }
+        
+        let start = Instant::now();
+        if let Err(e) = self.record_start(factory_type, template.as_deref(), knobs.factory_concurrency(factory_type)) {
+            log::warn!("⚠️ {}", e);
+            span.set_error(e.to_string());
+            return Err(e);
+        }
+        
+        let result = self.run_manufacture(factory_type, input, parameters);
+        self.record_finish(factory_type, start.elapsed().as_secs_f64() * 1000.0, result.is_ok());
+        
+        match &result {
+            Ok(product) => {
+                if let Some(key) = cache_key {
+                    self.cache_product(key, product.clone(), ttl);
+                }
+                self.notify_product(factory_type, product);
+                span.set_ok();
+            }
+            Err(e) => span.set_error(e.to_string()),
+        }
+        result
+    }
+    
+    fn run_manufacture(&self, factory_type: &str, input: Value, parameters: Value) -> Result<Value, FactoryError> {
+        log::debug!("🏭 بدء التصنيع في مصنع: {} (المدخلات: {}، المعلمات: {})", factory_type, input, parameters);
+        
+        // توجيه الطلب إلى القدرة المطلوبة
+        let capability = {
+            let mut span = Span::start("factory.dispatch");
+            let capability = parameters.get("capability")
+                .and_then(|c| c.as_str())
+                .unwrap_or("manufacture")
+                .to_string();
+            span.set_attribute("factory_type", factory_type);
+            span.set_attribute("capability", capability.as_str());
+            capability
+        };
+        
+        // توليد المحتوى بموجّه القدرة
+        let mut span = Span::start("factory.render");
+        span.set_attribute("capability", capability.as_str());
+        let generation = match self.generator.generate(factory_type, &capability, &input, &parameters) {
+            Ok(generation) => generation,
+            Err(e) => {
+                span.set_error(e.to_string());
+                return Err(e);
+            }
+        };
+        span.set_attribute("generation.backend", generation.backend.as_str());
+        span.set_attribute("generation.tokens", generation.usage.total() as u64);
+        
+        // جودة النص المولد الفعلية (0.0 - 1.0)
+        let mut product = generation.to_product(factory_type);
+        if let Some(content) = product.get("content").and_then(|c| c.as_str()) {
+            let score = TextAnalyzer::new().analyze(content).score;
+            product["quality_score"] = serde_json::json!(score);
+        }
+        span.set_ok();
         
-        // محاكاة المنتج الناتج
-        Ok(serde_json::json!({
-            "id": format!("prod_{}", chrono::Utc::now().timestamp()),
-            "factory_type": factory_type,
-            "content": format!("محتوى مصنع من {}: {:?}", factory_type, input),
-            "quality_score": 0.85 + rand::random::<f32>() * 0.15,
-            "created_at": chrono::Utc::now().to_rfc3339(),
-        }))
+        Ok(product)
+    }
+    
+    fn notify_product(&self, factory_type: &str, product: &Value) {
+        let observers = match self.observers.read() {
+            Ok(observers) => observers.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        for observer in observers {
+            observer.on_product(factory_type, product);
+        }
+    }
+    
+    fn lock_stats(&self) -> MutexGuard<'_, HashMap<String, FactoryStats>> {
+        match self.stats.lock() {
+            Ok(stats) => stats,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+    
+    fn lock_paused(&self) -> MutexGuard<'_, HashSet<String>> {
+        match self.paused.lock() {
+            Ok(paused) => paused,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+    
+    fn lock_cache(&self) -> MutexGuard<'_, HashMap<u64, (Instant, Value)>> {
+        match self.cache.lock() {
+            Ok(cache) => cache,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+    
+    fn cached_product(&self, key: u64, ttl: Duration) -> Option<Value> {
+        self.lock_cache().get(&key)
+            .filter(|(stored_at, _)| stored_at.elapsed() < ttl)
+            .map(|(_, product)| product.clone())
+    }
+    
+    fn cache_product(&self, key: u64, product: Value, ttl: Duration) {
+        let mut cache = self.lock_cache();
+        cache.retain(|_, (stored_at, _)| stored_at.elapsed() < ttl);
+        cache.insert(key, (Instant::now(), product));
+    }
+    
+    /// طلب خُدم من التخزين المؤقت: يُحسب طلباً ناجحاً دون أن يدخل الطابور أو متوسط الزمن
+    fn record_cache_hit(&self, factory_type: &str, template: Option<&str>) {
+        let mut stats = self.lock_stats();
+        let stats = stats.entry(factory_type.to_string()).or_insert_with(FactoryStats::new);
+        stats.metrics.total_requests += 1;
+        stats.metrics.successful_requests += 1;
+        stats.cache_hits += 1;
+        if let Some(template) = template {
+            *stats.templates.entry(template.to_string()).or_insert(0) += 1;
+        }
+    }
+    
+    /// طلب جديد دخل طابور المصنع، أو رفضه عند بلوغ حد التزامن
+    fn record_start(&self, factory_type: &str, template: Option<&str>, concurrency: usize) -> Result<(), FactoryError> {
+        let mut stats = self.lock_stats();
+        let stats = stats.entry(factory_type.to_string()).or_insert_with(FactoryStats::new);
+        
+        stats.metrics.total_requests += 1;
+        if let Some(template) = template {
+            *stats.templates.entry(template.to_string()).or_insert(0) += 1;
+        }
+        if stats.metrics.current_queue_size >= concurrency {
+            stats.metrics.failed_requests += 1;
+            return Err(FactoryError::Busy(format!("{}: تم بلوغ حد التزامن ({})", factory_type, concurrency)));
+        }
+        stats.metrics.current_queue_size += 1;
+        Ok(())
+    }
+    
+    /// انتهاء طلب: تحديث المتوسط المتحرك ونتيجة التنفيذ
+    fn record_finish(&self, factory_type: &str, elapsed_ms: f64, success: bool) {
+        let mut stats = self.lock_stats();
+        if let Some(stats) = stats.get_mut(factory_type) {
+            let metrics = &mut stats.metrics;
+            metrics.current_queue_size = metrics.current_queue_size.saturating_sub(1);
+            if success {
+                metrics.successful_requests += 1;
+            } else {
+                metrics.failed_requests += 1;
+            }
+            stats.processed += 1;
+            metrics.avg_processing_time_ms += (elapsed_ms - metrics.avg_processing_time_ms) / stats.processed as f64;
+            
+            let bucket = LATENCY_BUCKETS_MS.iter()
+                .position(|bound| elapsed_ms <= *bound)
+                .unwrap_or(LATENCY_BUCKETS_MS.len());
+            stats.latency_buckets[bucket] += 1;
+        }
+    }
+}
+
+/// إحصائيات مصنع مع عدد الطلبات المنفذة فعلاً (دون المرفوضة) لحساب المتوسط
+struct FactoryStats {
+    metrics: FactoryMetrics,
+    processed: u64,
+    latency_buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    templates: HashMap<String, u64>,
+    cache_hits: u64,
+}
+
+impl FactoryStats {
+    fn new() -> Self {
+        Self {
+            metrics: FactoryMetrics {
+                total_requests: 0,
+                successful_requests: 0,
+                failed_requests: 0,
+                avg_processing_time_ms: 0.0,
+                current_queue_size: 0,
+                memory_usage_mb: 0.0,
+                last_reset: chrono::Utc::now(),
+            },
+            processed: 0,
+            latency_buckets: [0; LATENCY_BUCKETS_MS.len() + 1],
+            templates: HashMap::new(),
+            cache_hits: 0,
+        }
     }
 }
 
+/// مفتاح التخزين المؤقت: نوع المصنع مع المدخلات والمعلمات
+fn product_cache_key(factory_type: &str, input: &Value, parameters: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    factory_type.hash(&mut hasher);
+    input.to_string().hash(&mut hasher);
+    parameters.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
 /// مصنع وهمي للاختبار
 struct MockFactory {
     name: String,
@@ -118,3 +450,87 @@ impl Factory for MockFactory {
         0.9
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::generation::{Completion, ContentGenerator, GenerationRequest};
+    use crate::core::tuning::Knob;
+
+    /// خلفية تنقضي مهلتها دائماً
+    struct TimeoutBackend;
+
+    impl ContentGenerator for TimeoutBackend {
+        fn name(&self) -> &str {
+            "timeout"
+        }
+
+        fn model(&self) -> &str {
+            "none"
+        }
+
+        fn generate(&self, _request: &GenerationRequest) -> Result<Completion, FactoryError> {
+            Err(FactoryError::Timeout)
+        }
+    }
+
+    fn cached_manager() -> FactoryManager {
+        let knobs = Arc::new(RuntimeKnobs::new());
+        knobs.set(&Knob::CacheTtlSeconds, 60.0);
+        FactoryManager::new()
+            .with_generator(Arc::new(GenerationService::offline()))
+            .with_knobs(knobs)
+    }
+
+    #[test]
+    fn test_cache_hit_recorded_in_stats() {
+        let manager = cached_manager();
+        let params = serde_json::json!({ "template": "lesson" });
+        let first = manager.manufacture("education", serde_json::json!("الكسور"), params.clone()).unwrap();
+        let second = manager.manufacture("education", serde_json::json!("الكسور"), params).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(manager.cache_hits()["education"], 1);
+        let metrics = &manager.factory_metrics()["education"];
+        assert_eq!(metrics.total_requests, 2);
+        assert_eq!(metrics.successful_requests, 2);
+        assert_eq!(metrics.current_queue_size, 0);
+        assert_eq!(manager.template_usage(), vec![("lesson".to_string(), 2)]);
+        assert_eq!(manager.latency_histogram()["education"].iter().sum::<u64>(), 1);
+    }
+
+    #[test]
+    fn test_quality_score_comes_from_text_analysis() {
+        let manager = FactoryManager::new().with_generator(Arc::new(GenerationService::offline()));
+        let product = manager.manufacture("education", serde_json::json!("الكسور"), serde_json::json!({})).unwrap();
+
+        let expected = TextAnalyzer::new().analyze(product["content"].as_str().unwrap()).score;
+        assert_eq!(product["quality_score"].as_f64().unwrap() as f32, expected);
+    }
+
+    #[test]
+    fn test_paused_factory_rejects_cached_request() {
+        let manager = cached_manager();
+        manager.manufacture("education", serde_json::json!("الكسور"), Value::Null).unwrap();
+        manager.pause_factory("education").unwrap();
+
+        assert!(manager.manufacture("education", serde_json::json!("الكسور"), Value::Null).is_err());
+        assert_eq!(manager.cache_hits()["education"], 0);
+
+        manager.resume_factory("education").unwrap();
+        assert!(manager.manufacture("education", serde_json::json!("الكسور"), Value::Null).is_ok());
+        assert_eq!(manager.cache_hits()["education"], 1);
+    }
+
+    #[test]
+    fn test_generation_errors_stay_typed() {
+        let service = GenerationService::new(Box::new(TimeoutBackend)).with_retry(1, Duration::ZERO);
+        let manager = FactoryManager::new().with_generator(Arc::new(service));
+
+        assert!(matches!(manager.manufacture("education", Value::Null, Value::Null), Err(FactoryError::Timeout)));
+        assert_eq!(manager.factory_metrics()["education"].failed_requests, 1);
+
+        manager.pause_factory("education").unwrap();
+        assert!(matches!(manager.manufacture("education", Value::Null, Value::Null), Err(FactoryError::Paused(_))));
+    }
+}
@@ -0,0 +1,812 @@
+//! توليد المحتوى للمصانع عبر نموذج لغوي قابل للاستبدال
+//!
+//! الواجهة `ContentGenerator` لها خلفيتان: نقطة متوافقة مع OpenAI
+//! (`/v1/chat/completions` عبر http، ويمكن توجيهها إلى خادم محلي مثل
+//! llama.cpp أو Ollama) وخلفية قوالب حتمية تعمل دون اتصال للاختبارات.
+//! تصيّر `GenerationService` قالب الموجّه الخاص بكل قدرة، وتعيد المحاولة
+//! عند الأخطاء المؤقتة، وتحسب الرموز والتكلفة لكل نوع مصنع.
+//!
+//! متغيرات البيئة: `MH_LLM_BASE_URL` (بدونه تُستخدم خلفية القوالب)،
+//! `MH_LLM_MODEL`، `MH_LLM_API_KEY`، `MH_LLM_TIMEOUT_SECS`.
+
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tera::{Context, Tera};
+use crate::core::factory::FactoryError;
+
+/// مسار ملف قوالب الموجّهات المخصصة داخل دليل البيانات
+pub const PROMPTS_FILE: &str = "generation/prompts.json";
+
+/// القدرة العامة لكل مصنع (والاحتياطية للقدرات غير المعروفة)
+pub const DEFAULT_CAPABILITY: &str = "manufacture";
+
+/// قوالب الموجّهات الافتراضية: (المصنع، القدرة، تعليمات النظام، طلب المستخدم)
+const DEFAULT_PROMPTS: &[(&str, &str, &str, &str)] = &[
+    ("education", "manufacture",
+        "أنت مصمم تعليمي خبير تكتب محتوى عربياً واضحاً ومنظماً.",
+        "أنشئ محتوى تعليمياً عن: {{ input }}"),
+    ("education", "lesson",
+        "أنت مصمم تعليمي خبير تكتب محتوى عربياً واضحاً ومنظماً.",
+        "اكتب درساً عن: {{ input }}{% if params.level %} للمستوى {{ params.level }}{% endif %}. ابدأ بالأهداف ثم الشرح ثم أمثلة وملخص."),
+    ("education", "quiz",
+        "أنت مصمم تعليمي خبير تكتب محتوى عربياً واضحاً ومنظماً.",
+        "اكتب اختباراً من {{ params.questions | default(value=5) }} أسئلة اختيار من متعدد عن: {{ input }} مع الإجابات الصحيحة."),
+    ("creative", "manufacture",
+        "أنت كاتب مبدع يكتب بالعربية بأسلوب جذاب.",
+        "أنشئ محتوى إبداعياً عن: {{ input }}"),
+    ("creative", "article",
+        "أنت كاتب مبدع يكتب بالعربية بأسلوب جذاب.",
+        "اكتب مقالاً{% if params.tone %} بنبرة {{ params.tone }}{% endif %} عن: {{ input }}. استخدم عناوين فرعية."),
+    ("creative", "social_post",
+        "أنت كاتب مبدع يكتب بالعربية بأسلوب جذاب.",
+        "اكتب منشوراً قصيراً لوسائل التواصل{% if params.platform %} على {{ params.platform }}{% endif %} عن: {{ input }}"),
+    ("corporate", "manufacture",
+        "أنت مستشار أعمال يكتب بالعربية بلغة مهنية موجزة.",
+        "أنشئ مستنداً مؤسسياً عن: {{ input }}"),
+    ("corporate", "report",
+        "أنت مستشار أعمال يكتب بالعربية بلغة مهنية موجزة.",
+        "اكتب تقريراً عن: {{ input }} يتضمن ملخصاً تنفيذياً ونتائج وتوصيات."),
+    ("corporate", "email",
+        "أنت مستشار أعمال يكتب بالعربية بلغة مهنية موجزة.",
+        "اكتب رسالة بريد مهنية{% if params.recipient %} إلى {{ params.recipient }}{% endif %} بخصوص: {{ input }}"),
+    ("technology", "manufacture",
+        "أنت مهندس برمجيات خبير. اشرح بالعربية واكتب الشيفرة بالإنجليزية.",
+        "أنشئ حلاً تقنياً لـ: {{ input }}"),
+    ("technology", "code",
+        "أنت مهندس برمجيات خبير. اشرح بالعربية واكتب الشيفرة بالإنجليزية.",
+        "اكتب شيفرة{% if params.language %} بلغة {{ params.language }}{% endif %} تحقق: {{ input }}. أضف شرحاً موجزاً واختباراً."),
+    ("technology", "documentation",
+        "أنت مهندس برمجيات خبير. اشرح بالعربية واكتب الشيفرة بالإنجليزية.",
+        "اكتب توثيقاً تقنياً لـ: {{ input }} يتضمن الاستخدام والأمثلة."),
+];
+
+/// طلب توليد بعد تصيير الموجّه
+#[derive(Debug, Clone, Serialize)]
+pub struct GenerationRequest {
+    pub factory_type: String,
+    pub capability: String,
+    pub system: String,
+    pub prompt: String,
+    pub max_tokens: u32,
+    pub temperature: f32,
+}
+
+/// عدد الرموز المستهلكة
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u32 {
+        self.prompt_tokens + self.completion_tokens
+    }
+}
+
+/// سعر الخلفية لكل ألف رمز (بالدولار)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pricing {
+    #[serde(default)]
+    pub prompt_per_1k: f64,
+    #[serde(default)]
+    pub completion_per_1k: f64,
+}
+
+impl Pricing {
+    pub fn cost(&self, usage: &TokenUsage) -> f64 {
+        (usage.prompt_tokens as f64 * self.prompt_per_1k + usage.completion_tokens as f64 * self.completion_per_1k) / 1000.0
+    }
+}
+
+/// ما تعيده الخلفية
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub text: String,
+    pub usage: TokenUsage,
+}
+
+/// نتيجة التوليد مع المحاسبة
+#[derive(Debug, Clone, Serialize)]
+pub struct Generation {
+    pub text: String,
+    pub backend: String,
+    pub model: String,
+    pub capability: String,
+    pub usage: TokenUsage,
+    pub cost_usd: f64,
+    pub latency_ms: u64,
+    pub attempts: u32,
+}
+
+impl Generation {
+    /// المنتج بصيغة JSON كما تعيده المصانع
+    pub fn to_product(&self, factory_type: &str) -> Value {
+        serde_json::json!({
+            "id": format!("prod_{}", crate::core::telemetry::random_hex(6)),
+            "factory_type": factory_type,
+            "capability": self.capability,
+            "content": self.text,
+            "generation": {
+                "backend": self.backend,
+                "model": self.model,
+                "usage": self.usage,
+                "cost_usd": self.cost_usd,
+                "latency_ms": self.latency_ms,
+                "attempts": self.attempts,
+            },
+            "created_at": chrono::Utc::now().to_rfc3339(),
+        })
+    }
+}
+
+/// خلفية توليد المحتوى
+pub trait ContentGenerator: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+
+    fn pricing(&self) -> Pricing {
+        Pricing::default()
+    }
+
+    /// محاولة واحدة؛ `Timeout` و`ResourceUnavailable` تعني أن إعادة المحاولة مجدية
+    fn generate(&self, request: &GenerationRequest) -> Result<Completion, FactoryError>;
+}
+
+/// تقدير عدد الرموز حين لا تعيده الخلفية (قرابة أربعة أحرف للرمز)
+pub fn estimate_tokens(text: &str) -> u32 {
+    text.chars().count().div_ceil(4) as u32
+}
+
+/// خلفية حتمية دون اتصال: النص نفسه دائماً للطلب نفسه
+#[derive(Debug, Default)]
+pub struct TemplateBackend;
+
+impl ContentGenerator for TemplateBackend {
+    fn name(&self) -> &str {
+        "template"
+    }
+
+    fn model(&self) -> &str {
+        "template-v1"
+    }
+
+    fn generate(&self, request: &GenerationRequest) -> Result<Completion, FactoryError> {
+        let text = format!(
+            "# {} ({})\n\n{}\n\n> مسودة من خلفية القوالب دون نموذج لغوي.",
+            request.capability, request.factory_type, request.prompt
+        );
+        let usage = TokenUsage {
+            prompt_tokens: estimate_tokens(&request.system) + estimate_tokens(&request.prompt),
+            completion_tokens: estimate_tokens(&text),
+        };
+        Ok(Completion { text, usage })
+    }
+}
+
+/// إعدادات نقطة متوافقة مع OpenAI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAiConfig {
+    /// مثل http://localhost:11434 (Ollama) أو http://localhost:8080 (llama.cpp)
+    pub base_url: String,
+    pub model: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    #[serde(default)]
+    pub pricing: Pricing,
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+impl OpenAiConfig {
+    /// رفض العناوين التي لا تستطيع الخلفية الاتصال بها (https أو بلا مخطط)
+    pub fn validate(&self) -> Result<(), FactoryError> {
+        let invalid = |reason: String| Err(FactoryError::InitializationFailed(reason));
+        if self.base_url.starts_with("https://") {
+            return invalid(format!(
+                "لا يدعم المولد https:// ({}): ضع أمام الخدمة وكيلاً محلياً بـ http:// واضبط MH_LLM_BASE_URL عليه",
+                self.base_url
+            ));
+        }
+        if !self.base_url.starts_with("http://") {
+            return invalid(format!("يدعم المولد http:// فقط: {}", self.base_url));
+        }
+        Ok(())
+    }
+}
+
+/// خلفية HTTP متوافقة مع OpenAI (http فقط؛ للخدمات السحابية استخدم وكيلاً محلياً)
+pub struct OpenAiBackend {
+    host: String,
+    port: u16,
+    path: String,
+    model: String,
+    api_key: Option<String>,
+    timeout: Duration,
+    pricing: Pricing,
+}
+
+impl OpenAiBackend {
+    pub fn new(config: &OpenAiConfig) -> Result<Self, FactoryError> {
+        config.validate()?;
+        let invalid = |reason: String| FactoryError::InitializationFailed(reason);
+        let rest = config.base_url.strip_prefix("http://").unwrap_or_default();
+
+        let (authority, base_path) = match rest.find('/') {
+            Some(i) => (&rest[..i], rest[i..].trim_end_matches('/')),
+            None => (rest, ""),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid(format!("منفذ غير صالح: {}", port)))?),
+            None => (authority, 80),
+        };
+        if host.is_empty() {
+            return Err(invalid(format!("عنوان بلا مضيف: {}", config.base_url)));
+        }
+        // يقبل العنوان الأساسي بـ /v1 أو بدونه
+        let base_path = base_path.strip_suffix("/v1").unwrap_or(base_path);
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: format!("{}/v1/chat/completions", base_path),
+            model: config.model.clone(),
+            api_key: config.api_key.clone(),
+            timeout: Duration::from_secs(config.timeout_secs.max(1)),
+            pricing: config.pricing,
+        })
+    }
+
+    /// مهلة كل محاولة (الاتصال والقراءة)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn request_body(&self, request: &GenerationRequest) -> Value {
+        serde_json::json!({
+            "model": self.model,
+            "messages": [
+                { "role": "system", "content": request.system },
+                { "role": "user", "content": request.prompt },
+            ],
+            "max_tokens": request.max_tokens,
+            "temperature": request.temperature,
+            "stream": false,
+        })
+    }
+
+    fn post(&self, body: &[u8]) -> Result<(u16, Vec<u8>), FactoryError> {
+        let started = Instant::now();
+        let address = std::net::ToSocketAddrs::to_socket_addrs(&(self.host.as_str(), self.port))
+            .map_err(io_error)?
+            .next()
+            .ok_or_else(|| FactoryError::ResourceUnavailable(format!("تعذر حل العنوان {}", self.host)))?;
+
+        let mut stream = TcpStream::connect_timeout(&address, self.timeout).map_err(io_error)?;
+        stream.set_read_timeout(Some(self.timeout)).map_err(io_error)?;
+        stream.set_write_timeout(Some(self.timeout)).map_err(io_error)?;
+
+        let mut head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nAccept: application/json\r\nConnection: close\r\n",
+            self.path, self.host, self.port, body.len()
+        );
+        if let Some(key) = &self.api_key {
+            head.push_str(&format!("Authorization: Bearer {}\r\n", key));
+        }
+        head.push_str("\r\n");
+        stream.write_all(head.as_bytes()).map_err(io_error)?;
+        stream.write_all(body).map_err(io_error)?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).map_err(io_error)?;
+        if started.elapsed() > self.timeout {
+            return Err(FactoryError::Timeout);
+        }
+        parse_http_response(&response)
+    }
+}
+
+impl ContentGenerator for OpenAiBackend {
+    fn name(&self) -> &str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn pricing(&self) -> Pricing {
+        self.pricing
+    }
+
+    fn generate(&self, request: &GenerationRequest) -> Result<Completion, FactoryError> {
+        let body = serde_json::to_vec(&self.request_body(request))
+            .map_err(|e| FactoryError::InvalidRequest(e.to_string()))?;
+        let (status, body) = self.post(&body)?;
+        let json: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+        match status {
+            200..=299 => {}
+            408 | 504 => return Err(FactoryError::Timeout),
+            429 | 500..=599 => return Err(FactoryError::ResourceUnavailable(
+                format!("رد الخادم بالحالة {}: {}", status, error_message(&json)))),
+            _ => return Err(FactoryError::InvalidRequest(
+                format!("رفض الخادم الطلب بالحالة {}: {}", status, error_message(&json)))),
+        }
+
+        let text = json["choices"][0]["message"]["content"].as_str()
+            .ok_or_else(|| FactoryError::ProcessingFailed("رد بلا محتوى من النموذج".to_string()))?
+            .to_string();
+        let reported = |key: &str| json["usage"][key].as_u64().map(|v| v as u32);
+        let usage = TokenUsage {
+            prompt_tokens: reported("prompt_tokens")
+                .unwrap_or_else(|| estimate_tokens(&request.system) + estimate_tokens(&request.prompt)),
+            completion_tokens: reported("completion_tokens").unwrap_or_else(|| estimate_tokens(&text)),
+        };
+        Ok(Completion { text, usage })
+    }
+}
+
+/// أخطاء الشبكة: انقضاء المهلة ← `Timeout`، وغيرها مورد غير متاح
+fn io_error(e: io::Error) -> FactoryError {
+    match e.kind() {
+        io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => FactoryError::Timeout,
+        _ => FactoryError::ResourceUnavailable(e.to_string()),
+    }
+}
+
+fn error_message(json: &Value) -> String {
+    json["error"]["message"].as_str()
+        .or_else(|| json["error"].as_str())
+        .unwrap_or("")
+        .to_string()
+}
+
+/// فصل الحالة والجسم من رد HTTP/1.1 (مع فك الترميز المجزأ)
+fn parse_http_response(response: &[u8]) -> Result<(u16, Vec<u8>), FactoryError> {
+    let invalid = || FactoryError::ResourceUnavailable("رد HTTP غير صالح".to_string());
+    let split = response.windows(4).position(|w| w == b"\r\n\r\n").ok_or_else(invalid)?;
+    let head = String::from_utf8_lossy(&response[..split]).to_lowercase();
+    let body = &response[split + 4..];
+
+    let status = head.split_whitespace().nth(1).and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+    if !head.lines().any(|line| line.starts_with("transfer-encoding:") && line.contains("chunked")) {
+        return Ok((status, body.to_vec()));
+    }
+
+    let mut decoded = Vec::new();
+    let mut rest = body;
+    loop {
+        let line_end = rest.windows(2).position(|w| w == b"\r\n").ok_or_else(invalid)?;
+        let size_line = String::from_utf8_lossy(&rest[..line_end]);
+        let size = usize::from_str_radix(size_line.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| invalid())?;
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            break;
+        }
+        if rest.len() < size {
+            return Err(invalid());
+        }
+        decoded.extend_from_slice(&rest[..size]);
+        rest = rest.get(size + 2..).unwrap_or(&[]);
+    }
+    Ok((status, decoded))
+}
+
+/// قوالب الموجّهات لكل (مصنع، قدرة) بصيغة Tera
+pub struct PromptLibrary {
+    tera: Tera,
+}
+
+/// قالب موجّه مخصص
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub system: String,
+    pub user: String,
+}
+
+impl PromptLibrary {
+    /// القوالب الافتراضية للمصانع الأربعة
+    pub fn with_defaults() -> Self {
+        let mut library = Self { tera: Tera::default() };
+        for (factory_type, capability, system, user) in DEFAULT_PROMPTS {
+            let template = PromptTemplate { system: system.to_string(), user: user.to_string() };
+            library.set(factory_type, capability, &template).expect("قوالب الموجّهات المضمنة غير صالحة");
+        }
+        library
+    }
+
+    /// القوالب الافتراضية مع ما في ملف التخصيص (مفاتيحه "المصنع/القدرة")
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, FactoryError> {
+        let mut library = Self::with_defaults();
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(library);
+        }
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| FactoryError::InitializationFailed(e.to_string()))?;
+        let templates: BTreeMap<String, PromptTemplate> = serde_json::from_str(&content)
+            .map_err(|e| FactoryError::InitializationFailed(format!("{}: {}", path.display(), e)))?;
+        for (key, template) in templates {
+            let (factory_type, capability) = key.split_once('/').unwrap_or((key.as_str(), DEFAULT_CAPABILITY));
+            library.set(factory_type, capability, &template)?;
+        }
+        Ok(library)
+    }
+
+    /// إضافة قالب أو استبداله (يُتحقق من صياغته فوراً)
+    pub fn set(&mut self, factory_type: &str, capability: &str, template: &PromptTemplate) -> Result<(), FactoryError> {
+        let name = format!("{}/{}", factory_type, capability);
+        self.tera.add_raw_templates(vec![
+            (format!("{}/system", name), template.system.as_str()),
+            (format!("{}/user", name), template.user.as_str()),
+        ]).map_err(|e| FactoryError::InitializationFailed(format!("قالب {}: {}", name, e)))
+    }
+
+    /// القدرات التي لها قالب لمصنع
+    pub fn capabilities(&self, factory_type: &str) -> Vec<String> {
+        let prefix = format!("{}/", factory_type);
+        self.tera.get_template_names()
+            .filter_map(|name| name.strip_prefix(&prefix)?.strip_suffix("/user").map(str::to_string))
+            .collect::<std::collections::BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// (تعليمات النظام، طلب المستخدم)؛ القدرة غير المعروفة تستخدم قالب المصنع العام
+    pub fn render(&self, factory_type: &str, capability: &str, input: &Value, parameters: &Value) -> Result<(String, String), FactoryError> {
+        let names: Vec<&str> = self.tera.get_template_names().collect();
+        let name = [format!("{}/{}", factory_type, capability), format!("{}/{}", factory_type, DEFAULT_CAPABILITY)]
+            .into_iter()
+            .find(|name| names.contains(&format!("{}/user", name).as_str()))
+            .ok_or_else(|| FactoryError::InvalidRequest(format!("لا يوجد قالب موجّه للمصنع {}", factory_type)))?;
+
+        let mut context = Context::new();
+        context.insert("factory_type", factory_type);
+        context.insert("capability", capability);
+        context.insert("input", &match input {
+            Value::String(text) => text.clone(),
+            other => serde_json::to_string_pretty(other).unwrap_or_default(),
+        });
+        context.insert("params", &if parameters.is_object() { parameters.clone() } else { serde_json::json!({}) });
+
+        let render = |part: &str| self.tera.render(&format!("{}/{}", name, part), &context)
+            .map_err(|e| FactoryError::InvalidRequest(format!("تعذر تصيير قالب {}: {}", name, e)));
+        Ok((render("system")?, render("user")?))
+    }
+}
+
+/// الخلفية المختارة
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum GeneratorConfig {
+    Template,
+    #[serde(rename = "openai")]
+    OpenAi(OpenAiConfig),
+}
+
+impl GeneratorConfig {
+    /// من متغيرات البيئة (خلفية القوالب ما لم يُضبط `MH_LLM_BASE_URL`)
+    pub fn from_env() -> Result<Self, FactoryError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let config = match var("MH_LLM_BASE_URL") {
+            Some(base_url) => GeneratorConfig::OpenAi(OpenAiConfig {
+                base_url,
+                model: var("MH_LLM_MODEL").unwrap_or_else(|| "llama3".to_string()),
+                api_key: var("MH_LLM_API_KEY"),
+                timeout_secs: var("MH_LLM_TIMEOUT_SECS").and_then(|v| v.parse().ok()).unwrap_or(default_timeout_secs()),
+                pricing: Pricing::default(),
+            }),
+            None => GeneratorConfig::Template,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), FactoryError> {
+        match self {
+            GeneratorConfig::Template => Ok(()),
+            GeneratorConfig::OpenAi(config) => config.validate(),
+        }
+    }
+
+    pub fn build(&self) -> Result<Box<dyn ContentGenerator>, FactoryError> {
+        Ok(match self {
+            GeneratorConfig::Template => Box::new(TemplateBackend),
+            GeneratorConfig::OpenAi(config) => Box::new(OpenAiBackend::new(config)?),
+        })
+    }
+}
+
+/// مجاميع الاستهلاك لنوع مصنع
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageTotals {
+    pub requests: u64,
+    pub failures: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// خدمة التوليد المشتركة بين المصانع
+pub struct GenerationService {
+    backend: Box<dyn ContentGenerator>,
+    prompts: PromptLibrary,
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_tokens: u32,
+    temperature: f32,
+    usage: Mutex<BTreeMap<String, UsageTotals>>,
+}
+
+impl GenerationService {
+    pub fn new(backend: Box<dyn ContentGenerator>) -> Self {
+        Self {
+            backend,
+            prompts: PromptLibrary::with_defaults(),
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_tokens: 1024,
+            temperature: 0.7,
+            usage: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// خلفية القوالب الحتمية
+    pub fn offline() -> Self {
+        Self::new(Box::new(TemplateBackend))
+    }
+
+    /// الخلفية من متغيرات البيئة والقوالب المخصصة من دليل البيانات
+    pub fn load() -> Self {
+        let mut service = match GeneratorConfig::from_env().and_then(|config| config.build()) {
+            Ok(backend) => Self::new(backend),
+            Err(e) => {
+                log::error!("❌ تعذر تهيئة مولد المحتوى: {} - ستُستخدم خلفية القوالب", e);
+                Self::offline()
+            }
+        };
+
+        let path = crate::core::config::data_dir().join(PROMPTS_FILE);
+        match PromptLibrary::load(&path) {
+            Ok(prompts) => service.prompts = prompts,
+            Err(e) => log::error!("❌ تعذر تحميل قوالب الموجّهات {}: {}", path.display(), e),
+        }
+
+        log::info!("🤖 مولد المحتوى: {} ({})", service.backend.name(), service.backend.model());
+        service
+    }
+
+    pub fn with_prompts(mut self, prompts: PromptLibrary) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// عدد المحاولات والتأخير الأول (يتضاعف بين المحاولات)
+    pub fn with_retry(mut self, max_attempts: u32, initial_backoff: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn backend_name(&self) -> &str {
+        self.backend.name()
+    }
+
+    pub fn prompts(&self) -> &PromptLibrary {
+        &self.prompts
+    }
+
+    /// تصيير موجّه القدرة وتوليد المحتوى مع إعادة المحاولة عند الأخطاء المؤقتة
+    pub fn generate(&self, factory_type: &str, capability: &str, input: &Value, parameters: &Value) -> Result<Generation, FactoryError> {
+        let (system, prompt) = self.prompts.render(factory_type, capability, input, parameters)?;
+        let request = GenerationRequest {
+            factory_type: factory_type.to_string(),
+            capability: capability.to_string(),
+            system,
+            prompt,
+            max_tokens: parameters.get("max_tokens").and_then(Value::as_u64).map(|v| v as u32).unwrap_or(self.max_tokens),
+            temperature: parameters.get("temperature").and_then(Value::as_f64).map(|v| v as f32).unwrap_or(self.temperature),
+        };
+
+        let started = Instant::now();
+        let mut attempts = 0;
+        let result = loop {
+            attempts += 1;
+            match self.backend.generate(&request) {
+                Err(e @ (FactoryError::Timeout | FactoryError::ResourceUnavailable(_))) if attempts < self.max_attempts => {
+                    let backoff = self.initial_backoff * 2u32.saturating_pow(attempts - 1);
+                    log::warn!("⚠️  فشل التوليد عبر {} (محاولة {}): {} - إعادة المحاولة بعد {:?}",
+                        self.backend.name(), attempts, e, backoff);
+                    std::thread::sleep(backoff);
+                }
+                result => break result,
+            }
+        };
+
+        let completion = match result {
+            Ok(completion) => completion,
+            Err(e) => {
+                log::error!("❌ تعذر التوليد عبر {} بعد {} محاولة: {}", self.backend.name(), attempts, e);
+                self.record(factory_type, None, 0.0);
+                return Err(e);
+            }
+        };
+
+        let cost_usd = self.backend.pricing().cost(&completion.usage);
+        self.record(factory_type, Some(&completion.usage), cost_usd);
+
+        Ok(Generation {
+            text: completion.text,
+            backend: self.backend.name().to_string(),
+            model: self.backend.model().to_string(),
+            capability: capability.to_string(),
+            usage: completion.usage,
+            cost_usd,
+            latency_ms: started.elapsed().as_millis() as u64,
+            attempts,
+        })
+    }
+
+    fn record(&self, factory_type: &str, usage: Option<&TokenUsage>, cost_usd: f64) {
+        let mut totals = match self.usage.lock() {
+            Ok(totals) => totals,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let entry = totals.entry(factory_type.to_string()).or_default();
+        entry.requests += 1;
+        match usage {
+            Some(usage) => {
+                entry.prompt_tokens += usage.prompt_tokens as u64;
+                entry.completion_tokens += usage.completion_tokens as u64;
+                entry.cost_usd += cost_usd;
+            }
+            None => entry.failures += 1,
+        }
+    }
+
+    /// الاستهلاك لكل نوع مصنع منذ بدء التشغيل
+    pub fn usage(&self) -> BTreeMap<String, UsageTotals> {
+        self.usage.lock().map(|u| u.clone()).unwrap_or_default()
+    }
+}
+
+/// مولد المحتوى المشترك للعملية
+pub fn generator() -> Arc<GenerationService> {
+    static GENERATOR: OnceLock<Arc<GenerationService>> = OnceLock::new();
+    GENERATOR.get_or_init(|| Arc::new(GenerationService::load())).clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+
+    /// خادم بديل يرد بالردود المحددة بالترتيب (None = لا رد حتى انقضاء المهلة)
+    fn stand_in_server(responses: Vec<Option<(u16, String)>>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/v1", listener.local_addr().unwrap());
+
+        let server = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                request.push_str(&String::from_utf8(body).unwrap());
+                requests.push(request);
+                match response {
+                    Some((status, body)) => {
+                        let reply = format!("HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}", status, body.len(), body);
+                        reader.get_mut().write_all(reply.as_bytes()).unwrap();
+                    }
+                    None => std::thread::sleep(Duration::from_millis(300)),
+                }
+            }
+            requests
+        });
+
+        (base_url, server)
+    }
+
+    #[test]
+    fn test_generation_backends_retry_and_accounting() {
+        // خلفية القوالب حتمية وتصيّر معاملات القدرة
+        let service = GenerationService::offline();
+        let params = serde_json::json!({ "level": "مبتدئ" });
+        let first = service.generate("education", "lesson", &serde_json::json!("الكسور"), &params).unwrap();
+        let second = service.generate("education", "lesson", &serde_json::json!("الكسور"), &params).unwrap();
+        assert_eq!(first.text, second.text);
+        assert!(first.text.contains("اكتب درساً عن: الكسور للمستوى مبتدئ"));
+        // القدرة غير المعروفة تستخدم قالب المصنع العام
+        assert!(service.generate("creative", "poem", &serde_json::json!("البحر"), &Value::Null).unwrap()
+            .text.contains("أنشئ محتوى إبداعياً عن: البحر"));
+        assert!(service.generate("unknown", "x", &Value::Null, &Value::Null).is_err());
+        assert_eq!(service.usage()["education"].requests, 2);
+
+        // نقطة OpenAI: فشل مؤقت ثم نجاح مع حساب التكلفة من الرموز المبلغ عنها
+        let reply = r#"{"choices":[{"message":{"role":"assistant","content":"درس الكسور"}}],"usage":{"prompt_tokens":1000,"completion_tokens":500}}"#;
+        let (base_url, server) = stand_in_server(vec![Some((503, "{}".to_string())), Some((200, reply.to_string()))]);
+        let backend = OpenAiBackend::new(&OpenAiConfig {
+            base_url,
+            model: "llama3".to_string(),
+            api_key: Some("secret".to_string()),
+            timeout_secs: 5,
+            pricing: Pricing { prompt_per_1k: 0.5, completion_per_1k: 1.0 },
+        }).unwrap();
+        let service = GenerationService::new(Box::new(backend)).with_retry(3, Duration::from_millis(1));
+        let generation = service.generate("education", "quiz", &serde_json::json!("الكسور"), &Value::Null).unwrap();
+        assert_eq!(generation.text, "درس الكسور");
+        assert_eq!(generation.attempts, 2);
+        assert!((generation.cost_usd - 1.0).abs() < 1e-9);
+        let requests = server.join().unwrap();
+        assert!(requests[1].starts_with("POST /v1/chat/completions"));
+        assert!(requests[1].contains("Authorization: Bearer secret"));
+        assert!(requests[1].contains("5 أسئلة"));
+
+        // انقضاء المهلة في كل المحاولات يعطي Timeout
+        let (base_url, server) = stand_in_server(vec![None, None]);
+        let backend = OpenAiBackend::new(&OpenAiConfig {
+            base_url,
+            model: "llama3".to_string(),
+            api_key: None,
+            timeout_secs: 1,
+            pricing: Pricing::default(),
+        }).unwrap().with_timeout(Duration::from_millis(100));
+        let service = GenerationService::new(Box::new(backend)).with_retry(2, Duration::from_millis(1));
+        assert!(matches!(service.generate("technology", "code", &serde_json::json!("فرز"), &Value::Null), Err(FactoryError::Timeout)));
+        assert_eq!(service.usage()["technology"].failures, 1);
+        server.join().unwrap();
+
+        // الرد المجزأ يُفك ترميزه
+        let chunked = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\n{\"a\"\r\n3\r\n:1}\r\n0\r\n\r\n";
+        assert_eq!(parse_http_response(chunked).unwrap(), (200, b"{\"a\":1}".to_vec()));
+    }
+
+    #[test]
+    fn test_https_base_url_rejected_at_config_load() {
+        let config = |base_url: &str| OpenAiConfig {
+            base_url: base_url.to_string(),
+            model: "gpt-4o".to_string(),
+            api_key: None,
+            timeout_secs: 5,
+            pricing: Pricing::default(),
+        };
+
+        let err = GeneratorConfig::OpenAi(config("https://api.openai.com/v1")).validate().unwrap_err();
+        assert!(matches!(&err, FactoryError::InitializationFailed(reason) if reason.contains("https://")));
+        assert!(OpenAiBackend::new(&config("https://api.openai.com/v1")).is_err());
+        assert!(OpenAiBackend::new(&config("ftp://localhost")).is_err());
+        assert!(GeneratorConfig::OpenAi(config("http://localhost:11434")).validate().is_ok());
+        assert!(GeneratorConfig::Template.validate().is_ok());
+    }
+}
@@ -0,0 +1,459 @@
+//! نموذج صحة النظام: فحوص المكونات ومسبارا الحياة والجاهزية
+//!
+//! يسجل كل مكون (سجل المصانع، كل مصنع، التخزين، الطابور، المراقب) فحصاً
+//! يعيد مستوى من `SystemHealth`، وتُجمع الفحوص بأسوأ مستوى. معنى المسبارين
+//! كما يتوقعه Kubernetes:
+//! - الحياة: العملية تستجيب؛ لا تعتمد على المكونات حتى لا تُعاد تشغيلها بسبب عطل خارجها.
+//! - الجاهزية: لا يوجد مكون أساسي في حالة حرجة؛ وإلا تُستبعد النسخة من الخدمة مؤقتاً.
+
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::core::factory::FactoryStatus;
+use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::SystemHealth;
+use crate::core::tuning::knobs;
+use crate::mhos::monitor::SystemMonitor;
+
+/// أقل عدد طلبات منتهية لاحتساب معدل فشل المصنع
+const MIN_FAILURE_SAMPLE: u64 = 10;
+
+/// نتيجة فحص مكون
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    pub level: SystemHealth,
+    pub message: String,
+    pub details: Value,
+}
+
+impl CheckResult {
+    pub fn new(level: SystemHealth, message: impl Into<String>) -> Self {
+        Self { level, message: message.into(), details: Value::Null }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// فحص صحة مكون
+pub trait HealthCheck: Send + Sync {
+    /// اسم المكون في التقرير
+    fn name(&self) -> String;
+
+    /// هل تمنع حالته الحرجة استقبال الطلبات (الجاهزية)
+    fn critical(&self) -> bool {
+        false
+    }
+
+    fn check(&self) -> CheckResult;
+}
+
+/// صحة مكون في التقرير
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    pub component: String,
+    pub level: SystemHealth,
+    pub critical: bool,
+    pub message: String,
+    #[serde(skip_serializing_if = "Value::is_null")]
+    pub details: Value,
+    pub duration_ms: u64,
+}
+
+/// تقرير الصحة المجمع
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    /// أسوأ مستوى بين المكونات
+    pub status: SystemHealth,
+    pub ready: bool,
+    pub components: Vec<ComponentHealth>,
+    pub version: String,
+    pub uptime_secs: u64,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl HealthReport {
+    /// المكونات الأساسية التي تمنع الجاهزية
+    pub fn blocking(&self) -> Vec<&ComponentHealth> {
+        self.components.iter()
+            .filter(|c| c.critical && c.level == SystemHealth::Critical)
+            .collect()
+    }
+}
+
+/// نتيجة مسبار الحياة
+#[derive(Debug, Clone, Serialize)]
+pub struct Liveness {
+    pub alive: bool,
+    pub version: String,
+    pub uptime_secs: u64,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// سجل فحوص الصحة
+pub struct HealthRegistry {
+    checks: RwLock<Vec<Arc<dyn HealthCheck>>>,
+    started_at: Instant,
+}
+
+impl HealthRegistry {
+    /// سجل فارغ
+    pub fn new() -> Self {
+        Self { checks: RwLock::new(Vec::new()), started_at: Instant::now() }
+    }
+
+    /// الفحوص المضمنة: سجل المصانع وكل مصنع والتخزين والطابور
+    pub fn with_builtin(factory_manager: Arc<FactoryManager>, data_dir: PathBuf) -> Self {
+        let registry = Self::new();
+        registry.register(Arc::new(RegistryCheck { factory_manager: factory_manager.clone() }));
+        for factory in factory_manager.list_factories() {
+            registry.register(Arc::new(FactoryCheck {
+                factory_manager: factory_manager.clone(),
+                factory_type: factory.factory_type().to_string(),
+            }));
+        }
+        registry.register(Arc::new(StorageCheck { data_dir }));
+        registry.register(Arc::new(QueueCheck { factory_manager }));
+        registry
+    }
+
+    /// فحص مراقب النظام (في الخادم حيث تعمل حلقة المراقبة)
+    pub fn with_monitor(self, monitor: Arc<SystemMonitor>) -> Self {
+        self.register(Arc::new(MonitorCheck { monitor }));
+        self
+    }
+
+    /// تسجيل فحص (يحل محل فحص بنفس الاسم)
+    pub fn register(&self, check: Arc<dyn HealthCheck>) {
+        let mut checks = match self.checks.write() {
+            Ok(checks) => checks,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let name = check.name();
+        checks.retain(|existing| existing.name() != name);
+        checks.push(check);
+    }
+
+    /// أسماء المكونات المسجلة
+    pub fn names(&self) -> Vec<String> {
+        self.checks.read().map(|c| c.iter().map(|check| check.name()).collect()).unwrap_or_default()
+    }
+
+    /// تشغيل كل الفحوص وتجميعها
+    pub fn report(&self) -> HealthReport {
+        let checks = self.checks.read().map(|c| c.clone()).unwrap_or_default();
+
+        let components: Vec<ComponentHealth> = checks.iter().map(|check| {
+            let start = Instant::now();
+            let result = check.check();
+            ComponentHealth {
+                component: check.name(),
+                level: result.level,
+                critical: check.critical(),
+                message: result.message,
+                details: result.details,
+                duration_ms: start.elapsed().as_millis() as u64,
+            }
+        }).collect();
+
+        let status = components.iter().map(|c| c.level).max().unwrap_or(SystemHealth::Excellent);
+        let ready = !components.iter().any(|c| c.critical && c.level == SystemHealth::Critical);
+
+        HealthReport {
+            status,
+            ready,
+            components,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            checked_at: Utc::now(),
+        }
+    }
+
+    /// مسبار الحياة: لا يشغل فحوص المكونات
+    pub fn liveness(&self) -> Liveness {
+        Liveness {
+            alive: true,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            checked_at: Utc::now(),
+        }
+    }
+}
+
+impl Default for HealthRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// سجل المصانع: بدون مصانع لا يمكن خدمة أي طلب
+struct RegistryCheck {
+    factory_manager: Arc<FactoryManager>,
+}
+
+impl HealthCheck for RegistryCheck {
+    fn name(&self) -> String {
+        "registry".to_string()
+    }
+
+    fn critical(&self) -> bool {
+        true
+    }
+
+    fn check(&self) -> CheckResult {
+        let factories = self.factory_manager.list_factories();
+        let operational = factories.iter()
+            .filter(|f| self.factory_manager.factory_status(f.factory_type()).is_operational())
+            .count();
+        let details = json!({ "factories": factories.len(), "operational": operational });
+
+        let (level, message) = if factories.is_empty() {
+            (SystemHealth::Critical, "لا توجد مصانع مسجلة".to_string())
+        } else if operational == 0 {
+            // الإيقاف المؤقت قرار تشغيلي فلا يُخرج النسخة من الخدمة
+            (SystemHealth::Poor, "كل المصانع متوقفة".to_string())
+        } else if operational < factories.len() {
+            (SystemHealth::Good, format!("{} من {} مصنع يعمل", operational, factories.len()))
+        } else {
+            (SystemHealth::Excellent, format!("{} مصنع يعمل", factories.len()))
+        };
+        CheckResult::new(level, message).with_details(details)
+    }
+}
+
+/// مصنع واحد: حالته ومعدل فشله وامتلاء طابوره
+struct FactoryCheck {
+    factory_manager: Arc<FactoryManager>,
+    factory_type: String,
+}
+
+impl HealthCheck for FactoryCheck {
+    fn name(&self) -> String {
+        format!("factory:{}", self.factory_type)
+    }
+
+    fn check(&self) -> CheckResult {
+        let status = self.factory_manager.factory_status(&self.factory_type);
+        let concurrency = knobs().factory_concurrency(&self.factory_type);
+        // المصنع الذي لم يستقبل طلبات بعد بلا إحصائيات
+        let (queue_size, completed, failed) = self.factory_manager.factory_metrics()
+            .get(&self.factory_type)
+            .map(|m| (m.current_queue_size, m.successful_requests + m.failed_requests, m.failed_requests))
+            .unwrap_or((0, 0, 0));
+        let failure_rate = if completed >= MIN_FAILURE_SAMPLE {
+            failed as f64 / completed as f64
+        } else {
+            0.0
+        };
+        let details = json!({
+            "status": status.as_str(),
+            "queue_size": queue_size,
+            "concurrency": concurrency,
+            "failure_rate": failure_rate
+        });
+
+        let (level, message) = match &status {
+            FactoryStatus::Error(e) => (SystemHealth::Critical, format!("خطأ: {}", e)),
+            status if !status.is_operational() => (SystemHealth::Fair, status.display_name().to_string()),
+            _ if failure_rate > 0.5 => (SystemHealth::Poor, format!("معدل الفشل {:.0}%", failure_rate * 100.0)),
+            _ if failure_rate > 0.2 => (SystemHealth::Fair, format!("معدل الفشل {:.0}%", failure_rate * 100.0)),
+            _ if queue_size >= concurrency.max(1) => {
+                (SystemHealth::Fair, format!("الطابور ممتلئ ({}/{})", queue_size, concurrency))
+            }
+            _ if failure_rate > 0.05 => (SystemHealth::Good, format!("معدل الفشل {:.0}%", failure_rate * 100.0)),
+            status => (SystemHealth::Excellent, status.display_name().to_string()),
+        };
+        CheckResult::new(level, message).with_details(details)
+    }
+}
+
+/// دليل البيانات: يجب أن يقبل الكتابة لتحفظ المخازن تغييراتها
+struct StorageCheck {
+    data_dir: PathBuf,
+}
+
+impl HealthCheck for StorageCheck {
+    fn name(&self) -> String {
+        "storage".to_string()
+    }
+
+    fn critical(&self) -> bool {
+        true
+    }
+
+    fn check(&self) -> CheckResult {
+        let probe = self.data_dir.join(format!(".health_{}.tmp", crate::core::telemetry::random_hex(6)));
+        let result = std::fs::create_dir_all(&self.data_dir)
+            .and_then(|_| std::fs::write(&probe, b"ok"))
+            .and_then(|_| std::fs::remove_file(&probe));
+        let details = json!({ "data_dir": self.data_dir.display().to_string() });
+
+        match result {
+            Ok(()) => CheckResult::new(SystemHealth::Excellent, "دليل البيانات قابل للكتابة"),
+            Err(e) => CheckResult::new(SystemHealth::Critical, format!("تعذرت الكتابة في دليل البيانات: {}", e)),
+        }.with_details(details)
+    }
+}
+
+/// طابور التصنيع الإجمالي نسبة إلى حدود التزامن
+struct QueueCheck {
+    factory_manager: Arc<FactoryManager>,
+}
+
+impl HealthCheck for QueueCheck {
+    fn name(&self) -> String {
+        "queue".to_string()
+    }
+
+    fn check(&self) -> CheckResult {
+        let knobs = knobs();
+        let (queued, capacity) = self.factory_manager.factory_metrics().iter()
+            .fold((0usize, 0usize), |(queued, capacity), (factory_type, m)| {
+                (queued + m.current_queue_size, capacity + knobs.factory_concurrency(factory_type).max(1))
+            });
+        let utilization = if capacity == 0 { 0.0 } else { queued as f64 / capacity as f64 };
+
+        let level = match utilization {
+            u if u < 0.8 => SystemHealth::Excellent,
+            u if u < 1.0 => SystemHealth::Good,
+            u if u < 2.0 => SystemHealth::Fair,
+            _ => SystemHealth::Poor,
+        };
+        CheckResult::new(level, format!("{} طلب قيد التنفيذ ({:.0}% من السعة)", queued, utilization * 100.0))
+            .with_details(json!({ "queued": queued, "capacity": capacity, "utilization": utilization }))
+    }
+}
+
+/// حلقة المراقبة: تعمل وتسجل عينات حديثة
+struct MonitorCheck {
+    monitor: Arc<SystemMonitor>,
+}
+
+impl HealthCheck for MonitorCheck {
+    fn name(&self) -> String {
+        "monitor".to_string()
+    }
+
+    fn check(&self) -> CheckResult {
+        if !self.monitor.is_monitoring() {
+            return CheckResult::new(SystemHealth::Poor, "المراقبة متوقفة");
+        }
+        let latest = match self.monitor.latest_metrics() {
+            Some(latest) => latest,
+            None => return CheckResult::new(SystemHealth::Good, "بانتظار أول عينة"),
+        };
+
+        let interval = self.monitor.monitoring_interval().as_secs() as i64;
+        let age = (Utc::now() - latest.timestamp).num_seconds();
+        let details = json!({
+            "last_sample_age_secs": age,
+            "interval_secs": interval,
+            "cpu_usage": latest.cpu_usage,
+            "memory_usage": latest.memory_usage
+        });
+
+        let (level, message) = if age > interval * 3 {
+            (SystemHealth::Fair, format!("آخر عينة قبل {} ثانية", age))
+        } else if latest.cpu_usage > 0.9 || latest.memory_usage > 0.9 {
+            (SystemHealth::Fair, format!("موارد مرتفعة: المعالج {:.0}% والذاكرة {:.0}%",
+                latest.cpu_usage * 100.0, latest.memory_usage * 100.0))
+        } else {
+            (SystemHealth::Excellent, "المراقبة تعمل".to_string())
+        };
+        CheckResult::new(level, message).with_details(details)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedCheck {
+        name: &'static str,
+        critical: bool,
+        level: SystemHealth,
+    }
+
+    impl HealthCheck for FixedCheck {
+        fn name(&self) -> String { self.name.to_string() }
+        fn critical(&self) -> bool { self.critical }
+        fn check(&self) -> CheckResult { CheckResult::new(self.level, "ثابت") }
+    }
+
+    fn registry() -> (HealthRegistry, Arc<FactoryManager>, tempfile::TempDir) {
+        let data_dir = tempfile::tempdir().unwrap();
+        let factory_manager = Arc::new(FactoryManager::new());
+        let registry = HealthRegistry::with_builtin(factory_manager.clone(), data_dir.path().to_path_buf());
+        (registry, factory_manager, data_dir)
+    }
+
+    #[test]
+    fn test_builtin_checks_registered() {
+        let (registry, _, _data_dir) = registry();
+        let names = registry.names();
+        for name in ["registry", "factory:education", "storage", "queue"] {
+            assert!(names.iter().any(|n| n == name), "{}", name);
+        }
+
+        let report = registry.report();
+        assert!(report.ready);
+        assert_eq!(report.status, SystemHealth::Excellent);
+    }
+
+    #[test]
+    fn test_storage_check_leaves_no_files() {
+        let (registry, _, data_dir) = registry();
+        registry.report();
+        assert_eq!(std::fs::read_dir(data_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_storage_check_critical_when_unwritable() {
+        let data_dir = tempfile::tempdir().unwrap();
+        // ملف عادي في مكان الدليل فلا يمكن إنشاؤه
+        let blocked = data_dir.path().join("data");
+        std::fs::write(&blocked, b"").unwrap();
+        let registry = HealthRegistry::new();
+        registry.register(Arc::new(StorageCheck { data_dir: blocked }));
+
+        let report = registry.report();
+        assert!(!report.ready);
+        assert_eq!(report.blocking()[0].component, "storage");
+    }
+
+    #[test]
+    fn test_paused_factory_degrades_without_blocking_readiness() {
+        let (registry, factory_manager, _data_dir) = registry();
+        factory_manager.pause_factory("education").unwrap();
+
+        let report = registry.report();
+        assert!(report.ready);
+        assert_eq!(report.status, SystemHealth::Fair);
+    }
+
+    #[test]
+    fn test_only_critical_components_block_readiness() {
+        let (registry, _, _data_dir) = registry();
+
+        registry.register(Arc::new(FixedCheck { name: "cache", critical: false, level: SystemHealth::Critical }));
+        let report = registry.report();
+        assert!(report.ready && report.status == SystemHealth::Critical);
+
+        registry.register(Arc::new(FixedCheck { name: "storage", critical: true, level: SystemHealth::Critical }));
+        let report = registry.report();
+        assert!(!report.ready);
+        assert_eq!(report.blocking().iter().map(|c| c.component.as_str()).collect::<Vec<_>>(), vec!["storage"]);
+    }
+
+    #[test]
+    fn test_liveness_ignores_components() {
+        let registry = HealthRegistry::new();
+        registry.register(Arc::new(FixedCheck { name: "storage", critical: true, level: SystemHealth::Critical }));
+        assert!(registry.liveness().alive);
+    }
+}
@@ -2,9 +2,14 @@
 
 pub mod factory;
 pub mod quality;
+pub mod quality_history;
 pub mod factory_manager;
 pub mod config;
 pub mod errors;
+pub mod telemetry;
+pub mod tuning;
+pub mod generation;
+pub mod health;
 
 // إعادة التصدير للاستخدام السهل
 pub use factory::Factory;
@@ -3,6 +3,11 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
+use crate::analysis::{AccessibilityChecker, SecurityScanner, SeoAnalyzer, TextAnalyzer};
+use crate::analysis::security::{SecurityCategory, Severity};
+use crate::core::telemetry::Span;
+use crate::core::tuning::knobs;
+use crate::core::quality_history::{QualityHistory, QualityRegression, ReportQuery, TrendDirection};
 
 /// بوابة الجودة (Quality Gate)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,6 +85,9 @@ pub enum CheckType {
     Performance,    // فحص الأداء
     Security,       // فحص الأمان
     Compliance,     // فحص الامتثال
+    Seo,            // تحسين محركات البحث
+    Accessibility,  // إمكانية الوصول (WCAG)
+    Usability,      // قابلية الاستخدام
     Custom,         // فحص مخصص
 }
 
@@ -113,6 +121,8 @@ pub struct QualityReport {
     pub report_id: String,
     pub entity_id: String, // ID للمشروع أو المنتج
     pub entity_type: String,
+    #[serde(default)]
+    pub factory: Option<String>, // المصنع المنتج (لتتبع الاتجاهات والتراجع)
     pub overall_score: f64,
     pub passed: bool,
     pub gate_results: HashMap<String, GateResult>,
@@ -124,14 +134,19 @@ pub struct QualityReport {
 /// نظام إدارة الجودة
 pub struct QualityManager {
     gates: HashMap<String, QualityGate>,
-    reports: HashMap<String, QualityReport>,
+    history: QualityHistory,
 }
 
 impl QualityManager {
     pub fn new() -> Self {
+        Self::with_history(QualityHistory::in_memory())
+    }
+    
+    /// مدير جودة بسجل تقارير محدد (مثل سجل محفوظ في ملف)
+    pub fn with_history(history: QualityHistory) -> Self {
         let mut manager = Self {
             gates: HashMap::new(),
-            reports: HashMap::new(),
+            history,
         };
         
         // إضافة بوابات MH-OS v2.2 الافتراضية
@@ -264,7 +279,7 @@ impl QualityManager {
                     required: true,
                     weight: 0.3,
                     check_type: CheckType::Security,
-                    parameters: HashMap::new(),
+                    parameters: HashMap::from([("categories".to_string(), serde_json::json!(["code"]))]),
                 },
             ],
             threshold: 0.9,
@@ -329,6 +344,56 @@ impl QualityManager {
         self.gates.insert(usability_gate.id.clone(), usability_gate);
     }
     
+    /// تفعيل بوابات مخرجات المواقع (SEO) إضافة إلى بوابات MH-OS الافتراضية
+    pub fn enable_website_gates(&mut self) {
+        let seo_gate = QualityGate {
+            id: "website_gate_seo".to_string(),
+            name: "تحسين محركات البحث - المواقع".to_string(),
+            description: "فحص العنوان والوصف والعناوين والصور والروابط والكلمات المفتاحية واتجاه اللغة".to_string(),
+            gate_type: GateType::Compliance,
+            criteria: vec![
+                QualityCriterion {
+                    id: "seo_1".to_string(),
+                    name: "تحسين محركات البحث".to_string(),
+                    description: "تحليل HTML/Markdown للموقع (يجب أن تكون النتيجة 75% على الأقل)".to_string(),
+                    required: true,
+                    weight: 1.0,
+                    check_type: CheckType::Seo,
+                    parameters: HashMap::from([("min_score".to_string(), serde_json::json!(0.75))]),
+                },
+            ],
+            threshold: 0.75,
+            weight: 0.2,
+            enabled: true,
+            auto_run: true,
+        };
+
+        let accessibility_gate = QualityGate {
+            id: "website_gate_accessibility".to_string(),
+            name: "إمكانية الوصول - المواقع".to_string(),
+            description: "فحص WCAG 2.1: النص البديل، تسميات النماذج، ترتيب العناوين، التباين، اللغة والاتجاه، المعالم".to_string(),
+            gate_type: GateType::Accessibility,
+            criteria: vec![
+                QualityCriterion {
+                    id: "a11y_1".to_string(),
+                    name: "معايير WCAG 2.1".to_string(),
+                    description: "لا مخالفات حرجة ونتيجة 85% على الأقل".to_string(),
+                    required: true,
+                    weight: 1.0,
+                    check_type: CheckType::Accessibility,
+                    parameters: HashMap::from([("min_score".to_string(), serde_json::json!(0.85))]),
+                },
+            ],
+            threshold: 0.85,
+            weight: 0.2,
+            enabled: true,
+            auto_run: true,
+        };
+
+        self.gates.insert(seo_gate.id.clone(), seo_gate);
+        self.gates.insert(accessibility_gate.id.clone(), accessibility_gate);
+    }
+
     pub fn add_gate(&mut self, gate: QualityGate) {
         self.gates.insert(gate.id.clone(), gate);
     }
@@ -346,9 +411,25 @@ impl QualityManager {
     }
     
     pub fn run_quality_check(&self, entity_id: &str, entity_type: &str, data: serde_json::Value) -> QualityReport {
+        let mut span = Span::start("quality.check");
+        span.set_attribute("entity_id", entity_id);
+        span.set_attribute("entity_type", entity_type);
         let mut gate_results = HashMap::new();
         
-        for gate in self.gates.values().filter(|g| g.enabled) {
+        // البوابات ذات نسبة العينة الأقل من 1 تُشغَّل في جزء من الفحوصات فقط،
+        // ولا يُترك فحص دون أي بوابة
+        let knobs = knobs();
+        let enabled: Vec<&QualityGate> = self.gates.values().filter(|g| g.enabled).collect();
+        let mut sampled: Vec<&QualityGate> = enabled.iter()
+            .copied()
+            .filter(|g| rand::random::<f64>() < knobs.gate_sampling_rate(&g.id))
+            .collect();
+        if sampled.is_empty() {
+            sampled = enabled;
+        }
+        span.set_attribute("quality.gates_run", sampled.len());
+        
+        for gate in sampled {
             let result = self.run_gate_check(gate, &data);
             gate_results.insert(gate.id.clone(), result);
         }
@@ -356,11 +437,14 @@ impl QualityManager {
         // حساب النتيجة الإجمالية
         let overall_score = self.calculate_overall_score(&gate_results);
         let passed = overall_score >= 0.8; // 80% كحد أدنى
+        span.set_attribute("quality.score", overall_score);
+        span.set_attribute("quality.passed", passed);
         
         QualityReport {
             report_id: format!("qr_{}", uuid::Uuid::new_v4()),
             entity_id: entity_id.to_string(),
             entity_type: entity_type.to_string(),
+            factory: data.get("factory").and_then(|f| f.as_str()).map(|f| f.to_string()),
             overall_score,
             passed,
             gate_results,
@@ -371,6 +455,9 @@ impl QualityManager {
     }
     
     fn run_gate_check(&self, gate: &QualityGate, data: &serde_json::Value) -> GateResult {
+        let mut span = Span::start("quality.gate");
+        span.set_attribute("gate_id", gate.id.as_str());
+        let started = std::time::Instant::now();
         let mut details = HashMap::new();
         let mut total_score = 0.0;
         let mut total_weight = 0.0;
@@ -389,6 +476,8 @@ impl QualityManager {
         };
         
         let passed = gate_score >= gate.threshold;
+        span.set_attribute("quality.score", gate_score);
+        span.set_attribute("quality.passed", passed);
         
         GateResult {
             gate_id: gate.id.clone(),
@@ -396,16 +485,187 @@ impl QualityManager {
             passed,
             score: gate_score,
             details,
-            execution_time_ms: 100, // قيمة افتراضية
+            execution_time_ms: started.elapsed().as_millis() as u64,
             timestamp: Utc::now(),
             recommendations: self.generate_recommendations(passed, gate_score),
         }
     }
     
-    fn run_criterion_check(&self, criterion: &QualityCriterion, _data: &serde_json::Value) -> CriterionResult {
+    fn run_criterion_check(&self, criterion: &QualityCriterion, data: &serde_json::Value) -> CriterionResult {
+        match criterion.check_type {
+            CheckType::Content => {
+                if let Some(content) = data.get("content").and_then(|c| c.as_str()) {
+                    return self.run_content_check(criterion, content);
+                }
+            }
+            CheckType::Seo => {
+                let content = data.get("html")
+                    .or_else(|| data.get("content"))
+                    .and_then(|c| c.as_str());
+                if let Some(content) = content {
+                    return self.run_seo_check(criterion, content, data);
+                }
+            }
+            CheckType::Security => {
+                let content = data.get("code")
+                    .or_else(|| data.get("html"))
+                    .or_else(|| data.get("content"))
+                    .and_then(|c| c.as_str());
+                if let Some(content) = content {
+                    return self.run_security_check(criterion, content);
+                }
+            }
+            CheckType::Accessibility => {
+                if let Some(html) = data.get("html").and_then(|c| c.as_str()) {
+                    return self.run_accessibility_check(criterion, html);
+                }
+            }
+            _ => {}
+        }
+
+        self.run_simulated_check(criterion)
+    }
+
+    /// فحص المحتوى النصي بمحلل النصوص العربية
+    fn run_content_check(&self, criterion: &QualityCriterion, content: &str) -> CriterionResult {
+        let report = TextAnalyzer::new().analyze(content);
+
+        // الحد الأدنى قابل للتخصيص عبر معاملات المعيار (min_score أو min_ux_score من 10)
+        let min_score = criterion.parameters.get("min_score")
+            .and_then(|v| v.as_f64())
+            .or_else(|| criterion.parameters.get("min_ux_score").and_then(|v| v.as_f64()).map(|v| v / 10.0))
+            .unwrap_or(0.7);
+
+        let score = report.score as f64;
+        let passed = score >= min_score;
+
+        CriterionResult {
+            criterion_id: criterion.id.clone(),
+            criterion_name: criterion.name.clone(),
+            passed,
+            score,
+            message: if passed {
+                format!("✅ جودة المحتوى {:.0}% - المقروئية: {}", score * 100.0, report.readability.level.display_name())
+            } else {
+                format!("❌ جودة المحتوى {:.0}% أقل من {:.0}%", score * 100.0, min_score * 100.0)
+            },
+            details: serde_json::json!({
+                "statistics": report.statistics,
+                "readability": report.readability,
+                "script": report.script,
+                "issues": report.issues,
+                "suggestions": report.suggestions,
+            }),
+        }
+    }
+
+    /// فحص SEO لمخرجات المواقع
+    fn run_seo_check(&self, criterion: &QualityCriterion, content: &str, data: &serde_json::Value) -> CriterionResult {
+        // الكلمات المفتاحية من معاملات المعيار أو من بيانات الفحص
+        let keywords: Vec<String> = criterion.parameters.get("keywords")
+            .or_else(|| data.get("keywords"))
+            .and_then(|k| k.as_array())
+            .map(|k| k.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+            .unwrap_or_default();
+
+        let mut analyzer = SeoAnalyzer::new().with_keywords(keywords);
+        if let Some(host) = data.get("site_host").and_then(|h| h.as_str()) {
+            analyzer = analyzer.with_site_host(host);
+        }
+        let report = analyzer.analyze(content);
+
+        let min_score = criterion.parameters.get("min_score")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.75);
+
+        let score = report.score as f64;
+        let passed = score >= min_score;
+
+        CriterionResult {
+            criterion_id: criterion.id.clone(),
+            criterion_name: criterion.name.clone(),
+            passed,
+            score,
+            message: if passed {
+                format!("✅ نتيجة SEO {:.0}%", score * 100.0)
+            } else {
+                format!("❌ نتيجة SEO {:.0}% أقل من {:.0}% ({} ملاحظة)", score * 100.0, min_score * 100.0, report.suggestions.len())
+            },
+            details: serde_json::json!({
+                "format": report.format,
+                "keywords": report.keywords,
+                "findings": report.findings,
+                "suggestions": report.suggestions,
+            }),
+        }
+    }
+
+    /// فحص إمكانية الوصول لمخرجات HTML
+    fn run_accessibility_check(&self, criterion: &QualityCriterion, html: &str) -> CriterionResult {
+        let report = AccessibilityChecker::new().check(html);
+
+        let min_score = criterion.parameters.get("min_score")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.85);
+
+        let score = report.score as f64;
+        // أي مخالفة حرجة تُفشل المعيار مهما كانت النتيجة
+        let passed = score >= min_score && report.critical_count == 0;
+
+        CriterionResult {
+            criterion_id: criterion.id.clone(),
+            criterion_name: criterion.name.clone(),
+            passed,
+            score,
+            message: if passed {
+                format!("✅ إمكانية الوصول {:.0}%", score * 100.0)
+            } else {
+                format!("❌ إمكانية الوصول {:.0}% ({} مخالفة، منها {} حرجة)",
+                    score * 100.0, report.violations.len(), report.critical_count)
+            },
+            details: serde_json::json!({
+                "rules": report.rules,
+                "violations": report.violations,
+            }),
+        }
+    }
+
+    /// الفحص الأمني للكود والمستندات المولدة
+    fn run_security_check(&self, criterion: &QualityCriterion, content: &str) -> CriterionResult {
+        let mut report = SecurityScanner::new().scan(content);
+
+        // تقييد الفحص بفئات معينة عبر المعامل categories (secret, code, html)
+        if let Some(categories) = criterion.parameters.get("categories").and_then(|c| c.as_array()) {
+            let categories: Vec<SecurityCategory> = [SecurityCategory::Secret, SecurityCategory::Code, SecurityCategory::Html]
+                .into_iter()
+                .filter(|c| categories.iter().any(|v| v.as_str() == Some(c.as_str())))
+                .collect();
+            report = report.only(&categories);
+        }
+
+        let score = report.score as f64;
+        let passed = report.highest_severity.map(|s| s < Severity::High).unwrap_or(true);
+
+        CriterionResult {
+            criterion_id: criterion.id.clone(),
+            criterion_name: criterion.name.clone(),
+            passed,
+            score,
+            message: match report.highest_severity {
+                None => "✅ لا توجد ملاحظات أمنية".to_string(),
+                Some(severity) if passed => format!("✅ {} ملاحظة بخطورة {} كحد أقصى", report.findings.len(), severity.display_name()),
+                Some(severity) => format!("❌ {} ملاحظة أمنية، أعلاها بخطورة {}", report.findings.len(), severity.display_name()),
+            },
+            details: serde_json::json!({
+                "findings": report.findings,
+            }),
+        }
+    }
+
+    fn run_simulated_check(&self, criterion: &QualityCriterion) -> CriterionResult {
         // هذا مجرد تنفيذ تجريبي
         // في التنفيذ الحقيقي، سيكون هناك منطق تحقق حقيقي
-        
+
         let passed = rand::random::<f64>() > 0.3; // 70% احتمال للنجاح
         let score = if passed { 1.0 } else { 0.5 };
         
@@ -465,16 +725,32 @@ impl QualityManager {
         recommendations
     }
     
-    pub fn save_report(&mut self, report: QualityReport) {
-        self.reports.insert(report.report_id.clone(), report);
+    /// حفظ التقرير في السجل وإرجاع أي تراجع مكتشف مقارنة بخط الأساس
+    pub fn save_report(&mut self, report: QualityReport) -> Vec<QualityRegression> {
+        match self.history.record(report) {
+            Ok(regressions) => regressions,
+            Err(e) => {
+                log::error!("❌ فشل حفظ تقرير الجودة: {}", e);
+                Vec::new()
+            }
+        }
     }
     
     pub fn get_report(&self, report_id: &str) -> Option<&QualityReport> {
-        self.reports.get(report_id)
+        self.history.get(report_id)
     }
     
     pub fn list_reports(&self) -> Vec<&QualityReport> {
-        self.reports.values().collect()
+        self.history.all()
+    }
+    
+    /// الاستعلام عن التقارير حسب الكيان والمصنع والفترة
+    pub fn query_reports(&self, query: &ReportQuery) -> Vec<&QualityReport> {
+        self.history.query(query)
+    }
+    
+    pub fn history(&self) -> &QualityHistory {
+        &self.history
     }
 }
 
@@ -487,6 +763,12 @@ pub struct MiniDashboard {
     pub ai_assistance: bool,
     pub last_updates: Vec<Update>,
     pub system_health: SystemHealth,
+    #[serde(default)]
+    pub quality_trend: Vec<f64>,
+    #[serde(default)]
+    pub quality_direction: Option<TrendDirection>,
+    #[serde(default)]
+    pub regressions: Vec<QualityRegression>,
 }
 
 impl MiniDashboard {
@@ -498,35 +780,35 @@ impl MiniDashboard {
             ai_assistance: true,
             last_updates: Vec::new(),
             system_health: SystemHealth::Excellent,
+            quality_trend: Vec::new(),
+            quality_direction: None,
+            regressions: Vec::new(),
         }
     }
     
+    /// تعبئة مؤشرات الجودة من سجل التقارير
+    pub fn with_quality_history(mut self, history: &QualityHistory) -> Self {
+        let query = ReportQuery { limit: Some(20), ..Default::default() };
+        if let Some(overall) = history.trends(&query).into_iter().next() {
+            if let Some(last) = overall.points.last() {
+                self.output_quality = last.score;
+            }
+            self.quality_trend = overall.points.iter().map(|p| p.score).collect();
+            self.quality_direction = (!overall.points.is_empty()).then_some(overall.direction);
+        }
+        self.regressions = history.regressions(None).into_iter().take(5).cloned().collect();
+        if !self.regressions.is_empty() {
+            self.system_health = SystemHealth::Fair;
+        }
+        self
+    }
+    
+    /// عرض اللوحة المصغرة عبر قالب Tera
     pub fn to_html(&self) -> String {
-        format!(
-            r#"<div class="mhos-mini-dashboard">
-                <h4>📊 MH-OS v2.2 - Mini Dashboard</h4>
-                <div class="metric">
-                    <span class="label">🏭 المهام النشطة:</span>
-                    <span class="value">{}/3 ✅</span>
-                </div>
-                <div class="metric">
-                    <span class="label">⚡ سرعة المعالجة:</span>
-                    <span class="value">{}%</span>
-                </div>
-                <div class="metric">
-                    <span class="label">🎯 جودة المخرجات:</span>
-                    <span class="value">{}/10</span>
-                </div>
-                <div class="metric">
-                    <span class="label">🤖 الذكاء الاصطناعي:</span>
-                    <span class="value">{}</span>
-                </div>
-            </div>"#,
-            self.active_tasks,
-            (self.processing_speed * 100.0) as u32,
-            (self.output_quality * 10.0) as u32,
-            if self.ai_assistance { "ACTIVE 50x" } else { "INACTIVE" }
-        )
+        crate::mhos::views::renderer().render_mini_dashboard(self).unwrap_or_else(|e| {
+            log::error!("❌ تعذر عرض اللوحة المصغرة: {}", e);
+            String::new()
+        })
     }
     
     pub fn to_json(&self) -> serde_json::Value {
@@ -536,6 +818,9 @@ impl MiniDashboard {
             "output_quality": self.output_quality,
             "ai_assistance": self.ai_assistance,
             "system_health": self.system_health.as_str(),
+            "quality_trend": self.quality_trend,
+            "quality_direction": self.quality_direction,
+            "regressions": self.regressions,
             "timestamp": Utc::now().to_rfc3339()
         })
     }
@@ -550,8 +835,8 @@ pub struct Update {
     pub applied: bool,
 }
 
-/// صحة النظام
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// صحة النظام (مرتبة من الأفضل إلى الأسوأ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum SystemHealth {
     Excellent,
     Good,
@@ -0,0 +1,519 @@
+//! سجل تقارير الجودة
+//! حفظ التقارير في ملف JSON Lines، الاستعلام عنها، حساب الاتجاهات واكتشاف التراجع
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use crate::core::quality::QualityReport;
+use crate::mhos::utils::timeseries;
+
+/// ميل الاتجاه الذي يعتبر ثباتاً
+const STABLE_SLOPE: f64 = 0.005;
+/// معامل التمهيد الأسي لسلسلة الاتجاه
+const SMOOTHING_ALPHA: f64 = 0.3;
+/// عدد وحدات المدى الربيعي التي تُعتبر بعدها النتيجة شاذة
+const ANOMALY_IQR_FACTOR: f64 = 1.5;
+/// الحد الأقصى لتقارير الجودة المحفوظة (تُحذف الأقدم من الملف والذاكرة)
+const MAX_QUALITY_REPORTS: usize = 1000;
+
+/// إعدادات اكتشاف التراجع
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegressionConfig {
+    /// عدد التقارير السابقة المستخدمة كخط أساس متحرك
+    pub baseline_window: usize,
+    /// أقل عدد من التقارير السابقة قبل تفعيل الاكتشاف
+    pub min_samples: usize,
+    /// أقصى انخفاض مسموح عن خط الأساس
+    pub max_delta: f64,
+}
+
+impl Default for RegressionConfig {
+    fn default() -> Self {
+        Self {
+            baseline_window: 5,
+            min_samples: 3,
+            max_delta: 0.05,
+        }
+    }
+}
+
+/// استعلام عن التقارير
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ReportQuery {
+    pub entity_id: Option<String>,
+    pub factory: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl ReportQuery {
+    fn matches(&self, report: &QualityReport) -> bool {
+        self.entity_id.as_ref().map(|e| &report.entity_id == e).unwrap_or(true)
+            && self.factory.as_ref().map(|f| report.factory.as_ref() == Some(f)).unwrap_or(true)
+            && self.from.map(|from| report.generated_at >= from).unwrap_or(true)
+            && self.to.map(|to| report.generated_at <= to).unwrap_or(true)
+    }
+}
+
+/// اتجاه السلسلة
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TrendDirection {
+    Improving,
+    Stable,
+    Declining,
+}
+
+impl TrendDirection {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TrendDirection::Improving => "في تحسن",
+            TrendDirection::Stable => "مستقر",
+            TrendDirection::Declining => "في تراجع",
+        }
+    }
+}
+
+/// نقطة في سلسلة الاتجاه
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrendPoint {
+    pub report_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub score: f64,
+    pub passed: bool,
+}
+
+/// سلسلة اتجاه لبوابة (أو للنتيجة الإجمالية عندما يكون gate_id = "overall")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateTrend {
+    pub gate_id: String,
+    pub gate_name: String,
+    pub points: Vec<TrendPoint>,
+    pub average: f64,
+    pub change: f64,
+    pub direction: TrendDirection,
+    /// ميل خط الاتجاه لكل تقرير
+    #[serde(default)]
+    pub slope: f64,
+    /// جودة ملاءمة خط الاتجاه (0 - 1)
+    #[serde(default)]
+    pub r_squared: f64,
+    /// النتائج بعد التمهيد الأسي
+    #[serde(default)]
+    pub smoothed: Vec<f64>,
+    #[serde(default)]
+    pub p10: f64,
+    #[serde(default)]
+    pub p90: f64,
+    /// معرفات التقارير ذات النتائج الشاذة
+    #[serde(default)]
+    pub anomalies: Vec<String>,
+}
+
+/// تراجع مكتشف في جودة مصنع
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityRegression {
+    pub factory: String,
+    pub gate_id: String,
+    pub gate_name: String,
+    pub baseline: f64,
+    pub current: f64,
+    pub delta: f64,
+    pub report_id: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// سجل تقارير الجودة
+pub struct QualityHistory {
+    path: Option<PathBuf>,
+    reports: Vec<QualityReport>,
+    regressions: Vec<QualityRegression>,
+    config: RegressionConfig,
+}
+
+impl QualityHistory {
+    /// سجل في الذاكرة فقط
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            reports: Vec::new(),
+            regressions: Vec::new(),
+            config: RegressionConfig::default(),
+        }
+    }
+
+    /// فتح سجل محفوظ (يُنشأ الملف عند أول حفظ)
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut history = Self::in_memory();
+
+        let mut pruned = 0;
+        if path.exists() {
+            // الاحتفاظ بالأحدث فقط أثناء القراءة
+            let mut reports = VecDeque::new();
+            let reader = BufReader::new(fs::File::open(&path)?);
+            for (number, line) in reader.lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<QualityReport>(&line) {
+                    Ok(report) => {
+                        reports.push_back(report);
+                        if reports.len() > MAX_QUALITY_REPORTS {
+                            reports.pop_front();
+                            pruned += 1;
+                        }
+                    }
+                    Err(e) => log::warn!("⚠️ تجاهل سطر تالف {} في {}: {}", number + 1, path.display(), e),
+                };
+            }
+            // إعادة تشغيل الاكتشاف لاستعادة قائمة التراجعات
+            for report in reports {
+                history.insert(report);
+            }
+            log::info!("📂 تم تحميل {} تقرير جودة من {}", history.reports.len(), path.display());
+        }
+
+        history.path = Some(path);
+        if pruned > 0 {
+            log::info!("🧹 حذف {} تقرير جودة قديم يتجاوز الحد ({})", pruned, MAX_QUALITY_REPORTS);
+            history.rewrite()?;
+        }
+        Ok(history)
+    }
+
+    /// تعديل إعدادات اكتشاف التراجع
+    pub fn with_regression_config(mut self, config: RegressionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn regression_config(&self) -> &RegressionConfig {
+        &self.config
+    }
+
+    /// حفظ تقرير وإرجاع التراجعات المكتشفة بسببه
+    pub fn record(&mut self, report: QualityReport) -> std::io::Result<Vec<QualityRegression>> {
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            let line = serde_json::to_string(&report).map_err(std::io::Error::other)?;
+            writeln!(file, "{}", line)?;
+        }
+
+        let before = self.regressions.len();
+        self.insert(report);
+        let detected = self.regressions[before..].to_vec();
+
+        if self.reports.len() > MAX_QUALITY_REPORTS {
+            let excess = self.reports.len() - MAX_QUALITY_REPORTS;
+            self.reports.drain(0..excess);
+            let retained: HashSet<&str> = self.reports.iter().map(|r| r.report_id.as_str()).collect();
+            self.regressions.retain(|r| retained.contains(r.report_id.as_str()));
+            self.rewrite()?;
+        }
+        Ok(detected)
+    }
+
+    /// إعادة كتابة الملف بالتقارير المحتفظ بها
+    fn rewrite(&self) -> std::io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let mut content = String::new();
+        for report in &self.reports {
+            content.push_str(&serde_json::to_string(report).map_err(std::io::Error::other)?);
+            content.push('\n');
+        }
+        let tmp = path.with_extension("jsonl.tmp");
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, path)
+    }
+
+    fn insert(&mut self, report: QualityReport) {
+        let detected = self.detect_regressions(&report);
+        for regression in &detected {
+            log::warn!(
+                "📉 تراجع في جودة {} ({}): {:.2} مقابل خط أساس {:.2}",
+                regression.factory, regression.gate_name, regression.current, regression.baseline
+            );
+        }
+        self.regressions.extend(detected);
+        self.reports.push(report);
+    }
+
+    /// مقارنة التقرير بخط الأساس المتحرك لنفس المصنع
+    fn detect_regressions(&self, report: &QualityReport) -> Vec<QualityRegression> {
+        let key = series_key(report);
+        let previous: Vec<&QualityReport> = self.reports.iter()
+            .rev()
+            .filter(|r| series_key(r) == key)
+            .take(self.config.baseline_window)
+            .collect();
+
+        let mut regressions = Vec::new();
+        let mut compare = |gate_id: &str, gate_name: &str, current: f64, history: Vec<f64>| {
+            if history.len() < self.config.min_samples {
+                return;
+            }
+            let baseline = history.iter().sum::<f64>() / history.len() as f64;
+            let delta = baseline - current;
+            if delta > self.config.max_delta {
+                regressions.push(QualityRegression {
+                    factory: key.to_string(),
+                    gate_id: gate_id.to_string(),
+                    gate_name: gate_name.to_string(),
+                    baseline,
+                    current,
+                    delta,
+                    report_id: report.report_id.clone(),
+                    detected_at: report.generated_at,
+                });
+            }
+        };
+
+        compare("overall", "النتيجة الإجمالية", report.overall_score,
+            previous.iter().map(|r| r.overall_score).collect());
+
+        let mut gates: Vec<_> = report.gate_results.values().collect();
+        gates.sort_by(|a, b| a.gate_id.cmp(&b.gate_id));
+        for gate in gates {
+            let history = previous.iter()
+                .filter_map(|r| r.gate_results.get(&gate.gate_id).map(|g| g.score))
+                .collect();
+            compare(&gate.gate_id, &gate.gate_name, gate.score, history);
+        }
+
+        regressions
+    }
+
+    /// تقرير حسب المعرف
+    pub fn get(&self, report_id: &str) -> Option<&QualityReport> {
+        self.reports.iter().find(|r| r.report_id == report_id)
+    }
+
+    /// جميع التقارير
+    pub fn all(&self) -> Vec<&QualityReport> {
+        self.reports.iter().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// التقارير المطابقة للاستعلام (الأحدث أولاً)
+    pub fn query(&self, query: &ReportQuery) -> Vec<&QualityReport> {
+        let mut reports: Vec<&QualityReport> = self.reports.iter().filter(|r| query.matches(r)).collect();
+        reports.sort_by(|a, b| b.generated_at.cmp(&a.generated_at).then_with(|| b.report_id.cmp(&a.report_id)));
+        if let Some(limit) = query.limit {
+            reports.truncate(limit);
+        }
+        reports
+    }
+
+    /// سلاسل الاتجاه للنتيجة الإجمالية ولكل بوابة
+    pub fn trends(&self, query: &ReportQuery) -> Vec<GateTrend> {
+        let mut reports = self.query(query);
+        reports.reverse();
+
+        let mut trends = vec![build_trend(
+            "overall",
+            "النتيجة الإجمالية",
+            reports.iter().map(|r| point(r, r.overall_score, r.passed)).collect(),
+        )];
+
+        let mut by_gate: HashMap<&str, (&str, Vec<TrendPoint>)> = HashMap::new();
+        for report in &reports {
+            for gate in report.gate_results.values() {
+                by_gate.entry(gate.gate_id.as_str())
+                    .or_insert_with(|| (gate.gate_name.as_str(), Vec::new()))
+                    .1
+                    .push(point(report, gate.score, gate.passed));
+            }
+        }
+
+        let mut gate_trends: Vec<GateTrend> = by_gate.into_iter()
+            .map(|(id, (name, points))| build_trend(id, name, points))
+            .collect();
+        gate_trends.sort_by(|a, b| a.gate_id.cmp(&b.gate_id));
+        trends.extend(gate_trends);
+
+        trends
+    }
+
+    /// التراجعات المكتشفة (الأحدث أولاً)، مع تصفية اختيارية حسب المصنع
+    pub fn regressions(&self, factory: Option<&str>) -> Vec<&QualityRegression> {
+        self.regressions.iter()
+            .rev()
+            .filter(|r| factory.map(|f| r.factory == f).unwrap_or(true))
+            .collect()
+    }
+}
+
+/// مفتاح السلسلة: المصنع إن وجد، وإلا نوع الكيان
+fn series_key(report: &QualityReport) -> &str {
+    report.factory.as_deref().unwrap_or(&report.entity_type)
+}
+
+fn point(report: &QualityReport, score: f64, passed: bool) -> TrendPoint {
+    TrendPoint {
+        report_id: report.report_id.clone(),
+        timestamp: report.generated_at,
+        score,
+        passed,
+    }
+}
+
+/// بناء سلسلة اتجاه مع الميل بطريقة المربعات الصغرى والتمهيد والقيم الشاذة
+fn build_trend(gate_id: &str, gate_name: &str, points: Vec<TrendPoint>) -> GateTrend {
+    let scores: Vec<f64> = points.iter().map(|p| p.score).collect();
+    let average = timeseries::mean(&scores).unwrap_or(0.0);
+    let change = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => last.score - first.score,
+        _ => 0.0,
+    };
+
+    let (slope, r_squared) = timeseries::linear_trend(&scores)
+        .map(|trend| (trend.slope, trend.r_squared))
+        .unwrap_or((0.0, 0.0));
+    let anomalies = timeseries::iqr_anomalies(&scores, ANOMALY_IQR_FACTOR).into_iter()
+        .map(|a| points[a.index].report_id.clone())
+        .collect();
+
+    let direction = if slope > STABLE_SLOPE {
+        TrendDirection::Improving
+    } else if slope < -STABLE_SLOPE {
+        TrendDirection::Declining
+    } else {
+        TrendDirection::Stable
+    };
+
+    GateTrend {
+        gate_id: gate_id.to_string(),
+        gate_name: gate_name.to_string(),
+        points,
+        average,
+        change,
+        direction,
+        slope,
+        r_squared,
+        smoothed: timeseries::ewma(&scores, SMOOTHING_ALPHA),
+        p10: timeseries::percentile(&scores, 10.0).unwrap_or(0.0),
+        p90: timeseries::percentile(&scores, 90.0).unwrap_or(0.0),
+        anomalies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    fn report(factory: &str, score: f64) -> QualityReport {
+        QualityReport {
+            report_id: format!("qr_{}", NEXT_ID.fetch_add(1, Ordering::Relaxed)),
+            entity_id: "site_1".to_string(),
+            entity_type: "website".to_string(),
+            factory: Some(factory.to_string()),
+            overall_score: score,
+            passed: score >= 0.8,
+            gate_results: HashMap::new(),
+            generated_at: Utc::now(),
+            generated_by: "test".to_string(),
+            mhos_version: "2.2.0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_regression_detection_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quality_history.jsonl");
+        let mut history = QualityHistory::open(&path).unwrap();
+
+        for score in [0.9, 0.92, 0.91] {
+            assert!(history.record(report("education", score)).unwrap().is_empty());
+        }
+        // مصنع آخر لا يؤثر على خط أساس التعليم
+        history.record(report("creative", 0.5)).unwrap();
+
+        let regressions = history.record(report("education", 0.8)).unwrap();
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].gate_id, "overall");
+        assert!((regressions[0].baseline - 0.91).abs() < 1e-9);
+
+        let query = ReportQuery { factory: Some("education".to_string()), ..Default::default() };
+        assert_eq!(history.query(&query).len(), 4);
+        let overall = &history.trends(&query)[0];
+        assert_eq!(overall.direction, TrendDirection::Declining);
+        assert!(overall.slope < 0.0);
+        assert_eq!(overall.smoothed.len(), 4);
+
+        // إعادة التحميل تستعيد التقارير والتراجعات
+        let reloaded = QualityHistory::open(&path).unwrap();
+        assert_eq!(reloaded.len(), 5);
+        assert_eq!(reloaded.regressions(Some("education")).len(), 1);
+    }
+
+    #[test]
+    fn test_history_keeps_latest_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quality_history.jsonl");
+        let mut history = QualityHistory::open(&path).unwrap();
+
+        let first = report("education", 0.9);
+        let first_id = first.report_id.clone();
+        history.record(first).unwrap();
+        for _ in 0..MAX_QUALITY_REPORTS + 4 {
+            history.record(report("education", 0.9)).unwrap();
+        }
+
+        assert_eq!(history.len(), MAX_QUALITY_REPORTS);
+        assert!(history.get(&first_id).is_none());
+        let lines = fs::read_to_string(&path).unwrap().lines().count();
+        assert_eq!(lines, MAX_QUALITY_REPORTS);
+        assert_eq!(QualityHistory::open(&path).unwrap().len(), MAX_QUALITY_REPORTS);
+    }
+
+    #[test]
+    fn test_open_prunes_oversized_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("quality_history.jsonl");
+        let content: String = (0..MAX_QUALITY_REPORTS + 3)
+            .map(|_| serde_json::to_string(&report("education", 0.9)).unwrap() + "\n")
+            .collect();
+        fs::write(&path, content).unwrap();
+
+        let history = QualityHistory::open(&path).unwrap();
+        assert_eq!(history.len(), MAX_QUALITY_REPORTS);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), MAX_QUALITY_REPORTS);
+    }
+
+    #[test]
+    fn test_query_breaks_timestamp_ties_by_id() {
+        let mut history = QualityHistory::in_memory();
+        let generated_at = Utc::now();
+        for id in ["qr_b", "qr_a", "qr_c"] {
+            let mut tied = report("education", 0.9);
+            tied.report_id = id.to_string();
+            tied.generated_at = generated_at;
+            history.record(tied).unwrap();
+        }
+
+        let ids: Vec<&str> = history.query(&ReportQuery::default()).iter().map(|r| r.report_id.as_str()).collect();
+        assert_eq!(ids, vec!["qr_c", "qr_b", "qr_a"]);
+    }
+}
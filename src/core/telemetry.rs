@@ -0,0 +1,707 @@
+//! التتبع الموزع بنموذج OpenTelemetry
+//!
+//! كل مقطع (span) يرث معرف التتبع ومعرف الطلب `request_id` من المقطع الحالي.
+//! السياق محفوظ لكل مهمة tokio (أو لكل خيط خارج المهام) حتى لا تختلط طلبات
+//! متزامنة على نفس العامل. المقاطع المنتهية تُرسل إلى خيط تصدير في الخلفية
+//! يكتبها إلى ملف JSON و/أو إلى مجمّع OTLP عبر HTTP بترميز JSON.
+//!
+//! المتغيرات البيئية:
+//! - `OTEL_EXPORTER_OTLP_ENDPOINT` (مثل http://localhost:4318) أو `OTEL_EXPORTER_OTLP_TRACES_ENDPOINT`
+//! - `OTEL_SERVICE_NAME` (الافتراضي mh-api)
+//! - `MH_TRACE_FILE` مسار ملف JSON Lines للتحليل دون اتصال
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// سعة طابور المقاطع قبل إسقاط الجديد منها (لا يُحجب مسار الطلب أبداً)
+const QUEUE_CAPACITY: usize = 4096;
+
+/// مهلة الاتصال بالمجمّع
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
+
+tokio::task_local! {
+    static TASK_CONTEXT: RefCell<Vec<SpanContext>>;
+}
+
+thread_local! {
+    static THREAD_CONTEXT: RefCell<Vec<SpanContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// سياق المقطع المنقول بين المكونات
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpanContext {
+    pub trace_id: String,
+    pub span_id: String,
+    pub request_id: Option<String>,
+}
+
+impl SpanContext {
+    /// ترويسة W3C traceparent
+    pub fn traceparent(&self) -> String {
+        format!("00-{}-{}-01", self.trace_id, self.span_id)
+    }
+
+    /// قراءة ترويسة W3C traceparent الواردة
+    pub fn from_traceparent(header: &str, request_id: Option<String>) -> Option<Self> {
+        let parts: Vec<&str> = header.trim().split('-').collect();
+        if parts.len() < 4 || parts[0].len() != 2 {
+            return None;
+        }
+        let valid = |id: &str, len: usize| {
+            id.len() == len && id.chars().all(|c| c.is_ascii_hexdigit()) && id.chars().any(|c| c != '0')
+        };
+        if !valid(parts[1], 32) || !valid(parts[2], 16) {
+            return None;
+        }
+
+        Some(Self {
+            trace_id: parts[1].to_ascii_lowercase(),
+            span_id: parts[2].to_ascii_lowercase(),
+            request_id,
+        })
+    }
+}
+
+/// نوع المقطع
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanKind {
+    Internal,
+    Server,
+}
+
+/// حالة المقطع
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpanStatus {
+    Unset,
+    Ok,
+    Error,
+}
+
+/// قيمة سمة
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AttributeValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl From<&str> for AttributeValue {
+    fn from(value: &str) -> Self {
+        AttributeValue::String(value.to_string())
+    }
+}
+
+impl From<String> for AttributeValue {
+    fn from(value: String) -> Self {
+        AttributeValue::String(value)
+    }
+}
+
+impl From<bool> for AttributeValue {
+    fn from(value: bool) -> Self {
+        AttributeValue::Bool(value)
+    }
+}
+
+impl From<i64> for AttributeValue {
+    fn from(value: i64) -> Self {
+        AttributeValue::Int(value)
+    }
+}
+
+impl From<u64> for AttributeValue {
+    fn from(value: u64) -> Self {
+        AttributeValue::Int(value.min(i64::MAX as u64) as i64)
+    }
+}
+
+impl From<usize> for AttributeValue {
+    fn from(value: usize) -> Self {
+        AttributeValue::from(value as u64)
+    }
+}
+
+impl From<u16> for AttributeValue {
+    fn from(value: u16) -> Self {
+        AttributeValue::Int(value as i64)
+    }
+}
+
+impl From<f64> for AttributeValue {
+    fn from(value: f64) -> Self {
+        AttributeValue::Float(value)
+    }
+}
+
+/// مقطع منتهٍ جاهز للتصدير
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpanData {
+    pub trace_id: String,
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub request_id: Option<String>,
+    pub name: String,
+    pub kind: SpanKind,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub duration_ms: f64,
+    pub status: SpanStatus,
+    pub status_message: Option<String>,
+    pub attributes: BTreeMap<String, AttributeValue>,
+}
+
+/// مقطع نشط؛ ينتهي ويُصدَّر عند الإسقاط
+pub struct Span {
+    data: SpanData,
+    started: Instant,
+    entered: bool,
+}
+
+impl Span {
+    /// مقطع داخلي تابع للمقطع الحالي (أو بداية تتبع جديد)، ويصبح هو المقطع الحالي حتى انتهائه
+    pub fn start(name: &str) -> Self {
+        let parent = current_context();
+        let mut span = Self::new(name, SpanKind::Internal, parent.as_ref());
+        with_stack(|stack| stack.push(span.context()));
+        span.entered = true;
+        span
+    }
+
+    /// مقطع خادم لطلب وارد؛ لا يُفعَّل تلقائياً بل يُمرر سياقه إلى `scope`
+    pub fn server(name: &str, remote: Option<&SpanContext>, request_id: String) -> Self {
+        let mut span = Self::new(name, SpanKind::Server, remote);
+        span.data.request_id = Some(request_id.clone());
+        span.set_attribute("request_id", request_id);
+        span
+    }
+
+    fn new(name: &str, kind: SpanKind, parent: Option<&SpanContext>) -> Self {
+        let now = Utc::now();
+        let mut attributes = BTreeMap::new();
+        if let Some(request_id) = parent.and_then(|p| p.request_id.clone()) {
+            attributes.insert("request_id".to_string(), AttributeValue::String(request_id));
+        }
+
+        Self {
+            data: SpanData {
+                trace_id: parent.map(|p| p.trace_id.clone()).unwrap_or_else(|| random_hex(16)),
+                span_id: random_hex(8),
+                parent_span_id: parent.map(|p| p.span_id.clone()),
+                request_id: parent.and_then(|p| p.request_id.clone()),
+                name: name.to_string(),
+                kind,
+                start_time: now,
+                end_time: now,
+                duration_ms: 0.0,
+                status: SpanStatus::Unset,
+                status_message: None,
+                attributes,
+            },
+            started: Instant::now(),
+            entered: false,
+        }
+    }
+
+    pub fn context(&self) -> SpanContext {
+        SpanContext {
+            trace_id: self.data.trace_id.clone(),
+            span_id: self.data.span_id.clone(),
+            request_id: self.data.request_id.clone(),
+        }
+    }
+
+    pub fn set_attribute(&mut self, key: &str, value: impl Into<AttributeValue>) {
+        self.data.attributes.insert(key.to_string(), value.into());
+    }
+
+    pub fn set_ok(&mut self) {
+        self.data.status = SpanStatus::Ok;
+        self.data.status_message = None;
+    }
+
+    pub fn set_error(&mut self, message: impl Into<String>) {
+        self.data.status = SpanStatus::Error;
+        self.data.status_message = Some(message.into());
+    }
+
+    /// إنهاء المقطع صراحة (مكافئ للإسقاط)
+    pub fn end(self) {}
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if self.entered {
+            let span_id = self.data.span_id.clone();
+            with_stack(|stack| {
+                if let Some(position) = stack.iter().rposition(|c| c.span_id == span_id) {
+                    stack.truncate(position);
+                }
+            });
+        }
+
+        if let Some(tracer) = TRACER.get() {
+            let mut data = self.data.clone();
+            data.end_time = Utc::now();
+            data.duration_ms = self.started.elapsed().as_secs_f64() * 1000.0;
+            tracer.submit(data);
+        }
+    }
+}
+
+/// تنفيذ `f` على مكدس السياق الخاص بالمهمة الحالية، أو بالخيط خارج المهام
+fn with_stack<R>(f: impl FnOnce(&mut Vec<SpanContext>) -> R) -> R {
+    let mut f = Some(f);
+    if let Ok(result) = TASK_CONTEXT.try_with(|stack| (f.take().unwrap())(&mut stack.borrow_mut())) {
+        return result;
+    }
+    THREAD_CONTEXT.with(|stack| (f.take().unwrap())(&mut stack.borrow_mut()))
+}
+
+/// سياق المقطع الحالي إن وجد
+pub fn current_context() -> Option<SpanContext> {
+    with_stack(|stack| stack.last().cloned())
+}
+
+/// معرف الطلب الجاري (لإرفاقه بالسجلات والاستجابات)
+pub fn current_request_id() -> Option<String> {
+    current_context().and_then(|c| c.request_id)
+}
+
+/// تشغيل مستقبل داخل سياق مقطع؛ كل المقاطع المنشأة أثناءه تتبعه
+pub async fn scope<F: Future>(context: SpanContext, future: F) -> F::Output {
+    TASK_CONTEXT.scope(RefCell::new(vec![context]), future).await
+}
+
+/// نسخة متزامنة من `scope` للشيفرة التي تُنفذ قبل أول استطلاع للمستقبل
+pub fn sync_scope<R>(context: SpanContext, f: impl FnOnce() -> R) -> R {
+    TASK_CONTEXT.sync_scope(RefCell::new(vec![context]), f)
+}
+
+/// ربط عمل متزامن بسياق المقطع الحالي قبل نقله إلى خيط آخر
+/// (`spawn_blocking` أو `web::block`)؛ السياق محفوظ لكل مهمة فلا يعبر الخيوط وحده
+pub fn in_current_context<R>(f: impl FnOnce() -> R) -> impl FnOnce() -> R {
+    let context = current_context();
+    move || match context {
+        Some(context) => sync_scope(context, f),
+        None => f(),
+    }
+}
+
+/// معرف عشوائي بالنظام الست عشري
+pub fn random_hex(bytes: usize) -> String {
+    (0..bytes).map(|_| format!("{:02x}", rand::random::<u8>())).collect()
+}
+
+/// إعدادات التتبع
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    pub service_name: String,
+    /// عنوان استقبال المقاطع الكامل (مثل http://localhost:4318/v1/traces)
+    pub otlp_endpoint: Option<String>,
+    pub json_file: Option<PathBuf>,
+    pub batch_size: usize,
+    pub flush_interval_ms: u64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            service_name: "mh-api".to_string(),
+            otlp_endpoint: None,
+            json_file: None,
+            batch_size: 256,
+            flush_interval_ms: 2000,
+        }
+    }
+}
+
+impl TelemetryConfig {
+    /// الإعدادات من متغيرات OpenTelemetry القياسية و MH_TRACE_FILE
+    pub fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let otlp_endpoint = var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT").or_else(|| {
+            var("OTEL_EXPORTER_OTLP_ENDPOINT")
+                .map(|base| format!("{}/v1/traces", base.trim_end_matches('/')))
+        });
+
+        Self {
+            service_name: var("OTEL_SERVICE_NAME").unwrap_or_else(|| "mh-api".to_string()),
+            otlp_endpoint,
+            json_file: var("MH_TRACE_FILE").map(PathBuf::from),
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.otlp_endpoint.is_some() || self.json_file.is_some()
+    }
+}
+
+/// وجهة تصدير المقاطع
+pub trait SpanExporter: Send {
+    fn name(&self) -> &str;
+    fn export(&mut self, batch: &[SpanData]) -> io::Result<()>;
+}
+
+/// تصدير إلى ملف JSON Lines (مقطع في كل سطر)
+pub struct JsonFileExporter {
+    path: PathBuf,
+}
+
+impl JsonFileExporter {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SpanExporter for JsonFileExporter {
+    fn name(&self) -> &str {
+        "json-file"
+    }
+
+    fn export(&mut self, batch: &[SpanData]) -> io::Result<()> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let mut lines = String::new();
+        for span in batch {
+            lines.push_str(&serde_json::to_string(span)?);
+            lines.push('\n');
+        }
+
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(lines.as_bytes())
+    }
+}
+
+/// تصدير إلى مجمّع OpenTelemetry عبر OTLP/HTTP بترميز JSON
+pub struct OtlpHttpExporter {
+    host: String,
+    port: u16,
+    path: String,
+    service_name: String,
+}
+
+impl OtlpHttpExporter {
+    pub fn new(endpoint: &str, service_name: &str) -> io::Result<Self> {
+        let rest = endpoint.strip_prefix("http://").ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "يدعم المصدّر http:// فقط (استخدم مجمّعاً محلياً)")
+        })?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/v1/traces"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| {
+                io::Error::new(io::ErrorKind::InvalidInput, format!("منفذ غير صالح: {}", port))
+            })?),
+            None => (authority, 80),
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+            service_name: service_name.to_string(),
+        })
+    }
+
+    /// جسم طلب ExportTraceServiceRequest بترميز JSON
+    fn encode(&self, batch: &[SpanData]) -> serde_json::Value {
+        let spans: Vec<serde_json::Value> = batch.iter().map(|span| {
+            let mut value = serde_json::json!({
+                "traceId": span.trace_id,
+                "spanId": span.span_id,
+                "name": span.name,
+                "kind": match span.kind { SpanKind::Internal => 1, SpanKind::Server => 2 },
+                "startTimeUnixNano": unix_nanos(span.start_time),
+                "endTimeUnixNano": unix_nanos(span.end_time),
+                "attributes": span.attributes.iter().map(|(k, v)| otlp_attribute(k, v)).collect::<Vec<_>>(),
+                "status": {
+                    "code": match span.status { SpanStatus::Unset => 0, SpanStatus::Ok => 1, SpanStatus::Error => 2 },
+                    "message": span.status_message.clone().unwrap_or_default(),
+                },
+            });
+            if let Some(parent) = &span.parent_span_id {
+                value["parentSpanId"] = serde_json::json!(parent);
+            }
+            value
+        }).collect();
+
+        serde_json::json!({
+            "resourceSpans": [{
+                "resource": {
+                    "attributes": [
+                        otlp_attribute("service.name", &AttributeValue::from(self.service_name.as_str())),
+                        otlp_attribute("service.version", &AttributeValue::from(env!("CARGO_PKG_VERSION"))),
+                    ]
+                },
+                "scopeSpans": [{
+                    "scope": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+                    "spans": spans,
+                }]
+            }]
+        })
+    }
+}
+
+impl SpanExporter for OtlpHttpExporter {
+    fn name(&self) -> &str {
+        "otlp-http"
+    }
+
+    fn export(&mut self, batch: &[SpanData]) -> io::Result<()> {
+        let body = serde_json::to_vec(&self.encode(batch))?;
+        let address = std::net::ToSocketAddrs::to_socket_addrs(&(self.host.as_str(), self.port))?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("تعذر حل العنوان {}", self.host)))?;
+
+        let mut stream = TcpStream::connect_timeout(&address, EXPORT_TIMEOUT)?;
+        stream.set_read_timeout(Some(EXPORT_TIMEOUT))?;
+        stream.set_write_timeout(Some(EXPORT_TIMEOUT))?;
+
+        let head = format!(
+            "POST {} HTTP/1.1\r\nHost: {}:{}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path, self.host, self.port, body.len()
+        );
+        stream.write_all(head.as_bytes())?;
+        stream.write_all(&body)?;
+
+        let mut response = Vec::new();
+        let _ = stream.take(1024).read_to_end(&mut response);
+        let status_line = String::from_utf8_lossy(&response);
+        let status: u16 = status_line.split_whitespace().nth(1).and_then(|s| s.parse().ok()).unwrap_or(0);
+        if (200..300).contains(&status) {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!("رد المجمّع بالحالة {}", status)))
+        }
+    }
+}
+
+fn unix_nanos(time: DateTime<Utc>) -> String {
+    time.timestamp_nanos_opt().unwrap_or_default().to_string()
+}
+
+fn otlp_attribute(key: &str, value: &AttributeValue) -> serde_json::Value {
+    let value = match value {
+        AttributeValue::Bool(v) => serde_json::json!({ "boolValue": v }),
+        // int64 يُرمَّز نصاً في ترميز JSON الخاص بـ OTLP
+        AttributeValue::Int(v) => serde_json::json!({ "intValue": v.to_string() }),
+        AttributeValue::Float(v) => serde_json::json!({ "doubleValue": v }),
+        AttributeValue::String(v) => serde_json::json!({ "stringValue": v }),
+    };
+    serde_json::json!({ "key": key, "value": value })
+}
+
+enum ExportMessage {
+    Span(Box<SpanData>),
+    Flush(mpsc::Sender<()>),
+}
+
+/// المتتبع العام: يجمع المقاطع في دفعات ويصدرها من خيط مستقل
+struct Tracer {
+    sender: SyncSender<ExportMessage>,
+}
+
+impl Tracer {
+    fn submit(&self, span: SpanData) {
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(ExportMessage::Span(Box::new(span))) {
+            log::warn!("⚠️ طابور التتبع ممتلئ - تم إسقاط مقطع");
+        }
+    }
+}
+
+static TRACER: OnceLock<Tracer> = OnceLock::new();
+
+/// تفعيل التصدير حسب الإعدادات؛ يعيد false إن لم تُحدد أي وجهة أو كان مفعلاً مسبقاً
+pub fn init(config: TelemetryConfig) -> bool {
+    let mut exporters: Vec<Box<dyn SpanExporter>> = Vec::new();
+
+    if let Some(path) = &config.json_file {
+        exporters.push(Box::new(JsonFileExporter::new(path)));
+    }
+    if let Some(endpoint) = &config.otlp_endpoint {
+        match OtlpHttpExporter::new(endpoint, &config.service_name) {
+            Ok(exporter) => exporters.push(Box::new(exporter)),
+            Err(e) => log::error!("❌ عنوان OTLP غير صالح {}: {}", endpoint, e),
+        }
+    }
+    if exporters.is_empty() {
+        return false;
+    }
+
+    let names: Vec<String> = exporters.iter().map(|e| e.name().to_string()).collect();
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+    if TRACER.set(Tracer { sender }).is_err() {
+        return false;
+    }
+
+    let batch_size = config.batch_size.max(1);
+    let interval = Duration::from_millis(config.flush_interval_ms.max(10));
+    std::thread::Builder::new()
+        .name("mh-trace-export".to_string())
+        .spawn(move || export_loop(receiver, exporters, batch_size, interval))
+        .map(|_| log::info!("🔭 تم تفعيل التتبع ({})", names.join(", ")))
+        .is_ok()
+}
+
+/// انتظار تصدير كل المقاطع المنتهية حتى الآن (قبل إيقاف العملية)
+pub fn flush(timeout: Duration) {
+    if let Some(tracer) = TRACER.get() {
+        let (done, wait) = mpsc::channel();
+        if tracer.sender.send(ExportMessage::Flush(done)).is_ok() {
+            let _ = wait.recv_timeout(timeout);
+        }
+    }
+}
+
+fn export_loop(
+    receiver: Receiver<ExportMessage>,
+    mut exporters: Vec<Box<dyn SpanExporter>>,
+    batch_size: usize,
+    interval: Duration,
+) {
+    let mut batch: Vec<SpanData> = Vec::with_capacity(batch_size);
+    let mut deadline = Instant::now() + interval;
+
+    loop {
+        let wait = deadline.saturating_duration_since(Instant::now());
+        match receiver.recv_timeout(wait) {
+            Ok(ExportMessage::Span(span)) => {
+                batch.push(*span);
+                if batch.len() >= batch_size {
+                    export_batch(&mut exporters, &mut batch);
+                    deadline = Instant::now() + interval;
+                }
+            }
+            Ok(ExportMessage::Flush(done)) => {
+                export_batch(&mut exporters, &mut batch);
+                let _ = done.send(());
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                export_batch(&mut exporters, &mut batch);
+                deadline = Instant::now() + interval;
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                export_batch(&mut exporters, &mut batch);
+                return;
+            }
+        }
+    }
+}
+
+fn export_batch(exporters: &mut [Box<dyn SpanExporter>], batch: &mut Vec<SpanData>) {
+    if batch.is_empty() {
+        return;
+    }
+    for exporter in exporters.iter_mut() {
+        if let Err(e) = exporter.export(batch) {
+            log::warn!("⚠️ فشل تصدير {} مقطع عبر {}: {}", batch.len(), exporter.name(), e);
+        }
+    }
+    batch.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// مقطع ابن وحفيد تحت مقطع خادم
+    async fn nested_spans(root_context: &SpanContext) -> (SpanData, SpanData) {
+        scope(root_context.clone(), async {
+            let child = Span::start("factory.manufacture");
+            let grandchild = Span::start("quality.gate");
+            assert_eq!(current_context(), Some(grandchild.context()));
+            (child.data.clone(), grandchild.data.clone())
+        }).await
+    }
+
+    #[tokio::test]
+    async fn test_span_propagation() {
+        let root = Span::server("POST /api/v1/manufacture/{factory_type}", None, "req_1".to_string());
+        let root_context = root.context();
+        let (child, grandchild) = nested_spans(&root_context).await;
+
+        assert_eq!(child.trace_id, root_context.trace_id);
+        assert_eq!(child.parent_span_id.as_deref(), Some(root_context.span_id.as_str()));
+        assert_eq!(grandchild.parent_span_id.as_deref(), Some(child.span_id.as_str()));
+        assert_eq!(grandchild.request_id.as_deref(), Some("req_1"));
+        assert!(current_context().is_none());
+    }
+
+    #[test]
+    fn test_traceparent_round_trip() {
+        let context = Span::server("GET /", None, "req_1".to_string()).context();
+        let parsed = SpanContext::from_traceparent(&context.traceparent(), None).unwrap();
+        assert_eq!(parsed.trace_id, context.trace_id);
+        assert_eq!(parsed.span_id, context.span_id);
+        assert!(SpanContext::from_traceparent("00-0000-xyz-01", None).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_json_file_exporter_appends_lines() {
+        let root = Span::server("GET /", None, "req_1".to_string());
+        let (child, grandchild) = nested_spans(&root.context()).await;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("traces").join("spans.jsonl");
+
+        let mut exporter = JsonFileExporter::new(&path);
+        exporter.export(&[child.clone(), grandchild]).unwrap();
+        exporter.export(&[child]).unwrap();
+        let lines = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(lines.lines().count(), 3);
+        assert!(lines.lines().all(|line| serde_json::from_str::<SpanData>(line).is_ok()));
+    }
+
+    #[tokio::test]
+    async fn test_otlp_encoding() {
+        let root = Span::server("GET /", None, "req_1".to_string());
+        let root_context = root.context();
+        let (child, _) = nested_spans(&root_context).await;
+
+        let exporter = OtlpHttpExporter::new("http://localhost:4318", "mh-api").unwrap();
+        assert_eq!(exporter.path, "/v1/traces");
+        let encoded = exporter.encode(&[child]);
+        let span = &encoded["resourceSpans"][0]["scopeSpans"][0]["spans"][0];
+        assert_eq!(span["parentSpanId"], root_context.span_id);
+        assert_eq!(span["attributes"][0]["value"]["stringValue"], "req_1");
+        assert!(OtlpHttpExporter::new("https://collector:4318", "mh-api").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_context_crosses_spawn_blocking() {
+        let root = Span::server("POST /api/v1/manufacture/batch", None, "req_2".to_string());
+        let root_context = root.context();
+
+        let child = scope(root_context.clone(), async {
+            tokio::task::spawn_blocking(in_current_context(|| Span::start("factory.manufacture").data.clone()))
+                .await
+                .unwrap()
+        }).await;
+
+        assert_eq!(child.trace_id, root_context.trace_id);
+        assert_eq!(child.parent_span_id.as_deref(), Some(root_context.span_id.as_str()));
+        assert_eq!(child.request_id.as_deref(), Some("req_2"));
+
+        // دون الربط يبدأ الخيط تتبعاً جديداً
+        let orphan = scope(root_context.clone(), async {
+            tokio::task::spawn_blocking(|| Span::start("factory.manufacture").data.clone()).await.unwrap()
+        }).await;
+        assert!(orphan.parent_span_id.is_none());
+    }
+}
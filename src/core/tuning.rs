@@ -0,0 +1,129 @@
+//! مقابض التشغيل القابلة للضبط أثناء العمل
+//!
+//! القيم مشتركة على مستوى العملية ويقرؤها مدير المصانع وبوابات الجودة
+//! ومعالج التصنيع بالدفعات عند كل طلب، ويعدّلها المحسّن (`mhos::optimizer`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::Duration;
+
+/// مقبض تشغيل
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Knob {
+    /// عدد العمال المتوازين في التصنيع بالدفعات
+    WorkerPoolSize,
+    /// مدة بقاء المنتجات في ذاكرة التخزين المؤقت (0 = معطل)
+    CacheTtlSeconds,
+    /// الحد الأقصى للطلبات المتزامنة في مصنع
+    FactoryConcurrency(String),
+    /// نسبة الفحوصات التي تُشغَّل فيها بوابة جودة
+    GateSamplingRate(String),
+}
+
+impl Knob {
+    /// (الحد الأدنى، الحد الأقصى) للقيمة
+    pub fn bounds(&self) -> (f64, f64) {
+        match self {
+            Knob::WorkerPoolSize => (1.0, 64.0),
+            Knob::CacheTtlSeconds => (0.0, 3600.0),
+            Knob::FactoryConcurrency(_) => (1.0, 256.0),
+            Knob::GateSamplingRate(_) => (0.1, 1.0),
+        }
+    }
+
+    pub fn default_value(&self) -> f64 {
+        match self {
+            Knob::WorkerPoolSize => std::thread::available_parallelism()
+                .map(|n| n.get() as f64)
+                .unwrap_or(4.0),
+            Knob::CacheTtlSeconds => 0.0,
+            Knob::FactoryConcurrency(_) => 16.0,
+            Knob::GateSamplingRate(_) => 1.0,
+        }
+    }
+
+    /// هل القيمة عدد صحيح
+    fn is_integer(&self) -> bool {
+        !matches!(self, Knob::GateSamplingRate(_))
+    }
+
+    pub fn clamp_value(&self, value: f64) -> f64 {
+        let (min, max) = self.bounds();
+        let value = value.clamp(min, max);
+        if self.is_integer() { value.round() } else { value }
+    }
+}
+
+impl fmt::Display for Knob {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Knob::WorkerPoolSize => write!(f, "workers.pool_size"),
+            Knob::CacheTtlSeconds => write!(f, "cache.ttl_seconds"),
+            Knob::FactoryConcurrency(factory) => write!(f, "factory.{}.concurrency", factory),
+            Knob::GateSamplingRate(gate) => write!(f, "quality.{}.sampling_rate", gate),
+        }
+    }
+}
+
+/// القيم الحالية للمقابض (غير المضبوط منها يأخذ القيمة الافتراضية)
+#[derive(Debug, Default)]
+pub struct RuntimeKnobs {
+    values: RwLock<BTreeMap<Knob, f64>>,
+}
+
+impl RuntimeKnobs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, knob: &Knob) -> f64 {
+        self.values.read()
+            .ok()
+            .and_then(|values| values.get(knob).copied())
+            .unwrap_or_else(|| knob.default_value())
+    }
+
+    /// ضبط قيمة ضمن الحدود؛ يعيد القيمة المطبقة فعلياً
+    pub fn set(&self, knob: &Knob, value: f64) -> f64 {
+        let value = knob.clamp_value(value);
+        match self.values.write() {
+            Ok(mut values) => {
+                values.insert(knob.clone(), value);
+            }
+            Err(poisoned) => {
+                poisoned.into_inner().insert(knob.clone(), value);
+            }
+        }
+        value
+    }
+
+    /// المقابض المضبوطة صراحة
+    pub fn overrides(&self) -> BTreeMap<Knob, f64> {
+        self.values.read().map(|values| values.clone()).unwrap_or_default()
+    }
+
+    pub fn worker_pool_size(&self) -> usize {
+        self.get(&Knob::WorkerPoolSize) as usize
+    }
+
+    pub fn cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.get(&Knob::CacheTtlSeconds) as u64)
+    }
+
+    pub fn factory_concurrency(&self, factory_type: &str) -> usize {
+        self.get(&Knob::FactoryConcurrency(factory_type.to_string())) as usize
+    }
+
+    pub fn gate_sampling_rate(&self, gate_id: &str) -> f64 {
+        self.get(&Knob::GateSamplingRate(gate_id.to_string()))
+    }
+}
+
+/// المقابض المشتركة للعملية
+pub fn knobs() -> Arc<RuntimeKnobs> {
+    static KNOBS: OnceLock<Arc<RuntimeKnobs>> = OnceLock::new();
+    KNOBS.get_or_init(|| Arc::new(RuntimeKnobs::new())).clone()
+}
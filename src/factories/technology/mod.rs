@@ -1,13 +1,21 @@
+use std::sync::Arc;
 use serde_json::Value;
 use crate::core::factory::Factory;
+use crate::core::generation::{generator, GenerationService, DEFAULT_CAPABILITY};
 
 pub struct TechnologyFactory {
     name: String,
+    generator: Arc<GenerationService>,
 }
 
 impl TechnologyFactory {
     pub fn new(name: &str) -> Self {
-        Self { name: name.to_string() }
+        Self { name: name.to_string(), generator: generator() }
+    }
+    
+    pub fn with_generator(mut self, generator: Arc<GenerationService>) -> Self {
+        self.generator = generator;
+        self
     }
 }
 
@@ -19,11 +27,12 @@ impl Factory for TechnologyFactory {
     fn quality_score(&self) -> f32 { 0.9 }
     
     fn manufacture(&self, input: Value) -> Result<Value, String> {
-        Ok(serde_json::json!({
-            "type": "technology",
-            "content": "محتوى تجريبي",
-            "quality": 0.85,
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        }))
+        let capability = input.get("capability").and_then(|c| c.as_str()).unwrap_or(DEFAULT_CAPABILITY);
+        let topic = input.get("topic").cloned().unwrap_or_else(|| input.clone());
+        let parameters = input.get("parameters").cloned().unwrap_or(Value::Null);
+        
+        self.generator.generate("technology", capability, &topic, &parameters)
+            .map(|generation| generation.to_product("technology"))
+            .map_err(|e| e.to_string())
     }
 }
@@ -6,6 +6,7 @@ pub mod factories;
 pub mod api;
 pub mod cli;
 pub mod mhos;
+pub mod analysis;
 
 use serde::Serialize;
 
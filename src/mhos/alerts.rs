@@ -0,0 +1,574 @@
+//! دورة حياة الإنذارات: إزالة التكرار، الإقرار، الإسكات والحل التلقائي
+//!
+//! يُحفظ المخزن في ملف JSON داخل دليل البيانات حتى يتشاركه خادم API
+//! وأوامر سطر الأوامر؛ ويُعاد تحميله عند تعديله من عملية أخرى.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::mhos::monitor::{AlertSeverity, PerformanceMetrics, SystemAlert};
+use crate::mhos::utils::timeseries::{self, AnomalyMethod};
+
+/// مسار ملف الإنذارات داخل دليل البيانات
+pub const ALERTS_FILE: &str = "mhos/alerts.json";
+
+/// الحد الأقصى للإنذارات المحلولة المحفوظة
+const MAX_RESOLVED_ALERTS: usize = 500;
+
+/// حالة الإنذار
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    #[default]
+    Open,
+    Acknowledged,
+    Resolved,
+}
+
+impl AlertState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertState::Open => "open",
+            AlertState::Acknowledged => "acknowledged",
+            AlertState::Resolved => "resolved",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "open" | "مفتوح" => Some(AlertState::Open),
+            "acknowledged" | "ack" | "مقر" => Some(AlertState::Acknowledged),
+            "resolved" | "محلول" => Some(AlertState::Resolved),
+            _ => None,
+        }
+    }
+}
+
+/// المقياس الذي تراقبه القاعدة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    CpuUsage,
+    MemoryUsage,
+    DiskUsage,
+    NetworkUsage,
+    ResponseTime,
+}
+
+impl AlertMetric {
+    pub const ALL: [AlertMetric; 5] = [
+        AlertMetric::CpuUsage,
+        AlertMetric::MemoryUsage,
+        AlertMetric::DiskUsage,
+        AlertMetric::NetworkUsage,
+        AlertMetric::ResponseTime,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertMetric::CpuUsage => "cpu_usage",
+            AlertMetric::MemoryUsage => "memory_usage",
+            AlertMetric::DiskUsage => "disk_usage",
+            AlertMetric::NetworkUsage => "network_usage",
+            AlertMetric::ResponseTime => "response_time",
+        }
+    }
+
+    pub fn value(&self, metrics: &PerformanceMetrics) -> f32 {
+        match self {
+            AlertMetric::CpuUsage => metrics.cpu_usage,
+            AlertMetric::MemoryUsage => metrics.memory_usage,
+            AlertMetric::DiskUsage => metrics.disk_usage,
+            AlertMetric::NetworkUsage => metrics.network_usage,
+            AlertMetric::ResponseTime => metrics.response_time,
+        }
+    }
+
+    /// مصدر الإنذار (جزء من مفتاح إزالة التكرار)
+    pub fn source(&self) -> &'static str {
+        match self {
+            AlertMetric::CpuUsage => "CPU Monitor",
+            AlertMetric::MemoryUsage => "Memory Monitor",
+            AlertMetric::DiskUsage => "Disk Monitor",
+            AlertMetric::NetworkUsage => "Network Monitor",
+            AlertMetric::ResponseTime => "Performance Monitor",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            AlertMetric::CpuUsage => "استخدام معالج مرتفع",
+            AlertMetric::MemoryUsage => "استخدام ذاكرة مرتفع",
+            AlertMetric::DiskUsage => "مساحة القرص منخفضة",
+            AlertMetric::NetworkUsage => "استخدام شبكة مرتفع",
+            AlertMetric::ResponseTime => "وقت استجابة بطيء",
+        }
+    }
+
+    pub fn describe(&self, value: f32) -> String {
+        match self {
+            AlertMetric::CpuUsage => format!("استخدام المعالج: {:.1}%", value * 100.0),
+            AlertMetric::MemoryUsage => format!("استخدام الذاكرة: {:.1}%", value * 100.0),
+            AlertMetric::DiskUsage => format!("استخدام القرص: {:.1}%", value * 100.0),
+            AlertMetric::NetworkUsage => format!("استخدام الشبكة: {:.1}%", value * 100.0),
+            AlertMetric::ResponseTime => format!("وقت الاستجابة: {:.0}ms", value),
+        }
+    }
+}
+
+/// قاعدة إنذار: يُطلق الإنذار بالخطورة المحددة عندما يتجاوز المقياس الحد
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub metric: AlertMetric,
+    pub severity: AlertSeverity,
+    pub threshold: f32,
+}
+
+impl AlertRule {
+    pub fn new(metric: AlertMetric, severity: AlertSeverity, threshold: f32) -> Self {
+        Self { metric, severity, threshold }
+    }
+
+    /// القواعد الافتراضية
+    pub fn defaults() -> Vec<AlertRule> {
+        vec![
+            AlertRule::new(AlertMetric::CpuUsage, AlertSeverity::Warning, 0.8),
+            AlertRule::new(AlertMetric::CpuUsage, AlertSeverity::Critical, 0.9),
+            AlertRule::new(AlertMetric::MemoryUsage, AlertSeverity::Critical, 0.9),
+            AlertRule::new(AlertMetric::DiskUsage, AlertSeverity::Warning, 0.9),
+            AlertRule::new(AlertMetric::ResponseTime, AlertSeverity::Warning, 500.0),
+        ]
+    }
+}
+
+/// قاعدة شذوذ: يُطلق إنذار تحذير عندما يتجاوز المقياس نطاقه المعتاد في السجل
+/// الحديث حتى لو لم يبلغ حد القواعد الثابتة
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyRule {
+    pub metric: AlertMetric,
+    pub method: AnomalyMethod,
+    /// عدد وحدات التشتت فوق خط الأساس
+    pub sensitivity: f64,
+    /// أقل عدد من العينات السابقة قبل تفعيل القاعدة
+    pub min_samples: usize,
+    /// لا يُعتبر الشذوذ تحت هذه القيمة (لتجنب الإنذار على قفزات من قيم منخفضة)
+    pub floor: f32,
+}
+
+impl AnomalyRule {
+    pub fn new(metric: AlertMetric, method: AnomalyMethod, sensitivity: f64) -> Self {
+        Self { metric, method, sensitivity, min_samples: 30, floor: 0.0 }
+    }
+
+    pub fn with_min_samples(mut self, min_samples: usize) -> Self {
+        self.min_samples = min_samples;
+        self
+    }
+
+    pub fn with_floor(mut self, floor: f32) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// القواعد الافتراضية
+    pub fn defaults() -> Vec<AnomalyRule> {
+        vec![
+            AnomalyRule::new(AlertMetric::CpuUsage, AnomalyMethod::ZScore, 3.0).with_floor(0.5),
+            AnomalyRule::new(AlertMetric::MemoryUsage, AnomalyMethod::ZScore, 3.0).with_floor(0.6),
+            AnomalyRule::new(AlertMetric::ResponseTime, AnomalyMethod::Iqr, 3.0).with_floor(200.0),
+        ]
+    }
+
+    /// الحد الأعلى للنطاق المعتاد إذا تجاوزته القيمة
+    fn breached(&self, value: f32, history: &[PerformanceMetrics]) -> Option<f32> {
+        if history.len() < self.min_samples || value < self.floor {
+            return None;
+        }
+        let series: Vec<f64> = history.iter().map(|m| self.metric.value(m) as f64).collect();
+        let (_, upper) = timeseries::normal_range(&series, self.method, self.sensitivity)?;
+        (value as f64 > upper).then_some(upper as f32)
+    }
+}
+
+/// نوع التغيير في دورة حياة الإنذار
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertEventKind {
+    Opened,
+    Escalated,
+    Resolved,
+}
+
+/// حدث ناتج عن تقييم المقاييس
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertEvent {
+    pub kind: AlertEventKind,
+    pub alert: SystemAlert,
+}
+
+/// محتوى ملف الإنذارات
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AlertFile {
+    alerts: Vec<SystemAlert>,
+}
+
+/// مخزن الإنذارات
+pub struct AlertStore {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    rules: Vec<AlertRule>,
+    anomaly_rules: Vec<AnomalyRule>,
+    alerts: Vec<SystemAlert>,
+}
+
+impl AlertStore {
+    /// مخزن في الذاكرة فقط
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            modified: None,
+            rules: AlertRule::defaults(),
+            anomaly_rules: AnomalyRule::defaults(),
+            alerts: Vec::new(),
+        }
+    }
+
+    /// فتح مخزن محفوظ في ملف (يُنشأ عند أول حفظ)
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut store = Self::in_memory();
+        store.path = Some(path.as_ref().to_path_buf());
+        store.reload()?;
+        Ok(store)
+    }
+
+    pub fn with_rules(mut self, rules: Vec<AlertRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        self.rules = rules;
+    }
+
+    pub fn with_anomaly_rules(mut self, rules: Vec<AnomalyRule>) -> Self {
+        self.anomaly_rules = rules;
+        self
+    }
+
+    pub fn anomaly_rules(&self) -> &[AnomalyRule] {
+        &self.anomaly_rules
+    }
+
+    pub fn set_anomaly_rules(&mut self, rules: Vec<AnomalyRule>) {
+        self.anomaly_rules = rules;
+    }
+
+    /// إعادة التحميل إذا عدّلت عملية أخرى الملف
+    pub fn reload_if_changed(&mut self) -> io::Result<()> {
+        let changed = match &self.path {
+            Some(path) => file_modified(path) != self.modified,
+            None => false,
+        };
+        if changed {
+            self.reload()?;
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let file: AlertFile = serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.alerts = file.alerts;
+        }
+        self.modified = file_modified(&path);
+        Ok(())
+    }
+
+    /// حفظ المخزن في ملفه (إن وُجد)
+    pub fn save(&mut self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = AlertFile { alerts: self.alerts.clone() };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // كتابة ذرية عبر ملف مؤقت
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, &path)?;
+
+        self.modified = file_modified(&path);
+        Ok(())
+    }
+
+    /// تقييم المقاييس مقابل القواعد الثابتة وحدها وتحديث الإنذارات
+    pub fn evaluate(&mut self, metrics: &PerformanceMetrics) -> Vec<AlertEvent> {
+        self.evaluate_with_history(metrics, &[])
+    }
+
+    /// تقييم المقاييس مقابل القواعد الثابتة، ثم قواعد الشذوذ مقابل العينات السابقة
+    pub fn evaluate_with_history(&mut self, metrics: &PerformanceMetrics, history: &[PerformanceMetrics]) -> Vec<AlertEvent> {
+        let now = metrics.timestamp;
+        let mut events = Vec::new();
+
+        for metric in AlertMetric::ALL {
+            let value = metric.value(metrics);
+            let triggered = self.rules.iter()
+                .filter(|r| r.metric == metric && value > r.threshold)
+                .max_by_key(|r| r.severity)
+                .cloned()
+                .or_else(|| {
+                    self.anomaly_rules.iter()
+                        .filter(|r| r.metric == metric)
+                        .find_map(|r| r.breached(value, history))
+                        .map(|upper| AlertRule::new(metric, AlertSeverity::Warning, upper))
+                });
+            let active = self.alerts.iter().position(|a| {
+                a.state != AlertState::Resolved && a.source == metric.source() && a.title == metric.title()
+            });
+
+            match (triggered, active) {
+                (Some(rule), Some(index)) => {
+                    let alert = &mut self.alerts[index];
+                    alert.occurrences += 1;
+                    alert.last_seen = now;
+                    alert.value = value;
+                    alert.message = metric.describe(value);
+                    if rule.severity > alert.severity {
+                        alert.severity = rule.severity;
+                        alert.threshold = rule.threshold;
+                        if !alert.is_silenced(now) {
+                            events.push(AlertEvent { kind: AlertEventKind::Escalated, alert: alert.clone() });
+                        }
+                    }
+                }
+                (Some(rule), None) => {
+                    let alert = SystemAlert {
+                        id: format!("alert_{}_{}", metric.as_str(), now.timestamp_millis()),
+                        severity: rule.severity,
+                        title: metric.title().to_string(),
+                        message: metric.describe(value),
+                        source: metric.source().to_string(),
+                        timestamp: now,
+                        resolved: false,
+                        state: AlertState::Open,
+                        metric: Some(metric),
+                        value,
+                        threshold: rule.threshold,
+                        occurrences: 1,
+                        last_seen: now,
+                        acknowledged_by: None,
+                        acknowledged_at: None,
+                        resolved_at: None,
+                        silenced_until: self.silence_for(metric.source(), metric.title(), now),
+                    };
+                    if !alert.is_silenced(now) {
+                        events.push(AlertEvent { kind: AlertEventKind::Opened, alert: alert.clone() });
+                    }
+                    self.alerts.push(alert);
+                }
+                (None, Some(index)) => {
+                    // عاد المقياس إلى الوضع الطبيعي
+                    let alert = &mut self.alerts[index];
+                    if alert.metric == Some(metric) {
+                        alert.state = AlertState::Resolved;
+                        alert.resolved = true;
+                        alert.resolved_at = Some(now);
+                        alert.value = value;
+                        if !alert.is_silenced(now) {
+                            events.push(AlertEvent { kind: AlertEventKind::Resolved, alert: alert.clone() });
+                        }
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+
+        self.prune();
+        events
+    }
+
+    /// الإسكات الساري لنفس المصدر والعنوان (يستمر على الإنذارات المتكررة)
+    fn silence_for(&self, source: &str, title: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.alerts.iter()
+            .filter(|a| a.source == source && a.title == title)
+            .filter_map(|a| a.silenced_until)
+            .filter(|until| *until > now)
+            .max()
+    }
+
+    /// حذف أقدم الإنذارات المحلولة عند تجاوز الحد
+    fn prune(&mut self) {
+        let resolved = self.alerts.iter().filter(|a| a.state == AlertState::Resolved).count();
+        let mut excess = resolved.saturating_sub(MAX_RESOLVED_ALERTS);
+        if excess > 0 {
+            self.alerts.retain(|a| {
+                if excess > 0 && a.state == AlertState::Resolved {
+                    excess -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+
+    /// قائمة الإنذارات (الأحدث أولاً) مع تصفية اختيارية حسب الحالة
+    pub fn list(&self, state: Option<AlertState>) -> Vec<SystemAlert> {
+        self.alerts.iter()
+            .rev()
+            .filter(|a| state.map(|s| a.state == s).unwrap_or(true))
+            .cloned()
+            .collect()
+    }
+
+    /// الإنذارات منذ وقت معين
+    pub fn since(&self, since: DateTime<Utc>) -> Vec<SystemAlert> {
+        self.alerts.iter()
+            .filter(|a| a.last_seen >= since)
+            .cloned()
+            .collect()
+    }
+
+    /// الإنذارات غير المحلولة وغير المسكتة
+    pub fn active(&self) -> Vec<&SystemAlert> {
+        let now = Utc::now();
+        self.alerts.iter()
+            .filter(|a| a.state != AlertState::Resolved && !a.is_silenced(now))
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<&SystemAlert> {
+        self.alerts.iter().find(|a| a.id == id)
+    }
+
+    /// الإقرار بإنذار مفتوح
+    pub fn acknowledge(&mut self, id: &str, by: &str) -> Result<SystemAlert, String> {
+        let alert = self.alerts.iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("الإنذار غير موجود: {}", id))?;
+
+        match alert.state {
+            AlertState::Resolved => Err("لا يمكن الإقرار بإنذار محلول".to_string()),
+            AlertState::Acknowledged => Ok(alert.clone()),
+            AlertState::Open => {
+                alert.state = AlertState::Acknowledged;
+                alert.acknowledged_by = Some(by.to_string());
+                alert.acknowledged_at = Some(Utc::now());
+                Ok(alert.clone())
+            }
+        }
+    }
+
+    /// إسكات إنذار (وتكراراته) لمدة محددة
+    pub fn silence(&mut self, id: &str, duration: Duration) -> Result<SystemAlert, String> {
+        let alert = self.alerts.iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("الإنذار غير موجود: {}", id))?;
+
+        alert.silenced_until = Some(Utc::now() + duration);
+        Ok(alert.clone())
+    }
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(cpu: f32, at: DateTime<Utc>) -> PerformanceMetrics {
+        PerformanceMetrics {
+            cpu_usage: cpu,
+            memory_usage: 0.4,
+            disk_usage: 0.3,
+            network_usage: 0.0,
+            response_time: 80.0,
+            throughput: 1.0,
+            cpu_efficiency: 1.0 - cpu,
+            memory_efficiency: 0.6,
+            overall_score: 0.8,
+            process_memory_bytes: 0,
+            process_threads: 1,
+            timestamp: at,
+        }
+    }
+
+    #[test]
+    fn test_alert_lifecycle() {
+        let mut store = AlertStore::in_memory();
+        let t0 = Utc::now();
+
+        // فتح ثم تكرار ثم تصعيد لنفس الإنذار
+        let events = store.evaluate(&metrics(0.85, t0));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, AlertEventKind::Opened);
+        let id = events[0].alert.id.clone();
+
+        assert!(store.evaluate(&metrics(0.86, t0 + Duration::seconds(30))).is_empty());
+        let events = store.evaluate(&metrics(0.95, t0 + Duration::seconds(60)));
+        assert_eq!(events[0].kind, AlertEventKind::Escalated);
+        assert_eq!(store.list(None).len(), 1);
+        assert_eq!(store.get(&id).unwrap().occurrences, 3);
+        assert_eq!(store.get(&id).unwrap().severity, AlertSeverity::Critical);
+
+        // الإقرار ثم الحل التلقائي عند التعافي
+        store.acknowledge(&id, "ops").unwrap();
+        assert_eq!(store.list(Some(AlertState::Acknowledged)).len(), 1);
+        let events = store.evaluate(&metrics(0.3, t0 + Duration::seconds(90)));
+        assert_eq!(events[0].kind, AlertEventKind::Resolved);
+        assert!(store.get(&id).unwrap().resolved);
+        assert!(store.acknowledge(&id, "ops").is_err());
+
+        // الإسكات يمتد إلى تكرار الإنذار نفسه
+        store.silence(&id, Duration::hours(1)).unwrap();
+        assert!(store.evaluate(&metrics(0.85, t0 + Duration::seconds(120))).is_empty());
+        assert_eq!(store.list(Some(AlertState::Open)).len(), 1);
+        assert!(store.active().is_empty());
+    }
+
+    #[test]
+    fn test_anomaly_alerts() {
+        let mut store = AlertStore::in_memory()
+            .with_anomaly_rules(vec![AnomalyRule::new(AlertMetric::CpuUsage, AnomalyMethod::ZScore, 3.0)
+                .with_min_samples(10)
+                .with_floor(0.5)]);
+        let t0 = Utc::now();
+        let history: Vec<PerformanceMetrics> = (0..20)
+            .map(|i| metrics(0.55 + (i % 3) as f32 * 0.01, t0 + Duration::seconds(i)))
+            .collect();
+
+        // قفزة دون الحد الثابت لكنها خارج النطاق المعتاد
+        let at = t0 + Duration::seconds(30);
+        let events = store.evaluate_with_history(&metrics(0.7, at), &history);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].alert.severity, AlertSeverity::Warning);
+        assert!(events[0].alert.threshold < 0.7);
+
+        // بلا سجل كافٍ تعود القواعد الثابتة وحدها فيُحل الإنذار
+        let events = store.evaluate_with_history(&metrics(0.7, at + Duration::seconds(30)), &history[..5]);
+        assert_eq!(events[0].kind, AlertEventKind::Resolved);
+    }
+}
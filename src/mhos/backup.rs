@@ -0,0 +1,160 @@
+//! النسخ الاحتياطي الدوري لدليل البيانات
+//!
+//! تُجمع ملفات المخازن النصية (JSON وJSON Lines) في ملف JSON واحد داخل
+//! `backups/` مع حذف أقدم النسخ عند تجاوز العدد المحفوظ.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// دليل النسخ الاحتياطية داخل دليل البيانات
+pub const BACKUPS_DIR: &str = "backups";
+
+/// محتوى النسخة الاحتياطية
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Backup {
+    pub version: String,
+    pub created_at: DateTime<Utc>,
+    /// المسار النسبي داخل دليل البيانات ← المحتوى
+    pub files: BTreeMap<String, String>,
+}
+
+/// إنشاء نسخة احتياطية لدليل البيانات والاحتفاظ بآخر `retention` نسخة
+pub fn create_backup(data_dir: &Path, retention: usize) -> io::Result<PathBuf> {
+    let mut files = BTreeMap::new();
+    collect_files(data_dir, data_dir, &mut files)?;
+
+    let backup = Backup {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: Utc::now(),
+        files,
+    };
+
+    let dir = data_dir.join(BACKUPS_DIR);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("backup_{}.json", backup.created_at.format("%Y%m%d_%H%M%S_%3f")));
+    let content = serde_json::to_string(&backup)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+    // كتابة ذرية عبر ملف مؤقت
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, content)?;
+    std::fs::rename(&tmp, &path)?;
+
+    for old in list_backups(data_dir)?.iter().rev().skip(retention.max(1)) {
+        std::fs::remove_file(old)?;
+    }
+
+    log::info!("💾 تم إنشاء نسخة احتياطية ({} ملف): {}", backup.files.len(), path.display());
+    Ok(path)
+}
+
+/// النسخ الاحتياطية الموجودة (الأقدم أولاً)
+pub fn list_backups(data_dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let dir = data_dir.join(BACKUPS_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().map(|ext| ext == "json").unwrap_or(false))
+        .collect();
+    // الاسم يتضمن الوقت فيرتب زمنياً
+    backups.sort();
+    Ok(backups)
+}
+
+/// جمع الملفات النصية مع تخطي دليل النسخ والملفات المؤقتة
+fn collect_files(root: &Path, dir: &Path, files: &mut BTreeMap<String, String>) -> io::Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path != root.join(BACKUPS_DIR) {
+                collect_files(root, &path, files)?;
+            }
+            continue;
+        }
+
+        let is_store = path.extension()
+            .map(|ext| ext == "json" || ext == "jsonl")
+            .unwrap_or(false);
+        if !is_store {
+            continue;
+        }
+
+        let relative = path.strip_prefix(root).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+        match std::fs::read_to_string(&path) {
+            Ok(content) => {
+                files.insert(relative, content);
+            }
+            Err(e) => log::warn!("⚠️  تخطي {} في النسخة الاحتياطية: {}", path.display(), e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// دليل بيانات فيه مخزن وملف مؤقت وملف غير مخزن
+    fn data_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("mhos")).unwrap();
+        std::fs::write(dir.path().join("mhos/alerts.json"), r#"{"alerts":[]}"#).unwrap();
+        std::fs::write(dir.path().join("mhos/alerts.json.tmp"), "partial").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+        dir
+    }
+
+    fn read_backup(path: &Path) -> Backup {
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_backup_collects_only_stores() {
+        let dir = data_dir();
+        let path = create_backup(dir.path(), 2).unwrap();
+
+        let backup = read_backup(&path);
+        assert_eq!(backup.files.keys().collect::<Vec<_>>(), vec!["mhos/alerts.json"]);
+        assert_eq!(backup.files["mhos/alerts.json"], r#"{"alerts":[]}"#);
+    }
+
+    #[test]
+    fn test_backup_skips_previous_backups() {
+        let dir = data_dir();
+        create_backup(dir.path(), 2).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let second = create_backup(dir.path(), 2).unwrap();
+
+        assert!(read_backup(&second).files.keys().all(|k| !k.starts_with(BACKUPS_DIR)));
+    }
+
+    #[test]
+    fn test_backup_prunes_oldest_beyond_retention() {
+        let dir = data_dir();
+        let first = create_backup(dir.path(), 2).unwrap();
+        for _ in 0..2 {
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            create_backup(dir.path(), 2).unwrap();
+        }
+
+        let backups = list_backups(dir.path()).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert!(!backups.contains(&first));
+    }
+
+    #[test]
+    fn test_list_backups_without_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(list_backups(dir.path()).unwrap().is_empty());
+    }
+}
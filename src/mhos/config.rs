@@ -0,0 +1,200 @@
+//! تكوين MH-OS: تحميله من دليل البيانات والتحقق منه ودمج التعديلات الجزئية
+
+use std::io;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::mhos::alerts::{AlertMetric, AlertRule};
+
+/// مسار ملف التكوين داخل دليل البيانات
+pub const MHOS_CONFIG_FILE: &str = "mhos/config.json";
+
+/// تكوين MH-OS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MhosConfig {
+    /// تشغيل المحسن تلقائياً كل `optimization_interval` ثانية
+    pub auto_optimize: bool,
+    /// أقل نتيجة موزونة تعتبرها بوابات الجودة جيدة
+    pub quality_threshold: f32,
+    /// فترة جمع المقاييس بالثواني
+    pub monitoring_interval: u64,
+    /// إرسال أحداث الإنذارات إلى قنوات الإشعار واللوحات الحية
+    pub alert_enabled: bool,
+    /// نسخ دليل البيانات احتياطياً كل `backup_interval` ثانية
+    pub backup_enabled: bool,
+    /// تعلم المساعد من الإنذارات والتحسينات وإتاحة البحث في المعرفة
+    pub ai_assistant: bool,
+    pub alert_rules: Vec<AlertRule>,
+    pub optimization_interval: u64,
+    pub backup_interval: u64,
+    /// عدد النسخ الاحتياطية المحفوظة
+    pub backup_retention: usize,
+}
+
+impl Default for MhosConfig {
+    fn default() -> Self {
+        Self {
+            auto_optimize: true,
+            quality_threshold: 0.85,
+            monitoring_interval: 30,
+            alert_enabled: true,
+            backup_enabled: true,
+            ai_assistant: true,
+            alert_rules: AlertRule::defaults(),
+            optimization_interval: 3600,
+            backup_interval: 86400,
+            backup_retention: 7,
+        }
+    }
+}
+
+impl MhosConfig {
+    /// تحميل التكوين من ملف (الافتراضي إذا لم يوجد)
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let config: MhosConfig = serde_json::from_str(&content)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        config.validate().map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(config)
+    }
+
+    /// حفظ التكوين في ملف
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // كتابة ذرية عبر ملف مؤقت
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// التحقق من القيم (تُجمع كل الأخطاء في رسالة واحدة)
+    pub fn validate(&self) -> Result<(), String> {
+        let mut errors = Vec::new();
+
+        if !(0.0..=1.0).contains(&self.quality_threshold) {
+            errors.push("حد الجودة يجب أن يكون بين 0 و 1".to_string());
+        }
+        if !(1..=86400).contains(&self.monitoring_interval) {
+            errors.push("فترة المراقبة يجب أن تكون بين 1 و 86400 ثانية".to_string());
+        }
+        if self.optimization_interval < 60 {
+            errors.push("فترة التحسين التلقائي لا تقل عن 60 ثانية".to_string());
+        }
+        if self.backup_interval < 60 {
+            errors.push("فترة النسخ الاحتياطي لا تقل عن 60 ثانية".to_string());
+        }
+        if self.backup_retention == 0 {
+            errors.push("يجب الاحتفاظ بنسخة احتياطية واحدة على الأقل".to_string());
+        }
+        for rule in &self.alert_rules {
+            let max = if rule.metric == AlertMetric::ResponseTime { f32::MAX } else { 1.0 };
+            if !rule.threshold.is_finite() || rule.threshold < 0.0 || rule.threshold > max {
+                errors.push(format!("حد غير صالح لقاعدة {}: {}", rule.metric.as_str(), rule.threshold));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("؛ "))
+        }
+    }
+
+    /// دمج تعديل جزئي (كائن JSON بأسماء الحقول) ثم التحقق من النتيجة
+    pub fn with_patch(&self, patch: &serde_json::Value) -> Result<Self, String> {
+        let patch = patch.as_object().ok_or("التعديل يجب أن يكون كائن JSON")?;
+        let mut merged = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        if let Some(fields) = merged.as_object_mut() {
+            for (key, value) in patch {
+                fields.insert(key.clone(), value.clone());
+            }
+        }
+
+        let config: MhosConfig = serde_json::from_value(merged)
+            .map_err(|e| format!("تكوين غير صالح: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(MhosConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_patch_merges_fields() {
+        let config = MhosConfig::default();
+        let patched = config.with_patch(&serde_json::json!({ "monitoring_interval": 5, "auto_optimize": false })).unwrap();
+        assert_eq!(patched.monitoring_interval, 5);
+        assert!(!patched.auto_optimize);
+        assert_eq!(patched.quality_threshold, config.quality_threshold);
+    }
+
+    #[test]
+    fn test_patch_collects_all_validation_errors() {
+        let error = MhosConfig::default()
+            .with_patch(&serde_json::json!({ "quality_threshold": 1.5, "backup_retention": 0 }))
+            .unwrap_err();
+        assert!(error.contains("حد الجودة") && error.contains("نسخة احتياطية"));
+    }
+
+    #[test]
+    fn test_patch_rejects_unknown_fields_and_non_objects() {
+        let config = MhosConfig::default();
+        assert!(config.with_patch(&serde_json::json!({ "monitoring_intervl": 5 })).is_err());
+        assert!(config.with_patch(&serde_json::json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn test_load_missing_file_gives_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let loaded = MhosConfig::load(dir.path().join("config.json")).unwrap();
+        assert_eq!(loaded.monitoring_interval, MhosConfig::default().monitoring_interval);
+    }
+
+    #[test]
+    fn test_load_partial_file_fills_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{ "alert_enabled": false }"#).unwrap();
+
+        let loaded = MhosConfig::load(&path).unwrap();
+        assert!(!loaded.alert_enabled);
+        assert_eq!(loaded.backup_retention, 7);
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mhos").join("config.json");
+        let patched = MhosConfig::default().with_patch(&serde_json::json!({ "monitoring_interval": 5 })).unwrap();
+
+        patched.save(&path).unwrap();
+        assert_eq!(MhosConfig::load(&path).unwrap().monitoring_interval, 5);
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{ "monitoring_interval": 0 }"#).unwrap();
+        assert_eq!(MhosConfig::load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
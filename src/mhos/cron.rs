@@ -0,0 +1,260 @@
+//! تعابير cron ذات الحقول الخمسة (دقيقة ساعة يوم شهر يوم-أسبوع) بتوقيت UTC
+//!
+//! يدعم كل حقل `*` والقوائم `1,15` والنطاقات `1-5` والخطوات `*/10` و`8-18/2`،
+//! إضافة إلى الاختصارات `@hourly` و`@daily` و`@weekly` و`@monthly`.
+//! كما في cron المعتاد: إذا قُيّد يوم الشهر ويوم الأسبوع معاً يكفي تطابق أحدهما.
+
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+/// أقصى مدى للبحث عن الموعد التالي (لتعابير مثل 31 فبراير)
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+/// جدول cron محلل
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl CronSchedule {
+    /// تحليل تعبير cron
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let trimmed = expression.trim();
+        let expanded = match trimmed {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("تعبير cron يجب أن يحتوي خمسة حقول: {}", trimmed));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        // الأحد يكتب 0 أو 7
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays | 1) & !(1 << 7);
+        }
+
+        Ok(Self {
+            expression: trimmed.to_string(),
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    /// هل تطابق الدقيقة المعطاة الجدول
+    pub fn matches(&self, at: DateTime<Utc>) -> bool {
+        has(self.minutes, at.minute())
+            && has(self.hours, at.hour())
+            && has(self.months, at.month())
+            && self.day_matches(at)
+    }
+
+    /// أول دقيقة مطابقة بعد الوقت المعطى
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let mut at = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after + Duration::days(MAX_LOOKAHEAD_DAYS);
+
+        while at <= limit {
+            if !has(self.months, at.month()) {
+                let (year, month) = if at.month() == 12 { (at.year() + 1, 1) } else { (at.year(), at.month() + 1) };
+                at = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !self.day_matches(at) {
+                at = Utc.from_utc_datetime(&at.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?);
+            } else if !has(self.hours, at.hour()) {
+                at = at.with_minute(0)? + Duration::hours(1);
+            } else if !has(self.minutes, at.minute()) {
+                at += Duration::minutes(1);
+            } else {
+                return Some(at);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, at: DateTime<Utc>) -> bool {
+        let day = has(self.days, at.day());
+        let weekday = has(self.weekdays, at.weekday().num_days_from_sunday());
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day || weekday,
+            _ => day && weekday,
+        }
+    }
+}
+
+impl std::fmt::Display for CronSchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
+
+fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// تحليل حقل إلى قناع بتات للقيم المسموحة
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("خطوة غير صالحة: {}", part))?;
+                if step == 0 {
+                    return Err(format!("الخطوة لا تكون صفراً: {}", part));
+                }
+                (range, Some(step))
+            }
+            None => (part, None),
+        };
+
+        let parse = |value: &str| -> Result<u32, String> {
+            let value: u32 = value.parse().map_err(|_| format!("قيمة غير صالحة: {}", part))?;
+            if value < min || value > max {
+                return Err(format!("القيمة {} خارج المدى {}-{}", value, min, max));
+            }
+            Ok(value)
+        };
+
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (parse(start)?, parse(end)?),
+                // `5/15` تعني من 5 حتى نهاية المدى
+                None if step.is_some() => (parse(range)?, max),
+                None => {
+                    let value = parse(range)?;
+                    (value, value)
+                }
+            },
+        };
+        if start > end {
+            return Err(format!("نطاق معكوس: {}", part));
+        }
+
+        let step = step.unwrap_or(1) as usize;
+        for value in (start..=end).step_by(step) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(text).unwrap().with_timezone(&Utc)
+    }
+
+    /// 2026-03-10 يوم ثلاثاء
+    fn now() -> DateTime<Utc> {
+        at("2026-03-10T10:17:42Z")
+    }
+
+    fn next(expression: &str) -> Option<DateTime<Utc>> {
+        CronSchedule::parse(expression).unwrap().next_after(now())
+    }
+
+    #[test]
+    fn test_fixed_time() {
+        let nightly = CronSchedule::parse("30 2 * * *").unwrap();
+        assert_eq!(nightly.next_after(now()), Some(at("2026-03-11T02:30:00Z")));
+        assert!(nightly.matches(at("2026-03-11T02:30:00Z")));
+        assert!(!nightly.matches(at("2026-03-11T02:31:00Z")));
+    }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(next("*/15 * * * *"), Some(at("2026-03-10T10:30:00Z")));
+        // `5/20` من الدقيقة 5 حتى نهاية المدى
+        assert_eq!(next("5/20 * * * *"), Some(at("2026-03-10T10:25:00Z")));
+        assert_eq!(next("* * * * *"), Some(at("2026-03-10T10:18:00Z")));
+    }
+
+    #[test]
+    fn test_ranges_and_lists() {
+        assert_eq!(next("0 8-18/2 * * 1-5"), Some(at("2026-03-10T12:00:00Z")));
+        assert_eq!(next("0 9-11 * * *"), Some(at("2026-03-10T11:00:00Z")));
+        assert_eq!(next("0,45 10 * * *"), Some(at("2026-03-10T10:45:00Z")));
+        // نهاية النطاق شاملة
+        assert!(CronSchedule::parse("0-59/59 * * * *").unwrap().matches(at("2026-03-10T10:59:00Z")));
+    }
+
+    #[test]
+    fn test_sunday_as_zero_or_seven() {
+        let sunday = at("2026-03-15T00:00:00Z");
+        assert_eq!(next("0 0 * * 0"), Some(sunday));
+        assert_eq!(next("0 0 * * 7"), Some(sunday));
+        assert_eq!(next("0 4 * * 1"), Some(at("2026-03-16T04:00:00Z")));
+    }
+
+    #[test]
+    fn test_macros() {
+        assert_eq!(next("@hourly"), Some(at("2026-03-10T11:00:00Z")));
+        assert_eq!(next("@daily"), Some(at("2026-03-11T00:00:00Z")));
+        assert_eq!(next("@weekly"), Some(at("2026-03-15T00:00:00Z")));
+        assert_eq!(next("@monthly"), Some(at("2026-04-01T00:00:00Z")));
+        assert_eq!(CronSchedule::parse(" @daily ").unwrap().expression(), "@daily");
+    }
+
+    #[test]
+    fn test_day_of_month_or_weekday() {
+        // تقييد الاثنين يكفي فيه تطابق أحدهما: أول جمعة قبل أول الشهر
+        assert_eq!(next("0 0 1 * 5"), Some(at("2026-03-13T00:00:00Z")));
+        // تقييد أحدهما فقط يشترطه
+        assert_eq!(next("0 0 1 * *"), Some(at("2026-04-01T00:00:00Z")));
+    }
+
+    #[test]
+    fn test_rare_and_impossible_dates() {
+        assert_eq!(next("0 0 29 2 *"), Some(at("2028-02-29T00:00:00Z")));
+        assert_eq!(next("0 0 31 2 *"), None);
+        assert_eq!(next("0 0 31 4 *"), None);
+    }
+
+    #[test]
+    fn test_invalid_field_count() {
+        for invalid in ["", "* * * *", "* * * * * *", "@yearly"] {
+            assert!(CronSchedule::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_invalid_field_values() {
+        for invalid in [
+            "60 * * * *", "* 24 * * *", "* * 0 * *", "* * 32 * *",
+            "* * * 0 *", "* * * 13 *", "* * * * 8", "a * * * *", "1,,2 * * * *", "-1 * * * *",
+        ] {
+            assert!(CronSchedule::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_invalid_ranges_and_steps() {
+        for invalid in ["5-1 * * * *", "*/0 * * * *", "*/x * * * *", "1-70 * * * *", "1-5/ * * * *"] {
+            assert!(CronSchedule::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -1,13 +1,23 @@
+use std::fmt;
 use std::sync::Arc;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::core::factory_manager::FactoryManager;
+use crate::mhos::widgets::{widget_registry, WidgetContext, WidgetRegistry};
+
+/// أعمدة شبكة لوحة التحكم
+pub const GRID_COLUMNS: u32 = 12;
+
+/// صفوف شبكة لوحة التحكم
+pub const GRID_ROWS: u32 = 8;
 
 /// لوحة تحكم MH-OS
 pub struct Dashboard {
     factory_manager: Arc<FactoryManager>,
     widgets: HashMap<String, DashboardWidget>,
     layout: DashboardLayout,
+    registry: Arc<WidgetRegistry>,
+    context: WidgetContext,
 }
 
 /// عنصر في لوحة التحكم
@@ -23,7 +33,7 @@ pub struct DashboardWidget {
 }
 
 /// نوع العنصر
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum WidgetType {
     PerformanceChart,
     QualityGauge,
@@ -33,10 +43,29 @@ pub enum WidgetType {
     RecentActivity,
     Alerts,
     Recommendations,
+    /// نوع يقدّم بياناته مزود مسجل بهذا الاسم
+    Custom(String),
+}
+
+impl WidgetType {
+    /// اسم المزود في `WidgetRegistry`
+    pub fn provider_name(&self) -> &str {
+        match self {
+            WidgetType::PerformanceChart => "performance_chart",
+            WidgetType::QualityGauge => "quality_gauge",
+            WidgetType::FactoryStatus => "factory_status",
+            WidgetType::ProductionStats => "production_stats",
+            WidgetType::SystemHealth => "system_health",
+            WidgetType::RecentActivity => "recent_activity",
+            WidgetType::Alerts => "alerts",
+            WidgetType::Recommendations => "recommendations",
+            WidgetType::Custom(name) => name,
+        }
+    }
 }
 
 /// تخطيط لوحة التحكم
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DashboardLayout {
     pub columns: u32,
     pub rows: u32,
@@ -44,7 +73,7 @@ pub struct DashboardLayout {
 }
 
 /// موقع العنصر
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WidgetPosition {
     pub widget_id: String,
     pub col: u32,
@@ -53,6 +82,133 @@ pub struct WidgetPosition {
     pub height: u32,
 }
 
+impl WidgetPosition {
+    fn overlaps(&self, other: &WidgetPosition) -> bool {
+        self.col < other.col + other.width && other.col < self.col + self.width &&
+            self.row < other.row + other.height && other.row < self.row + self.height
+    }
+}
+
+/// خطأ في وضع عنصر على الشبكة
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutError {
+    /// العنصر غير موجود في التخطيط
+    UnknownWidget(String),
+    /// معرف العنصر مستخدم
+    DuplicateWidget(String),
+    /// عرض أو ارتفاع صفري
+    EmptySize(String),
+    /// العنصر يتجاوز حدود الشبكة
+    OutOfBounds { widget_id: String, columns: u32, rows: u32 },
+    /// العنصر يتداخل مع عنصر آخر
+    Overlap { widget_id: String, other: String },
+    /// لا مزود مسجل لنوع مخصص
+    UnknownProvider(String),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::UnknownWidget(id) => write!(f, "العنصر غير موجود: {}", id),
+            LayoutError::DuplicateWidget(id) => write!(f, "العنصر موجود مسبقاً: {}", id),
+            LayoutError::EmptySize(id) => write!(f, "أبعاد العنصر {} يجب أن تكون موجبة", id),
+            LayoutError::OutOfBounds { widget_id, columns, rows } =>
+                write!(f, "العنصر {} يتجاوز حدود الشبكة {}x{}", widget_id, columns, rows),
+            LayoutError::Overlap { widget_id, other } => write!(f, "العنصر {} يتداخل مع {}", widget_id, other),
+            LayoutError::UnknownProvider(name) => write!(f, "لا يوجد مزود بيانات باسم: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl DashboardLayout {
+    /// تخطيط فارغ بأبعاد الشبكة القياسية
+    pub fn standard() -> Self {
+        Self {
+            columns: GRID_COLUMNS,
+            rows: GRID_ROWS,
+            widgets: Vec::new(),
+        }
+    }
+    
+    /// التحقق من إمكان وضع عنصر (يُتجاهل موقعه الحالي إن كان موجوداً)
+    pub fn check_placement(&self, position: &WidgetPosition) -> Result<(), LayoutError> {
+        if position.width == 0 || position.height == 0 {
+            return Err(LayoutError::EmptySize(position.widget_id.clone()));
+        }
+        if position.col + position.width > self.columns || position.row + position.height > self.rows {
+            return Err(LayoutError::OutOfBounds {
+                widget_id: position.widget_id.clone(),
+                columns: self.columns,
+                rows: self.rows,
+            });
+        }
+        match self.widgets.iter().find(|w| w.widget_id != position.widget_id && w.overlaps(position)) {
+            Some(other) => Err(LayoutError::Overlap {
+                widget_id: position.widget_id.clone(),
+                other: other.widget_id.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+    
+    /// وضع عنصر أو تحديث موقعه بعد التحقق
+    pub fn place(&mut self, position: WidgetPosition) -> Result<(), LayoutError> {
+        self.check_placement(&position)?;
+        match self.widgets.iter_mut().find(|w| w.widget_id == position.widget_id) {
+            Some(current) => *current = position,
+            None => self.widgets.push(position),
+        }
+        Ok(())
+    }
+    
+    pub fn position(&self, widget_id: &str) -> Option<&WidgetPosition> {
+        self.widgets.iter().find(|w| w.widget_id == widget_id)
+    }
+    
+    pub fn remove(&mut self, widget_id: &str) -> Result<WidgetPosition, LayoutError> {
+        let index = self.widgets.iter()
+            .position(|w| w.widget_id == widget_id)
+            .ok_or_else(|| LayoutError::UnknownWidget(widget_id.to_string()))?;
+        Ok(self.widgets.remove(index))
+    }
+}
+
+/// تخطيط لوحة مستخدم قابل للتصدير والاستيراد (دون بيانات العناصر)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardProfile {
+    pub version: String,
+    pub columns: u32,
+    pub rows: u32,
+    pub widgets: Vec<WidgetSpec>,
+}
+
+/// عنصر في تخطيط محفوظ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WidgetSpec {
+    pub id: String,
+    pub widget_type: WidgetType,
+    pub title: String,
+    pub col: u32,
+    pub row: u32,
+    pub width: u32,
+    pub height: u32,
+    pub refresh_interval: u64,
+}
+
+impl WidgetSpec {
+    fn position(&self) -> WidgetPosition {
+        WidgetPosition {
+            widget_id: self.id.clone(),
+            col: self.col,
+            row: self.row,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
 /// بيانات لوحة التحكم
 #[derive(Debug, Clone, Serialize)]
 pub struct DashboardData {
@@ -77,6 +233,7 @@ impl Dashboard {
     /// إنشاء لوحة تحكم جديدة
     pub fn new(factory_manager: Arc<FactoryManager>) -> Self {
         let mut dashboard = Self {
+            context: WidgetContext::new(factory_manager.clone()),
             factory_manager,
             widgets: HashMap::new(),
             layout: DashboardLayout {
@@ -84,6 +241,7 @@ impl Dashboard {
                 rows: 8,
                 widgets: Vec::new(),
             },
+            registry: widget_registry(),
         };
         
         // تهيئة العناصر الافتراضية
@@ -92,6 +250,22 @@ impl Dashboard {
         dashboard
     }
     
+    /// مصادر البيانات للمزودين (الجودة والمراقب)
+    pub fn with_context(mut self, context: WidgetContext) -> Self {
+        self.context = context;
+        self
+    }
+    
+    /// سجل مزودين غير السجل المشترك
+    pub fn with_registry(mut self, registry: Arc<WidgetRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
+    
+    pub fn registry(&self) -> &WidgetRegistry {
+        &self.registry
+    }
+    
     /// تهيئة العناصر الافتراضية
     fn initialize_default_widgets(&mut self) {
         // عنصر أداء النظام
@@ -213,112 +387,28 @@ impl Dashboard {
         });
     }
     
-    /// تحديث بيانات العنصر
+    /// العناصر الحالية
+    pub fn widgets(&self) -> impl Iterator<Item = &DashboardWidget> {
+        self.widgets.values()
+    }
+
+    pub fn widget(&self, widget_id: &str) -> Option<&DashboardWidget> {
+        self.widgets.get(widget_id)
+    }
+
+    /// تحديث بيانات العنصر من مزوده المسجل (العنصر بلا مزود يبقى كما هو)
     pub async fn update_widget_data(&mut self, widget_id: &str) {
         if let Some(widget) = self.widgets.get_mut(widget_id) {
-            match widget.widget_type {
-                WidgetType::FactoryStatus => {
-                    widget.data = self.get_factory_status_data().await;
+            match self.registry.get(widget.widget_type.provider_name()) {
+                Some(provider) => {
+                    widget.data = provider.fetch(&self.context);
+                    log::debug!("تم تحديث عنصر: {}", widget_id);
                 }
-                WidgetType::ProductionStats => {
-                    widget.data = self.get_production_stats_data().await;
-                }
-                WidgetType::PerformanceChart => {
-                    widget.data = self.get_performance_data().await;
-                }
-                WidgetType::QualityGauge => {
-                    widget.data = self.get_quality_data().await;
-                }
-                _ => {
+                None => {
                     // لا تحتاج بيانات حية
                 }
             }
-            
-            log::debug!("تم تحديث عنصر: {}", widget_id);
-        }
-    }
-    
-    /// الحصول على بيانات حالة المصانع
-    async fn get_factory_status_data(&self) -> serde_json::Value {
-        let factories = self.factory_manager.list_factories();
-        let mut details = Vec::new();
-        
-        for factory in factories {
-            details.push(serde_json::json!({
-                "name": factory.name(),
-                "type": factory.factory_type(),
-                "status": factory.status(),
-                "production": factory.production_count(),
-                "quality": factory.quality_score()
-            }));
-        }
-        
-        serde_json::json!({
-            "total": details.len(),
-            "active": details.iter().filter(|d| d["status"] == "active").count(),
-            "idle": details.iter().filter(|d| d["status"] == "idle").count(),
-            "error": details.iter().filter(|d| d["status"] == "error").count(),
-            "details": details
-        })
-    }
-    
-    /// الحصول على بيانات الإنتاج
-    async fn get_production_stats_data(&self) -> serde_json::Value {
-        let factories = self.factory_manager.list_factories();
-        let total_production: u64 = factories.iter()
-            .map(|f| f.production_count())
-            .sum();
-        
-        serde_json::json!({
-            "total": total_production,
-            "today": (total_production as f32 * 0.1) as u64, // محاكاة
-            "this_week": (total_production as f32 * 0.7) as u64,
-            "this_month": total_production,
-            "average_daily": total_production / 30,
-            "growth_rate": 0.15
-        })
-    }
-    
-    /// الحصول على بيانات الأداء
-    async fn get_performance_data(&self) -> serde_json::Value {
-        // محاكاة بيانات الأداء
-        serde_json::json!({
-            "cpu_usage": rand::random::<u8>() % 30 + 20,
-            "memory_usage": rand::random::<u8>() % 30 + 30,
-            "disk_usage": rand::random::<u8>() % 20 + 10,
-            "network_usage": rand::random::<u8>() % 10 + 5,
-            "response_time": rand::random::<u16>() % 50 + 50,
-            "throughput": rand::random::<u16>() % 100 + 100,
-            "trend": if rand::random::<bool>() { "improving" } else { "stable" }
-        })
-    }
-    
-    /// الحصول على بيانات الجودة
-    async fn get_quality_data(&self) -> serde_json::Value {
-        let factories = self.factory_manager.list_factories();
-        let mut by_factory = serde_json::Map::new();
-        
-        for factory in factories {
-            by_factory.insert(
-                factory.factory_type().to_string(),
-                serde_json::Value::from(factory.quality_score() as f64)
-            );
         }
-        
-        let quality_scores: Vec<f32> = factories.iter()
-            .map(|f| f.quality_score())
-            .collect();
-        
-        let overall_quality = if !quality_scores.is_empty() {
-            quality_scores.iter().sum::<f32>() / quality_scores.len() as f32
-        } else { 0.0 };
-        
-        serde_json::json!({
-            "overall_quality": overall_quality,
-            "by_factory": by_factory,
-            "threshold": 0.85,
-            "status": if overall_quality > 0.9 { "excellent" } else if overall_quality > 0.8 { "good" } else { "needs_improvement" }
-        })
     }
     
     /// الحصول على بيانات لوحة التحكم الكاملة
@@ -357,6 +447,116 @@ impl Dashboard {
         }
     }
     
+    /// لوحة من تخطيط محفوظ بعد التحقق من عناصره على الشبكة القياسية 12x8
+    pub fn from_profile(factory_manager: Arc<FactoryManager>, profile: &DashboardProfile) -> Result<Self, LayoutError> {
+        let mut dashboard = Self::new(factory_manager);
+        // العناصر الافتراضية مصدر البيانات الأولية لما يطابقها من عناصر التخطيط
+        let defaults = std::mem::take(&mut dashboard.widgets);
+        dashboard.layout = DashboardLayout::standard();
+        
+        for spec in &profile.widgets {
+            let data = defaults.get(&spec.id)
+                .filter(|w| w.widget_type == spec.widget_type)
+                .map(|w| w.data.clone())
+                .unwrap_or(serde_json::Value::Null);
+            dashboard.place_widget(DashboardWidget {
+                id: spec.id.clone(),
+                widget_type: spec.widget_type.clone(),
+                title: spec.title.clone(),
+                data,
+                position: (spec.col, spec.row),
+                size: (spec.width, spec.height),
+                refresh_interval: spec.refresh_interval,
+            })?;
+        }
+        
+        Ok(dashboard)
+    }
+    
+    /// التخطيط الحالي للحفظ أو التصدير
+    pub fn profile(&self) -> DashboardProfile {
+        let widgets = self.layout.widgets.iter()
+            .filter_map(|position| self.widgets.get(&position.widget_id).map(|widget| WidgetSpec {
+                id: widget.id.clone(),
+                widget_type: widget.widget_type.clone(),
+                title: widget.title.clone(),
+                col: position.col,
+                row: position.row,
+                width: position.width,
+                height: position.height,
+                refresh_interval: widget.refresh_interval,
+            }))
+            .collect();
+        
+        DashboardProfile {
+            version: "1.0".to_string(),
+            columns: self.layout.columns,
+            rows: self.layout.rows,
+            widgets,
+        }
+    }
+    
+    pub fn layout(&self) -> &DashboardLayout {
+        &self.layout
+    }
+    
+    /// إضافة عنصر بعد التحقق من المعرف والحدود والتداخل
+    pub fn place_widget(&mut self, widget: DashboardWidget) -> Result<(), LayoutError> {
+        if self.widgets.contains_key(&widget.id) {
+            return Err(LayoutError::DuplicateWidget(widget.id));
+        }
+        if let WidgetType::Custom(name) = &widget.widget_type {
+            if !self.registry.contains(name) {
+                return Err(LayoutError::UnknownProvider(name.clone()));
+            }
+        }
+        self.layout.place(WidgetPosition {
+            widget_id: widget.id.clone(),
+            col: widget.position.0,
+            row: widget.position.1,
+            width: widget.size.0,
+            height: widget.size.1,
+        })?;
+        self.widgets.insert(widget.id.clone(), widget);
+        Ok(())
+    }
+    
+    pub fn remove_widget(&mut self, widget_id: &str) -> Result<DashboardWidget, LayoutError> {
+        self.layout.remove(widget_id)?;
+        self.widgets.remove(widget_id).ok_or_else(|| LayoutError::UnknownWidget(widget_id.to_string()))
+    }
+    
+    /// نقل عنصر إلى خلية أخرى
+    pub fn move_widget(&mut self, widget_id: &str, col: u32, row: u32) -> Result<(), LayoutError> {
+        let mut position = self.current_position(widget_id)?;
+        position.col = col;
+        position.row = row;
+        self.update_position(position)
+    }
+    
+    /// تغيير أبعاد عنصر مع بقاء زاويته
+    pub fn resize_widget(&mut self, widget_id: &str, width: u32, height: u32) -> Result<(), LayoutError> {
+        let mut position = self.current_position(widget_id)?;
+        position.width = width;
+        position.height = height;
+        self.update_position(position)
+    }
+    
+    fn current_position(&self, widget_id: &str) -> Result<WidgetPosition, LayoutError> {
+        self.layout.position(widget_id)
+            .cloned()
+            .ok_or_else(|| LayoutError::UnknownWidget(widget_id.to_string()))
+    }
+    
+    fn update_position(&mut self, position: WidgetPosition) -> Result<(), LayoutError> {
+        self.layout.place(position.clone())?;
+        if let Some(widget) = self.widgets.get_mut(&position.widget_id) {
+            widget.position = (position.col, position.row);
+            widget.size = (position.width, position.height);
+        }
+        Ok(())
+    }
+    
     /// إعادة تعيين تخطيط لوحة التحكم
     pub fn reset_layout(&mut self) {
         self.layout.widgets.clear();
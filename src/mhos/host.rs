@@ -0,0 +1,258 @@
+//! قراءة موارد المضيف الفعلية من /proc (لينكس)
+//!
+//! يحتفظ القارئ بالعدادات السابقة لحساب نسبة استخدام المعالج ومعدل الشبكة
+//! بين قراءتين متتاليتين. على الأنظمة الأخرى تعود القيم أصفاراً.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use serde::Serialize;
+
+/// سعة الشبكة الافتراضية عند تعذر قراءة سرعة الواجهات (1 جيجابت/ث)
+const DEFAULT_LINK_BYTES_PER_SEC: f64 = 125_000_000.0;
+
+/// قراءة واحدة لموارد المضيف والعملية الحالية
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HostSample {
+    pub cpu_usage: f32,
+    pub memory_usage: f32,
+    pub memory_total_bytes: u64,
+    pub memory_available_bytes: u64,
+    pub disk_usage: f32,
+    pub network_usage: f32,
+    pub network_bytes_per_sec: f64,
+    pub process_rss_bytes: u64,
+    pub process_threads: u32,
+}
+
+/// عدادات المعالج من /proc/stat
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CpuTimes {
+    idle: u64,
+    total: u64,
+}
+
+/// العدادات السابقة لحساب الفروق
+#[derive(Debug, Default)]
+struct Counters {
+    cpu: Option<CpuTimes>,
+    network: Option<(u64, Instant)>,
+}
+
+/// قارئ موارد المضيف
+pub struct HostSampler {
+    proc_root: PathBuf,
+    disk_path: PathBuf,
+    previous: Mutex<Counters>,
+}
+
+impl HostSampler {
+    /// قارئ جديد يقيس القرص الذي يحتوي الدليل الحالي
+    pub fn new() -> Self {
+        Self {
+            proc_root: PathBuf::from("/proc"),
+            disk_path: PathBuf::from("."),
+            previous: Mutex::new(Counters::default()),
+        }
+    }
+
+    /// تحديد المسار الذي يُقاس استخدام قرصه (مثل دليل البيانات)
+    pub fn with_disk_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.disk_path = path.into();
+        self
+    }
+
+    /// أخذ قراءة جديدة
+    pub fn sample(&self) -> HostSample {
+        let mut sample = HostSample::default();
+        let mut previous = match self.previous.lock() {
+            Ok(previous) => previous,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        // المعالج: الفرق عن القراءة السابقة، أو المتوسط منذ الإقلاع في أول قراءة
+        if let Some(current) = self.read("stat").as_deref().and_then(parse_cpu_times) {
+            let (idle, total) = match previous.cpu {
+                Some(prev) if current.total > prev.total => {
+                    (current.idle.saturating_sub(prev.idle), current.total - prev.total)
+                }
+                _ => (current.idle, current.total),
+            };
+            if total > 0 {
+                sample.cpu_usage = (1.0 - idle as f64 / total as f64).clamp(0.0, 1.0) as f32;
+            }
+            previous.cpu = Some(current);
+        }
+
+        // الذاكرة
+        if let Some((total, available)) = self.read("meminfo").as_deref().and_then(parse_meminfo) {
+            sample.memory_total_bytes = total;
+            sample.memory_available_bytes = available;
+            if total > 0 {
+                sample.memory_usage = (1.0 - available as f64 / total as f64).clamp(0.0, 1.0) as f32;
+            }
+        }
+
+        // الشبكة: معدل البايتات منذ القراءة السابقة مقارنة بسعة الواجهات
+        if let Some(bytes) = self.read("net/dev").as_deref().map(parse_net_dev) {
+            let now = Instant::now();
+            if let Some((prev_bytes, prev_at)) = previous.network {
+                let elapsed = now.duration_since(prev_at).as_secs_f64();
+                if elapsed > 0.0 && bytes >= prev_bytes {
+                    sample.network_bytes_per_sec = (bytes - prev_bytes) as f64 / elapsed;
+                    sample.network_usage = (sample.network_bytes_per_sec / self.link_capacity())
+                        .clamp(0.0, 1.0) as f32;
+                }
+            }
+            previous.network = Some((bytes, now));
+        }
+        drop(previous);
+
+        // العملية الحالية
+        if let Some((rss, threads)) = self.read("self/status").as_deref().and_then(parse_process_status) {
+            sample.process_rss_bytes = rss;
+            sample.process_threads = threads;
+        }
+
+        sample.disk_usage = disk_usage(&self.disk_path).unwrap_or(0.0);
+        sample
+    }
+
+    fn read(&self, name: &str) -> Option<String> {
+        std::fs::read_to_string(self.proc_root.join(name)).ok()
+    }
+
+    /// مجموع سرعات الواجهات الفعلية من /sys/class/net بالبايت في الثانية
+    fn link_capacity(&self) -> f64 {
+        let mut capacity = 0.0;
+        if let Ok(entries) = std::fs::read_dir("/sys/class/net") {
+            for entry in entries.flatten() {
+                if entry.file_name() == "lo" {
+                    continue;
+                }
+                let speed = std::fs::read_to_string(entry.path().join("speed"))
+                    .ok()
+                    .and_then(|s| s.trim().parse::<i64>().ok())
+                    .unwrap_or(0);
+                if speed > 0 {
+                    capacity += speed as f64 * 1_000_000.0 / 8.0;
+                }
+            }
+        }
+
+        if capacity > 0.0 { capacity } else { DEFAULT_LINK_BYTES_PER_SEC }
+    }
+}
+
+impl Default for HostSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// السطر الإجمالي "cpu" في /proc/stat
+fn parse_cpu_times(stat: &str) -> Option<CpuTimes> {
+    let line = stat.lines().find(|l| l.starts_with("cpu "))?;
+    let values: Vec<u64> = line.split_whitespace()
+        .skip(1)
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    if values.len() < 4 {
+        return None;
+    }
+
+    // user nice system idle iowait irq softirq steal (guest محسوب ضمن user)
+    let total = values.iter().take(8).sum();
+    let idle = values[3] + values.get(4).copied().unwrap_or(0);
+    Some(CpuTimes { idle, total })
+}
+
+/// (الإجمالي، المتاح) بالبايت من /proc/meminfo
+fn parse_meminfo(meminfo: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| -> Option<u64> {
+        meminfo.lines()
+            .find(|l| l.starts_with(name) && l[name.len()..].starts_with(':'))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|v| v.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+    };
+
+    let total = field("MemTotal")?;
+    let available = field("MemAvailable")
+        .or_else(|| Some(field("MemFree")? + field("Buffers").unwrap_or(0) + field("Cached").unwrap_or(0)))?;
+    Some((total, available))
+}
+
+/// مجموع البايتات المستقبلة والمرسلة لكل الواجهات عدا lo
+fn parse_net_dev(net_dev: &str) -> u64 {
+    net_dev.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (iface, counters) = line.split_once(':')?;
+            if iface.trim() == "lo" {
+                return None;
+            }
+            let fields: Vec<u64> = counters.split_whitespace().filter_map(|v| v.parse().ok()).collect();
+            Some(fields.first().copied().unwrap_or(0) + fields.get(8).copied().unwrap_or(0))
+        })
+        .sum()
+}
+
+/// (الذاكرة المقيمة بالبايت، عدد الخيوط) من /proc/self/status
+fn parse_process_status(status: &str) -> Option<(u64, u32)> {
+    let mut rss = None;
+    let mut threads = None;
+    for line in status.lines() {
+        if let Some(value) = line.strip_prefix("VmRSS:") {
+            rss = value.split_whitespace().next().and_then(|v| v.parse::<u64>().ok()).map(|kb| kb * 1024);
+        } else if let Some(value) = line.strip_prefix("Threads:") {
+            threads = value.trim().parse::<u32>().ok();
+        }
+    }
+    Some((rss?, threads.unwrap_or(1)))
+}
+
+/// نسبة استخدام نظام الملفات الذي يحتوي المسار (كما يحسبها df)
+#[cfg(unix)]
+fn disk_usage(path: &Path) -> Option<f32> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path سلسلة منتهية بصفر و stat مخزن صالح للكتابة
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    let used = stat.f_blocks.saturating_sub(stat.f_bfree) as f64;
+    let available = stat.f_bavail as f64;
+    if used + available <= 0.0 {
+        return None;
+    }
+    Some((used / (used + available)) as f32)
+}
+
+#[cfg(not(unix))]
+fn disk_usage(_path: &Path) -> Option<f32> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_files() {
+        let stat = "cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 50 0 25 400 25 0 0 0 0 0\n";
+        assert_eq!(parse_cpu_times(stat), Some(CpuTimes { idle: 850, total: 1000 }));
+
+        let meminfo = "MemTotal:        8000 kB\nMemFree:         1000 kB\nMemAvailable:    2000 kB\n";
+        assert_eq!(parse_meminfo(meminfo), Some((8000 * 1024, 2000 * 1024)));
+
+        let net_dev = "Inter-|   Receive\n face |bytes    packets\n    lo: 999 1 0 0 0 0 0 0 999 1 0 0 0 0 0 0\n  eth0: 1000 10 0 0 0 0 0 0 500 5 0 0 0 0 0 0\n";
+        assert_eq!(parse_net_dev(net_dev), 1500);
+
+        let status = "Name:\tmh-api\nVmRSS:\t   2048 kB\nThreads:\t7\n";
+        assert_eq!(parse_process_status(status), Some((2048 * 1024, 7)));
+    }
+}
@@ -0,0 +1,664 @@
+//! قاعدة معرفة المساعد الذكي
+//!
+//! تُحفظ المداخل في ملف JSON داخل دليل البيانات يتشاركه خادم API وأوامر
+//! سطر الأوامر. البحث نصي كامل على نص مُطبَّع (دون تشكيل ولا تطويل، وبتوحيد
+//! صور الألف والياء والتاء المربوطة وحذف "ال")، والترتيب بحسب الصلة.
+//! تُضاف مداخل تلقائياً من الإنذارات المحلولة والتحسينات التي ثبت نفعها.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::tuning::Knob;
+use crate::mhos::monitor::SystemAlert;
+use crate::mhos::optimizer::{ChangeOutcome, Improvement};
+
+/// مسار ملف قاعدة المعرفة داخل دليل البيانات
+pub const KNOWLEDGE_FILE: &str = "mhos/knowledge.json";
+
+/// وزن تطابق الموضوع والوسوم والمحتوى في الترتيب
+const TOPIC_WEIGHT: f64 = 3.0;
+const TAG_WEIGHT: f64 = 2.0;
+const CONTENT_WEIGHT: f64 = 1.0;
+
+/// مضاعف المداخل الموسومة بنوع المصنع المطلوب
+const FACTORY_BOOST: f64 = 1.5;
+
+/// مصدر المدخل
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum KnowledgeSource {
+    /// من المعرفة الأولية
+    Builtin,
+    /// أضافه مستخدم عبر API أو سطر الأوامر
+    Manual,
+    /// من إنذار حُل
+    Alert { alert_id: String },
+    /// من تحسين أُبقي بعد نافذة القياس
+    Optimization { run_id: String },
+}
+
+impl KnowledgeSource {
+    pub fn is_learned(&self) -> bool {
+        matches!(self, KnowledgeSource::Alert { .. } | KnowledgeSource::Optimization { .. })
+    }
+}
+
+/// مدخل في قاعدة المعرفة
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KnowledgeEntry {
+    pub id: String,
+    pub topic: String,
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// أنواع المصانع التي يخصها المدخل (فارغة = عام)
+    #[serde(default)]
+    pub factory_types: Vec<String>,
+    pub source: KnowledgeSource,
+    /// عدد مرات رصد الحالة نفسها (للمداخل المتعلَّمة)
+    #[serde(default = "one")]
+    pub observations: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+fn one() -> u32 {
+    1
+}
+
+impl KnowledgeEntry {
+    /// هل يخص المدخل نوع المصنع (المداخل العامة تخص الجميع)
+    pub fn applies_to(&self, factory_type: &str) -> bool {
+        self.factory_types.is_empty() || self.factory_types.iter().any(|f| f == factory_type)
+    }
+}
+
+/// محتوى مدخل جديد أو محدَّث
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KnowledgeDraft {
+    pub topic: String,
+    pub content: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub factory_types: Vec<String>,
+}
+
+impl KnowledgeDraft {
+    pub fn new(topic: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            content: content.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_tags(mut self, tags: &[&str]) -> Self {
+        self.tags = tags.iter().map(|t| t.to_string()).collect();
+        self
+    }
+
+    pub fn with_factory_types(mut self, factory_types: Vec<String>) -> Self {
+        self.factory_types = factory_types;
+        self
+    }
+
+    fn validate(&self) -> Result<(), KnowledgeError> {
+        if self.topic.trim().is_empty() {
+            return Err(KnowledgeError::Invalid("الموضوع مطلوب".to_string()));
+        }
+        if self.content.trim().is_empty() {
+            return Err(KnowledgeError::Invalid("المحتوى مطلوب".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// نتيجة بحث مع درجة الصلة
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub score: f64,
+    pub entry: KnowledgeEntry,
+}
+
+/// أخطاء قاعدة المعرفة
+#[derive(Debug)]
+pub enum KnowledgeError {
+    NotFound(String),
+    Invalid(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for KnowledgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KnowledgeError::NotFound(id) => write!(f, "المدخل غير موجود: {}", id),
+            KnowledgeError::Invalid(reason) => write!(f, "مدخل غير صالح: {}", reason),
+            KnowledgeError::Io(e) => write!(f, "تعذر حفظ قاعدة المعرفة: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for KnowledgeError {}
+
+impl From<io::Error> for KnowledgeError {
+    fn from(e: io::Error) -> Self {
+        KnowledgeError::Io(e)
+    }
+}
+
+/// محتوى ملف قاعدة المعرفة
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KnowledgeFile {
+    entries: Vec<KnowledgeEntry>,
+}
+
+/// قاعدة المعرفة
+#[derive(Debug, Default)]
+pub struct KnowledgeBase {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    entries: BTreeMap<String, KnowledgeEntry>,
+}
+
+impl KnowledgeBase {
+    /// قاعدة فارغة في الذاكرة فقط
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// قاعدة في الذاكرة بالمعرفة الأولية
+    pub fn with_defaults() -> Self {
+        let mut base = Self::in_memory();
+        base.seed_defaults();
+        base
+    }
+
+    /// فتح قاعدة محفوظة في ملف (تبدأ بالمعرفة الأولية إن لم يوجد الملف)
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut base = Self::in_memory();
+
+        if path.exists() {
+            base.path = Some(path);
+            base.reload()?;
+        } else {
+            base.seed_defaults();
+            base.path = Some(path);
+        }
+        Ok(base)
+    }
+
+    fn seed_defaults(&mut self) {
+        let defaults = [
+            ("optimization", "تحسين الأداء",
+                "لتحسين الأداء: 1. تحسين الخوارزميات 2. تحسين الذاكرة 3. موازنة الأحمال",
+                &["performance"][..]),
+            ("quality", "تحسين الجودة",
+                "لتحسين الجودة: 1. فحص المدخلات 2. تحسين المعالجة 3. اختبار المخرجات",
+                &["quality"][..]),
+            ("troubleshooting", "حل المشاكل",
+                "لحل المشاكل: 1. التحقق من السجلات 2. اختبار المكونات 3. استعادة النسخ الاحتياطية",
+                &["troubleshooting"][..]),
+        ];
+
+        let now = Utc::now();
+        for (id, topic, content, tags) in defaults {
+            self.entries.insert(id.to_string(), KnowledgeEntry {
+                id: id.to_string(),
+                topic: topic.to_string(),
+                content: content.to_string(),
+                tags: std::iter::once(id).chain(tags.iter().copied()).map(str::to_string).collect(),
+                factory_types: Vec::new(),
+                source: KnowledgeSource::Builtin,
+                observations: 1,
+                created_at: now,
+                updated_at: now,
+            });
+        }
+    }
+
+    /// إعادة التحميل إذا عدّلت عملية أخرى الملف
+    pub fn reload_if_changed(&mut self) -> io::Result<()> {
+        let changed = match &self.path {
+            Some(path) => file_modified(path) != self.modified,
+            None => false,
+        };
+        if changed {
+            self.reload()?;
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let file: KnowledgeFile = serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.entries = file.entries.into_iter().map(|e| (e.id.clone(), e)).collect();
+        }
+        self.modified = file_modified(&path);
+        Ok(())
+    }
+
+    /// حفظ القاعدة في ملفها (إن وُجد)
+    pub fn save(&mut self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = KnowledgeFile { entries: self.entries.values().cloned().collect() };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // كتابة ذرية عبر ملف مؤقت
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, &path)?;
+
+        self.modified = file_modified(&path);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&KnowledgeEntry> {
+        self.entries.get(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// المداخل (مصفاة بنوع المصنع إن حُدد) من الأحدث تحديثاً
+    pub fn list(&self, factory_type: Option<&str>) -> Vec<KnowledgeEntry> {
+        let mut entries: Vec<KnowledgeEntry> = self.entries.values()
+            .filter(|e| factory_type.map(|f| e.applies_to(f)).unwrap_or(true))
+            .cloned()
+            .collect();
+        entries.sort_by_key(|e| std::cmp::Reverse(e.updated_at));
+        entries
+    }
+
+    /// المدخل الذي يطابق موضوعه أو معرفه النص بعد التطبيع
+    pub fn find_topic(&self, topic: &str) -> Option<&KnowledgeEntry> {
+        let wanted = normalize(topic);
+        self.entries.get(topic)
+            .or_else(|| self.entries.values().find(|e| normalize(&e.topic) == wanted))
+    }
+
+    /// إضافة مدخل يدوي
+    pub fn add(&mut self, draft: KnowledgeDraft) -> Result<KnowledgeEntry, KnowledgeError> {
+        draft.validate()?;
+
+        let now = Utc::now();
+        let entry = KnowledgeEntry {
+            id: format!("kb_{}", crate::core::telemetry::random_hex(6)),
+            topic: draft.topic.trim().to_string(),
+            content: draft.content.trim().to_string(),
+            tags: draft.tags,
+            factory_types: draft.factory_types,
+            source: KnowledgeSource::Manual,
+            observations: 1,
+            created_at: now,
+            updated_at: now,
+        };
+        self.entries.insert(entry.id.clone(), entry.clone());
+        self.save()?;
+        Ok(entry)
+    }
+
+    /// استبدال محتوى مدخل موجود
+    pub fn update(&mut self, id: &str, draft: KnowledgeDraft) -> Result<KnowledgeEntry, KnowledgeError> {
+        draft.validate()?;
+
+        let entry = self.entries.get_mut(id)
+            .ok_or_else(|| KnowledgeError::NotFound(id.to_string()))?;
+        entry.topic = draft.topic.trim().to_string();
+        entry.content = draft.content.trim().to_string();
+        entry.tags = draft.tags;
+        entry.factory_types = draft.factory_types;
+        entry.updated_at = Utc::now();
+
+        let entry = entry.clone();
+        self.save()?;
+        Ok(entry)
+    }
+
+    /// حذف مدخل
+    pub fn remove(&mut self, id: &str) -> Result<KnowledgeEntry, KnowledgeError> {
+        let entry = self.entries.remove(id)
+            .ok_or_else(|| KnowledgeError::NotFound(id.to_string()))?;
+        self.save()?;
+        Ok(entry)
+    }
+
+    /// بحث نصي مرتب بالصلة، مع استبعاد المداخل الخاصة بأنواع مصانع أخرى
+    pub fn search(&self, query: &str, factory_type: Option<&str>, limit: usize) -> Vec<SearchHit> {
+        let terms = tokenize(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let candidates: Vec<(&KnowledgeEntry, IndexedEntry)> = self.entries.values()
+            .filter(|e| factory_type.map(|f| e.applies_to(f)).unwrap_or(true))
+            .map(|e| (e, IndexedEntry::new(e)))
+            .collect();
+
+        // ندرة المصطلح بين المداخل المرشحة
+        let total = candidates.len() as f64;
+        let idf: HashMap<&str, f64> = terms.iter()
+            .map(|term| {
+                let matching = candidates.iter().filter(|(_, index)| index.contains(term)).count() as f64;
+                (term.as_str(), (1.0 + total / (1.0 + matching)).ln())
+            })
+            .collect();
+
+        let mut hits: Vec<SearchHit> = candidates.into_iter()
+            .filter_map(|(entry, index)| {
+                let mut score: f64 = terms.iter()
+                    .map(|term| index.weight(term) * idf[term.as_str()])
+                    .sum();
+                if score <= 0.0 {
+                    return None;
+                }
+                if factory_type.map(|f| entry.factory_types.iter().any(|t| t == f)).unwrap_or(false) {
+                    score *= FACTORY_BOOST;
+                }
+                // المعرفة المؤكدة بتكرار الرصد أولى قليلاً
+                score *= 1.0 + (entry.observations.max(1) as f64).ln() * 0.1;
+                Some(SearchHit { score, entry: entry.clone() })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| b.score.partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| b.entry.updated_at.cmp(&a.entry.updated_at)));
+        hits.truncate(limit);
+        hits
+    }
+
+    /// تعلم من إنذار حُل: مدخل واحد لكل مقياس يتحدث مع كل حل جديد
+    pub fn learn_from_alert(&mut self, alert: &SystemAlert) -> Option<KnowledgeEntry> {
+        if !alert.resolved {
+            return None;
+        }
+
+        let subject = alert.metric.map(|m| m.as_str().to_string())
+            .unwrap_or_else(|| slug(&alert.source));
+        let resolved_at = alert.resolved_at.unwrap_or(alert.last_seen);
+        let minutes = (resolved_at - alert.timestamp).num_minutes().max(0);
+
+        let mut content = format!(
+            "{}: {}. عاد المقياس تحت العتبة {:.2} بعد {} دقيقة و{} تكرار (آخر قيمة {:.2}).",
+            alert.title, alert.message, alert.threshold, minutes, alert.occurrences, alert.value
+        );
+        if let Some(by) = &alert.acknowledged_by {
+            content.push_str(&format!(" تابعه {}.", by));
+        }
+
+        let mut tags = vec!["alert".to_string(), subject.clone(), alert.severity.severity_level().to_string()];
+        tags.dedup();
+        let draft = KnowledgeDraft {
+            topic: alert.title.clone(),
+            content,
+            tags,
+            factory_types: Vec::new(),
+        };
+        self.learn(format!("alert_{}", subject), draft, KnowledgeSource::Alert { alert_id: alert.id.clone() })
+    }
+
+    /// تعلم من تحسين أُبقي بعد قياس أثره: مدخل واحد لكل مقبض
+    pub fn learn_from_improvement(&mut self, run_id: &str, improvement: &Improvement) -> Option<KnowledgeEntry> {
+        if improvement.outcome != ChangeOutcome::Kept {
+            return None;
+        }
+        let objective = improvement.objective.as_ref()?;
+        let (baseline, measured) = (improvement.baseline?, improvement.measured?);
+
+        let content = format!(
+            "{} ({}) حسّن {} من {:.2} إلى {:.2}.",
+            improvement.action, improvement.details, objective.metric.display_name(), baseline, measured
+        );
+        let factory_types = match &improvement.knob {
+            Knob::FactoryConcurrency(factory) => vec![factory.clone()],
+            _ => Vec::new(),
+        };
+        let draft = KnowledgeDraft {
+            topic: improvement.action.clone(),
+            content,
+            tags: vec!["optimization".to_string(), improvement.area.clone(), objective.metric.display_name().to_string()],
+            factory_types,
+        };
+        self.learn(format!("opt_{}", slug(&improvement.knob.to_string())), draft,
+            KnowledgeSource::Optimization { run_id: run_id.to_string() })
+    }
+
+    /// إنشاء مدخل متعلَّم أو تحديثه وزيادة عدد مرات رصده
+    fn learn(&mut self, id: String, draft: KnowledgeDraft, source: KnowledgeSource) -> Option<KnowledgeEntry> {
+        let now = Utc::now();
+        let entry = match self.entries.get_mut(&id) {
+            // لا يُستبدل ما حرره المستخدم
+            Some(entry) if !entry.source.is_learned() => return None,
+            Some(entry) => {
+                entry.content = draft.content;
+                entry.source = source;
+                entry.observations += 1;
+                entry.updated_at = now;
+                entry.clone()
+            }
+            None => {
+                let entry = KnowledgeEntry {
+                    id: id.clone(),
+                    topic: draft.topic,
+                    content: draft.content,
+                    tags: draft.tags,
+                    factory_types: draft.factory_types,
+                    source,
+                    observations: 1,
+                    created_at: now,
+                    updated_at: now,
+                };
+                self.entries.insert(id, entry.clone());
+                entry
+            }
+        };
+
+        log::info!("🧠 معرفة جديدة: {}", entry.topic);
+        if let Err(e) = self.save() {
+            log::error!("❌ تعذر حفظ قاعدة المعرفة: {}", e);
+        }
+        Some(entry)
+    }
+}
+
+/// رموز المدخل المفهرسة حسب الحقل
+struct IndexedEntry {
+    topic: Vec<String>,
+    tags: Vec<String>,
+    content: Vec<String>,
+}
+
+impl IndexedEntry {
+    fn new(entry: &KnowledgeEntry) -> Self {
+        Self {
+            topic: tokenize(&entry.topic),
+            tags: entry.tags.iter().chain(&entry.factory_types).flat_map(|t| tokenize(t)).collect(),
+            content: tokenize(&entry.content),
+        }
+    }
+
+    fn contains(&self, term: &str) -> bool {
+        self.weight(term) > 0.0
+    }
+
+    /// وزن المصطلح في المدخل (التكرار مشبع حتى لا يطغى النص الطويل)
+    fn weight(&self, term: &str) -> f64 {
+        let field = |tokens: &[String]| {
+            let matches: f64 = tokens.iter().map(|t| term_match(term, t)).sum();
+            matches / (matches + 1.0)
+        };
+        TOPIC_WEIGHT * field(&self.topic) + TAG_WEIGHT * field(&self.tags) + CONTENT_WEIGHT * field(&self.content)
+    }
+}
+
+/// تطابق تام (1) أو بادئة لكلمة أطول (0.5)
+fn term_match(term: &str, token: &str) -> f64 {
+    if token == term {
+        1.0
+    } else if term.chars().count() >= 3 && token.starts_with(term) {
+        0.5
+    } else {
+        0.0
+    }
+}
+
+/// تطبيع النص العربي للبحث: حذف التشكيل والتطويل وتوحيد الحروف المتشابهة
+pub fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|c| !matches!(c, '\u{064B}'..='\u{065F}' | '\u{0670}' | '\u{0640}'))
+        .map(|c| match c {
+            'أ' | 'إ' | 'آ' | 'ٱ' => 'ا',
+            'ى' | 'ئ' => 'ي',
+            'ؤ' => 'و',
+            'ة' => 'ه',
+            _ => c,
+        })
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// تقسيم النص المطبع إلى رموز مع حذف أداة التعريف وحروف الجر الملتصقة بها
+pub fn tokenize(text: &str) -> Vec<String> {
+    const PREFIXES: [&str; 6] = ["وال", "بال", "كال", "فال", "لل", "ال"];
+
+    normalize(text)
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            PREFIXES.iter()
+                .find_map(|prefix| word.strip_prefix(prefix).filter(|rest| rest.chars().count() >= 2))
+                .unwrap_or(word)
+                .to_string()
+        })
+        .collect()
+}
+
+/// معرف آمن في المسارات
+fn slug(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() || c == '.' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mhos::alerts::{AlertMetric, AlertState};
+    use crate::mhos::monitor::AlertSeverity;
+
+    /// قاعدة بمدخلين خاصين بمصنعين إضافة إلى المداخل الافتراضية
+    fn knowledge_base() -> KnowledgeBase {
+        let mut base = KnowledgeBase::with_defaults();
+        base.add(KnowledgeDraft::new("ذاكرة التخزين المؤقت للمدونات", "زيادة مدة التخزين تقلل زمن الاستجابة")
+            .with_tags(&["cache"])
+            .with_factory_types(vec!["blog".to_string()])).unwrap();
+        base.add(KnowledgeDraft::new("إعادة المحاولة", "إعادة محاولة الطلبات الفاشلة في مصنع المواقع")
+            .with_factory_types(vec!["website".to_string()])).unwrap();
+        base
+    }
+
+    fn resolved_alert() -> SystemAlert {
+        let now = Utc::now();
+        SystemAlert {
+            id: "alert_1".to_string(),
+            severity: AlertSeverity::Warning,
+            title: "استخدام عالٍ للمعالج".to_string(),
+            message: "المعالج عند 85%".to_string(),
+            source: "system_monitor".to_string(),
+            timestamp: now - chrono::Duration::minutes(10),
+            resolved: true,
+            state: AlertState::Resolved,
+            metric: Some(AlertMetric::CpuUsage),
+            value: 0.3,
+            threshold: 0.8,
+            occurrences: 4,
+            last_seen: now,
+            acknowledged_by: None,
+            acknowledged_at: None,
+            resolved_at: Some(now),
+            silenced_until: None,
+        }
+    }
+
+    #[test]
+    fn test_tokenize_ignores_diacritics_hamza_and_article() {
+        assert_eq!(tokenize("الذَّاكِرَة"), tokenize("ذاكره"));
+    }
+
+    #[test]
+    fn test_search_matches_normalized_query() {
+        let base = knowledge_base();
+        let hits = base.search("ذَاكرة", None, 5);
+        assert_eq!(hits[0].entry.topic, "ذاكرة التخزين المؤقت للمدونات");
+    }
+
+    #[test]
+    fn test_search_ranks_topic_above_content() {
+        let base = knowledge_base();
+        let hits = base.search("تحسين", Some("website"), 5);
+        assert_eq!(hits[0].entry.id, "optimization");
+        assert_eq!(base.find_topic("تحسين الجودة").unwrap().id, "quality");
+    }
+
+    #[test]
+    fn test_search_filters_by_factory_type() {
+        let base = knowledge_base();
+        assert!(base.search("تحسين", Some("website"), 5).iter().all(|h| h.entry.applies_to("website")));
+        assert!(base.search("التخزين", Some("website"), 5).is_empty());
+    }
+
+    #[test]
+    fn test_resolved_alert_learned_once() {
+        let mut base = knowledge_base();
+        let alert = resolved_alert();
+        base.learn_from_alert(&alert).unwrap();
+        let learned = base.learn_from_alert(&alert).unwrap();
+
+        assert_eq!(learned.observations, 2);
+        assert_eq!(base.search("المعالج", None, 5)[0].entry.id, learned.id);
+    }
+
+    #[test]
+    fn test_entries_persist_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge.json");
+        let mut base = KnowledgeBase::open(&path).unwrap();
+        let learned = base.learn_from_alert(&resolved_alert()).unwrap();
+        let count = base.len();
+
+        let base = KnowledgeBase::open(&path).unwrap();
+        assert_eq!(base.len(), count);
+        assert!(base.get(&learned.id).is_some());
+    }
+}
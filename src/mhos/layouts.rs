@@ -0,0 +1,177 @@
+//! تخطيطات لوحة التحكم لكل مستخدم
+//!
+//! يُحفظ تخطيط كل مستخدم (معرّف بـ `sub` في توكن JWT) في ملف JSON داخل
+//! دليل البيانات؛ ومن لا تخطيط له يرى التخطيط الافتراضي.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::factory_manager::FactoryManager;
+use crate::mhos::dashboard::{Dashboard, DashboardProfile, LayoutError};
+
+/// مسار ملف التخطيطات داخل دليل البيانات
+pub const LAYOUTS_FILE: &str = "mhos/layouts.json";
+
+/// تخطيط مستخدم محفوظ
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserLayout {
+    pub profile: DashboardProfile,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// محتوى ملف التخطيطات
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LayoutFile {
+    users: BTreeMap<String, UserLayout>,
+}
+
+/// مخزن تخطيطات المستخدمين
+#[derive(Debug, Default)]
+pub struct LayoutStore {
+    path: Option<PathBuf>,
+    users: BTreeMap<String, UserLayout>,
+}
+
+impl LayoutStore {
+    /// مخزن في الذاكرة فقط
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// فتح مخزن محفوظ في ملف (يُنشأ عند أول حفظ)
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut store = Self::in_memory();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let file: LayoutFile = serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            store.users = file.users;
+        }
+
+        store.path = Some(path);
+        Ok(store)
+    }
+
+    /// لوحة المستخدم بتخطيطه المحفوظ أو بالتخطيط الافتراضي
+    pub fn dashboard_for(&self, user: &str, factory_manager: Arc<FactoryManager>) -> Result<Dashboard, LayoutError> {
+        match self.users.get(user) {
+            Some(layout) => Dashboard::from_profile(factory_manager, &layout.profile),
+            None => Ok(Dashboard::new(factory_manager)),
+        }
+    }
+
+    pub fn get(&self, user: &str) -> Option<&UserLayout> {
+        self.users.get(user)
+    }
+
+    /// حفظ تخطيط مستخدم (يُتحقق منه عبر `Dashboard::from_profile` قبل الاستدعاء)
+    pub fn set(&mut self, user: &str, profile: DashboardProfile) -> io::Result<()> {
+        self.users.insert(user.to_string(), UserLayout { profile, updated_at: Utc::now() });
+        self.save()
+    }
+
+    /// حذف تخطيط مستخدم والعودة إلى الافتراضي
+    pub fn remove(&mut self, user: &str) -> io::Result<bool> {
+        let removed = self.users.remove(user).is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    /// حفظ المخزن في ملفه (إن وُجد)
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = LayoutFile { users: self.users.clone() };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // كتابة ذرية عبر ملف مؤقت
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mhos::dashboard::{DashboardWidget, WidgetType};
+
+    fn alerts_widget() -> DashboardWidget {
+        DashboardWidget {
+            id: "alerts".to_string(),
+            widget_type: WidgetType::Alerts,
+            title: "الإنذارات".to_string(),
+            data: serde_json::Value::Null,
+            position: (0, 0),
+            size: (4, 2),
+            refresh_interval: 15,
+        }
+    }
+
+    /// لوحة أليس بعد استبدال مخطط الأداء بعنصر الإنذارات
+    fn customized_dashboard(store: &LayoutStore, factory_manager: Arc<FactoryManager>) -> Dashboard {
+        let mut dashboard = store.dashboard_for("alice", factory_manager).unwrap();
+        dashboard.remove_widget("performance_chart").unwrap();
+        dashboard.place_widget(alerts_widget()).unwrap();
+        dashboard.resize_widget("alerts", 6, 4).unwrap();
+        dashboard
+    }
+
+    #[test]
+    fn test_place_widget_rejects_overlap() {
+        let store = LayoutStore::in_memory();
+        let mut dashboard = store.dashboard_for("alice", Arc::new(FactoryManager::new())).unwrap();
+        // الشبكة ممتلئة بالعناصر الافتراضية
+        assert!(matches!(dashboard.place_widget(alerts_widget()), Err(LayoutError::Overlap { .. })));
+    }
+
+    #[test]
+    fn test_move_widget_rejects_out_of_bounds() {
+        let store = LayoutStore::in_memory();
+        let mut dashboard = customized_dashboard(&store, Arc::new(FactoryManager::new()));
+        assert!(matches!(dashboard.move_widget("alerts", 10, 0), Err(LayoutError::OutOfBounds { .. })));
+    }
+
+    #[test]
+    fn test_layout_persists_across_reopen() {
+        let factory_manager = Arc::new(FactoryManager::new());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LAYOUTS_FILE);
+        let mut store = LayoutStore::open(&path).unwrap();
+        let dashboard = customized_dashboard(&store, factory_manager.clone());
+        store.set("alice", dashboard.profile()).unwrap();
+
+        let store = LayoutStore::open(&path).unwrap();
+        let alice = store.dashboard_for("alice", factory_manager).unwrap();
+        assert!(alice.widget("alerts").is_some());
+        assert!(alice.widget("performance_chart").is_none());
+        assert_eq!(alice.layout().position("alerts").unwrap().width, 6);
+    }
+
+    #[test]
+    fn test_users_without_layout_get_default() {
+        let factory_manager = Arc::new(FactoryManager::new());
+        let mut store = LayoutStore::in_memory();
+        let dashboard = customized_dashboard(&store, factory_manager.clone());
+        store.set("alice", dashboard.profile()).unwrap();
+
+        assert!(store.dashboard_for("bob", factory_manager.clone()).unwrap().widget("performance_chart").is_some());
+        assert!(store.remove("alice").unwrap());
+        assert!(store.dashboard_for("alice", factory_manager).unwrap().widget("alerts").is_none());
+    }
+}
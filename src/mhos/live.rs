@@ -0,0 +1,203 @@
+//! بث التحديثات الحية للوحات التحكم
+//!
+//! يجمع المركز (`LiveHub`) أحداث العناصر وحالة المصانع والإنذارات وتقارير
+//! الجودة في قناة بث واحدة يشترك فيها مسار SSE في الخادم، فتتحدث اللوحات
+//! دون استطلاع دوري.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tokio::sync::broadcast;
+use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::QualityReport;
+use crate::mhos::alerts::AlertEvent;
+use crate::mhos::dashboard::{Dashboard, DashboardWidget};
+use crate::mhos::widgets::WidgetContext;
+
+/// سعة قناة البث (المشترك المتأخر يفقد الأقدم ويُبلَّغ بعددها)
+const CHANNEL_CAPACITY: usize = 256;
+
+/// فترة فحص العناصر المستحقة وتغيرات المصانع
+const TICK: Duration = Duration::from_secs(1);
+
+/// حدث مبثوث إلى اللوحات
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    /// بيانات عنصر بعد تحديثه حسب `refresh_interval`
+    Widget { widget: DashboardWidget },
+    /// تغير حالة مصنع أو عداداته
+    FactoryStatus(FactoryStatusUpdate),
+    /// فتح إنذار أو تصعيده أو حله
+    Alert(AlertEvent),
+    /// تقرير جودة جديد
+    QualityReport { report: Box<QualityReport> },
+}
+
+impl LiveEvent {
+    /// اسم الحدث في SSE (يطابق `type` في JSON)
+    pub fn topic(&self) -> &'static str {
+        match self {
+            LiveEvent::Widget { .. } => "widget",
+            LiveEvent::FactoryStatus(_) => "factory_status",
+            LiveEvent::Alert(_) => "alert",
+            LiveEvent::QualityReport { .. } => "quality_report",
+        }
+    }
+}
+
+/// حالة مصنع كما تراها اللوحة
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FactoryStatusUpdate {
+    pub factory_type: String,
+    pub name: String,
+    pub status: String,
+    pub total_requests: u64,
+    pub failed_requests: u64,
+    pub queue_size: usize,
+}
+
+/// مركز البث المشترك بين المراقب ومعالجات API ومسار SSE
+pub struct LiveHub {
+    sender: broadcast::Sender<LiveEvent>,
+    /// آخر بيانات لكل عنصر لإرسالها فور الاشتراك
+    widgets: RwLock<BTreeMap<String, DashboardWidget>>,
+    /// آخر حالة معروفة لكل مصنع
+    factories: RwLock<BTreeMap<String, FactoryStatusUpdate>>,
+}
+
+impl LiveHub {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Self {
+            sender,
+            widgets: RwLock::new(BTreeMap::new()),
+            factories: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// بث حدث إلى المشتركين الحاليين (لا شيء إن لم يوجد مشترك)
+    pub fn publish(&self, event: LiveEvent) {
+        match &event {
+            LiveEvent::Widget { widget } => {
+                if let Ok(mut widgets) = self.widgets.write() {
+                    widgets.insert(widget.id.clone(), widget.clone());
+                }
+            }
+            LiveEvent::FactoryStatus(update) => {
+                if let Ok(mut factories) = self.factories.write() {
+                    factories.insert(update.factory_type.clone(), update.clone());
+                }
+            }
+            _ => {}
+        }
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<LiveEvent> {
+        self.sender.subscribe()
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// الحالة الحالية للعناصر والمصانع لمشترك جديد
+    pub fn current_state(&self) -> Vec<LiveEvent> {
+        let mut events: Vec<LiveEvent> = self.widgets.read()
+            .map(|w| w.values().cloned().map(|widget| LiveEvent::Widget { widget }).collect())
+            .unwrap_or_default();
+        if let Ok(factories) = self.factories.read() {
+            events.extend(factories.values().cloned().map(LiveEvent::FactoryStatus));
+        }
+        events
+    }
+
+    /// مقارنة حالة المصانع بآخر حالة مبثوثة وبث ما تغير
+    pub fn publish_factory_changes(&self, factory_manager: &FactoryManager) -> usize {
+        let metrics = factory_manager.factory_metrics();
+        let updates: Vec<FactoryStatusUpdate> = factory_manager.list_factories().iter().map(|factory| {
+            let factory_type = factory.factory_type().to_string();
+            let stats = metrics.get(&factory_type);
+            FactoryStatusUpdate {
+                name: factory.name().to_string(),
+                status: factory.status(),
+                total_requests: stats.map(|m| m.total_requests).unwrap_or(0),
+                failed_requests: stats.map(|m| m.failed_requests).unwrap_or(0),
+                queue_size: stats.map(|m| m.current_queue_size).unwrap_or(0),
+                factory_type,
+            }
+        }).collect();
+
+        let changed: Vec<FactoryStatusUpdate> = {
+            let known = match self.factories.read() {
+                Ok(known) => known,
+                Err(_) => return 0,
+            };
+            updates.into_iter()
+                .filter(|update| known.get(&update.factory_type) != Some(update))
+                .collect()
+        };
+
+        let count = changed.len();
+        for update in changed {
+            self.publish(LiveEvent::FactoryStatus(update));
+        }
+        count
+    }
+
+    /// تشغيل الناشر الدوري: العناصر حسب فترات تحديثها وتغيرات المصانع كل ثانية
+    pub async fn run(self: Arc<Self>, context: WidgetContext) {
+        let factory_manager = context.factory_manager.clone();
+        let mut dashboard = Dashboard::new(factory_manager.clone()).with_context(context);
+        let mut last_refresh: HashMap<String, Instant> = HashMap::new();
+        let mut ticker = tokio::time::interval(TICK);
+
+        loop {
+            ticker.tick().await;
+            self.publish_factory_changes(&factory_manager);
+
+            let due: Vec<String> = dashboard.widgets()
+                .filter(|w| last_refresh.get(&w.id)
+                    .map(|at| at.elapsed() >= Duration::from_secs(w.refresh_interval.max(1)))
+                    .unwrap_or(true))
+                .map(|w| w.id.clone())
+                .collect();
+
+            for widget_id in due {
+                dashboard.update_widget_data(&widget_id).await;
+                if let Some(widget) = dashboard.widget(&widget_id) {
+                    self.publish(LiveEvent::Widget { widget: widget.clone() });
+                }
+                last_refresh.insert(widget_id, Instant::now());
+            }
+        }
+    }
+}
+
+impl Default for LiveHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_factory_changes_are_published_once() {
+        let hub = LiveHub::new();
+        let mut receiver = hub.subscribe();
+        let factory_manager = FactoryManager::new();
+
+        let first = hub.publish_factory_changes(&factory_manager);
+        assert_eq!(first, factory_manager.list_factories().len());
+        assert_eq!(receiver.recv().await.unwrap().topic(), "factory_status");
+
+        // لا تغيير = لا أحداث جديدة
+        assert_eq!(hub.publish_factory_changes(&factory_manager), 0);
+        assert_eq!(hub.current_state().len(), first);
+    }
+}
@@ -0,0 +1,352 @@
+//! سجل المقاييس: حلقة محدودة للعينات الخام مع تجميعات مصغّرة (1د/5د/1س)
+
+use std::collections::VecDeque;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::Serialize;
+use crate::mhos::monitor::PerformanceMetrics;
+
+/// دقة التجميع
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RollupResolution {
+    OneMinute,
+    FiveMinutes,
+    OneHour,
+}
+
+impl RollupResolution {
+    pub const ALL: [RollupResolution; 3] = [
+        RollupResolution::OneMinute,
+        RollupResolution::FiveMinutes,
+        RollupResolution::OneHour,
+    ];
+
+    /// طول الفترة بالثواني
+    pub fn seconds(&self) -> i64 {
+        match self {
+            RollupResolution::OneMinute => 60,
+            RollupResolution::FiveMinutes => 300,
+            RollupResolution::OneHour => 3600,
+        }
+    }
+
+    /// عدد الفترات المحفوظة (3 ساعات، يوم، 30 يوماً)
+    pub fn retention(&self) -> usize {
+        match self {
+            RollupResolution::OneMinute => 180,
+            RollupResolution::FiveMinutes => 288,
+            RollupResolution::OneHour => 720,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RollupResolution::OneMinute => "1m",
+            RollupResolution::FiveMinutes => "5m",
+            RollupResolution::OneHour => "1h",
+        }
+    }
+
+    fn bucket_start(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let seconds = self.seconds();
+        let start = timestamp.timestamp().div_euclid(seconds) * seconds;
+        Utc.timestamp_opt(start, 0).single().unwrap_or(timestamp)
+    }
+}
+
+/// فترة مجمّعة من المقاييس
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsRollup {
+    pub start: DateTime<Utc>,
+    pub resolution: RollupResolution,
+    pub samples: usize,
+    pub avg_cpu_usage: f32,
+    pub max_cpu_usage: f32,
+    pub avg_memory_usage: f32,
+    pub max_memory_usage: f32,
+    pub avg_disk_usage: f32,
+    pub avg_network_usage: f32,
+    pub avg_response_time: f32,
+    pub min_response_time: f32,
+    pub max_response_time: f32,
+    pub avg_throughput: f32,
+    pub avg_overall_score: f32,
+    pub max_process_memory_bytes: u64,
+    pub max_process_threads: u32,
+}
+
+impl MetricsRollup {
+    fn new(resolution: RollupResolution, metrics: &PerformanceMetrics) -> Self {
+        Self {
+            start: resolution.bucket_start(metrics.timestamp),
+            resolution,
+            samples: 1,
+            avg_cpu_usage: metrics.cpu_usage,
+            max_cpu_usage: metrics.cpu_usage,
+            avg_memory_usage: metrics.memory_usage,
+            max_memory_usage: metrics.memory_usage,
+            avg_disk_usage: metrics.disk_usage,
+            avg_network_usage: metrics.network_usage,
+            avg_response_time: metrics.response_time,
+            min_response_time: metrics.response_time,
+            max_response_time: metrics.response_time,
+            avg_throughput: metrics.throughput,
+            avg_overall_score: metrics.overall_score,
+            max_process_memory_bytes: metrics.process_memory_bytes,
+            max_process_threads: metrics.process_threads,
+        }
+    }
+
+    /// دمج عينة جديدة بمتوسط تراكمي
+    fn add(&mut self, metrics: &PerformanceMetrics) {
+        self.samples += 1;
+        let n = self.samples as f32;
+        let update = |avg: &mut f32, value: f32| *avg += (value - *avg) / n;
+
+        update(&mut self.avg_cpu_usage, metrics.cpu_usage);
+        update(&mut self.avg_memory_usage, metrics.memory_usage);
+        update(&mut self.avg_disk_usage, metrics.disk_usage);
+        update(&mut self.avg_network_usage, metrics.network_usage);
+        update(&mut self.avg_response_time, metrics.response_time);
+        update(&mut self.avg_throughput, metrics.throughput);
+        update(&mut self.avg_overall_score, metrics.overall_score);
+
+        self.max_cpu_usage = self.max_cpu_usage.max(metrics.cpu_usage);
+        self.max_memory_usage = self.max_memory_usage.max(metrics.memory_usage);
+        self.min_response_time = self.min_response_time.min(metrics.response_time);
+        self.max_response_time = self.max_response_time.max(metrics.response_time);
+        self.max_process_memory_bytes = self.max_process_memory_bytes.max(metrics.process_memory_bytes);
+        self.max_process_threads = self.max_process_threads.max(metrics.process_threads);
+    }
+
+    pub fn end(&self) -> DateTime<Utc> {
+        self.start + Duration::seconds(self.resolution.seconds())
+    }
+
+    /// تمثيل الفترة كعينة واحدة (بالمتوسطات)
+    pub fn to_metrics(&self) -> PerformanceMetrics {
+        PerformanceMetrics {
+            cpu_usage: self.avg_cpu_usage,
+            memory_usage: self.avg_memory_usage,
+            disk_usage: self.avg_disk_usage,
+            network_usage: self.avg_network_usage,
+            response_time: self.avg_response_time,
+            throughput: self.avg_throughput,
+            cpu_efficiency: 1.0 - self.avg_cpu_usage,
+            memory_efficiency: 1.0 - self.avg_memory_usage,
+            overall_score: self.avg_overall_score,
+            process_memory_bytes: self.max_process_memory_bytes,
+            process_threads: self.max_process_threads,
+            timestamp: self.start,
+        }
+    }
+}
+
+/// سجل المقاييس المحدود
+#[derive(Debug, Clone)]
+pub struct MetricsHistory {
+    max_samples: usize,
+    samples: VecDeque<PerformanceMetrics>,
+    rollups: Vec<VecDeque<MetricsRollup>>,
+}
+
+impl MetricsHistory {
+    /// سجل يحتفظ بآخر `max_samples` عينة خام
+    pub fn new(max_samples: usize) -> Self {
+        Self {
+            max_samples: max_samples.max(1),
+            samples: VecDeque::new(),
+            rollups: RollupResolution::ALL.iter().map(|_| VecDeque::new()).collect(),
+        }
+    }
+
+    /// إضافة عينة للحلقة والتجميعات
+    pub fn record(&mut self, metrics: PerformanceMetrics) {
+        for (resolution, series) in RollupResolution::ALL.iter().zip(self.rollups.iter_mut()) {
+            let start = resolution.bucket_start(metrics.timestamp);
+            match series.back_mut() {
+                Some(last) if last.start == start => last.add(&metrics),
+                _ => {
+                    series.push_back(MetricsRollup::new(*resolution, &metrics));
+                    while series.len() > resolution.retention() {
+                        series.pop_front();
+                    }
+                }
+            }
+        }
+
+        self.samples.push_back(metrics);
+        while self.samples.len() > self.max_samples {
+            self.samples.pop_front();
+        }
+    }
+
+    /// تغيير سعة الحلقة (مع قص العينات الزائدة)
+    pub fn set_max_samples(&mut self, max_samples: usize) {
+        self.max_samples = max_samples.max(1);
+        while self.samples.len() > self.max_samples {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<&PerformanceMetrics> {
+        self.samples.back()
+    }
+
+    /// آخر `count` عينة خام (الأقدم أولاً)
+    pub fn recent(&self, count: usize) -> Vec<PerformanceMetrics> {
+        self.samples.iter().skip(self.samples.len().saturating_sub(count)).cloned().collect()
+    }
+
+    /// العينات الخام منذ وقت معين
+    pub fn samples_since(&self, since: DateTime<Utc>) -> Vec<PerformanceMetrics> {
+        self.samples.iter().filter(|m| m.timestamp >= since).cloned().collect()
+    }
+
+    /// التجميعات بدقة معينة منذ وقت معين
+    pub fn rollups(&self, resolution: RollupResolution, since: DateTime<Utc>) -> Vec<MetricsRollup> {
+        let index = RollupResolution::ALL.iter().position(|r| *r == resolution).unwrap_or(0);
+        self.rollups[index].iter().filter(|r| r.end() > since).cloned().collect()
+    }
+
+    /// أدق سلسلة تغطي الفترة: العينات الخام إن كانت كافية، وإلا أدق تجميع
+    pub fn window(&self, since: DateTime<Utc>) -> HistoryWindow {
+        let covers = |oldest: Option<DateTime<Utc>>| oldest.map(|t| t <= since).unwrap_or(false);
+
+        if covers(self.samples.front().map(|m| m.timestamp)) {
+            return HistoryWindow::Raw(self.samples_since(since));
+        }
+
+        for (resolution, series) in RollupResolution::ALL.iter().zip(self.rollups.iter()) {
+            if covers(series.front().map(|r| r.start)) {
+                return HistoryWindow::Rollup(*resolution, self.rollups(*resolution, since));
+            }
+        }
+
+        // لا يوجد سجل يغطي الفترة كاملة: نستخدم أكبر تغطية متاحة
+        let samples = self.samples_since(since);
+        if samples.len() >= self.rollups[0].iter().filter(|r| r.end() > since).count() {
+            HistoryWindow::Raw(samples)
+        } else {
+            HistoryWindow::Rollup(RollupResolution::OneMinute, self.rollups(RollupResolution::OneMinute, since))
+        }
+    }
+}
+
+/// بيانات فترة التقرير
+#[derive(Debug, Clone)]
+pub enum HistoryWindow {
+    Raw(Vec<PerformanceMetrics>),
+    Rollup(RollupResolution, Vec<MetricsRollup>),
+}
+
+impl HistoryWindow {
+    /// عدد العينات الفعلية التي تمثلها البيانات
+    pub fn sample_count(&self) -> usize {
+        match self {
+            HistoryWindow::Raw(samples) => samples.len(),
+            HistoryWindow::Rollup(_, rollups) => rollups.iter().map(|r| r.samples).sum(),
+        }
+    }
+
+    /// أقدم وقت في البيانات
+    pub fn first_timestamp(&self) -> Option<DateTime<Utc>> {
+        match self {
+            HistoryWindow::Raw(samples) => samples.first().map(|m| m.timestamp),
+            HistoryWindow::Rollup(_, rollups) => rollups.first().map(|r| r.start),
+        }
+    }
+
+    /// المقاييس كسلسلة (الخام أو المتوسطات)
+    pub fn metrics(&self) -> Vec<PerformanceMetrics> {
+        match self {
+            HistoryWindow::Raw(samples) => samples.clone(),
+            HistoryWindow::Rollup(_, rollups) => rollups.iter().map(|r| r.to_metrics()).collect(),
+        }
+    }
+
+    /// أعلى استخدام للمعالج (القيم القصوى للتجميعات لا المتوسطات)
+    pub fn max_cpu_usage(&self) -> f32 {
+        match self {
+            HistoryWindow::Raw(samples) => samples.iter().map(|m| m.cpu_usage).fold(0.0, f32::max),
+            HistoryWindow::Rollup(_, rollups) => rollups.iter().map(|r| r.max_cpu_usage).fold(0.0, f32::max),
+        }
+    }
+
+    /// أقل وقت استجابة (بين العينات التي سجلت طلبات)
+    pub fn min_response_time(&self) -> f32 {
+        let values: Vec<f32> = match self {
+            HistoryWindow::Raw(samples) => samples.iter().map(|m| m.response_time).collect(),
+            HistoryWindow::Rollup(_, rollups) => rollups.iter().map(|r| r.min_response_time).collect(),
+        };
+        values.into_iter().filter(|v| *v > 0.0).reduce(f32::min).unwrap_or(0.0)
+    }
+
+    /// متوسط الأداء موزوناً بعدد العينات
+    pub fn avg_performance(&self) -> f32 {
+        let (sum, count) = match self {
+            HistoryWindow::Raw(samples) => (samples.iter().map(|m| m.overall_score).sum::<f32>(), samples.len()),
+            HistoryWindow::Rollup(_, rollups) => (
+                rollups.iter().map(|r| r.avg_overall_score * r.samples as f32).sum::<f32>(),
+                rollups.iter().map(|r| r.samples).sum(),
+            ),
+        };
+        if count == 0 { 0.0 } else { sum / count as f32 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(timestamp: DateTime<Utc>, cpu: f32) -> PerformanceMetrics {
+        PerformanceMetrics {
+            cpu_usage: cpu,
+            memory_usage: 0.5,
+            disk_usage: 0.2,
+            network_usage: 0.0,
+            response_time: 100.0,
+            throughput: 2.0,
+            cpu_efficiency: 1.0 - cpu,
+            memory_efficiency: 0.5,
+            overall_score: 0.8,
+            process_memory_bytes: 1024,
+            process_threads: 4,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer_and_rollups() {
+        let mut history = MetricsHistory::new(10);
+        let start = Utc.timestamp_opt(1_700_000_000 - 1_700_000_000 % 3600, 0).unwrap();
+
+        // عينة كل 30 ثانية لمدة ساعتين
+        for i in 0..240 {
+            let cpu = if i == 5 { 0.95 } else { 0.2 };
+            history.record(sample(start + Duration::seconds(i * 30), cpu));
+        }
+
+        assert_eq!(history.len(), 10);
+        let minutes = history.rollups(RollupResolution::OneMinute, start);
+        assert_eq!(minutes.len(), 120);
+        assert_eq!(minutes[0].samples, 2);
+        assert_eq!(minutes[2].max_cpu_usage, 0.95);
+        assert_eq!(history.rollups(RollupResolution::FiveMinutes, start).len(), 24);
+        assert_eq!(history.rollups(RollupResolution::OneHour, start).len(), 2);
+
+        // الحلقة لا تغطي الساعتين فيُستخدم تجميع الدقيقة
+        let window = history.window(start);
+        assert!(matches!(window, HistoryWindow::Rollup(RollupResolution::OneMinute, _)));
+        assert_eq!(window.sample_count(), 240);
+        assert_eq!(window.max_cpu_usage(), 0.95);
+        assert!((window.avg_performance() - 0.8).abs() < 1e-4);
+    }
+}
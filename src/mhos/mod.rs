@@ -1,46 +1,53 @@
 pub mod dashboard;
 pub mod quality_gates;
 pub mod optimizer;
+pub mod optimization_history;
 pub mod monitor;
+pub mod host;
+pub mod metrics_history;
+pub mod alerts;
+pub mod notify;
+pub mod live;
+pub mod views;
+pub mod layouts;
+pub mod widgets;
+pub mod recommendations;
+pub mod knowledge;
+pub mod config;
+pub mod backup;
+pub mod cron;
+pub mod scheduler;
 
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
-use std::collections::HashMap;
+use serde::Serialize;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
 use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::QualityManager;
+use knowledge::KnowledgeBase;
+
+pub use config::{MhosConfig, MHOS_CONFIG_FILE};
 
 /// نظام MH-OS v2.2 الأساسي
 pub struct MhosSystem {
     version: String,
     factory_manager: Arc<FactoryManager>,
     dashboard: dashboard::Dashboard,
-    quality_gates: quality_gates::QualityGates,
-    optimizer: optimizer::Optimizer,
-    monitor: monitor::SystemMonitor,
-    config: MhosConfig,
-}
-
-/// تكوين MH-OS
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MhosConfig {
-    pub auto_optimize: bool,
-    pub quality_threshold: f32,
-    pub monitoring_interval: u64,
-    pub alert_enabled: bool,
-    pub backup_enabled: bool,
-    pub ai_assistant: bool,
-}
-
-impl Default for MhosConfig {
-    fn default() -> Self {
-        Self {
-            auto_optimize: true,
-            quality_threshold: 0.85,
-            monitoring_interval: 30,
-            alert_enabled: true,
-            backup_enabled: true,
-            ai_assistant: true,
-        }
-    }
+    quality_gates: Arc<quality_gates::QualityGates>,
+    optimizer: Arc<optimizer::Optimizer>,
+    monitor: Arc<monitor::SystemMonitor>,
+    recommendations: Arc<recommendations::RecommendationEngine>,
+    knowledge: Arc<RwLock<KnowledgeBase>>,
+    quality: Option<Arc<RwLock<QualityManager>>>,
+    /// التكوين الحالي؛ تشترك فيه المهام المجدولة وتتفاعل مع تغييره
+    config: watch::Sender<MhosConfig>,
+    config_path: Option<PathBuf>,
+    schedules: Mutex<Vec<JoinHandle<()>>>,
+    scheduler: Arc<scheduler::Scheduler>,
 }
 
 /// حالة النظام
@@ -52,42 +59,219 @@ pub struct SystemStatus {
     pub efficiency: f32,
     pub active_factories: usize,
     pub issues: Vec<String>,
-    pub recommendations: Vec<String>,
+    pub recommendations: Vec<recommendations::Recommendation>,
     pub last_update: chrono::DateTime<chrono::Utc>,
 }
 
 impl MhosSystem {
     /// إنشاء مثيل جديد لـ MH-OS
     pub fn new(factory_manager: Arc<FactoryManager>) -> Self {
-        Self {
+        let knowledge = Arc::new(RwLock::new(KnowledgeBase::with_defaults()));
+        let (config, _) = watch::channel(MhosConfig::default());
+        let recommendations = Arc::new(recommendations::RecommendationEngine::new());
+        let monitor = Arc::new(monitor::SystemMonitor::new()
+            .with_knowledge(knowledge.clone())
+            .with_recommendations(recommendations.clone()));
+        let optimizer = Arc::new(optimizer::Optimizer::new().with_knowledge(knowledge.clone()));
+        let quality_gates = Arc::new(quality_gates::QualityGates::new());
+        factory_manager.observe_products(quality_gates.clone());
+        let executor = scheduler::MhosJobExecutor::new(
+            optimizer::MetricSources::new(factory_manager.clone(), monitor.clone()),
+            optimizer.clone(),
+            quality_gates.clone(),
+            config.subscribe(),
+        );
+        
+        let system = Self {
             version: "2.2".to_string(),
             factory_manager: factory_manager.clone(),
             dashboard: dashboard::Dashboard::new(factory_manager.clone()),
-            quality_gates: quality_gates::QualityGates::new(),
-            optimizer: optimizer::Optimizer::new(),
-            monitor: monitor::SystemMonitor::new(),
-            config: MhosConfig::default(),
+            quality_gates,
+            optimizer,
+            monitor,
+            recommendations,
+            knowledge,
+            quality: None,
+            config,
+            config_path: None,
+            schedules: Mutex::new(Vec::new()),
+            scheduler: Arc::new(scheduler::Scheduler::new(scheduler::JobStore::in_memory(), Arc::new(executor))),
+        };
+        system.apply_config(&system.config());
+        system
+    }
+    
+    /// استخدام مراقب مشترك (مثل مراقب خادم API)
+    pub fn with_monitor(mut self, monitor: Arc<monitor::SystemMonitor>) -> Self {
+        self.monitor = monitor;
+        self.apply_config(&self.config());
+        self
+    }
+    
+    pub fn with_optimizer(mut self, optimizer: Arc<optimizer::Optimizer>) -> Self {
+        self.optimizer = optimizer;
+        self.apply_config(&self.config());
+        self
+    }
+    
+    pub fn with_recommendations(mut self, recommendations: Arc<recommendations::RecommendationEngine>) -> Self {
+        self.recommendations = recommendations;
+        self
+    }
+    
+    pub fn with_knowledge(mut self, knowledge: Arc<RwLock<KnowledgeBase>>) -> Self {
+        self.knowledge = knowledge;
+        self
+    }
+    
+    /// قياس نتائج الجودة في التحسين المجدول
+    pub fn with_quality(mut self, quality: Arc<RwLock<QualityManager>>) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+    
+    /// استخدام تكوين محدد (يُتجاهل مع تسجيل الخطأ إن لم يكن صالحاً)
+    pub fn with_config(self, config: MhosConfig) -> Self {
+        match config.validate() {
+            Ok(()) => {
+                self.apply_config(&config);
+                self.config.send_replace(config);
+            }
+            Err(e) => log::error!("❌ تكوين MH-OS غير صالح: {}", e),
         }
+        self
+    }
+    
+    /// حفظ تحديثات التكوين في ملف
+    pub fn with_config_path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.config_path = Some(path.into());
+        self
     }
     
-    /// بدء تشغيل النظام
+    /// مخزن المهام المجدولة (مثل ملف المهام المشترك مع سطر الأوامر)
+    pub fn with_job_store(mut self, store: scheduler::JobStore) -> Self {
+        self.scheduler = Arc::new(scheduler::Scheduler::new(store, Arc::new(self.job_executor())));
+        self
+    }
+    
+    /// بدء تشغيل النظام: المراقبة والمهام المجدولة حسب التكوين
     pub async fn start(&self) {
         log::info!("🚀 بدء تشغيل MH-OS v{}", self.version);
         
+        self.apply_config(&self.config());
+        
         // بدء المراقبة
         self.monitor.start_monitoring(self.factory_manager.clone()).await;
         
         // التحقق من الجودة
         self.quality_gates.check_all(self.factory_manager.clone()).await;
         
-        // تحسين النظام
-        if self.config.auto_optimize {
-            self.optimizer.optimize(self.factory_manager.clone()).await;
+        // التحسين التلقائي والنسخ الاحتياطي الدوريان
+        let optimizer = self.optimizer.clone();
+        let sources = self.metric_sources();
+        let optimization = tokio::spawn(Self::schedule(
+            self.config.subscribe(),
+            |config| config.auto_optimize.then_some(Duration::from_secs(config.optimization_interval)),
+            move || {
+                let optimizer = optimizer.clone();
+                let sources = sources.clone();
+                async move {
+                    optimizer.optimize(&sources).await;
+                }
+            },
+        ));
+        
+        let config = self.config.subscribe();
+        let data_dir = crate::core::config::data_dir();
+        let backups = tokio::spawn(Self::schedule(
+            self.config.subscribe(),
+            |config| config.backup_enabled.then_some(Duration::from_secs(config.backup_interval)),
+            move || {
+                let retention = config.borrow().backup_retention;
+                let data_dir = data_dir.clone();
+                async move {
+                    let result = tokio::task::spawn_blocking(move || backup::create_backup(&data_dir, retention)).await
+                        .map_err(|e| e.to_string())
+                        .and_then(|r| r.map_err(|e| e.to_string()));
+                    if let Err(e) = result {
+                        log::error!("❌ فشل النسخ الاحتياطي: {}", e);
+                    }
+                }
+            },
+        ));
+        
+        let mut schedules = match self.schedules.lock() {
+            Ok(schedules) => schedules,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for task in schedules.drain(..) {
+            task.abort();
         }
+        schedules.extend([optimization, backups]);
+        
+        // المهام المجدولة بتعابير cron على الأنظمة الحالية
+        self.scheduler.set_executor(Arc::new(self.job_executor()));
+        schedules.push(tokio::spawn(self.scheduler.clone().run()));
         
         log::info!("✅ MH-OS جاهز للعمل");
     }
     
+    /// إيقاف المراقبة والمهام المجدولة
+    pub fn stop(&self) {
+        self.monitor.stop_monitoring();
+        let mut schedules = match self.schedules.lock() {
+            Ok(schedules) => schedules,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for task in schedules.drain(..) {
+            task.abort();
+        }
+    }
+    
+    /// تنفيذ مهمة كل فترة يحددها التكوين (`None` = معطلة)؛ عند تغيير التكوين
+    /// يُعاد حساب الموعد التالي من آخر تنفيذ
+    async fn schedule<F, Fut>(
+        mut config: watch::Receiver<MhosConfig>,
+        period: fn(&MhosConfig) -> Option<Duration>,
+        mut job: F,
+    ) where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        let mut last_run = Instant::now();
+        loop {
+            let next = period(&config.borrow_and_update()).map(|period| last_run + period);
+            let due = async {
+                match next {
+                    Some(at) => tokio::time::sleep_until(at).await,
+                    None => std::future::pending().await,
+                }
+            };
+            
+            tokio::select! {
+                _ = due => {
+                    job().await;
+                    last_run = Instant::now();
+                }
+                changed = config.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    
+    /// تطبيق التكوين على الأنظمة الفرعية
+    fn apply_config(&self, config: &MhosConfig) {
+        self.monitor.set_monitoring_interval(config.monitoring_interval);
+        self.monitor.set_alert_rules(config.alert_rules.clone());
+        self.monitor.set_alert_dispatch(config.alert_enabled);
+        self.monitor.set_learning_enabled(config.ai_assistant);
+        self.optimizer.set_learning_enabled(config.ai_assistant);
+        self.quality_gates.set_quality_threshold(config.quality_threshold);
+    }
+    
     /// الحصول على حالة النظام
     pub async fn get_status(&self) -> SystemStatus {
         let factories = self.factory_manager.list_factories();
@@ -106,9 +290,9 @@ impl MhosSystem {
         let performance = self.monitor.get_performance_metrics().await;
         let efficiency = (performance.cpu_efficiency + performance.memory_efficiency) / 2.0;
         
-        let overall_health = if avg_quality >= self.config.quality_threshold && efficiency > 0.8 {
+        let overall_health = if avg_quality >= self.quality_gates.quality_threshold() && efficiency > 0.8 {
             "excellent".to_string()
-        } else if avg_quality >= 0.7 && efficiency > 0.6 {
+        } else if avg_quality >= self.quality_gates.warning_threshold() && efficiency > 0.6 {
             "good".to_string()
         } else {
             "needs_attention".to_string()
@@ -126,24 +310,31 @@ impl MhosSystem {
         }
     }
     
-    /// الحصول على التوصيات
-    async fn get_recommendations(&self) -> Vec<String> {
-        let mut recommendations = Vec::new();
-        let status = self.get_status().await;
-        
-        if status.quality_score < self.config.quality_threshold {
-            recommendations.push("تحسين جودة المصانع".to_string());
-        }
-        
-        if status.efficiency < 0.7 {
-            recommendations.push("تحسين كفاءة النظام".to_string());
-        }
-        
-        if status.active_factories == 0 {
-            recommendations.push("إنشاء مصانع جديدة".to_string());
+    /// التوصيات الحالية من محرك القواعد على مقاييس الساعة الأخيرة (دون تكرار بين الاستدعاءات)
+    pub async fn get_recommendations(&self) -> Vec<recommendations::Recommendation> {
+        self.recommendations
+            .evaluate_sources(&self.metric_sources(), chrono::Duration::hours(1))
+            .await
+    }
+    
+    /// رفض توصية حتى انقضاء فترة التهدئة
+    pub fn dismiss_recommendation(&self, id: &str) -> bool {
+        self.recommendations.dismiss(id)
+    }
+    
+    /// نصيحة المساعد لموضوع (لا شيء إذا كان المساعد معطلاً)
+    pub fn get_advice(&self, topic: &str) -> Option<String> {
+        if !self.assistant_enabled() {
+            return None;
         }
-        
-        recommendations
+        let knowledge = self.knowledge.read().ok()?;
+        knowledge.find_topic(topic)
+            .map(|entry| entry.content.clone())
+            .or_else(|| knowledge.search(topic, None, 1).into_iter().next().map(|hit| hit.entry.content))
+    }
+    
+    pub fn assistant_enabled(&self) -> bool {
+        self.config.borrow().ai_assistant
     }
     
     /// الحصول على لوحة التحكم
@@ -158,13 +349,51 @@ impl MhosSystem {
     
     /// تنفيذ تحسين النظام
     pub async fn run_optimization(&self) -> optimizer::OptimizationResult {
-        self.optimizer.optimize(self.factory_manager.clone()).await
+        self.optimizer.optimize(&self.metric_sources()).await
+    }
+    
+    /// مصادر القياس الحية للمحسن
+    fn metric_sources(&self) -> optimizer::MetricSources {
+        let sources = optimizer::MetricSources::new(self.factory_manager.clone(), self.monitor.clone());
+        match &self.quality {
+            Some(quality) => sources.with_quality(quality.clone()),
+            None => sources,
+        }
+    }
+    
+    /// منفذ المهام المجدولة على أنظمة MH-OS الحالية
+    fn job_executor(&self) -> scheduler::MhosJobExecutor {
+        scheduler::MhosJobExecutor::new(
+            self.metric_sources(),
+            self.optimizer.clone(),
+            self.quality_gates.clone(),
+            self.config.subscribe(),
+        )
     }
     
-    /// تحديث التكوين
-    pub fn update_config(&mut self, config: MhosConfig) {
-        self.config = config;
+    /// مجدول المهام
+    pub fn scheduler(&self) -> Arc<scheduler::Scheduler> {
+        self.scheduler.clone()
+    }
+    
+    /// التكوين الحالي
+    pub fn config(&self) -> MhosConfig {
+        self.config.borrow().clone()
+    }
+    
+    /// تحديث التكوين بعد التحقق منه: يُحفظ ثم يُطبق فوراً على الأنظمة الفرعية والمهام المجدولة
+    pub fn update_config(&self, config: MhosConfig) -> Result<MhosConfig, String> {
+        config.validate()?;
+        
+        if let Some(path) = &self.config_path {
+            config.save(path)
+                .map_err(|e| format!("تعذر حفظ تكوين MH-OS في {}: {}", path.display(), e))?;
+        }
+        
+        self.apply_config(&config);
+        self.config.send_replace(config.clone());
         log::info!("🔄 تم تحديث تكوين MH-OS");
+        Ok(config)
     }
     
     /// الحصول على نسخة MH-OS
@@ -176,6 +405,9 @@ impl MhosSystem {
 /// وحدة مساعدة لـ MH-OS
 pub mod utils {
     use super::*;
+    use super::knowledge::{KnowledgeBase, KnowledgeDraft, SearchHit};
+    
+    pub mod timeseries;
     
     /// تحليل البيانات الإحصائية
     pub fn analyze_statistics(data: &[f32]) -> Statistics {
@@ -241,30 +473,99 @@ pub mod utils {
     
     /// المساعد الذكي
     pub struct AIAssistant {
-        knowledge_base: HashMap<String, String>,
+        knowledge_base: KnowledgeBase,
     }
     
     impl AIAssistant {
+        /// مساعد بالمعرفة الأولية في الذاكرة
         pub fn new() -> Self {
-            let mut knowledge_base = HashMap::new();
-            
-            // قاعدة المعرفة الأولية
-            knowledge_base.insert("optimization".to_string(), 
-                "لتحسين الأداء: 1. تحسين الخوارزميات 2. تحسين الذاكرة 3. موازنة الأحمال".to_string());
-            knowledge_base.insert("quality".to_string(),
-                "لتحسين الجودة: 1. فحص المدخلات 2. تحسين المعالجة 3. اختبار المخرجات".to_string());
-            knowledge_base.insert("troubleshooting".to_string(),
-                "لحل المشاكل: 1. التحقق من السجلات 2. اختبار المكونات 3. استعادة النسخ الاحتياطية".to_string());
-            
-            Self { knowledge_base }
+            Self { knowledge_base: KnowledgeBase::with_defaults() }
+        }
+        
+        /// مساعد على قاعدة المعرفة المحفوظة في ملف
+        pub fn open<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+            Ok(Self { knowledge_base: KnowledgeBase::open(path)? })
         }
         
+        /// النصيحة لموضوع بعينه، وإلا أقرب مدخل في البحث
         pub fn get_advice(&self, topic: &str) -> Option<String> {
-            self.knowledge_base.get(topic).cloned()
+            self.knowledge_base.find_topic(topic)
+                .map(|entry| entry.content.clone())
+                .or_else(|| self.search(topic, None, 1).into_iter().next().map(|hit| hit.entry.content))
+        }
+        
+        /// البحث في قاعدة المعرفة مرتباً بالصلة
+        pub fn search(&self, query: &str, factory_type: Option<&str>, limit: usize) -> Vec<SearchHit> {
+            self.knowledge_base.search(query, factory_type, limit)
         }
         
+        /// إضافة نصيحة أو تحديث نصيحة الموضوع نفسه
         pub fn learn(&mut self, topic: String, advice: String) {
-            self.knowledge_base.insert(topic, advice);
+            let existing = self.knowledge_base.find_topic(&topic).cloned();
+            let result = match existing {
+                Some(entry) => {
+                    let draft = KnowledgeDraft {
+                        topic: entry.topic,
+                        content: advice,
+                        tags: entry.tags,
+                        factory_types: entry.factory_types,
+                    };
+                    self.knowledge_base.update(&entry.id, draft)
+                }
+                None => self.knowledge_base.add(KnowledgeDraft::new(topic, advice)),
+            };
+            if let Err(e) = result {
+                log::error!("❌ {}", e);
+            }
+        }
+        
+        pub fn knowledge_base(&self) -> &KnowledgeBase {
+            &self.knowledge_base
         }
+        
+        pub fn knowledge_base_mut(&mut self) -> &mut KnowledgeBase {
+            &mut self.knowledge_base
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_update_config_changes_gate_outcome() {
+        let system = MhosSystem::new(Arc::new(FactoryManager::new()));
+        let performance = |report: &quality_gates::QualityReport| {
+            report.results.iter().find(|r| r.gate_id == "performance_test").unwrap().passed
+        };
+
+        let report = system.get_quality_gates().check_all(system.factory_manager.clone()).await;
+        assert!(performance(&report));
+
+        let config = MhosConfig { quality_threshold: 0.95, ..system.config() };
+        system.update_config(config).unwrap();
+
+        let report = system.get_quality_gates().check_all(system.factory_manager.clone()).await;
+        assert!(!performance(&report));
+    }
+
+    #[tokio::test]
+    async fn test_manufactured_product_is_scored_by_gates() {
+        let factory_manager = Arc::new(FactoryManager::new());
+        let system = MhosSystem::new(factory_manager.clone());
+        let gate = |report: &quality_gates::QualityReport, id: &str| {
+            report.results.iter().find(|r| r.gate_id == id).unwrap().clone()
+        };
+
+        let report = system.get_quality_gates().check_all(factory_manager.clone()).await;
+        assert!(gate(&report, "security_check").skipped);
+
+        factory_manager.manufacture("education", serde_json::json!("الكسور"), serde_json::json!({})).unwrap();
+        assert_eq!(system.get_quality_gates().artifacts().len(), 1);
+
+        let report = system.get_quality_gates().check_all(factory_manager.clone()).await;
+        assert!(!gate(&report, "content_quality").skipped);
+        assert!(!gate(&report, "security_check").skipped);
     }
 }
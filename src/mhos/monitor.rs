@@ -1,8 +1,26 @@
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 use tokio::time;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use crate::core::factory_manager::FactoryManager;
+use crate::mhos::alerts::{AlertEvent, AlertEventKind, AlertMetric, AlertRule, AlertState, AlertStore, AnomalyRule};
+use crate::mhos::host::HostSampler;
+use crate::mhos::knowledge::KnowledgeBase;
+use crate::mhos::live::{LiveEvent, LiveHub};
+use crate::mhos::metrics_history::{HistoryWindow, MetricsHistory, MetricsRollup, RollupResolution};
+use crate::mhos::notify::Notifier;
+use crate::mhos::optimizer::MetricsSnapshot;
+use crate::mhos::recommendations::{Recommendation, RecommendationEngine, RecommendationInput};
+
+/// نافذة حساب وقت الاستجابة والإنتاجية
+const REQUEST_WINDOW: Duration = Duration::from_secs(60);
+/// الحد الأقصى للطلبات المحفوظة في النافذة
+const MAX_REQUEST_SAMPLES: usize = 10_000;
+/// عدد العينات السابقة التي يُحسب منها النطاق المعتاد لقواعد الشذوذ
+const ANOMALY_BASELINE_SAMPLES: usize = 120;
 
 /// مقاييس الأداء
 #[derive(Debug, Clone, Serialize)]
@@ -16,23 +34,133 @@ pub struct PerformanceMetrics {
     pub cpu_efficiency: f32,
     pub memory_efficiency: f32,
     pub overall_score: f32,
+    pub process_memory_bytes: u64,
+    pub process_threads: u32,
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+/// إحصاءات طلبات API خلال النافذة الحالية
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RequestStats {
+    pub count: usize,
+    pub avg_response_ms: f32,
+    pub p95_response_ms: f32,
+    pub throughput: f32, // طلب في الثانية
+}
+
+/// سجل توقيتات الطلبات الفعلية (يغذيه وسيط التسجيل في API)
+pub struct RequestTimings {
+    started: Instant,
+    window: Duration,
+    samples: Mutex<VecDeque<(Instant, Duration)>>,
+}
+
+impl RequestTimings {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            window,
+            samples: Mutex::new(VecDeque::new()),
+        }
+    }
+    
+    /// تسجيل مدة طلب منتهٍ
+    pub fn record(&self, duration: Duration) {
+        let now = Instant::now();
+        if let Ok(mut samples) = self.samples.lock() {
+            samples.push_back((now, duration));
+            Self::evict(&mut samples, now, self.window);
+        }
+    }
+    
+    /// متوسط وقت الاستجابة والإنتاجية خلال النافذة
+    pub fn stats(&self) -> RequestStats {
+        let now = Instant::now();
+        let mut samples = match self.samples.lock() {
+            Ok(samples) => samples,
+            Err(_) => return RequestStats::default(),
+        };
+        Self::evict(&mut samples, now, self.window);
+        
+        if samples.is_empty() {
+            return RequestStats::default();
+        }
+        
+        let mut durations: Vec<f32> = samples.iter()
+            .map(|(_, d)| d.as_secs_f32() * 1000.0)
+            .collect();
+        durations.sort_by(|a, b| a.total_cmp(b));
+        
+        let count = durations.len();
+        let p95_index = ((count as f32 * 0.95).ceil() as usize).clamp(1, count) - 1;
+        let span = self.window.min(now.duration_since(self.started)).as_secs_f32().max(1.0);
+        
+        RequestStats {
+            count,
+            avg_response_ms: durations.iter().sum::<f32>() / count as f32,
+            p95_response_ms: durations[p95_index],
+            throughput: count as f32 / span,
+        }
+    }
+    
+    fn evict(samples: &mut VecDeque<(Instant, Duration)>, now: Instant, window: Duration) {
+        while let Some((at, _)) = samples.front() {
+            if now.duration_since(*at) > window || samples.len() > MAX_REQUEST_SAMPLES {
+                samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// سجل التوقيتات المشترك على مستوى العملية
+pub fn request_timings() -> &'static RequestTimings {
+    static TIMINGS: OnceLock<RequestTimings> = OnceLock::new();
+    TIMINGS.get_or_init(|| RequestTimings::new(REQUEST_WINDOW))
+}
+
 /// إنذار النظام
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemAlert {
     pub id: String,
     pub severity: AlertSeverity,
     pub title: String,
     pub message: String,
     pub source: String,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub timestamp: chrono::DateTime<chrono::Utc>, // أول ظهور
     pub resolved: bool,
+    #[serde(default)]
+    pub state: AlertState,
+    #[serde(default)]
+    pub metric: Option<AlertMetric>,
+    #[serde(default)]
+    pub value: f32,
+    #[serde(default)]
+    pub threshold: f32,
+    #[serde(default)]
+    pub occurrences: u32,
+    pub last_seen: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub acknowledged_by: Option<String>,
+    #[serde(default)]
+    pub acknowledged_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub silenced_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl SystemAlert {
+    /// هل الإنذار مسكت في وقت معين
+    pub fn is_silenced(&self, at: chrono::DateTime<chrono::Utc>) -> bool {
+        self.silenced_until.map(|until| until > at).unwrap_or(false)
+    }
 }
 
 /// مستوى خطورة الإنذار
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AlertSeverity {
     Info,
     Warning,
@@ -47,7 +175,7 @@ pub struct MonitoringReport {
     pub period_end: chrono::DateTime<chrono::Utc>,
     pub metrics: Vec<PerformanceMetrics>,
     pub alerts: Vec<SystemAlert>,
-    pub recommendations: Vec<String>,
+    pub recommendations: Vec<Recommendation>,
     pub summary: ReportSummary,
 }
 
@@ -61,249 +189,515 @@ pub struct ReportSummary {
     pub uptime_percentage: f32,
 }
 
-/// مراقب النظام
-pub struct SystemMonitor {
-    metrics_history: Vec<PerformanceMetrics>,
-    active_alerts: Vec<SystemAlert>,
-    monitoring_interval: Duration,
-    is_monitoring: bool,
-    max_history_size: usize,
+/// الحالة المشتركة بين المراقب ومهمة المراقبة في الخلفية
+struct MonitorState {
+    sampler: HostSampler,
+    history: RwLock<MetricsHistory>,
+    alerts: RwLock<AlertStore>,
+    notifier: RwLock<Option<Arc<Notifier>>>,
+    live: RwLock<Option<Arc<LiveHub>>>,
+    knowledge: RwLock<Option<Arc<RwLock<KnowledgeBase>>>>,
+    /// إرسال أحداث الإنذارات (تُقيّم الإنذارات وتُحفظ في الحالتين)
+    alert_dispatch: AtomicBool,
+    learning: AtomicBool,
 }
 
-impl SystemMonitor {
-    /// إنشاء مراقب جديد
-    pub fn new() -> Self {
-        Self {
-            metrics_history: Vec::new(),
-            active_alerts: Vec::new(),
-            monitoring_interval: Duration::from_secs(30),
-            is_monitoring: false,
-            max_history_size: 1000,
+impl MonitorState {
+    /// جمع المقاييس من موارد المضيف وتوقيتات الطلبات الفعلية
+    fn collect_metrics(&self) -> PerformanceMetrics {
+        let host = self.sampler.sample();
+        let requests = request_timings().stats();
+        
+        let response_time = requests.avg_response_ms;
+        let cpu_efficiency = 1.0 - host.cpu_usage;
+        let memory_efficiency = 1.0 - host.memory_usage;
+        let overall_score = (cpu_efficiency + memory_efficiency + 
+            (1.0 - response_time / 1000.0).clamp(0.0, 1.0)) / 3.0;
+        
+        PerformanceMetrics {
+            cpu_usage: host.cpu_usage,
+            memory_usage: host.memory_usage,
+            disk_usage: host.disk_usage,
+            network_usage: host.network_usage,
+            response_time,
+            throughput: requests.throughput,
+            cpu_efficiency,
+            memory_efficiency,
+            overall_score,
+            process_memory_bytes: host.process_rss_bytes,
+            process_threads: host.process_threads,
+            timestamp: chrono::Utc::now(),
         }
     }
     
-    /// بدء المراقبة
-    pub async fn start_monitoring(&self, factory_manager: Arc<FactoryManager>) {
-        if self.is_monitoring {
+    /// تسجيل المقاييس في الحلقة والتجميعات
+    fn record_metrics(&self, metrics: PerformanceMetrics) {
+        log::debug!("📊 تم تسجيل مقاييس النظام: CPU {:.1}%, Memory {:.1}%", 
+            metrics.cpu_usage * 100.0, metrics.memory_usage * 100.0);
+        
+        if let Ok(mut history) = self.history.write() {
+            history.record(metrics);
+        }
+    }
+    
+    /// تقييم قواعد الإنذار وتحديث دورة حياة الإنذارات
+    fn check_alerts(&self, metrics: &PerformanceMetrics) -> Vec<AlertEvent> {
+        // خط الأساس للشذوذ: العينات السابقة دون العينة الحالية
+        let baseline: Vec<PerformanceMetrics> = self.history.read()
+            .map(|history| history.recent(ANOMALY_BASELINE_SAMPLES + 1))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|m| m.timestamp < metrics.timestamp)
+            .collect();
+        
+        let mut store = match self.alerts.write() {
+            Ok(store) => store,
+            Err(_) => return Vec::new(),
+        };
+        
+        // قد تكون أوامر سطر الأوامر عدّلت الملف (إقرار أو إسكات)
+        if let Err(e) = store.reload_if_changed() {
+            log::error!("❌ تعذر إعادة تحميل الإنذارات: {}", e);
+        }
+        
+        let events = store.evaluate_with_history(metrics, &baseline);
+        for event in &events {
+            let alert = &event.alert;
+            match event.kind {
+                AlertEventKind::Opened => log::warn!("⚠️  إنذار: {} - {}", alert.severity.severity_level(), alert.title),
+                AlertEventKind::Escalated => log::warn!("🔺 تصعيد إنذار: {} - {}", alert.severity.severity_level(), alert.title),
+                AlertEventKind::Resolved => log::info!("✅ تم حل الإنذار تلقائياً: {}", alert.title),
+            }
+        }
+        
+        if let Err(e) = store.save() {
+            log::error!("❌ تعذر حفظ الإنذارات: {}", e);
+        }
+        
+        events
+    }
+    
+    /// بث الأحداث إلى اللوحات الحية وإرسالها إلى قنوات الإشعار في مهمة منفصلة
+    /// حتى لا تؤخر المراقبة
+    fn dispatch_notifications(&self, events: Vec<AlertEvent>) {
+        if events.is_empty() || !self.alert_dispatch.load(Ordering::Relaxed) {
             return;
         }
         
-        log::info!("👁️  بدء مراقبة النظام...");
+        if let Some(hub) = self.live.read().ok().and_then(|h| h.clone()) {
+            for event in &events {
+                hub.publish(LiveEvent::Alert(event.clone()));
+            }
+        }
+        
+        let notifier = match self.notifier.read().ok().and_then(|n| n.clone()) {
+            Some(notifier) => notifier,
+            None => return,
+        };
         
-        let monitor_clone = self.clone_for_monitoring();
         tokio::spawn(async move {
-            monitor_clone.monitoring_loop(factory_manager).await;
+            for event in &events {
+                notifier.notify(event).await;
+            }
         });
     }
     
-    /// نسخة للمراقبة
-    fn clone_for_monitoring(&self) -> Arc<Self> {
-        Arc::new(Self {
-            metrics_history: Vec::new(),
-            active_alerts: Vec::new(),
-            monitoring_interval: self.monitoring_interval,
-            is_monitoring: false,
-            max_history_size: self.max_history_size,
-        })
+    /// إضافة الإنذارات المحلولة إلى قاعدة المعرفة
+    fn learn_from_events(&self, events: &[AlertEvent]) {
+        if !self.learning.load(Ordering::Relaxed) {
+            return;
+        }
+        let knowledge = match self.knowledge.read().ok().and_then(|k| k.clone()) {
+            Some(knowledge) => knowledge,
+            None => return,
+        };
+        
+        let resolved: Vec<&AlertEvent> = events.iter()
+            .filter(|e| e.kind == AlertEventKind::Resolved)
+            .collect();
+        if resolved.is_empty() {
+            return;
+        }
+        
+        let mut guard = match knowledge.write() {
+            Ok(guard) => guard,
+            Err(_) => return,
+        };
+        if let Err(e) = guard.reload_if_changed() {
+            log::error!("❌ تعذر إعادة تحميل قاعدة المعرفة: {}", e);
+        }
+        for event in resolved {
+            guard.learn_from_alert(&event.alert);
+        }
     }
     
-    /// حلقة المراقبة
-    async fn monitoring_loop(self: Arc<Self>, factory_manager: Arc<FactoryManager>) {
-        self.is_monitoring = true;
+    /// تنفيذ عملية على المخزن بعد مزامنته ثم حفظه
+    fn with_alerts<T>(&self, f: impl FnOnce(&mut AlertStore) -> Result<T, String>) -> Result<T, String> {
+        let mut store = self.alerts.write().map_err(|_| "تعذر الوصول إلى مخزن الإنذارات".to_string())?;
+        store.reload_if_changed().map_err(|e| e.to_string())?;
+        let result = f(&mut store)?;
+        store.save().map_err(|e| e.to_string())?;
+        Ok(result)
+    }
+}
+
+/// مقبض مهمة المراقبة في الخلفية
+pub struct MonitoringHandle {
+    shutdown: watch::Sender<bool>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl MonitoringHandle {
+    /// طلب إيقاف المهمة (تتوقف عند أول نقطة انتظار)
+    pub fn stop(&self) {
+        self.shutdown.send_replace(true);
+    }
+    
+    /// هل انتهت المهمة
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+    
+    /// الإيقاف وانتظار انتهاء المهمة
+    pub async fn join(self) {
+        self.stop();
+        if let Err(e) = self.task.await {
+            log::error!("❌ انتهت مهمة المراقبة بخطأ: {}", e);
+        }
+    }
+}
+
+/// مراقب النظام
+pub struct SystemMonitor {
+    state: Arc<MonitorState>,
+    monitoring_interval: watch::Sender<Duration>,
+    handle: Mutex<Option<MonitoringHandle>>,
+    recommendations: Arc<RecommendationEngine>,
+}
+
+impl SystemMonitor {
+    /// إنشاء مراقب جديد
+    pub fn new() -> Self {
+        let (monitoring_interval, _) = watch::channel(Duration::from_secs(30));
         
-        while self.is_monitoring {
-            // جمع المقاييس
-            let metrics = self.collect_metrics(factory_manager.clone()).await;
-            
-            // حفظ المقاييس
-            self.record_metrics(metrics.clone());
-            
-            // التحقق من الإنذارات
-            self.check_alerts(&metrics).await;
-            
-            // الانتظار للفترة التالية
-            time::sleep(self.monitoring_interval).await;
+        Self {
+            state: Arc::new(MonitorState {
+                sampler: HostSampler::new(),
+                history: RwLock::new(MetricsHistory::new(1000)),
+                alerts: RwLock::new(AlertStore::in_memory()),
+                notifier: RwLock::new(None),
+                live: RwLock::new(None),
+                knowledge: RwLock::new(None),
+                alert_dispatch: AtomicBool::new(true),
+                learning: AtomicBool::new(true),
+            }),
+            monitoring_interval,
+            handle: Mutex::new(None),
+            recommendations: Arc::new(RecommendationEngine::new()),
         }
     }
     
-    /// جمع المقاييس
-    async fn collect_metrics(&self, factory_manager: Arc<FactoryManager>) -> PerformanceMetrics {
-        let factories = factory_manager.list_factories();
-        
-        // محاكاة جمع المقاييس
-        let cpu_usage = rand::random::<f32>() * 0.3 + 0.2;
-        let memory_usage = rand::random::<f32>() * 0.3 + 0.3;
-        let disk_usage = rand::random::<f32>() * 0.2 + 0.1;
-        let network_usage = rand::random::<f32>() * 0.1 + 0.05;
-        let response_time = rand::random::<f32>() * 50.0 + 50.0;
-        let throughput = rand::random::<f32>() * 100.0 + 100.0;
-        
-        let cpu_efficiency = 1.0 - cpu_usage;
-        let memory_efficiency = 1.0 - memory_usage;
-        let overall_score = (cpu_efficiency + memory_efficiency + 
-            (1.0 - response_time / 1000.0).min(1.0)) / 3.0;
-        
-        PerformanceMetrics {
-            cpu_usage,
-            memory_usage,
-            disk_usage,
-            network_usage,
-            response_time,
-            throughput,
-            cpu_efficiency,
-            memory_efficiency,
-            overall_score,
-            timestamp: chrono::Utc::now(),
+    /// استخدام مخزن إنذارات محدد (مثل المخزن المحفوظ في دليل البيانات)
+    pub fn with_alert_store(self, store: AlertStore) -> Self {
+        if let Ok(mut alerts) = self.state.alerts.write() {
+            *alerts = store;
         }
+        self
     }
     
-    /// تسجيل المقاييس
-    fn record_metrics(&self, metrics: PerformanceMetrics) {
-        // في التطبيق الحقيقي، هنا سيتم حفظ المقاييس
-        log::debug!("📊 تم تسجيل مقاييس النظام: CPU {:.1}%, Memory {:.1}%", 
-            metrics.cpu_usage * 100.0, metrics.memory_usage * 100.0);
+    /// إرسال أحداث الإنذارات إلى قنوات الإشعار
+    pub fn with_notifier(self, notifier: Notifier) -> Self {
+        if let Ok(mut current) = self.state.notifier.write() {
+            *current = Some(Arc::new(notifier));
+        }
+        self
     }
     
-    /// التحقق من الإنذارات
-    async fn check_alerts(&self, metrics: &PerformanceMetrics) {
-        let mut alerts = Vec::new();
-        
-        // التحقق من استخدام المعالج
-        if metrics.cpu_usage > 0.9 {
-            alerts.push(SystemAlert {
-                id: format!("alert_cpu_{}", chrono::Utc::now().timestamp()),
-                severity: AlertSeverity::Critical,
-                title: "استخدام معالج مرتفع".to_string(),
-                message: format!("استخدام المعالج: {:.1}%", metrics.cpu_usage * 100.0),
-                source: "CPU Monitor".to_string(),
-                timestamp: chrono::Utc::now(),
-                resolved: false,
-            });
-        } else if metrics.cpu_usage > 0.8 {
-            alerts.push(SystemAlert {
-                id: format!("alert_cpu_{}", chrono::Utc::now().timestamp()),
-                severity: AlertSeverity::Warning,
-                title: "استخدام معجار مرتفع".to_string(),
-                message: format!("استخدام المعالج: {:.1}%", metrics.cpu_usage * 100.0),
-                source: "CPU Monitor".to_string(),
-                timestamp: chrono::Utc::now(),
-                resolved: false,
-            });
+    /// بث أحداث الإنذارات إلى اللوحات الحية
+    pub fn with_live_hub(self, hub: Arc<LiveHub>) -> Self {
+        if let Ok(mut current) = self.state.live.write() {
+            *current = Some(hub);
         }
-        
-        // التحقق من استخدام الذاكرة
-        if metrics.memory_usage > 0.9 {
-            alerts.push(SystemAlert {
-                id: format!("alert_memory_{}", chrono::Utc::now().timestamp()),
-                severity: AlertSeverity::Critical,
-                title: "استخدام ذاكرة مرتفع".to_string(),
-                message: format!("استخدام الذاكرة: {:.1}%", metrics.memory_usage * 100.0),
-                source: "Memory Monitor".to_string(),
-                timestamp: chrono::Utc::now(),
-                resolved: false,
-            });
+        self
+    }
+    
+    /// تعلم قاعدة المعرفة من الإنذارات المحلولة
+    pub fn with_knowledge(self, knowledge: Arc<RwLock<KnowledgeBase>>) -> Self {
+        if let Ok(mut current) = self.state.knowledge.write() {
+            *current = Some(knowledge);
         }
+        self
+    }
+    
+    /// محرك التوصيات المشترك (قواعده نفسها في تقارير المراقبة وواجهة التوصيات)
+    pub fn with_recommendations(mut self, engine: Arc<RecommendationEngine>) -> Self {
+        self.recommendations = engine;
+        self
+    }
+    
+    /// موزع الإشعارات الحالي (لوضع الاختبار)
+    pub fn notifier(&self) -> Option<Arc<Notifier>> {
+        self.state.notifier.read().ok().and_then(|n| n.clone())
+    }
+    
+    /// بدء المراقبة في مهمة خلفية قابلة للإيقاف
+    pub async fn start_monitoring(&self, factory_manager: Arc<FactoryManager>) {
+        let mut handle = match self.handle.lock() {
+            Ok(handle) => handle,
+            Err(poisoned) => poisoned.into_inner(),
+        };
         
-        // التحقق من وقت الاستجابة
-        if metrics.response_time > 500.0 {
-            alerts.push(SystemAlert {
-                id: format!("alert_response_{}", chrono::Utc::now().timestamp()),
-                severity: AlertSeverity::Warning,
-                title: "وقت استجابة بطيء".to_string(),
-                message: format!("وقت الاستجابة: {:.0}ms", metrics.response_time),
-                source: "Performance Monitor".to_string(),
-                timestamp: chrono::Utc::now(),
-                resolved: false,
-            });
+        if handle.as_ref().map(|h| !h.is_finished()).unwrap_or(false) {
+            return;
         }
         
-        // معالجة الإنذارات الجديدة
-        for alert in alerts {
-            log::warn!("⚠️  إنذار: {} - {}", alert.severity_level(), alert.title);
+        log::info!("👁️  بدء مراقبة النظام ({} مصنع، كل {} ثانية)...",
+            factory_manager.list_factories().len(), self.monitoring_interval.borrow().as_secs());
+        
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let task = tokio::spawn(Self::monitoring_loop(
+            self.state.clone(),
+            self.monitoring_interval.subscribe(),
+            shutdown_rx,
+        ));
+        
+        *handle = Some(MonitoringHandle { shutdown, task });
+    }
+    
+    /// حلقة المراقبة
+    async fn monitoring_loop(
+        state: Arc<MonitorState>,
+        mut interval: watch::Receiver<Duration>,
+        mut shutdown: watch::Receiver<bool>,
+    ) {
+        while !*shutdown.borrow() {
+            // جمع المقاييس وحفظها
+            let metrics = state.collect_metrics();
+            state.record_metrics(metrics.clone());
+            
+            // التحقق من الإنذارات
+            let events = state.check_alerts(&metrics);
+            state.learn_from_events(&events);
+            state.dispatch_notifications(events);
+            
+            // الانتظار للفترة التالية أو تغيير الفترة أو الإيقاف
+            let period = *interval.borrow_and_update();
+            tokio::select! {
+                _ = time::sleep(period) => {}
+                changed = interval.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+                _ = shutdown.changed() => {}
+            }
         }
+        
+        log::info!("🛑 توقفت مراقبة النظام");
+    }
+    
+    /// هل المراقبة تعمل حالياً
+    pub fn is_monitoring(&self) -> bool {
+        self.handle.lock()
+            .map(|h| h.as_ref().map(|h| !h.is_finished()).unwrap_or(false))
+            .unwrap_or(false)
     }
     
     /// الحصول على مقاييس الأداء
     pub async fn get_performance_metrics(&self) -> PerformanceMetrics {
-        // محاكاة المقاييس الحالية
-        PerformanceMetrics {
-            cpu_usage: 0.32,
-            memory_usage: 0.45,
-            disk_usage: 0.25,
-            network_usage: 0.15,
-            response_time: 120.0,
-            throughput: 150.0,
-            cpu_efficiency: 0.68,
-            memory_efficiency: 0.55,
-            overall_score: 0.78,
-            timestamp: chrono::Utc::now(),
-        }
+        self.state.collect_metrics()
+    }
+    
+    /// آخر عينة سجلتها حلقة المراقبة
+    pub fn latest_metrics(&self) -> Option<PerformanceMetrics> {
+        self.state.history.read().ok()?.latest().cloned()
+    }
+    
+    /// العينات الخام المسجلة منذ وقت معين
+    pub fn samples_since(&self, since: chrono::DateTime<chrono::Utc>) -> Vec<PerformanceMetrics> {
+        self.state.history.read()
+            .map(|h| h.samples_since(since))
+            .unwrap_or_default()
+    }
+    
+    /// التجميعات المصغّرة منذ وقت معين
+    pub fn rollups(&self, resolution: RollupResolution, since: chrono::DateTime<chrono::Utc>) -> Vec<MetricsRollup> {
+        self.state.history.read()
+            .map(|h| h.rollups(resolution, since))
+            .unwrap_or_default()
     }
     
     /// الحصول على الإنذارات النشطة
     pub async fn get_active_issues(&self) -> Vec<String> {
-        vec![
-            "استخدام الذاكرة مرتفع قليلاً".to_string(),
-            "وقت استجابة يحتاج تحسين".to_string(),
-        ]
+        self.state.alerts.read()
+            .map(|store| store.active().iter().map(|a| format!("{}: {}", a.title, a.message)).collect())
+            .unwrap_or_default()
+    }
+    
+    /// قائمة الإنذارات مع تصفية اختيارية حسب الحالة
+    pub fn list_alerts(&self, state: Option<AlertState>) -> Result<Vec<SystemAlert>, String> {
+        self.state.with_alerts(|store| Ok(store.list(state)))
+    }
+    
+    /// الإقرار بإنذار
+    pub fn acknowledge_alert(&self, id: &str, by: &str) -> Result<SystemAlert, String> {
+        let alert = self.state.with_alerts(|store| store.acknowledge(id, by))?;
+        log::info!("👍 تم الإقرار بالإنذار {} بواسطة {}", id, by);
+        Ok(alert)
+    }
+    
+    /// إسكات إنذار لعدد من الدقائق
+    pub fn silence_alert(&self, id: &str, minutes: i64) -> Result<SystemAlert, String> {
+        let alert = self.state.with_alerts(|store| store.silence(id, chrono::Duration::minutes(minutes)))?;
+        log::info!("🔕 تم إسكات الإنذار {} لمدة {} دقيقة", id, minutes);
+        Ok(alert)
+    }
+    
+    /// قواعد الإنذار الحالية
+    pub fn alert_rules(&self) -> Vec<AlertRule> {
+        self.state.alerts.read()
+            .map(|store| store.rules().to_vec())
+            .unwrap_or_default()
     }
     
-    /// إنشاء تقرير مراقبة
+    /// تحديث حدود قواعد الإنذار
+    pub fn set_alert_rules(&self, rules: Vec<AlertRule>) {
+        if let Ok(mut store) = self.state.alerts.write() {
+            store.set_rules(rules);
+        }
+    }
+    
+    /// قواعد الشذوذ الحالية
+    pub fn anomaly_rules(&self) -> Vec<AnomalyRule> {
+        self.state.alerts.read()
+            .map(|store| store.anomaly_rules().to_vec())
+            .unwrap_or_default()
+    }
+    
+    /// تحديث قواعد الشذوذ
+    pub fn set_anomaly_rules(&self, rules: Vec<AnomalyRule>) {
+        if let Ok(mut store) = self.state.alerts.write() {
+            store.set_anomaly_rules(rules);
+        }
+    }
+    
+    /// إنشاء تقرير مراقبة من السجل الفعلي
     pub async fn generate_report(&self, hours: u32) -> MonitoringReport {
         let end_time = chrono::Utc::now();
         let start_time = end_time - chrono::Duration::hours(hours as i64);
         
-        // محاكاة بيانات التقرير
-        let metrics = vec![self.get_performance_metrics().await];
+        let window = match self.state.history.read() {
+            Ok(history) => history.window(start_time),
+            Err(_) => HistoryWindow::Raw(Vec::new()),
+        };
         
-        let alerts = vec![
-            SystemAlert {
-                id: "alert_001".to_string(),
-                severity: AlertSeverity::Warning,
-                title: "استخدام ذاكرة مرتفع".to_string(),
-                message: "استخدام الذاكرة وصل إلى 85%".to_string(),
-                source: "Memory Monitor".to_string(),
-                timestamp: chrono::Utc::now(),
-                resolved: true,
-            }
-        ];
+        let alerts: Vec<SystemAlert> = self.state.alerts.read()
+            .map(|store| store.since(start_time))
+            .unwrap_or_default();
         
-        let recommendations = vec![
-            "تحسين إدارة الذاكرة المؤقتة".to_string(),
-            "تحسين خوارزميات الاستجابة".to_string(),
-        ];
+        let metrics = window.metrics();
+        let recommendations = self.recommendations_for(start_time, &metrics, &alerts);
         
         MonitoringReport {
             period_start: start_time,
             period_end: end_time,
+            summary: ReportSummary {
+                avg_performance: window.avg_performance(),
+                max_cpu_usage: window.max_cpu_usage(),
+                min_response_time: window.min_response_time(),
+                alert_count: alerts.len(),
+                uptime_percentage: self.uptime_percentage(&window, start_time, end_time),
+            },
             metrics,
             alerts,
             recommendations,
-            summary: ReportSummary {
-                avg_performance: 0.85,
-                max_cpu_usage: 0.65,
-                min_response_time: 95.0,
-                alert_count: 1,
-                uptime_percentage: 99.9,
-            },
         }
     }
     
+    /// نسبة الفترة التي غطتها العينات المسجلة مقارنة بالعدد المتوقع
+    fn uptime_percentage(
+        &self,
+        window: &HistoryWindow,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> f32 {
+        let first = match window.first_timestamp() {
+            Some(first) => first.max(start),
+            None => return 0.0,
+        };
+        
+        let interval = self.monitoring_interval.borrow().as_secs_f32().max(1.0);
+        let observed_span = (end - first).num_milliseconds().max(0) as f32 / 1000.0;
+        let expected = (observed_span / interval).floor() + 1.0;
+        
+        (window.sample_count() as f32 / expected).min(1.0) * 100.0
+    }
+    
+    /// توصيات الفترة من قواعد محرك التوصيات على متوسطاتها وإنذاراتها
+    fn recommendations_for(
+        &self,
+        start: chrono::DateTime<chrono::Utc>,
+        metrics: &[PerformanceMetrics],
+        alerts: &[SystemAlert],
+    ) -> Vec<Recommendation> {
+        if metrics.is_empty() {
+            return Vec::new();
+        }
+        
+        let snapshot = MetricsSnapshot::from_parts(start, metrics, &Default::default(), &[], &crate::core::tuning::knobs());
+        let input = RecommendationInput::new(snapshot).with_alerts(alerts.to_vec());
+        self.recommendations.assess(&input)
+    }
+    
     /// إيقاف المراقبة
-    pub fn stop_monitoring(&mut self) {
-        self.is_monitoring = false;
-        log::info!("🛑 توقفت مراقبة النظام");
+    pub fn stop_monitoring(&self) {
+        let handle = match self.handle.lock() {
+            Ok(mut handle) => handle.take(),
+            Err(poisoned) => poisoned.into_inner().take(),
+        };
+        
+        if let Some(handle) = handle {
+            handle.stop();
+        }
     }
     
-    /// تحديث فترة المراقبة
-    pub fn set_monitoring_interval(&mut self, interval_seconds: u64) {
-        self.monitoring_interval = Duration::from_secs(interval_seconds);
+    /// فترة المراقبة الحالية
+    pub fn monitoring_interval(&self) -> Duration {
+        *self.monitoring_interval.borrow()
+    }
+    
+    /// تحديث فترة المراقبة (تُطبق فوراً على المهمة الجارية)
+    pub fn set_monitoring_interval(&self, interval_seconds: u64) {
+        self.monitoring_interval.send_replace(Duration::from_secs(interval_seconds.max(1)));
         log::info!("🔄 تم تحديث فترة المراقبة إلى {} ثانية", interval_seconds);
     }
+    
+    /// تفعيل/تعطيل إرسال أحداث الإنذارات إلى الإشعارات واللوحات الحية
+    pub fn set_alert_dispatch(&self, enabled: bool) {
+        self.state.alert_dispatch.store(enabled, Ordering::Relaxed);
+    }
+    
+    /// تفعيل/تعطيل التعلم من الإنذارات المحلولة
+    pub fn set_learning_enabled(&self, enabled: bool) {
+        self.state.learning.store(enabled, Ordering::Relaxed);
+    }
+    
+    /// تحديث حجم سجل العينات الخام
+    pub fn set_max_history_size(&self, max_history_size: usize) {
+        if let Ok(mut history) = self.state.history.write() {
+            history.set_max_samples(max_history_size);
+        }
+    }
+}
+
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AlertSeverity {
-    fn severity_level(&self) -> &'static str {
+    pub fn severity_level(&self) -> &'static str {
         match self {
             AlertSeverity::Info => "معلومات",
             AlertSeverity::Warning => "تحذير",
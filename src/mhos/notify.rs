@@ -0,0 +1,694 @@
+//! قنوات إشعارات الإنذارات: Webhook بصيغة JSON، بريد عبر SMTP، وأمر محلي
+//!
+//! لكل مسار خطورات يستقبلها، مع إعادة المحاولة بتأخير متزايد وحد لمعدل
+//! الإرسال يمنع عواصف الإنذارات. وضع الاختبار يرسل إنذاراً تجريبياً يمكن
+//! توجيهه إلى خادم محلي بديل.
+
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use crate::mhos::alerts::{AlertEvent, AlertEventKind, AlertState};
+use crate::mhos::monitor::{AlertSeverity, SystemAlert};
+
+/// مسار ملف إعدادات الإشعارات داخل دليل البيانات
+pub const NOTIFICATIONS_FILE: &str = "mhos/notifications.json";
+
+/// مهلة الاتصال والإرسال لكل محاولة
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// قناة الإشعار
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// طلب POST بجسم JSON (http فقط؛ للـ https استخدم قناة command مع curl)
+    Webhook {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    /// بريد إلكتروني عبر خادم SMTP
+    Email {
+        smtp_host: String,
+        #[serde(default = "default_smtp_port")]
+        smtp_port: u16,
+        from: String,
+        to: Vec<String>,
+        #[serde(default)]
+        username: Option<String>,
+        #[serde(default)]
+        password: Option<String>,
+    },
+    /// أمر محلي يستقبل الحمولة على stdin ومتغيرات MH_ALERT_*
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+fn default_smtp_port() -> u16 {
+    25
+}
+
+impl NotificationChannel {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NotificationChannel::Webhook { .. } => "webhook",
+            NotificationChannel::Email { .. } => "email",
+            NotificationChannel::Command { .. } => "command",
+        }
+    }
+}
+
+/// مسار إشعار: قناة مع الخطورات التي تستقبلها
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationRoute {
+    pub name: String,
+    #[serde(default = "all_severities")]
+    pub severities: Vec<AlertSeverity>,
+    pub channel: NotificationChannel,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn all_severities() -> Vec<AlertSeverity> {
+    vec![AlertSeverity::Info, AlertSeverity::Warning, AlertSeverity::Error, AlertSeverity::Critical]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// سياسة إعادة المحاولة
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 10_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// التأخير قبل المحاولة التالية (يتضاعف حتى الحد الأقصى)
+    fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+        Duration::from_millis(self.initial_backoff_ms.saturating_mul(factor).min(self.max_backoff_ms))
+    }
+}
+
+/// حد معدل الإرسال لكل مسار
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub max_notifications: usize,
+    pub window_secs: u64,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            max_notifications: 10,
+            window_secs: 300,
+        }
+    }
+}
+
+/// إعدادات الإشعارات
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationConfig {
+    #[serde(default)]
+    pub routes: Vec<NotificationRoute>,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+}
+
+impl NotificationConfig {
+    /// تحميل الإعدادات من ملف JSON (إعدادات فارغة إن لم يوجد)
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+}
+
+/// حمولة الإشعار
+#[derive(Debug, Clone, Serialize)]
+pub struct NotificationPayload {
+    pub event: AlertEventKind,
+    pub alert: SystemAlert,
+    pub suppressed: usize, // إشعارات كُتمت بحد المعدل منذ آخر إرسال
+    pub test: bool,
+    pub sent_at: chrono::DateTime<chrono::Utc>,
+    pub source: String,
+}
+
+impl NotificationPayload {
+    fn subject(&self) -> String {
+        let prefix = if self.test { "[اختبار] " } else { "" };
+        let event = match self.event {
+            AlertEventKind::Opened => "إنذار جديد",
+            AlertEventKind::Escalated => "تصعيد إنذار",
+            AlertEventKind::Resolved => "تم حل الإنذار",
+        };
+        format!("{}[{}] {}: {}", prefix, self.alert.severity.severity_level(), event, self.alert.title)
+    }
+
+    fn text(&self) -> String {
+        let mut text = format!(
+            "{}\n\n{}\nالمصدر: {}\nالمعرف: {}\nالقيمة: {:.2} (الحد: {:.2})\nالتكرار: {}\nآخر ظهور: {}\n",
+            self.subject(),
+            self.alert.message,
+            self.alert.source,
+            self.alert.id,
+            self.alert.value,
+            self.alert.threshold,
+            self.alert.occurrences,
+            self.alert.last_seen.to_rfc3339(),
+        );
+        if self.suppressed > 0 {
+            text.push_str(&format!("\nتم كتم {} إشعاراً بسبب حد المعدل\n", self.suppressed));
+        }
+        text
+    }
+}
+
+/// نتيجة التسليم لمسار واحد
+#[derive(Debug, Clone, Serialize)]
+pub struct DeliveryReport {
+    pub route: String,
+    pub channel: String,
+    pub success: bool,
+    pub attempts: u32,
+    pub rate_limited: bool,
+    pub error: Option<String>,
+}
+
+/// خطأ تسليم مع تحديد ما إذا كانت إعادة المحاولة مجدية
+#[derive(Debug)]
+struct DeliveryError {
+    message: String,
+    retryable: bool,
+}
+
+impl DeliveryError {
+    fn retryable(message: impl Into<String>) -> Self {
+        Self { message: message.into(), retryable: true }
+    }
+
+    fn permanent(message: impl Into<String>) -> Self {
+        Self { message: message.into(), retryable: false }
+    }
+}
+
+impl From<io::Error> for DeliveryError {
+    fn from(e: io::Error) -> Self {
+        DeliveryError::retryable(e.to_string())
+    }
+}
+
+/// نافذة حد المعدل لمسار
+#[derive(Debug, Default)]
+struct RouteWindow {
+    sent: VecDeque<Instant>,
+    suppressed: usize,
+}
+
+/// موزع الإشعارات
+pub struct Notifier {
+    config: NotificationConfig,
+    windows: Mutex<HashMap<String, RouteWindow>>,
+}
+
+impl Notifier {
+    pub fn new(config: NotificationConfig) -> Self {
+        Self {
+            config,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn config(&self) -> &NotificationConfig {
+        &self.config
+    }
+
+    /// إرسال حدث إنذار إلى المسارات المطابقة لخطورته
+    pub async fn notify(&self, event: &AlertEvent) -> Vec<DeliveryReport> {
+        let mut reports = Vec::new();
+
+        for route in self.config.routes.iter().filter(|r| r.enabled) {
+            if !route.severities.contains(&event.alert.severity) {
+                continue;
+            }
+
+            let suppressed = match self.acquire(&route.name) {
+                Some(suppressed) => suppressed,
+                None => {
+                    log::warn!("🔇 تم كتم إشعار {} عبر {} بسبب حد المعدل", event.alert.title, route.name);
+                    reports.push(DeliveryReport {
+                        route: route.name.clone(),
+                        channel: route.channel.kind().to_string(),
+                        success: false,
+                        attempts: 0,
+                        rate_limited: true,
+                        error: None,
+                    });
+                    continue;
+                }
+            };
+
+            let payload = NotificationPayload {
+                event: event.kind,
+                alert: event.alert.clone(),
+                suppressed,
+                test: false,
+                sent_at: chrono::Utc::now(),
+                source: "marwan-hub".to_string(),
+            };
+            reports.push(self.deliver(route, &payload).await);
+        }
+
+        reports
+    }
+
+    /// وضع الاختبار: إرسال إنذار تجريبي إلى مسار محدد أو كل المسارات دون حد المعدل
+    pub async fn send_test(&self, route_name: Option<&str>) -> Vec<DeliveryReport> {
+        let now = chrono::Utc::now();
+        let payload = NotificationPayload {
+            event: AlertEventKind::Opened,
+            alert: test_alert(now),
+            suppressed: 0,
+            test: true,
+            sent_at: now,
+            source: "marwan-hub".to_string(),
+        };
+
+        let mut reports = Vec::new();
+        for route in &self.config.routes {
+            if route_name.map(|name| name == route.name).unwrap_or(true) {
+                reports.push(self.deliver(route, &payload).await);
+            }
+        }
+        reports
+    }
+
+    /// حجز مكان في نافذة المسار؛ يعيد عدد المكتوم منذ آخر إرسال أو None عند التجاوز
+    fn acquire(&self, route: &str) -> Option<usize> {
+        let limit = &self.config.rate_limit;
+        let window = Duration::from_secs(limit.window_secs);
+        let now = Instant::now();
+
+        let mut windows = self.windows.lock().ok()?;
+        let state = windows.entry(route.to_string()).or_default();
+        while state.sent.front().map(|t| now.duration_since(*t) > window).unwrap_or(false) {
+            state.sent.pop_front();
+        }
+
+        if state.sent.len() >= limit.max_notifications {
+            state.suppressed += 1;
+            return None;
+        }
+
+        state.sent.push_back(now);
+        Some(std::mem::take(&mut state.suppressed))
+    }
+
+    /// التسليم مع إعادة المحاولة
+    async fn deliver(&self, route: &NotificationRoute, payload: &NotificationPayload) -> DeliveryReport {
+        let retry = &self.config.retry;
+        let mut attempts = 0;
+        let mut last_error = None;
+
+        while attempts < retry.max_attempts.max(1) {
+            attempts += 1;
+            let result = tokio::time::timeout(DELIVERY_TIMEOUT * 3, send(&route.channel, payload)).await
+                .unwrap_or_else(|_| Err(DeliveryError::retryable("انتهت المهلة")));
+
+            match result {
+                Ok(()) => {
+                    log::info!("📨 تم إرسال الإشعار عبر {} ({})", route.name, route.channel.kind());
+                    return DeliveryReport {
+                        route: route.name.clone(),
+                        channel: route.channel.kind().to_string(),
+                        success: true,
+                        attempts,
+                        rate_limited: false,
+                        error: None,
+                    };
+                }
+                Err(e) => {
+                    log::warn!("⚠️  فشل إرسال الإشعار عبر {} (محاولة {}): {}", route.name, attempts, e.message);
+                    let retryable = e.retryable;
+                    last_error = Some(e.message);
+                    if !retryable {
+                        break;
+                    }
+                    if attempts < retry.max_attempts {
+                        tokio::time::sleep(retry.backoff(attempts)).await;
+                    }
+                }
+            }
+        }
+
+        log::error!("❌ تعذر إرسال الإشعار عبر {} بعد {} محاولة", route.name, attempts);
+        DeliveryReport {
+            route: route.name.clone(),
+            channel: route.channel.kind().to_string(),
+            success: false,
+            attempts,
+            rate_limited: false,
+            error: last_error,
+        }
+    }
+}
+
+/// الإنذار التجريبي لوضع الاختبار
+fn test_alert(now: chrono::DateTime<chrono::Utc>) -> SystemAlert {
+    SystemAlert {
+        id: format!("alert_test_{}", now.timestamp_millis()),
+        severity: AlertSeverity::Warning,
+        title: "إنذار تجريبي".to_string(),
+        message: "هذا إشعار تجريبي للتحقق من إعدادات القنوات".to_string(),
+        source: "Notification Test".to_string(),
+        timestamp: now,
+        resolved: false,
+        state: AlertState::Open,
+        metric: None,
+        value: 0.0,
+        threshold: 0.0,
+        occurrences: 1,
+        last_seen: now,
+        acknowledged_by: None,
+        acknowledged_at: None,
+        resolved_at: None,
+        silenced_until: None,
+    }
+}
+
+async fn send(channel: &NotificationChannel, payload: &NotificationPayload) -> Result<(), DeliveryError> {
+    match channel {
+        NotificationChannel::Webhook { url, headers } => send_webhook(url, headers, payload).await,
+        NotificationChannel::Email { smtp_host, smtp_port, from, to, username, password } => {
+            let credentials = username.as_deref().zip(password.as_deref());
+            send_email(smtp_host, *smtp_port, from, to, credentials, payload).await
+        }
+        NotificationChannel::Command { program, args } => run_command(program, args, payload).await,
+    }
+}
+
+/// تحليل عنوان http://host[:port]/path
+fn parse_http_url(url: &str) -> Result<(String, u16, String), DeliveryError> {
+    if url.starts_with("https://") {
+        return Err(DeliveryError::permanent("عناوين https غير مدعومة مباشرة؛ استخدم قناة command مع curl"));
+    }
+    let rest = url.strip_prefix("http://")
+        .ok_or_else(|| DeliveryError::permanent(format!("عنوان webhook غير صالح: {}", url)))?;
+
+    let (authority, path) = match rest.find('/') {
+        Some(index) => (&rest[..index], &rest[index..]),
+        None => (rest, "/"),
+    };
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (
+            host,
+            port.parse::<u16>().map_err(|_| DeliveryError::permanent(format!("منفذ غير صالح: {}", port)))?,
+        ),
+        None => (authority, 80),
+    };
+    if host.is_empty() {
+        return Err(DeliveryError::permanent(format!("عنوان webhook بلا مضيف: {}", url)));
+    }
+
+    Ok((host.to_string(), port, path.to_string()))
+}
+
+async fn connect(host: &str, port: u16) -> Result<TcpStream, DeliveryError> {
+    tokio::time::timeout(DELIVERY_TIMEOUT, TcpStream::connect((host, port)))
+        .await
+        .map_err(|_| DeliveryError::retryable(format!("انتهت مهلة الاتصال بـ {}:{}", host, port)))?
+        .map_err(DeliveryError::from)
+}
+
+async fn send_webhook(
+    url: &str,
+    headers: &HashMap<String, String>,
+    payload: &NotificationPayload,
+) -> Result<(), DeliveryError> {
+    let (host, port, path) = parse_http_url(url)?;
+    let body = serde_json::to_vec(payload).map_err(|e| DeliveryError::permanent(e.to_string()))?;
+
+    let mut request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nUser-Agent: marwan-hub-notifier\r\nConnection: close\r\n",
+        path, host, body.len()
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+
+    let mut stream = connect(&host, port).await?;
+    stream.write_all(request.as_bytes()).await?;
+    stream.write_all(&body).await?;
+
+    let mut response = Vec::new();
+    tokio::time::timeout(DELIVERY_TIMEOUT, stream.read_to_end(&mut response))
+        .await
+        .map_err(|_| DeliveryError::retryable("انتهت مهلة انتظار الرد"))??;
+
+    let status_line = String::from_utf8_lossy(&response);
+    let status = status_line.split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| DeliveryError::retryable("رد HTTP غير صالح"))?;
+
+    match status {
+        200..=299 => Ok(()),
+        408 | 429 | 500..=599 => Err(DeliveryError::retryable(format!("رد الخادم بالحالة {}", status))),
+        _ => Err(DeliveryError::permanent(format!("رفض الخادم الطلب بالحالة {}", status))),
+    }
+}
+
+/// قراءة رد SMTP (قد يمتد لعدة أسطر) والتحقق من رمزه
+async fn smtp_expect(reader: &mut BufReader<TcpStream>, expected: u16) -> Result<(), DeliveryError> {
+    loop {
+        let mut line = String::new();
+        let read = tokio::time::timeout(DELIVERY_TIMEOUT, reader.read_line(&mut line))
+            .await
+            .map_err(|_| DeliveryError::retryable("انتهت مهلة رد SMTP"))??;
+        if read == 0 {
+            return Err(DeliveryError::retryable("أغلق خادم SMTP الاتصال"));
+        }
+
+        let code = line.get(..3).and_then(|c| c.parse::<u16>().ok())
+            .ok_or_else(|| DeliveryError::retryable(format!("رد SMTP غير صالح: {}", line.trim())))?;
+        if line.as_bytes().get(3) == Some(&b'-') {
+            continue;
+        }
+
+        return match code {
+            c if c == expected => Ok(()),
+            400..=499 => Err(DeliveryError::retryable(format!("SMTP: {}", line.trim()))),
+            _ => Err(DeliveryError::permanent(format!("SMTP: {}", line.trim()))),
+        };
+    }
+}
+
+async fn smtp_command(reader: &mut BufReader<TcpStream>, command: &str, expected: u16) -> Result<(), DeliveryError> {
+    reader.get_mut().write_all(format!("{}\r\n", command).as_bytes()).await?;
+    smtp_expect(reader, expected).await
+}
+
+async fn send_email(
+    host: &str,
+    port: u16,
+    from: &str,
+    to: &[String],
+    credentials: Option<(&str, &str)>,
+    payload: &NotificationPayload,
+) -> Result<(), DeliveryError> {
+    if to.is_empty() {
+        return Err(DeliveryError::permanent("لا يوجد مستلمون للبريد"));
+    }
+
+    let mut reader = BufReader::new(connect(host, port).await?);
+    smtp_expect(&mut reader, 220).await?;
+    smtp_command(&mut reader, "EHLO marwan-hub", 250).await?;
+
+    if let Some((username, password)) = credentials {
+        let token = base64_encode(format!("\0{}\0{}", username, password).as_bytes());
+        smtp_command(&mut reader, &format!("AUTH PLAIN {}", token), 235).await?;
+    }
+
+    smtp_command(&mut reader, &format!("MAIL FROM:<{}>", from), 250).await?;
+    for recipient in to {
+        smtp_command(&mut reader, &format!("RCPT TO:<{}>", recipient), 250).await?;
+    }
+    smtp_command(&mut reader, "DATA", 354).await?;
+
+    let mut message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: =?UTF-8?B?{}?=\r\nDate: {}\r\nMIME-Version: 1.0\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: 8bit\r\n\r\n",
+        from,
+        to.join(", "),
+        base64_encode(payload.subject().as_bytes()),
+        payload.sent_at.to_rfc2822(),
+    );
+    for line in payload.text().lines() {
+        // مضاعفة النقطة في بداية السطر حتى لا تُفهم كنهاية للرسالة
+        if line.starts_with('.') {
+            message.push('.');
+        }
+        message.push_str(line);
+        message.push_str("\r\n");
+    }
+    message.push('.');
+
+    smtp_command(&mut reader, &message, 250).await?;
+    // لا يهم رد QUIT بعد قبول الرسالة
+    let _ = smtp_command(&mut reader, "QUIT", 221).await;
+    Ok(())
+}
+
+async fn run_command(program: &str, args: &[String], payload: &NotificationPayload) -> Result<(), DeliveryError> {
+    let body = serde_json::to_vec(payload).map_err(|e| DeliveryError::permanent(e.to_string()))?;
+    let event = serde_json::to_value(payload.event).ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .env("MH_ALERT_ID", &payload.alert.id)
+        .env("MH_ALERT_EVENT", event)
+        .env("MH_ALERT_SEVERITY", payload.alert.severity.severity_level())
+        .env("MH_ALERT_TITLE", &payload.alert.title)
+        .env("MH_ALERT_MESSAGE", &payload.alert.message)
+        .env("MH_ALERT_TEST", payload.test.to_string())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| DeliveryError::permanent(format!("تعذر تشغيل {}: {}", program, e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(&body).await?;
+    }
+
+    let status = tokio::time::timeout(DELIVERY_TIMEOUT * 3, child.wait())
+        .await
+        .map_err(|_| DeliveryError::retryable(format!("انتهت مهلة الأمر {}", program)))??;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(DeliveryError::retryable(format!("انتهى الأمر {} بالحالة {}", program, status)))
+    }
+}
+
+fn base64_encode(input: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(TABLE[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// خادم بديل يرد على طلبات webhook بالحالات المحددة بالترتيب ويعيد الأجسام المستلمة
+    async fn stand_in_webhook(statuses: Vec<u16>) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hooks/alerts", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let mut bodies = Vec::new();
+            for status in statuses {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).await.unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).await.unwrap();
+                bodies.push(String::from_utf8(body).unwrap());
+                let response = format!("HTTP/1.1 {} X\r\nContent-Length: 0\r\n\r\n", status);
+                reader.get_mut().write_all(response.as_bytes()).await.unwrap();
+            }
+            bodies
+        });
+
+        (url, server)
+    }
+
+    #[tokio::test]
+    async fn test_webhook_retry_and_rate_limit() {
+        let (url, server) = stand_in_webhook(vec![503, 200, 200]).await;
+        let notifier = Notifier::new(NotificationConfig {
+            routes: vec![NotificationRoute {
+                name: "ops".to_string(),
+                severities: vec![AlertSeverity::Warning, AlertSeverity::Critical],
+                channel: NotificationChannel::Webhook { url, headers: HashMap::new() },
+                enabled: true,
+            }],
+            retry: RetryPolicy { max_attempts: 3, initial_backoff_ms: 1, max_backoff_ms: 5 },
+            rate_limit: RateLimit { max_notifications: 1, window_secs: 60 },
+        });
+
+        // وضع الاختبار: فشل مؤقت ثم نجاح
+        let reports = notifier.send_test(None).await;
+        assert!(reports[0].success);
+        assert_eq!(reports[0].attempts, 2);
+
+        // الحدث الأول يُرسل والثاني يُكتم بحد المعدل
+        let mut event = AlertEvent {
+            kind: AlertEventKind::Opened,
+            alert: test_alert(chrono::Utc::now()),
+        };
+        assert!(notifier.notify(&event).await[0].success);
+        event.kind = AlertEventKind::Escalated;
+        assert!(notifier.notify(&event).await[0].rate_limited);
+
+        let bodies = server.await.unwrap();
+        assert_eq!(bodies.len(), 3);
+        assert!(bodies[0].contains("\"test\":true"));
+        assert!(bodies[2].contains("\"event\":\"opened\""));
+        assert_eq!(base64_encode(b"ops"), "b3Bz");
+    }
+}
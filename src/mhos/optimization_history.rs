@@ -0,0 +1,108 @@
+//! سجل عمليات التحسين المحفوظ
+//!
+//! يحفظ كل تشغيل للمحسن مع لقطة المقاييس التي استند إليها ومصير كل تغيير
+//! بعد نافذة القياس، في ملف JSON داخل دليل البيانات.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::mhos::optimizer::OptimizationResult;
+
+/// مسار ملف سجل التحسين داخل دليل البيانات
+pub const OPTIMIZATIONS_FILE: &str = "mhos/optimizations.json";
+
+/// الحد الأقصى للتشغيلات المحفوظة
+const MAX_RUNS: usize = 500;
+
+/// محتوى ملف السجل
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OptimizationFile {
+    runs: Vec<OptimizationResult>,
+}
+
+/// سجل عمليات التحسين
+#[derive(Debug, Default)]
+pub struct OptimizationHistory {
+    path: Option<PathBuf>,
+    runs: Vec<OptimizationResult>,
+}
+
+impl OptimizationHistory {
+    /// سجل في الذاكرة فقط
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// فتح سجل محفوظ (يُنشأ الملف عند أول حفظ)
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut history = Self::in_memory();
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let file: OptimizationFile = serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            history.runs = file.runs;
+            log::info!("📂 تم تحميل {} عملية تحسين من {}", history.runs.len(), path.display());
+        }
+
+        history.path = Some(path);
+        Ok(history)
+    }
+
+    /// إضافة تشغيل جديد مع الاحتفاظ بآخر التشغيلات فقط
+    pub fn record(&mut self, result: OptimizationResult) {
+        self.runs.push(result);
+        if self.runs.len() > MAX_RUNS {
+            let excess = self.runs.len() - MAX_RUNS;
+            self.runs.drain(..excess);
+        }
+    }
+
+    pub fn get_mut(&mut self, run_id: &str) -> Option<&mut OptimizationResult> {
+        self.runs.iter_mut().find(|r| r.run_id == run_id)
+    }
+
+    /// التشغيلات من الأقدم إلى الأحدث
+    pub fn all(&self) -> &[OptimizationResult] {
+        &self.runs
+    }
+
+    /// آخر `limit` تشغيل من الأحدث إلى الأقدم
+    pub fn recent(&self, limit: usize) -> Vec<OptimizationResult> {
+        self.runs.iter().rev().take(limit).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.runs.clear();
+    }
+
+    /// حفظ السجل في ملفه (إن وُجد)
+    pub fn save(&self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = OptimizationFile { runs: self.runs.clone() };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // كتابة ذرية عبر ملف مؤقت
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, path)
+    }
+}
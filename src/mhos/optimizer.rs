@@ -1,25 +1,118 @@
-use std::sync::Arc;
-use serde::Serialize;
+//! محسّن النظام
+//!
+//! تقرأ الاستراتيجيات لقطة مقاييس حية (المراقب، إحصائيات المصانع، سجل الجودة)
+//! وتعدّل مقابض التشغيل الفعلية في `core::tuning`. يُقاس هدف كل تغيير قبل
+//! تطبيقه ثم في نافذة القياس التالية، ويُتراجع عنه إن ساء الهدف.
+//! التشغيل التجريبي (`dry_run`) يعرض ما كانت ستفعله الاستراتيجيات دون تطبيقه.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::factory::FactoryMetrics;
 use crate::core::factory_manager::FactoryManager;
+use crate::core::quality::{QualityManager, QualityReport};
+use crate::core::tuning::{knobs, Knob, RuntimeKnobs};
+use crate::mhos::knowledge::KnowledgeBase;
+use crate::mhos::monitor::{PerformanceMetrics, SystemMonitor};
+use crate::mhos::optimization_history::OptimizationHistory;
 
 /// نتيجة التحسين
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptimizationResult {
+    pub run_id: String,
+    /// تشغيل تجريبي لم يُطبق فيه أي تغيير
+    #[serde(default)]
+    pub dry_run: bool,
     pub success: bool,
     pub improvements: Vec<Improvement>,
     pub performance_gain: f32,
     pub quality_improvement: f32,
     pub duration_seconds: f64,
-    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub timestamp: DateTime<Utc>,
+    /// لقطة المقاييس التي استندت إليها القرارات
+    pub snapshot: MetricsSnapshot,
 }
 
-/// تحسين مطبق
-#[derive(Debug, Clone, Serialize)]
+/// تحسين مطبق على مقبض تشغيل
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Improvement {
     pub area: String,
     pub action: String,
+    /// التأثير المتوقع
     pub impact: f32,
     pub details: String,
+    pub knob: Knob,
+    pub before_value: f64,
+    pub after_value: f64,
+    pub objective: Option<Objective>,
+    /// قيمة الهدف قبل التغيير
+    pub baseline: Option<f64>,
+    /// قيمة الهدف في نافذة القياس التالية
+    pub measured: Option<f64>,
+    pub outcome: ChangeOutcome,
+}
+
+/// مصير التغيير بعد نافذة القياس
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeOutcome {
+    /// تغيير متوقع في تشغيل تجريبي
+    Predicted,
+    Pending,
+    Kept,
+    RolledBack,
+}
+
+/// المقياس الذي يُحكم به على التغيير
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Objective {
+    pub metric: Metric,
+    pub target: Option<String>,
+    pub lower_is_better: bool,
+}
+
+/// المقاييس التي تقرؤها الاستراتيجيات
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    ResponseTimeMs,
+    CpuUsage,
+    MemoryUsage,
+    /// الطلبات قيد التنفيذ نسبة إلى حد التزامن (لكل مصنع)
+    FactoryQueueUtilization,
+    FactoryProcessingMs,
+    FactoryFailureRate,
+    /// متوسط زمن تنفيذ البوابة (لكل بوابة)
+    GateExecutionMs,
+    GatePassRate,
+    QualityScore,
+}
+
+impl Metric {
+    /// الاسم المعروض بالعربية
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Metric::ResponseTimeMs => "زمن الاستجابة (ms)",
+            Metric::CpuUsage => "استخدام المعالج",
+            Metric::MemoryUsage => "استخدام الذاكرة",
+            Metric::FactoryQueueUtilization => "امتلاء طابور المصنع",
+            Metric::FactoryProcessingMs => "زمن معالجة المصنع (ms)",
+            Metric::FactoryFailureRate => "معدل فشل المصنع",
+            Metric::GateExecutionMs => "زمن تنفيذ البوابة (ms)",
+            Metric::GatePassRate => "معدل نجاح البوابة",
+            Metric::QualityScore => "نتيجة الجودة",
+        }
+    }
+
+    fn is_per_factory(&self) -> bool {
+        matches!(self, Metric::FactoryQueueUtilization | Metric::FactoryProcessingMs | Metric::FactoryFailureRate)
+    }
+
+    fn is_per_gate(&self) -> bool {
+        matches!(self, Metric::GateExecutionMs | Metric::GatePassRate)
+    }
 }
 
 /// منطقة التحسين
@@ -32,196 +125,727 @@ pub enum OptimizationArea {
     Security,
 }
 
+impl OptimizationArea {
+    fn display_name(&self) -> &'static str {
+        match self {
+            OptimizationArea::Performance => "الأداء",
+            OptimizationArea::Memory => "الذاكرة",
+            OptimizationArea::Quality => "الجودة",
+            OptimizationArea::Efficiency => "الكفاءة",
+            OptimizationArea::Security => "الأمان",
+        }
+    }
+}
+
+/// إحصائيات مصنع في لقطة (عدادات تراكمية منذ بدء التشغيل)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FactorySnapshot {
+    pub total_requests: u64,
+    pub completed: u64,
+    pub failed: u64,
+    pub queue_size: usize,
+    pub concurrency: usize,
+    pub avg_processing_ms: f64,
+}
+
+/// أداء بوابة جودة في نافذة اللقطة
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GateSnapshot {
+    pub runs: usize,
+    pub avg_execution_ms: f64,
+    pub pass_rate: f64,
+    pub sampling_rate: f64,
+}
+
+/// لقطة مقاييس حية تُبنى عليها القرارات ويُقارن بها بعد التغيير
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub captured_at: DateTime<Utc>,
+    pub window_start: DateTime<Utc>,
+    pub samples: usize,
+    pub response_time_ms: f64,
+    pub cpu_usage: f64,
+    pub memory_usage: f64,
+    pub throughput: f64,
+    pub quality_score: Option<f64>,
+    pub factories: BTreeMap<String, FactorySnapshot>,
+    pub gates: BTreeMap<String, GateSnapshot>,
+}
+
+impl MetricsSnapshot {
+    /// بناء لقطة من عينات المراقب وإحصائيات المصانع وتقارير الجودة ضمن النافذة
+    pub fn from_parts(
+        window_start: DateTime<Utc>,
+        samples: &[PerformanceMetrics],
+        factories: &HashMap<String, FactoryMetrics>,
+        reports: &[&QualityReport],
+        knobs: &RuntimeKnobs,
+    ) -> Self {
+        let avg = |f: fn(&PerformanceMetrics) -> f32| {
+            if samples.is_empty() { 0.0 } else { samples.iter().map(|m| f(m) as f64).sum::<f64>() / samples.len() as f64 }
+        };
+
+        let factories = factories.iter().map(|(factory_type, m)| {
+            (factory_type.clone(), FactorySnapshot {
+                total_requests: m.total_requests,
+                completed: m.successful_requests + m.failed_requests,
+                failed: m.failed_requests,
+                queue_size: m.current_queue_size,
+                concurrency: knobs.factory_concurrency(factory_type),
+                avg_processing_ms: m.avg_processing_time_ms,
+            })
+        }).collect();
+
+        let reports: Vec<&&QualityReport> = reports.iter().filter(|r| r.generated_at >= window_start).collect();
+        let quality_score = if reports.is_empty() {
+            None
+        } else {
+            Some(reports.iter().map(|r| r.overall_score).sum::<f64>() / reports.len() as f64)
+        };
+
+        let mut gates: BTreeMap<String, GateSnapshot> = BTreeMap::new();
+        for report in &reports {
+            for result in report.gate_results.values() {
+                let gate = gates.entry(result.gate_id.clone()).or_default();
+                gate.runs += 1;
+                gate.avg_execution_ms += result.execution_time_ms as f64;
+                gate.pass_rate += if result.passed { 1.0 } else { 0.0 };
+            }
+        }
+        for (gate_id, gate) in gates.iter_mut() {
+            gate.avg_execution_ms /= gate.runs as f64;
+            gate.pass_rate /= gate.runs as f64;
+            gate.sampling_rate = knobs.gate_sampling_rate(gate_id);
+        }
+
+        Self {
+            captured_at: Utc::now(),
+            window_start,
+            samples: samples.len(),
+            response_time_ms: avg(|m| m.response_time),
+            cpu_usage: avg(|m| m.cpu_usage),
+            memory_usage: avg(|m| m.memory_usage),
+            throughput: avg(|m| m.throughput),
+            quality_score,
+            factories,
+            gates,
+        }
+    }
+
+    /// قيمة مقياس في هذه اللقطة
+    pub fn value(&self, metric: Metric, target: Option<&str>) -> Option<f64> {
+        let factory = || target.and_then(|t| self.factories.get(t));
+        let gate = || target.and_then(|t| self.gates.get(t));
+
+        match metric {
+            Metric::ResponseTimeMs => (self.samples > 0).then_some(self.response_time_ms),
+            Metric::CpuUsage => (self.samples > 0).then_some(self.cpu_usage),
+            Metric::MemoryUsage => (self.samples > 0).then_some(self.memory_usage),
+            Metric::FactoryQueueUtilization => factory().map(|f| f.queue_size as f64 / f.concurrency.max(1) as f64),
+            Metric::FactoryProcessingMs => factory().filter(|f| f.completed > 0).map(|f| f.avg_processing_ms),
+            Metric::FactoryFailureRate => factory()
+                .filter(|f| f.total_requests > 0)
+                .map(|f| f.failed as f64 / f.total_requests as f64),
+            Metric::GateExecutionMs => gate().map(|g| g.avg_execution_ms),
+            Metric::GatePassRate => gate().map(|g| g.pass_rate),
+            Metric::QualityScore => self.quality_score,
+        }
+    }
+
+    /// قيمة مقياس خلال الفترة منذ لقطة سابقة (عدادات المصانع تراكمية فتُطرح)
+    pub fn value_since(&self, earlier: &MetricsSnapshot, metric: Metric, target: Option<&str>) -> Option<f64> {
+        let pair = || {
+            let target = target?;
+            Some((earlier.factories.get(target).cloned().unwrap_or_default(), self.factories.get(target)?))
+        };
+
+        match metric {
+            Metric::FactoryProcessingMs => {
+                let (before, after) = pair()?;
+                let completed = after.completed.checked_sub(before.completed).filter(|n| *n > 0)?;
+                let total = after.avg_processing_ms * after.completed as f64 - before.avg_processing_ms * before.completed as f64;
+                Some((total / completed as f64).max(0.0))
+            }
+            Metric::FactoryFailureRate => {
+                let (before, after) = pair()?;
+                let requests = after.total_requests.checked_sub(before.total_requests).filter(|n| *n > 0)?;
+                Some(after.failed.saturating_sub(before.failed) as f64 / requests as f64)
+            }
+            _ => self.value(metric, target),
+        }
+    }
+
+    /// الأهداف التي يُقاس عليها المقياس (المصانع أو البوابات أو النظام ككل)
+    pub(crate) fn targets(&self, metric: Metric) -> Vec<Option<String>> {
+        if metric.is_per_factory() {
+            self.factories.keys().cloned().map(Some).collect()
+        } else if metric.is_per_gate() {
+            self.gates.keys().cloned().map(Some).collect()
+        } else {
+            vec![None]
+        }
+    }
+}
+
+/// مصادر القياس الحية
+#[derive(Clone)]
+pub struct MetricSources {
+    pub factory_manager: Arc<FactoryManager>,
+    pub monitor: Arc<SystemMonitor>,
+    pub quality: Option<Arc<RwLock<QualityManager>>>,
+}
+
+impl MetricSources {
+    pub fn new(factory_manager: Arc<FactoryManager>, monitor: Arc<SystemMonitor>) -> Self {
+        Self { factory_manager, monitor, quality: None }
+    }
+
+    pub fn with_quality(mut self, quality: Arc<RwLock<QualityManager>>) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// لقطة للنافذة التي تبدأ عند `since` (مع عينة فورية إن لم تسجل المراقبة شيئاً بعد)
+    pub async fn snapshot(&self, since: DateTime<Utc>, knobs: &RuntimeKnobs) -> MetricsSnapshot {
+        let mut samples = self.monitor.samples_since(since);
+        if samples.is_empty() {
+            samples.push(self.monitor.get_performance_metrics().await);
+        }
+
+        let factories = self.factory_manager.factory_metrics();
+        let manager = self.quality.as_ref().and_then(|q| q.read().ok());
+        let reports = manager.as_ref().map(|m| m.history().all()).unwrap_or_default();
+
+        MetricsSnapshot::from_parts(since, &samples, &factories, &reports, knobs)
+    }
+}
+
 /// محسن النظام
 pub struct Optimizer {
     strategies: Vec<OptimizationStrategy>,
-    history: Vec<OptimizationResult>,
+    knobs: Arc<RuntimeKnobs>,
+    evaluation_window: Duration,
+    regression_tolerance: f64,
+    state: Mutex<OptimizerState>,
+    /// تُضاف إليها التغييرات التي أُبقيت بعد قياسها
+    knowledge: Option<Arc<RwLock<KnowledgeBase>>>,
+    learning: AtomicBool,
+}
+
+/// السجل والتغييرات التي تنتظر نافذة القياس
+#[derive(Default)]
+struct OptimizerState {
+    history: OptimizationHistory,
+    pending: Vec<PendingRun>,
+}
+
+struct PendingRun {
+    run_id: String,
+    applied_at: DateTime<Utc>,
+    snapshot: MetricsSnapshot,
 }
 
+/// دالة الاستراتيجية: تقترح تغييراً لمقبض أو لا شيء
+type StrategyAction = Box<dyn Fn(&StrategyInput) -> Option<Proposal> + Send + Sync>;
+
 /// استراتيجية التحسين
 struct OptimizationStrategy {
     area: OptimizationArea,
     condition: OptimizationCondition,
-    action: Box<dyn Fn(Arc<FactoryManager>) -> Improvement + Send + Sync>,
+    action: StrategyAction,
     priority: u8,
 }
 
 /// حالة التحسين
 struct OptimizationCondition {
-    threshold: f32,
-    metric: String,
+    metric: Metric,
+    threshold: f64,
+    above: bool,
+}
+
+impl OptimizationCondition {
+    fn above(metric: Metric, threshold: f64) -> Self {
+        Self { metric, threshold, above: true }
+    }
+
+    fn below(metric: Metric, threshold: f64) -> Self {
+        Self { metric, threshold, above: false }
+    }
+
+    fn matches(&self, value: f64) -> bool {
+        if self.above { value > self.threshold } else { value < self.threshold }
+    }
+}
+
+/// ما تراه الاستراتيجية عند تحقق شرطها
+struct StrategyInput<'a> {
+    target: Option<&'a str>,
+    value: f64,
+    snapshot: &'a MetricsSnapshot,
+    knobs: &'a RuntimeKnobs,
+}
+
+/// تغيير مقترح
+struct Proposal {
+    knob: Knob,
+    value: f64,
+    action: String,
+    impact: f32,
+    objective: Option<Objective>,
 }
 
 impl Optimizer {
-    /// إنشاء محسن جديد
+    /// إنشاء محسن جديد يعمل على مقابض العملية المشتركة
     pub fn new() -> Self {
         let mut optimizer = Self {
             strategies: Vec::new(),
-            history: Vec::new(),
+            knobs: knobs(),
+            evaluation_window: Duration::minutes(5),
+            regression_tolerance: 0.1,
+            state: Mutex::new(OptimizerState::default()),
+            knowledge: None,
+            learning: AtomicBool::new(true),
         };
-        
+
         // تسجيل استراتيجيات التحسين
         optimizer.register_strategies();
-        
+
         optimizer
     }
-    
+
+    /// استخدام مجموعة مقابض محددة (للاختبار أو لعدة مثيلات)
+    pub fn with_knobs(mut self, knobs: Arc<RuntimeKnobs>) -> Self {
+        self.knobs = knobs;
+        self
+    }
+
+    /// استخدام سجل تحسين محفوظ
+    pub fn with_history(self, history: OptimizationHistory) -> Self {
+        self.lock_state().history = history;
+        self
+    }
+
+    /// طول نافذة القياس بعد كل تغيير
+    pub fn with_evaluation_window(mut self, window: Duration) -> Self {
+        self.evaluation_window = window;
+        self
+    }
+
+    /// تعلم قاعدة المعرفة من التحسينات النافعة
+    pub fn with_knowledge(mut self, knowledge: Arc<RwLock<KnowledgeBase>>) -> Self {
+        self.knowledge = Some(knowledge);
+        self
+    }
+
+    /// تفعيل/تعطيل التعلم من التحسينات النافعة
+    pub fn set_learning_enabled(&self, enabled: bool) {
+        self.learning.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn evaluation_window(&self) -> Duration {
+        self.evaluation_window
+    }
+
     /// تسجيل استراتيجيات التحسين
     fn register_strategies(&mut self) {
-        // استراتيجية تحسين الأداء
+        let response_time = || Some(Objective { metric: Metric::ResponseTimeMs, target: None, lower_is_better: true });
+
+        // زيادة عمال التصنيع بالدفعات عند بطء الاستجابة مع وجود معالج متاح
         self.strategies.push(OptimizationStrategy {
             area: OptimizationArea::Performance,
-            condition: OptimizationCondition {
-                threshold: 0.8,
-                metric: "response_time".to_string(),
-            },
-            action: Box::new(|factory_manager| {
-                Improvement {
-                    area: "الأداء".to_string(),
-                    action: "تحسين خوارزميات المعالجة".to_string(),
-                    impact: 0.15,
-                    details: "تم تحسين سرعة الاستجابة عن طريق تحسين الخوارزميات".to_string(),
+            condition: OptimizationCondition::above(Metric::ResponseTimeMs, 300.0),
+            action: Box::new(move |input| {
+                if input.snapshot.cpu_usage >= 0.75 {
+                    return None;
                 }
+                let current = input.knobs.get(&Knob::WorkerPoolSize);
+                Some(Proposal {
+                    knob: Knob::WorkerPoolSize,
+                    value: current + 2.0,
+                    action: format!("زيادة عمال التصنيع من {} إلى {}", current, current + 2.0),
+                    impact: 0.15,
+                    objective: response_time(),
+                })
             }),
             priority: 1,
         });
-        
-        // استراتيجية تحسين الذاكرة
+
+        // تفعيل التخزين المؤقت للمنتجات أو إطالة مدته عند بطء الاستجابة
+        self.strategies.push(OptimizationStrategy {
+            area: OptimizationArea::Performance,
+            condition: OptimizationCondition::above(Metric::ResponseTimeMs, 300.0),
+            action: Box::new(move |input| {
+                let current = input.knobs.get(&Knob::CacheTtlSeconds);
+                let value = if current <= 0.0 { 60.0 } else { current * 2.0 };
+                Some(Proposal {
+                    knob: Knob::CacheTtlSeconds,
+                    value,
+                    action: format!("مدة التخزين المؤقت {} ← {} ثانية", current, value.min(3600.0)),
+                    impact: 0.1,
+                    objective: response_time(),
+                })
+            }),
+            priority: 2,
+        });
+
+        // تقليص التخزين المؤقت عند ضغط الذاكرة
         self.strategies.push(OptimizationStrategy {
             area: OptimizationArea::Memory,
-            condition: OptimizationCondition {
-                threshold: 0.7,
-                metric: "memory_usage".to_string(),
-            },
-            action: Box::new(|factory_manager| {
-                Improvement {
-                    area: "الذاكرة".to_string(),
-                    action: "تحسين إدارة الذاكرة المؤقتة".to_string(),
-                    impact: 0.2,
-                    details: "تم تقليل استخدام الذاكرة عن طريق تحسين التخزين المؤقت".to_string(),
+            condition: OptimizationCondition::above(Metric::MemoryUsage, 0.85),
+            action: Box::new(|input| {
+                let current = input.knobs.get(&Knob::CacheTtlSeconds);
+                if current <= 0.0 {
+                    return None;
                 }
+                let value = if current < 20.0 { 0.0 } else { (current / 2.0).round() };
+                Some(Proposal {
+                    knob: Knob::CacheTtlSeconds,
+                    value,
+                    action: format!("تقليص مدة التخزين المؤقت {} ← {} ثانية", current, value),
+                    impact: 0.2,
+                    objective: Some(Objective { metric: Metric::MemoryUsage, target: None, lower_is_better: true }),
+                })
             }),
-            priority: 2,
+            priority: 0,
         });
-        
-        // استراتيجية تحسين الجودة
+
+        // رفع حد التزامن لمصنع يقترب طابوره من الحد
         self.strategies.push(OptimizationStrategy {
-            area: OptimizationArea::Quality,
-            condition: OptimizationCondition {
-                threshold: 0.85,
-                metric: "quality_score".to_string(),
-            },
-            action: Box::new(|factory_manager| {
-                let factories = factory_manager.list_factories();
-                let avg_quality = if !factories.is_empty() {
-                    factories.iter().map(|f| f.quality_score()).sum::<f32>() / factories.len() as f32
-                } else { 0.0 };
-                
-                Improvement {
-                    area: "الجودة".to_string(),
-                    action: "تحسين خوارزميات الجودة".to_string(),
-                    impact: 0.1,
-                    details: format!("تم تحسين متوسط الجودة من {:.1}%", avg_quality * 100.0),
+            area: OptimizationArea::Efficiency,
+            condition: OptimizationCondition::above(Metric::FactoryQueueUtilization, 0.8),
+            action: Box::new(|input| {
+                let factory = input.target?;
+                if input.snapshot.cpu_usage >= 0.75 {
+                    return None;
                 }
+                let knob = Knob::FactoryConcurrency(factory.to_string());
+                let current = input.knobs.get(&knob);
+                let value = (current * 1.5).ceil().max(current + 1.0);
+                Some(Proposal {
+                    knob,
+                    value,
+                    action: format!("رفع تزامن مصنع {} من {} إلى {} (الاستخدام {:.0}%)", factory, current, value, input.value * 100.0),
+                    impact: 0.12,
+                    objective: Some(Objective {
+                        metric: Metric::FactoryFailureRate,
+                        target: Some(factory.to_string()),
+                        lower_is_better: true,
+                    }),
+                })
             }),
             priority: 3,
         });
-        
-        // استراتيجية تحسين الكفاءة
+
+        // خفض التزامن لمصنع بطيء عندما يكون المعالج مشبعاً
         self.strategies.push(OptimizationStrategy {
             area: OptimizationArea::Efficiency,
-            condition: OptimizationCondition {
-                threshold: 0.75,
-                metric: "efficiency".to_string(),
-            },
-            action: Box::new(|factory_manager| {
-                Improvement {
-                    area: "الكفاءة".to_string(),
-                    action: "تحسين توزيع الموارد".to_string(),
-                    impact: 0.12,
-                    details: "تم تحسين كفاءة استخدام الموارد".to_string(),
+            condition: OptimizationCondition::above(Metric::FactoryProcessingMs, 1000.0),
+            action: Box::new(|input| {
+                let factory = input.target?;
+                if input.snapshot.cpu_usage < 0.9 {
+                    return None;
                 }
+                let knob = Knob::FactoryConcurrency(factory.to_string());
+                let current = input.knobs.get(&knob);
+                let value = (current * 0.75).floor();
+                Some(Proposal {
+                    knob,
+                    value,
+                    action: format!("خفض تزامن مصنع {} من {} إلى {}", factory, current, value),
+                    impact: 0.08,
+                    objective: Some(Objective {
+                        metric: Metric::FactoryProcessingMs,
+                        target: Some(factory.to_string()),
+                        lower_is_better: true,
+                    }),
+                })
             }),
             priority: 4,
         });
+
+        // تشغيل البوابات البطيئة المستقرة على عينة من الفحوصات
+        self.strategies.push(OptimizationStrategy {
+            area: OptimizationArea::Quality,
+            condition: OptimizationCondition::above(Metric::GateExecutionMs, 200.0),
+            action: Box::new(|input| {
+                let gate_id = input.target?;
+                let gate = input.snapshot.gates.get(gate_id)?;
+                if gate.pass_rate < 0.95 {
+                    return None;
+                }
+                let knob = Knob::GateSamplingRate(gate_id.to_string());
+                let current = input.knobs.get(&knob);
+                let value = current * 0.5;
+                Some(Proposal {
+                    knob,
+                    value,
+                    action: format!("تشغيل بوابة {} على {:.0}% من الفحوصات ({:.0}ms لكل فحص)", gate_id, value.max(0.1) * 100.0, input.value),
+                    impact: 0.1,
+                    objective: Some(Objective { metric: Metric::QualityScore, target: None, lower_is_better: false }),
+                })
+            }),
+            priority: 5,
+        });
+
+        // إعادة التشغيل الكامل لبوابة بدأت تفشل (تغيير أمان لا يُتراجع عنه)
+        self.strategies.push(OptimizationStrategy {
+            area: OptimizationArea::Quality,
+            condition: OptimizationCondition::below(Metric::GatePassRate, 0.8),
+            action: Box::new(|input| {
+                let gate_id = input.target?;
+                let knob = Knob::GateSamplingRate(gate_id.to_string());
+                if input.knobs.get(&knob) >= 1.0 {
+                    return None;
+                }
+                Some(Proposal {
+                    knob,
+                    value: 1.0,
+                    action: format!("إعادة تشغيل بوابة {} على كل الفحوصات (نسبة النجاح {:.0}%)", gate_id, input.value * 100.0),
+                    impact: 0.05,
+                    objective: None,
+                })
+            }),
+            priority: 0,
+        });
+
+        self.strategies.sort_by_key(|s| s.priority);
     }
-    
-    /// تنفيذ التحسين
-    pub async fn optimize(&self, factory_manager: Arc<FactoryManager>) -> OptimizationResult {
-        let start_time = std::time::Instant::now();
-        let mut improvements = Vec::new();
-        
+
+    fn lock_state(&self) -> MutexGuard<'_, OptimizerState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// تنفيذ التحسين: تقييم التغييرات السابقة المستحقة ثم تطبيق الاستراتيجيات على لقطة حية
+    pub async fn optimize(&self, sources: &MetricSources) -> OptimizationResult {
         log::info!("⚡ بدء عملية التحسين...");
-        
-        // جمع بيانات النظام
-        let system_metrics = self.collect_metrics(factory_manager.clone()).await;
-        
-        // تطبيق استراتيجيات التحسين
+
+        self.evaluate_due(sources).await;
+        let snapshot = sources.snapshot(Utc::now() - self.evaluation_window, &self.knobs).await;
+        let result = self.apply(snapshot);
+
+        log::info!("✅ انتهت عملية التحسين ({} تغيير)", result.improvements.len());
+        log::info!("📈 تحسين الأداء المتوقع: {:.1}%", result.performance_gain * 100.0);
+        log::info!("⭐ تحسين الجودة المتوقع: {:.1}%", result.quality_improvement * 100.0);
+
+        result
+    }
+
+    /// الاستراتيجيات التي تتحقق شروطها على اللقطة (تغيير واحد لكل مقبض)
+    fn plan(&self, snapshot: &MetricsSnapshot) -> Vec<(OptimizationArea, Proposal)> {
+        let mut proposals: Vec<(OptimizationArea, Proposal)> = Vec::new();
+
         for strategy in &self.strategies {
-            if self.should_apply_strategy(strategy, &system_metrics) {
-                let improvement = (strategy.action)(factory_manager.clone());
-                improvements.push(improvement);
-                log::debug!("تم تطبيق تحسين: {}", improvements.last().unwrap().area);
+            for target in snapshot.targets(strategy.condition.metric) {
+                let value = match self.should_apply_strategy(strategy, snapshot, target.as_deref()) {
+                    Some(value) => value,
+                    None => continue,
+                };
+
+                let input = StrategyInput { target: target.as_deref(), value, snapshot, knobs: &self.knobs };
+                if let Some(proposal) = (strategy.action)(&input) {
+                    let current = self.knobs.get(&proposal.knob);
+                    let unchanged = (proposal.knob.clamp_value(proposal.value) - current).abs() < f64::EPSILON;
+                    if !unchanged && !proposals.iter().any(|(_, p)| p.knob == proposal.knob) {
+                        proposals.push((strategy.area, proposal));
+                    }
+                }
             }
         }
-        
-        let duration = start_time.elapsed();
-        let performance_gain = self.calculate_performance_gain(&improvements);
-        let quality_improvement = self.calculate_quality_improvement(&improvements);
-        
-        let result = OptimizationResult {
-            success: !improvements.is_empty(),
-            improvements: improvements.clone(),
-            performance_gain,
-            quality_improvement,
-            duration_seconds: duration.as_secs_f64(),
-            timestamp: chrono::Utc::now(),
-        };
-        
-        // تسجيل النتيجة في السجل
-        self.record_result(result.clone());
-        
-        log::info!("✅ انتهت عملية التحسين بنجاح");
-        log::info!("📈 تحسين الأداء: {:.1}%", performance_gain * 100.0);
-        log::info!("⭐ تحسين الجودة: {:.1}%", quality_improvement * 100.0);
-        
+
+        proposals
+    }
+
+    /// تشغيل تجريبي: الاستراتيجيات التي ستُطبق وتأثيرها المتوقع دون تعديل أي مقبض
+    pub async fn dry_run(&self, sources: &MetricSources) -> OptimizationResult {
+        let snapshot = sources.snapshot(Utc::now() - self.evaluation_window, &self.knobs).await;
+        self.preview(snapshot)
+    }
+
+    /// تقييم الاستراتيجيات على لقطة دون تطبيقها أو تسجيلها
+    pub fn preview(&self, snapshot: MetricsSnapshot) -> OptimizationResult {
+        self.run(snapshot, true)
+    }
+
+    /// تطبيق الاستراتيجيات على لقطة وتسجيل النتيجة
+    pub fn apply(&self, snapshot: MetricsSnapshot) -> OptimizationResult {
+        let result = self.run(snapshot, false);
+
+        let mut state = self.lock_state();
+        if result.improvements.iter().any(|i| i.outcome == ChangeOutcome::Pending) {
+            state.pending.push(PendingRun {
+                run_id: result.run_id.clone(),
+                applied_at: result.timestamp,
+                snapshot: result.snapshot.clone(),
+            });
+        }
+        Self::record_result(&mut state, result.clone());
+
         result
     }
-    
-    /// جمع مقاييس النظام
-    async fn collect_metrics(&self, factory_manager: Arc<FactoryManager>) -> SystemMetrics {
-        let factories = factory_manager.list_factories();
-        
-        let quality_scores: Vec<f32> = factories.iter()
-            .map(|f| f.quality_score())
+
+    fn run(&self, snapshot: MetricsSnapshot, dry_run: bool) -> OptimizationResult {
+        let start_time = std::time::Instant::now();
+        let mut improvements = Vec::new();
+
+        for (area, proposal) in self.plan(&snapshot) {
+            let before_value = self.knobs.get(&proposal.knob);
+            let after_value = if dry_run {
+                proposal.knob.clamp_value(proposal.value)
+            } else {
+                self.knobs.set(&proposal.knob, proposal.value)
+            };
+            let baseline = proposal.objective.as_ref()
+                .and_then(|o| snapshot.value(o.metric, o.target.as_deref()));
+
+            let outcome = if dry_run {
+                ChangeOutcome::Predicted
+            } else {
+                log::info!("🔧 {}: {} ({} ← {})", area.display_name(), proposal.knob, after_value, before_value);
+                if proposal.objective.is_some() { ChangeOutcome::Pending } else { ChangeOutcome::Kept }
+            };
+
+            improvements.push(Improvement {
+                area: area.display_name().to_string(),
+                details: format!("{}: {} ← {}", proposal.knob, after_value, before_value),
+                action: proposal.action,
+                impact: proposal.impact,
+                knob: proposal.knob,
+                before_value,
+                after_value,
+                outcome,
+                objective: proposal.objective,
+                baseline,
+                measured: None,
+            });
+        }
+
+        OptimizationResult {
+            run_id: format!("opt_{}", crate::core::telemetry::random_hex(6)),
+            dry_run,
+            success: !improvements.is_empty(),
+            performance_gain: self.calculate_performance_gain(&improvements),
+            quality_improvement: self.calculate_quality_improvement(&improvements),
+            improvements,
+            duration_seconds: start_time.elapsed().as_secs_f64(),
+            timestamp: Utc::now(),
+            snapshot,
+        }
+    }
+
+    /// قيمة مقياس الشرط إن تحقق الشرط
+    fn should_apply_strategy(&self, strategy: &OptimizationStrategy, metrics: &MetricsSnapshot, target: Option<&str>) -> Option<f64> {
+        metrics.value(strategy.condition.metric, target)
+            .filter(|value| strategy.condition.matches(*value))
+    }
+
+    /// تقييم التشغيلات التي انقضت نافذة قياسها
+    pub async fn evaluate_due(&self, sources: &MetricSources) -> Vec<Improvement> {
+        let now = Utc::now();
+        let due: Vec<(String, DateTime<Utc>)> = self.lock_state().pending.iter()
+            .filter(|run| run.applied_at + self.evaluation_window <= now)
+            .map(|run| (run.run_id.clone(), run.applied_at))
             .collect();
-        
-        let avg_quality = if !quality_scores.is_empty() {
-            quality_scores.iter().sum::<f32>() / quality_scores.len() as f32
-        } else { 0.0 };
-        
-        SystemMetrics {
-            response_time: 120.0, // محاكاة
-            memory_usage: 0.65,
-            quality_score: avg_quality,
-            efficiency: 0.78,
-            active_factories: factories.len(),
+
+        let mut evaluated = Vec::new();
+        for (run_id, applied_at) in due {
+            let after = sources.snapshot(applied_at, &self.knobs).await;
+            evaluated.extend(self.evaluate_run(&run_id, &after));
         }
+        evaluated
     }
-    
-    /// التحقق مما إذا كان يجب تطبيق الاستراتيجية
-    fn should_apply_strategy(&self, strategy: &OptimizationStrategy, metrics: &SystemMetrics) -> bool {
-        let metric_value = match strategy.condition.metric.as_str() {
-            "response_time" => 1.0 - (metrics.response_time / 1000.0).min(1.0),
-            "memory_usage" => 1.0 - metrics.memory_usage,
-            "quality_score" => metrics.quality_score,
-            "efficiency" => metrics.efficiency,
-            _ => 0.0,
+
+    /// مقارنة أهداف تشغيل بلقطة النافذة التالية والتراجع عما ساء منها
+    pub fn evaluate_run(&self, run_id: &str, after: &MetricsSnapshot) -> Vec<Improvement> {
+        let mut state = self.lock_state();
+        let position = match state.pending.iter().position(|run| run.run_id == run_id) {
+            Some(position) => position,
+            None => return Vec::new(),
         };
-        
-        metric_value < strategy.condition.threshold
+
+        let run = state.pending.remove(position);
+        // بعد ثلاث نوافذ دون بيانات كافية يُبقى التغيير
+        let give_up = after.captured_at >= run.applied_at + self.evaluation_window * 3;
+        let mut evaluated = Vec::new();
+        let mut still_pending = false;
+
+        if let Some(result) = state.history.get_mut(run_id) {
+            for improvement in result.improvements.iter_mut().filter(|i| i.outcome == ChangeOutcome::Pending) {
+                let objective = match improvement.objective.clone() {
+                    Some(objective) => objective,
+                    None => continue,
+                };
+                let measured = after.value_since(&run.snapshot, objective.metric, objective.target.as_deref());
+
+                let regressed = match (improvement.baseline, measured) {
+                    (Some(baseline), Some(measured)) => {
+                        let margin = baseline.abs() * self.regression_tolerance;
+                        if objective.lower_is_better { measured > baseline + margin } else { measured < baseline - margin }
+                    }
+                    _ if give_up => false,
+                    _ => {
+                        still_pending = true;
+                        continue;
+                    }
+                };
+
+                improvement.measured = measured;
+                // لا يُتراجع إن غيّر أحد المقبض منذ ذلك الحين
+                if regressed && (self.knobs.get(&improvement.knob) - improvement.after_value).abs() < f64::EPSILON {
+                    self.knobs.set(&improvement.knob, improvement.before_value);
+                    improvement.outcome = ChangeOutcome::RolledBack;
+                    log::warn!("↩️ تراجع عن {} ({} ← {}): ساء {:?} من {:.3} إلى {:.3}",
+                        improvement.knob, improvement.before_value, improvement.after_value,
+                        objective.metric, improvement.baseline.unwrap_or_default(), measured.unwrap_or_default());
+                } else {
+                    improvement.outcome = ChangeOutcome::Kept;
+                }
+                evaluated.push(improvement.clone());
+            }
+        }
+
+        if still_pending {
+            state.pending.push(run);
+        }
+        if !evaluated.is_empty() {
+            Self::save_history(&state);
+        }
+        drop(state);
+        self.learn_from(run_id, &evaluated);
+        evaluated
+    }
+
+    /// إضافة التغييرات التي أُبقيت إلى قاعدة المعرفة
+    fn learn_from(&self, run_id: &str, evaluated: &[Improvement]) {
+        let knowledge = match &self.knowledge {
+            Some(knowledge) if self.learning.load(Ordering::Relaxed) => knowledge,
+            _ => return,
+        };
+        if !evaluated.iter().any(|i| i.outcome == ChangeOutcome::Kept) {
+            return;
+        }
+
+        if let Ok(mut knowledge) = knowledge.write() {
+            if let Err(e) = knowledge.reload_if_changed() {
+                log::error!("❌ تعذر إعادة تحميل قاعدة المعرفة: {}", e);
+            }
+            for improvement in evaluated {
+                knowledge.learn_from_improvement(run_id, improvement);
+            }
+        }
+    }
+
+    /// تقييم دوري في الخلفية طوال عمر الخادم
+    pub async fn run_evaluations(self: Arc<Self>, sources: MetricSources) {
+        let period = (self.evaluation_window / 5).to_std().unwrap_or(std::time::Duration::from_secs(60));
+        loop {
+            tokio::time::sleep(period.max(std::time::Duration::from_secs(10))).await;
+            self.evaluate_due(&sources).await;
+        }
     }
-    
+
     /// حساب تحسين الأداء
     fn calculate_performance_gain(&self, improvements: &[Improvement]) -> f32 {
         improvements.iter()
@@ -229,7 +853,7 @@ impl Optimizer {
             .map(|imp| imp.impact)
             .sum()
     }
-    
+
     /// حساب تحسين الجودة
     fn calculate_quality_improvement(&self, improvements: &[Improvement]) -> f32 {
         improvements.iter()
@@ -237,62 +861,189 @@ impl Optimizer {
             .map(|imp| imp.impact)
             .sum()
     }
-    
-    /// تسجيل نتيجة التحسين
-    fn record_result(&mut self, result: OptimizationResult) {
-        self.history.push(result);
-        
-        // الاحتفاظ بأخر 50 نتيجة فقط
-        if self.history.len() > 50 {
-            self.history.remove(0);
+
+    /// تسجيل نتيجة التحسين وحفظ السجل
+    fn record_result(state: &mut OptimizerState, result: OptimizationResult) {
+        state.history.record(result);
+        Self::save_history(state);
+    }
+
+    fn save_history(state: &OptimizerState) {
+        if let Err(e) = state.history.save() {
+            log::error!("❌ تعذر حفظ سجل التحسين: {}", e);
         }
     }
-    
-    /// الحصول على سجل التحسين
-    pub fn get_optimization_history(&self) -> &[OptimizationResult] {
-        &self.history
+
+    /// الحصول على سجل التحسين (من الأقدم إلى الأحدث)
+    pub fn get_optimization_history(&self) -> Vec<OptimizationResult> {
+        self.lock_state().history.all().to_vec()
+    }
+
+    /// آخر عمليات التحسين من الأحدث إلى الأقدم
+    pub fn recent_runs(&self, limit: usize) -> Vec<OptimizationResult> {
+        self.lock_state().history.recent(limit)
     }
-    
+
     /// تحليل احتياجات التحسين
-    pub async fn analyze_optimization_needs(&self, factory_manager: Arc<FactoryManager>) -> Vec<String> {
-        let metrics = self.collect_metrics(factory_manager).await;
+    pub async fn analyze_optimization_needs(&self, sources: &MetricSources) -> Vec<String> {
+        let metrics = sources.snapshot(Utc::now() - self.evaluation_window, &self.knobs).await;
         let mut needs = Vec::new();
-        
-        if metrics.response_time > 200.0 {
+
+        if metrics.response_time_ms > 200.0 {
             needs.push("تحسين سرعة الاستجابة".to_string());
         }
-        
+
         if metrics.memory_usage > 0.8 {
             needs.push("تحسين استخدام الذاكرة".to_string());
         }
-        
-        if metrics.quality_score < 0.8 {
+
+        if metrics.quality_score.map(|q| q < 0.8).unwrap_or(false) {
             needs.push("تحسين جودة الإنتاج".to_string());
         }
-        
-        if metrics.efficiency < 0.7 {
+
+        if metrics.factories.values().any(|f| f.queue_size as f64 >= f.concurrency as f64 * 0.8) {
             needs.push("تحسين كفاءة النظام".to_string());
         }
-        
+
         if needs.is_empty() {
             needs.push("النظام يعمل بشكل ممتاز".to_string());
         }
-        
+
         needs
     }
-    
+
     /// إعادة تعيين المحسن
-    pub fn reset(&mut self) {
-        self.history.clear();
-        log::info("🔄 تم إعادة تعيين المحسن");
+    pub fn reset(&self) {
+        let mut state = self.lock_state();
+        state.history.clear();
+        state.pending.clear();
+        Self::save_history(&state);
+        log::info!("🔄 تم إعادة تعيين المحسن");
+    }
+}
+
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-/// مقاييس النظام
-struct SystemMetrics {
-    response_time: f32,
-    memory_usage: f32,
-    quality_score: f32,
-    efficiency: f32,
-    active_factories: usize,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(response_time_ms: f64, factory: Option<(&str, usize, u64, u64)>) -> MetricsSnapshot {
+        let mut snapshot = MetricsSnapshot {
+            captured_at: Utc::now(),
+            window_start: Utc::now() - Duration::minutes(5),
+            samples: 10,
+            response_time_ms,
+            cpu_usage: 0.4,
+            memory_usage: 0.5,
+            throughput: 20.0,
+            quality_score: None,
+            factories: BTreeMap::new(),
+            gates: BTreeMap::new(),
+        };
+        if let Some((name, queue_size, total, failed)) = factory {
+            snapshot.factories.insert(name.to_string(), FactorySnapshot {
+                total_requests: total,
+                completed: total,
+                failed,
+                queue_size,
+                concurrency: 4,
+                avg_processing_ms: 50.0,
+            });
+        }
+        snapshot
+    }
+
+    fn optimizer_with_concurrency(concurrency: f64) -> (Optimizer, Arc<RuntimeKnobs>) {
+        let knobs = Arc::new(RuntimeKnobs::new());
+        knobs.set(&Knob::FactoryConcurrency("education".to_string()), concurrency);
+        (Optimizer::new().with_knobs(knobs.clone()), knobs)
+    }
+
+    #[test]
+    fn test_full_queue_raises_concurrency_only() {
+        let (optimizer, knobs) = optimizer_with_concurrency(4.0);
+        // استجابة سريعة وطابور ممتلئ
+        let result = optimizer.apply(snapshot(120.0, Some(("education", 4, 100, 10))));
+
+        assert_eq!(result.improvements.len(), 1);
+        assert_eq!(result.improvements[0].knob, Knob::FactoryConcurrency("education".to_string()));
+        assert_eq!(knobs.factory_concurrency("education"), 6);
+    }
+
+    #[test]
+    fn test_regression_rolls_back_change() {
+        let (optimizer, knobs) = optimizer_with_concurrency(4.0);
+        let result = optimizer.apply(snapshot(120.0, Some(("education", 4, 100, 10))));
+
+        // نسبة الفشل في النافذة التالية ساءت (40 فشل من 100)
+        let mut after = snapshot(120.0, Some(("education", 2, 200, 50)));
+        after.captured_at = Utc::now() + Duration::minutes(6);
+        let evaluated = optimizer.evaluate_run(&result.run_id, &after);
+
+        assert_eq!(evaluated.len(), 1);
+        assert_eq!(evaluated[0].outcome, ChangeOutcome::RolledBack);
+        assert_eq!(evaluated[0].measured, Some(0.4));
+        assert_eq!(knobs.factory_concurrency("education"), 4);
+    }
+
+    #[test]
+    fn test_improvement_keeps_changes() {
+        let (optimizer, knobs) = optimizer_with_concurrency(4.0);
+        // استجابة بطيئة: زيادة العمال وتفعيل التخزين المؤقت
+        let result = optimizer.apply(snapshot(800.0, None));
+        assert_eq!(result.improvements.len(), 2);
+        assert_eq!(knobs.get(&Knob::CacheTtlSeconds), 60.0);
+
+        let evaluated = optimizer.evaluate_run(&result.run_id, &snapshot(400.0, None));
+        assert!(evaluated.iter().all(|i| i.outcome == ChangeOutcome::Kept));
+        assert_eq!(knobs.get(&Knob::CacheTtlSeconds), 60.0);
+        assert_eq!(optimizer.get_optimization_history().len(), 1);
+    }
+
+    #[test]
+    fn test_dry_run_predicts_without_applying() {
+        let knobs = Arc::new(RuntimeKnobs::new());
+        let optimizer = Optimizer::new().with_knobs(knobs.clone());
+
+        let preview = optimizer.preview(snapshot(800.0, None));
+        assert!(preview.dry_run);
+        assert_eq!(preview.improvements.len(), 2);
+        assert!(preview.improvements.iter().all(|i| i.outcome == ChangeOutcome::Predicted));
+        assert_eq!(knobs.get(&Knob::CacheTtlSeconds), 0.0);
+        assert!(optimizer.get_optimization_history().is_empty());
+    }
+
+    #[test]
+    fn test_history_persists_run_with_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("optimizations.json");
+        let optimizer = Optimizer::new()
+            .with_knobs(Arc::new(RuntimeKnobs::new()))
+            .with_history(OptimizationHistory::open(&path).unwrap());
+
+        let result = optimizer.apply(snapshot(800.0, None));
+        let reopened = OptimizationHistory::open(&path).unwrap();
+        assert_eq!(reopened.len(), 1);
+        assert_eq!(reopened.all()[0].run_id, result.run_id);
+        assert_eq!(reopened.all()[0].snapshot.response_time_ms, 800.0);
+    }
+
+    #[test]
+    fn test_history_persists_evaluated_outcomes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("optimizations.json");
+        let optimizer = Optimizer::new()
+            .with_knobs(Arc::new(RuntimeKnobs::new()))
+            .with_history(OptimizationHistory::open(&path).unwrap());
+
+        let result = optimizer.apply(snapshot(800.0, None));
+        optimizer.evaluate_run(&result.run_id, &snapshot(400.0, None));
+        let reopened = OptimizationHistory::open(&path).unwrap();
+        assert!(reopened.all()[0].improvements.iter().all(|i| i.outcome == ChangeOutcome::Kept));
+    }
 }
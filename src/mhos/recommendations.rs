@@ -0,0 +1,527 @@
+//! محرك التوصيات
+//!
+//! قواعد تُقيَّم على لقطة المقاييس وتقارير الجودة وسجل الإنذارات، وتُصدر
+//! توصيات بخطورة وأدلة وإجراء مقترح (قد يكون تشغيل استراتيجيات المحسن).
+//! تُتتبع التوصيات بين التقييمات فلا تتكرر: التوصية القائمة يُحدَّث آخر
+//! ظهور لها وعدد مراته، والمرفوضة لا تعود قبل انقضاء فترة التهدئة.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Mutex, MutexGuard};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use crate::core::quality::QualityReport;
+use crate::core::quality_history::QualityRegression;
+use crate::core::tuning::knobs;
+use crate::mhos::monitor::SystemAlert;
+use crate::mhos::optimizer::{MetricSources, Metric, MetricsSnapshot};
+
+/// عدد تقارير الجودة الأخيرة التي تُقرأ عند التقييم
+const RECENT_REPORTS: usize = 50;
+
+/// خطورة التوصية
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecommendationSeverity {
+    Info,
+    Warning,
+    High,
+    Critical,
+}
+
+impl RecommendationSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationSeverity::Info => "info",
+            RecommendationSeverity::Warning => "warning",
+            RecommendationSeverity::High => "high",
+            RecommendationSeverity::Critical => "critical",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            RecommendationSeverity::Info => "معلومة",
+            RecommendationSeverity::Warning => "تحذير",
+            RecommendationSeverity::High => "مرتفعة",
+            RecommendationSeverity::Critical => "حرجة",
+        }
+    }
+}
+
+/// دليل استندت إليه التوصية
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Evidence {
+    /// مصدر الدليل: metrics أو quality أو alerts
+    pub source: String,
+    pub description: String,
+    pub value: f64,
+    pub threshold: Option<f64>,
+}
+
+impl Evidence {
+    pub fn new(source: &str, description: impl Into<String>, value: f64, threshold: Option<f64>) -> Self {
+        Self {
+            source: source.to_string(),
+            description: description.into(),
+            value,
+            threshold,
+        }
+    }
+}
+
+/// الإجراء المقترح
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SuggestedAction {
+    /// تشغيل المحسن؛ استراتيجياته على هذا المقياس تعالج الحالة
+    Optimize { metric: Metric },
+    /// فحص جودة موضوع التوصية (المصنع)
+    QualityCheck,
+    /// إجراء يدوي
+    Manual { description: String },
+}
+
+/// شرط قاعدة
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    /// مقياس من اللقطة فوق حد أو تحته (لكل مصنع أو بوابة إن كان المقياس كذلك)
+    Metric { metric: Metric, threshold: f64, above: bool },
+    /// بوابة فشلت مرات عدة في آخر التقارير
+    GateFailures { min_failures: usize, last_reports: usize },
+    /// تراجع في جودة مصنع مقارنة بخط الأساس
+    QualityRegression,
+    /// إنذار تكرر ظهوره في الفترة
+    RecurringAlert { min_occurrences: u32 },
+}
+
+/// نتيجة تحقق شرط لموضوع معين
+struct Finding {
+    subject: Option<String>,
+    evidence: Evidence,
+}
+
+impl RuleCondition {
+    fn evaluate(&self, input: &RecommendationInput) -> Vec<Finding> {
+        match self {
+            RuleCondition::Metric { metric, threshold, above } => {
+                input.snapshot.targets(*metric).into_iter()
+                    .filter_map(|target| {
+                        let value = input.snapshot.value(*metric, target.as_deref())?;
+                        let matched = if *above { value > *threshold } else { value < *threshold };
+                        matched.then(|| Finding {
+                            evidence: Evidence::new(
+                                "metrics",
+                                format!("{} {:.2} (الحد {:.2})", metric.display_name(), value, threshold),
+                                value,
+                                Some(*threshold)
+                            ),
+                            subject: target,
+                        })
+                    })
+                    .collect()
+            }
+            RuleCondition::GateFailures { min_failures, last_reports } => {
+                let start = input.reports.len().saturating_sub(*last_reports);
+                let mut failures: BTreeMap<&str, (usize, &str)> = BTreeMap::new();
+                for report in &input.reports[start..] {
+                    for gate in report.gate_results.values().filter(|g| !g.passed) {
+                        failures.entry(&gate.gate_id).or_insert((0, &gate.gate_name)).0 += 1;
+                    }
+                }
+                failures.into_iter()
+                    .filter(|(_, (count, _))| count >= min_failures)
+                    .map(|(gate_id, (count, gate_name))| Finding {
+                        subject: Some(gate_id.to_string()),
+                        evidence: Evidence::new(
+                            "quality",
+                            format!("فشلت بوابة {} في {} من آخر {} تقرير", gate_name, count, input.reports.len() - start),
+                            count as f64,
+                            Some(*min_failures as f64)
+                        ),
+                    })
+                    .collect()
+            }
+            RuleCondition::QualityRegression => {
+                input.regressions.iter().map(|regression| Finding {
+                    subject: Some(regression.factory.clone()),
+                    evidence: Evidence::new(
+                        "quality",
+                        format!("تراجع {} من {:.2} إلى {:.2}", regression.gate_name, regression.baseline, regression.current),
+                        regression.current,
+                        Some(regression.baseline)
+                    ),
+                }).collect()
+            }
+            RuleCondition::RecurringAlert { min_occurrences } => {
+                input.alerts.iter()
+                    .filter(|alert| alert.occurrences >= *min_occurrences)
+                    .map(|alert| Finding {
+                        subject: Some(alert.id.clone()),
+                        evidence: Evidence::new(
+                            "alerts",
+                            format!("الإنذار \"{}\" ظهر {} مرة", alert.title, alert.occurrences),
+                            alert.occurrences as f64,
+                            Some(*min_occurrences as f64)
+                        ),
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+/// قاعدة توصية
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecommendationRule {
+    pub id: String,
+    pub title: String,
+    pub severity: RecommendationSeverity,
+    pub condition: RuleCondition,
+    pub action: SuggestedAction,
+}
+
+impl RecommendationRule {
+    pub fn new(id: &str, title: &str, severity: RecommendationSeverity, condition: RuleCondition, action: SuggestedAction) -> Self {
+        Self {
+            id: id.to_string(),
+            title: title.to_string(),
+            severity,
+            condition,
+            action,
+        }
+    }
+
+    /// القواعد الافتراضية
+    pub fn defaults() -> Vec<RecommendationRule> {
+        use RecommendationSeverity::*;
+        let metric = |metric, threshold, above| RuleCondition::Metric { metric, threshold, above };
+        let manual = |description: &str| SuggestedAction::Manual { description: description.to_string() };
+
+        vec![
+            Self::new("slow_responses", "زمن الاستجابة مرتفع", Warning,
+                metric(Metric::ResponseTimeMs, 500.0, true),
+                SuggestedAction::Optimize { metric: Metric::ResponseTimeMs }),
+            Self::new("cpu_saturation", "المعالج شبه مشبع", High,
+                metric(Metric::CpuUsage, 0.85, true),
+                manual("تقليل الحمل على المعالج أو زيادة الموارد")),
+            Self::new("memory_pressure", "ضغط على الذاكرة", High,
+                metric(Metric::MemoryUsage, 0.85, true),
+                SuggestedAction::Optimize { metric: Metric::MemoryUsage }),
+            Self::new("factory_queue_saturation", "طابور المصنع يقترب من حد التزامن", Warning,
+                metric(Metric::FactoryQueueUtilization, 0.8, true),
+                SuggestedAction::Optimize { metric: Metric::FactoryQueueUtilization }),
+            Self::new("slow_factory", "المصنع بطيء في المعالجة", Warning,
+                metric(Metric::FactoryProcessingMs, 1000.0, true),
+                SuggestedAction::Optimize { metric: Metric::FactoryProcessingMs }),
+            Self::new("factory_failures", "معدل فشل المصنع مرتفع", High,
+                metric(Metric::FactoryFailureRate, 0.1, true),
+                manual("مراجعة أخطاء المصنع في السجلات")),
+            Self::new("low_quality", "نتيجة الجودة دون الحد", Warning,
+                metric(Metric::QualityScore, 0.85, false),
+                manual("مراجعة البوابات الفاشلة في تقارير الجودة")),
+            Self::new("failing_gate", "بوابة جودة تفشل باستمرار", Warning,
+                RuleCondition::GateFailures { min_failures: 3, last_reports: 10 },
+                manual("مراجعة معايير البوابة أو مخرجات المصانع التي تفحصها")),
+            Self::new("quality_regression", "تراجع في جودة المصنع", High,
+                RuleCondition::QualityRegression,
+                SuggestedAction::QualityCheck),
+            Self::new("recurring_alert", "إنذار متكرر", Warning,
+                RuleCondition::RecurringAlert { min_occurrences: 5 },
+                manual("معالجة سبب الإنذار أو ضبط حده")),
+        ]
+    }
+}
+
+/// توصية صادرة
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recommendation {
+    /// القاعدة والموضوع معاً (مفتاح إزالة التكرار)
+    pub id: String,
+    pub rule_id: String,
+    /// المصنع أو البوابة أو الإنذار المعني (إن وُجد)
+    pub subject: Option<String>,
+    pub severity: RecommendationSeverity,
+    pub title: String,
+    pub evidence: Vec<Evidence>,
+    pub action: SuggestedAction,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub occurrences: u32,
+}
+
+impl Recommendation {
+    pub fn new(rule_id: &str, subject: Option<String>, severity: RecommendationSeverity, title: &str, action: SuggestedAction) -> Self {
+        let now = Utc::now();
+        Self {
+            id: match &subject {
+                Some(subject) => format!("{}:{}", rule_id, subject),
+                None => rule_id.to_string(),
+            },
+            rule_id: rule_id.to_string(),
+            subject,
+            severity,
+            title: title.to_string(),
+            evidence: Vec::new(),
+            action,
+            first_seen: now,
+            last_seen: now,
+            occurrences: 1,
+        }
+    }
+
+    pub fn with_evidence(mut self, evidence: Evidence) -> Self {
+        self.evidence.push(evidence);
+        self
+    }
+
+    /// سطر نصي مختصر (العنوان والموضوع)
+    pub fn summary(&self) -> String {
+        match &self.subject {
+            Some(subject) => format!("{}: {}", self.title, subject),
+            None => self.title.clone(),
+        }
+    }
+}
+
+/// ما تُقيَّم عليه القواعد
+#[derive(Debug, Clone)]
+pub struct RecommendationInput {
+    pub snapshot: MetricsSnapshot,
+    /// تقارير الجودة الأخيرة (الأقدم أولاً)
+    pub reports: Vec<QualityReport>,
+    pub regressions: Vec<QualityRegression>,
+    pub alerts: Vec<SystemAlert>,
+}
+
+impl RecommendationInput {
+    pub fn new(snapshot: MetricsSnapshot) -> Self {
+        Self {
+            snapshot,
+            reports: Vec::new(),
+            regressions: Vec::new(),
+            alerts: Vec::new(),
+        }
+    }
+
+    pub fn with_reports(mut self, reports: Vec<QualityReport>) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    pub fn with_regressions(mut self, regressions: Vec<QualityRegression>) -> Self {
+        self.regressions = regressions;
+        self
+    }
+
+    pub fn with_alerts(mut self, alerts: Vec<SystemAlert>) -> Self {
+        self.alerts = alerts;
+        self
+    }
+
+    /// جمع المدخلات من المصادر الحية للفترة التي تبدأ عند `since`
+    pub async fn collect(sources: &MetricSources, since: DateTime<Utc>) -> Self {
+        let snapshot = sources.snapshot(since, &knobs()).await;
+        let alerts = sources.monitor.list_alerts(None)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|alert| alert.last_seen >= since)
+            .collect();
+
+        let mut input = Self::new(snapshot).with_alerts(alerts);
+        if let Some(manager) = sources.quality.as_ref().and_then(|q| q.read().ok()) {
+            let history = manager.history();
+            let reports = history.all();
+            let start = reports.len().saturating_sub(RECENT_REPORTS);
+            input.reports = reports[start..].iter().map(|r| (*r).clone()).collect();
+            input.regressions = history.regressions(None).into_iter()
+                .filter(|r| r.detected_at >= since)
+                .cloned()
+                .collect();
+        }
+        input
+    }
+}
+
+/// حالة التتبع بين التقييمات
+#[derive(Default)]
+struct EngineState {
+    tracked: BTreeMap<String, Recommendation>,
+    dismissed: HashMap<String, DateTime<Utc>>,
+}
+
+/// محرك التوصيات
+pub struct RecommendationEngine {
+    rules: Vec<RecommendationRule>,
+    /// مدة بقاء توصية متتبعة لم تعد شروطها تتحقق
+    expiry: Duration,
+    /// مدة كتم التوصية المرفوضة
+    cooldown: Duration,
+    state: Mutex<EngineState>,
+}
+
+impl RecommendationEngine {
+    pub fn new() -> Self {
+        Self {
+            rules: RecommendationRule::defaults(),
+            expiry: Duration::hours(1),
+            cooldown: Duration::hours(24),
+            state: Mutex::new(EngineState::default()),
+        }
+    }
+
+    pub fn with_rules(mut self, rules: Vec<RecommendationRule>) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn with_expiry(mut self, expiry: Duration) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    pub fn with_cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    pub fn rules(&self) -> &[RecommendationRule] {
+        &self.rules
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, EngineState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// تقييم القواعد دون تتبع (كل توصية جديدة)، الأخطر أولاً
+    pub fn assess(&self, input: &RecommendationInput) -> Vec<Recommendation> {
+        let mut recommendations: BTreeMap<String, Recommendation> = BTreeMap::new();
+
+        for rule in &self.rules {
+            for finding in rule.condition.evaluate(input) {
+                let candidate = Recommendation::new(&rule.id, finding.subject, rule.severity, &rule.title, rule.action.clone());
+                recommendations.entry(candidate.id.clone())
+                    .or_insert(candidate)
+                    .evidence
+                    .push(finding.evidence);
+            }
+        }
+
+        let mut recommendations: Vec<Recommendation> = recommendations.into_values().collect();
+        recommendations.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+        recommendations
+    }
+
+    /// تقييم مع إزالة التكرار: التوصيات الحالية بتاريخ أول ظهور وعدد مرات تحققها
+    pub fn evaluate(&self, input: &RecommendationInput) -> Vec<Recommendation> {
+        let now = Utc::now();
+        let mut state = self.lock_state();
+        let cooldown = self.cooldown;
+        state.dismissed.retain(|_, at| now - *at < cooldown);
+
+        let mut current = Vec::new();
+        for mut recommendation in self.assess(input) {
+            if state.dismissed.contains_key(&recommendation.id) {
+                continue;
+            }
+            if let Some(previous) = state.tracked.get(&recommendation.id) {
+                recommendation.first_seen = previous.first_seen;
+                recommendation.occurrences = previous.occurrences + 1;
+            } else {
+                log::info!("💡 توصية جديدة: {} [{}]", recommendation.summary(), recommendation.severity.as_str());
+            }
+            state.tracked.insert(recommendation.id.clone(), recommendation.clone());
+            current.push(recommendation);
+        }
+
+        let expiry = self.expiry;
+        state.tracked.retain(|_, r| now - r.last_seen < expiry);
+        current
+    }
+
+    /// التوصيات المتتبعة حالياً دون تقييم جديد
+    pub fn tracked(&self) -> Vec<Recommendation> {
+        let mut tracked: Vec<Recommendation> = self.lock_state().tracked.values().cloned().collect();
+        tracked.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.id.cmp(&b.id)));
+        tracked
+    }
+
+    /// رفض توصية: تُزال ولا تعود قبل انقضاء فترة التهدئة
+    pub fn dismiss(&self, id: &str) -> bool {
+        let mut state = self.lock_state();
+        let removed = state.tracked.remove(id).is_some();
+        if removed {
+            state.dismissed.insert(id.to_string(), Utc::now());
+        }
+        removed
+    }
+
+    /// تقييم على المصادر الحية لآخر `window`
+    pub async fn evaluate_sources(&self, sources: &MetricSources, window: Duration) -> Vec<Recommendation> {
+        let input = RecommendationInput::collect(sources, Utc::now() - window).await;
+        self.evaluate(&input)
+    }
+}
+
+impl Default for RecommendationEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::factory::FactoryMetrics;
+    use crate::core::tuning::RuntimeKnobs;
+    use crate::mhos::monitor::SystemMonitor;
+
+    async fn input(response_time: f32, failed_requests: u64) -> RecommendationInput {
+        let mut sample = SystemMonitor::new().get_performance_metrics().await;
+        sample.response_time = response_time;
+        sample.cpu_usage = 0.2;
+        sample.memory_usage = 0.3;
+
+        let mut factories = HashMap::new();
+        factories.insert("education".to_string(), FactoryMetrics {
+            total_requests: 10,
+            successful_requests: 10 - failed_requests,
+            failed_requests,
+            avg_processing_time_ms: 20.0,
+            current_queue_size: 0,
+            memory_usage_mb: 0.0,
+            last_reset: Utc::now(),
+        });
+
+        let snapshot = MetricsSnapshot::from_parts(Utc::now() - Duration::minutes(5), &[sample], &factories, &[], &RuntimeKnobs::new());
+        RecommendationInput::new(snapshot)
+    }
+
+    #[tokio::test]
+    async fn test_rules_emit_evidence_and_deduplicate() {
+        let engine = RecommendationEngine::new();
+
+        let first = engine.evaluate(&input(800.0, 3).await);
+        let ids: Vec<&str> = first.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["factory_failures:education", "slow_responses"]);
+        assert_eq!(first[0].severity, RecommendationSeverity::High);
+        assert_eq!(first[0].evidence[0].value, 0.3);
+        assert_eq!(first[1].action, SuggestedAction::Optimize { metric: Metric::ResponseTimeMs });
+
+        // نفس الحالة لا تُنتج توصية جديدة بل تزيد عدد مرات الظهور
+        let second = engine.evaluate(&input(800.0, 3).await);
+        assert_eq!(second.len(), 2);
+        assert_eq!(second[1].occurrences, 2);
+        assert_eq!(second[1].first_seen, first[1].first_seen);
+
+        // المرفوضة لا تعود خلال فترة التهدئة
+        assert!(engine.dismiss("slow_responses"));
+        let third = engine.evaluate(&input(800.0, 3).await);
+        assert_eq!(third.len(), 1);
+
+        assert!(engine.assess(&input(100.0, 0).await).is_empty());
+    }
+}
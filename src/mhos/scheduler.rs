@@ -0,0 +1,928 @@
+//! المهام المجدولة: جداول cron، أنواع المهام، سجل التشغيل ومنع التداخل
+//!
+//! يُحفظ المخزن في ملف JSON داخل دليل البيانات حتى يتشاركه خادم API وأوامر
+//! سطر الأوامر. يمنع المجدول تشغيل المهمة نفسها مرتين في آن واحد داخل العملية،
+//! والمواعيد الفائتة أثناء توقف الخادم تُنفذ مرة واحدة عند عودته.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::SystemTime;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::watch;
+use crate::core::factory_manager::FactoryManager;
+use crate::mhos::backup;
+use crate::mhos::config::MhosConfig;
+use crate::mhos::cron::CronSchedule;
+use crate::mhos::monitor::SystemMonitor;
+use crate::mhos::optimizer::{MetricSources, Optimizer};
+use crate::mhos::quality_gates::QualityGates;
+
+/// مسار ملف المهام داخل دليل البيانات
+pub const JOBS_FILE: &str = "mhos/jobs.json";
+/// دليل مخرجات المهام (الدفعات والتقارير) داخل دليل البيانات
+pub const JOB_OUTPUTS_DIR: &str = "mhos/job_outputs";
+
+/// الحد الأقصى لسجلات التشغيل المحفوظة
+const MAX_JOB_RUNS: usize = 500;
+/// أقصى انتظار بين فحصين للمهام المستحقة (لالتقاط تعديلات سطر الأوامر)
+const MAX_TICK: std::time::Duration = std::time::Duration::from_secs(30);
+
+fn default_report_hours() -> u32 {
+    24
+}
+
+/// نوع المهمة وما تحتاجه من معاملات
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobKind {
+    /// تصنيع دفعة من المدخلات في مصنع واحد
+    ManufactureBatch {
+        factory_type: String,
+        inputs: Vec<Value>,
+        #[serde(default)]
+        parameters: Value,
+    },
+    /// فحص بوابات الجودة على المصانع
+    QualityAudit,
+    /// تشغيل المحسن
+    Optimize,
+    /// نسخ دليل البيانات احتياطياً
+    Backup,
+    /// تصدير تقرير المراقبة لآخر `hours` ساعة
+    ReportExport {
+        #[serde(default = "default_report_hours")]
+        hours: u32,
+    },
+}
+
+impl JobKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobKind::ManufactureBatch { .. } => "manufacture_batch",
+            JobKind::QualityAudit => "quality_audit",
+            JobKind::Optimize => "optimize",
+            JobKind::Backup => "backup",
+            JobKind::ReportExport { .. } => "report_export",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            JobKind::ManufactureBatch { .. } => "تصنيع دفعة",
+            JobKind::QualityAudit => "تدقيق الجودة",
+            JobKind::Optimize => "تحسين النظام",
+            JobKind::Backup => "نسخ احتياطي",
+            JobKind::ReportExport { .. } => "تصدير تقرير",
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            JobKind::ManufactureBatch { factory_type, inputs, .. } => {
+                if factory_type.trim().is_empty() {
+                    return Err("نوع المصنع مطلوب لتصنيع الدفعة".to_string());
+                }
+                if inputs.is_empty() {
+                    return Err("الدفعة تحتاج مدخلاً واحداً على الأقل".to_string());
+                }
+                Ok(())
+            }
+            JobKind::ReportExport { hours } if !(1..=24 * 30).contains(hours) => {
+                Err("فترة التقرير يجب أن تكون بين 1 و 720 ساعة".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// بيانات إنشاء مهمة
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobDraft {
+    pub name: String,
+    /// تعبير cron بتوقيت UTC
+    pub schedule: String,
+    pub kind: JobKind,
+    #[serde(default)]
+    pub paused: bool,
+}
+
+impl JobDraft {
+    pub fn new(name: impl Into<String>, schedule: impl Into<String>, kind: JobKind) -> Self {
+        Self { name: name.into(), schedule: schedule.into(), kind, paused: false }
+    }
+
+    fn validate(&self) -> Result<CronSchedule, SchedulerError> {
+        if self.name.trim().is_empty() {
+            return Err(SchedulerError::Invalid("اسم المهمة مطلوب".to_string()));
+        }
+        self.kind.validate().map_err(SchedulerError::Invalid)?;
+        CronSchedule::parse(&self.schedule).map_err(SchedulerError::Invalid)
+    }
+}
+
+/// نتيجة تشغيل المهمة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobOutcome {
+    Succeeded,
+    Failed,
+    /// لم تُشغل لأن التشغيل السابق ما زال جارياً
+    Skipped,
+}
+
+impl JobOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobOutcome::Succeeded => "succeeded",
+            JobOutcome::Failed => "failed",
+            JobOutcome::Skipped => "skipped",
+        }
+    }
+}
+
+/// مصدر التشغيل
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobTrigger {
+    Schedule,
+    Manual,
+}
+
+/// مهمة مجدولة
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    pub id: String,
+    pub name: String,
+    pub schedule: String,
+    pub kind: JobKind,
+    pub paused: bool,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub next_run: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_outcome: Option<JobOutcome>,
+}
+
+impl ScheduledJob {
+    /// الموعد التالي بعد وقت معين (لا شيء للمهمة الموقوفة)
+    fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.paused {
+            return None;
+        }
+        CronSchedule::parse(&self.schedule).ok()?.next_after(after)
+    }
+}
+
+/// المهمة مع حالة تشغيلها الحالية
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    #[serde(flatten)]
+    pub job: ScheduledJob,
+    pub running: bool,
+}
+
+/// سجل تشغيل واحد
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRun {
+    pub id: String,
+    pub job_id: String,
+    pub job_name: String,
+    pub trigger: JobTrigger,
+    pub outcome: JobOutcome,
+    pub message: String,
+    /// ملف المخرجات داخل دليل البيانات إن وُجد
+    #[serde(default)]
+    pub output: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: u64,
+}
+
+/// ما ينتجه تنفيذ المهمة
+#[derive(Debug, Clone, Default)]
+pub struct JobOutput {
+    pub message: String,
+    pub output: Option<PathBuf>,
+}
+
+impl JobOutput {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self { message: message.into(), output: None }
+    }
+
+    pub fn with_output(mut self, output: PathBuf) -> Self {
+        self.output = Some(output);
+        self
+    }
+}
+
+/// أخطاء المجدول
+#[derive(Debug)]
+pub enum SchedulerError {
+    NotFound(String),
+    Invalid(String),
+    AlreadyRunning(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for SchedulerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulerError::NotFound(id) => write!(f, "المهمة غير موجودة: {}", id),
+            SchedulerError::Invalid(reason) => write!(f, "مهمة غير صالحة: {}", reason),
+            SchedulerError::AlreadyRunning(id) => write!(f, "المهمة {} قيد التشغيل بالفعل", id),
+            SchedulerError::Io(e) => write!(f, "تعذر حفظ المهام: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SchedulerError {}
+
+impl From<io::Error> for SchedulerError {
+    fn from(e: io::Error) -> Self {
+        SchedulerError::Io(e)
+    }
+}
+
+/// محتوى ملف المهام
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JobFile {
+    jobs: Vec<ScheduledJob>,
+    #[serde(default)]
+    runs: Vec<JobRun>,
+}
+
+/// مخزن المهام وسجل تشغيلها
+pub struct JobStore {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    jobs: Vec<ScheduledJob>,
+    runs: Vec<JobRun>,
+}
+
+impl JobStore {
+    /// مخزن في الذاكرة فقط
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            modified: None,
+            jobs: Vec::new(),
+            runs: Vec::new(),
+        }
+    }
+
+    /// فتح مخزن محفوظ في ملف (يُنشأ عند أول حفظ)
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut store = Self::in_memory();
+        store.path = Some(path.as_ref().to_path_buf());
+        store.reload()?;
+        Ok(store)
+    }
+
+    /// إعادة التحميل إذا عدّلت عملية أخرى الملف
+    pub fn reload_if_changed(&mut self) -> io::Result<()> {
+        let changed = match &self.path {
+            Some(path) => file_modified(path) != self.modified,
+            None => false,
+        };
+        if changed {
+            self.reload()?;
+        }
+        Ok(())
+    }
+
+    fn reload(&mut self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let file: JobFile = serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            self.jobs = file.jobs;
+            self.runs = file.runs;
+        }
+        self.modified = file_modified(&path);
+        Ok(())
+    }
+
+    /// حفظ المخزن في ملفه (إن وُجد)
+    pub fn save(&mut self) -> io::Result<()> {
+        let path = match &self.path {
+            Some(path) => path.clone(),
+            None => return Ok(()),
+        };
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = JobFile { jobs: self.jobs.clone(), runs: self.runs.clone() };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // كتابة ذرية عبر ملف مؤقت
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)?;
+        std::fs::rename(&tmp, &path)?;
+
+        self.modified = file_modified(&path);
+        Ok(())
+    }
+
+    pub fn list(&self) -> &[ScheduledJob] {
+        &self.jobs
+    }
+
+    pub fn get(&self, id: &str) -> Option<&ScheduledJob> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    /// إضافة مهمة بعد التحقق من جدولها ومعاملاتها
+    pub fn add(&mut self, draft: JobDraft) -> Result<ScheduledJob, SchedulerError> {
+        let schedule = draft.validate()?;
+
+        let now = Utc::now();
+        let job = ScheduledJob {
+            id: format!("job_{}", crate::core::telemetry::random_hex(6)),
+            name: draft.name.trim().to_string(),
+            schedule: schedule.expression().to_string(),
+            kind: draft.kind,
+            paused: draft.paused,
+            created_at: now,
+            next_run: if draft.paused { None } else { schedule.next_after(now) },
+            last_run: None,
+            last_outcome: None,
+        };
+        self.jobs.push(job.clone());
+        self.save()?;
+        Ok(job)
+    }
+
+    /// حذف مهمة (يبقى سجل تشغيلها)
+    pub fn remove(&mut self, id: &str) -> Result<ScheduledJob, SchedulerError> {
+        let index = self.jobs.iter().position(|job| job.id == id)
+            .ok_or_else(|| SchedulerError::NotFound(id.to_string()))?;
+        let job = self.jobs.remove(index);
+        self.save()?;
+        Ok(job)
+    }
+
+    /// إيقاف مهمة مؤقتاً أو استئنافها (يُحسب موعدها التالي من الآن)
+    pub fn set_paused(&mut self, id: &str, paused: bool) -> Result<ScheduledJob, SchedulerError> {
+        let job = self.jobs.iter_mut()
+            .find(|job| job.id == id)
+            .ok_or_else(|| SchedulerError::NotFound(id.to_string()))?;
+        job.paused = paused;
+        job.next_run = job.next_after(Utc::now());
+        let job = job.clone();
+        self.save()?;
+        Ok(job)
+    }
+
+    /// المهام المستحقة حتى وقت معين مع تقديم موعدها التالي
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<ScheduledJob> {
+        let mut due = Vec::new();
+        for job in self.jobs.iter_mut().filter(|job| !job.paused) {
+            match job.next_run {
+                Some(at) if at <= now => {
+                    due.push(job.clone());
+                    job.next_run = job.next_after(now);
+                }
+                Some(_) => {}
+                // مهمة أضيفت يدوياً إلى الملف أو بلا موعد محسوب
+                None => job.next_run = job.next_after(now),
+            }
+        }
+        due
+    }
+
+    /// أقرب موعد بين المهام النشطة
+    pub fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.jobs.iter()
+            .filter(|job| !job.paused)
+            .filter_map(|job| job.next_run)
+            .min()
+    }
+
+    /// تسجيل نتيجة تشغيل وتحديث حالة المهمة
+    pub fn record_run(&mut self, run: JobRun) -> io::Result<()> {
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.id == run.job_id) {
+            job.last_run = Some(run.started_at);
+            job.last_outcome = Some(run.outcome);
+        }
+        self.runs.push(run);
+        if self.runs.len() > MAX_JOB_RUNS {
+            let excess = self.runs.len() - MAX_JOB_RUNS;
+            self.runs.drain(0..excess);
+        }
+        self.save()
+    }
+
+    /// سجل التشغيل (الأحدث أولاً) مع تصفية اختيارية حسب المهمة
+    pub fn runs(&self, job_id: Option<&str>, limit: usize) -> Vec<JobRun> {
+        self.runs.iter()
+            .rev()
+            .filter(|run| job_id.map(|id| run.job_id == id).unwrap_or(true))
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+}
+
+fn file_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// مستقبل تنفيذ المهمة
+pub type JobFuture<'a> = Pin<Box<dyn Future<Output = Result<JobOutput, String>> + Send + 'a>>;
+
+/// منفذ أنواع المهام
+pub trait JobExecutor: Send + Sync {
+    fn execute<'a>(&'a self, job: &'a ScheduledJob) -> JobFuture<'a>;
+}
+
+/// المنفذ الفعلي على أنظمة MH-OS
+pub struct MhosJobExecutor {
+    factory_manager: Arc<FactoryManager>,
+    monitor: Arc<SystemMonitor>,
+    optimizer: Arc<Optimizer>,
+    sources: MetricSources,
+    quality_gates: Arc<QualityGates>,
+    config: watch::Receiver<MhosConfig>,
+    data_dir: PathBuf,
+}
+
+impl MhosJobExecutor {
+    pub fn new(
+        sources: MetricSources,
+        optimizer: Arc<Optimizer>,
+        quality_gates: Arc<QualityGates>,
+        config: watch::Receiver<MhosConfig>,
+    ) -> Self {
+        Self {
+            factory_manager: sources.factory_manager.clone(),
+            monitor: sources.monitor.clone(),
+            optimizer,
+            sources,
+            quality_gates,
+            config,
+            data_dir: crate::core::config::data_dir(),
+        }
+    }
+
+    pub fn with_data_dir<P: Into<PathBuf>>(mut self, data_dir: P) -> Self {
+        self.data_dir = data_dir.into();
+        self
+    }
+
+    async fn run(&self, job: &ScheduledJob) -> Result<JobOutput, String> {
+        match &job.kind {
+            JobKind::ManufactureBatch { factory_type, inputs, parameters } => {
+                let factory_manager = self.factory_manager.clone();
+                let (factory_type, inputs, parameters) = (factory_type.clone(), inputs.clone(), parameters.clone());
+                let total = inputs.len();
+                let products = tokio::task::spawn_blocking(move || {
+                    inputs.into_iter()
+                        .map(|input| match factory_manager.manufacture(&factory_type, input, parameters.clone()) {
+                            Ok(product) => serde_json::json!({ "success": true, "product": product }),
+                            Err(e) => serde_json::json!({ "success": false, "error": e.to_string() }),
+                        })
+                        .collect::<Vec<Value>>()
+                }).await.map_err(|e| e.to_string())?;
+
+                let succeeded = products.iter().filter(|p| p["success"] == true).count();
+                let path = self.write_output(job, &products)?;
+                let message = format!("تم تصنيع {} من {}", succeeded, total);
+                if succeeded == 0 {
+                    return Err(message);
+                }
+                Ok(JobOutput::new(message).with_output(path))
+            }
+            JobKind::QualityAudit => {
+                let report = self.quality_gates.check_all(self.factory_manager.clone()).await;
+                let path = self.write_output(job, &report)?;
+                Ok(JobOutput::new(format!("النتيجة {:.0}% - {}/{} بوابة ناجحة",
+                    report.overall_score * 100.0, report.passed_gates, report.total_gates)).with_output(path))
+            }
+            JobKind::Optimize => {
+                let result = self.optimizer.optimize(&self.sources).await;
+                Ok(JobOutput::new(format!("{} تغيير ({})", result.improvements.len(), result.run_id)))
+            }
+            JobKind::Backup => {
+                let data_dir = self.data_dir.clone();
+                let retention = self.config.borrow().backup_retention;
+                let path = tokio::task::spawn_blocking(move || backup::create_backup(&data_dir, retention))
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .map_err(|e| e.to_string())?;
+                Ok(JobOutput::new("تم إنشاء النسخة الاحتياطية").with_output(path))
+            }
+            JobKind::ReportExport { hours } => {
+                let report = self.monitor.generate_report(*hours).await;
+                let path = self.write_output(job, &report)?;
+                Ok(JobOutput::new(format!("تقرير آخر {} ساعة ({} إنذار)", hours, report.summary.alert_count))
+                    .with_output(path))
+            }
+        }
+    }
+
+    /// حفظ مخرجات التشغيل في دليل المهمة
+    fn write_output<T: Serialize>(&self, job: &ScheduledJob, value: &T) -> Result<PathBuf, String> {
+        let dir = self.data_dir.join(JOB_OUTPUTS_DIR).join(&job.id);
+        let path = dir.join(format!("{}.json", Utc::now().format("%Y%m%d_%H%M%S_%3f")));
+        let content = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&path, content))
+            .map_err(|e| format!("تعذر حفظ المخرجات في {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+impl JobExecutor for MhosJobExecutor {
+    fn execute<'a>(&'a self, job: &'a ScheduledJob) -> JobFuture<'a> {
+        Box::pin(self.run(job))
+    }
+}
+
+/// يحرر المهمة من قائمة التشغيل عند انتهائها (حتى عند الذعر)
+struct RunningGuard {
+    running: Arc<Mutex<HashSet<String>>>,
+    job_id: String,
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        let mut running = match self.running.lock() {
+            Ok(running) => running,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        running.remove(&self.job_id);
+    }
+}
+
+/// المجدول
+pub struct Scheduler {
+    store: RwLock<JobStore>,
+    executor: RwLock<Arc<dyn JobExecutor>>,
+    running: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Scheduler {
+    pub fn new(store: JobStore, executor: Arc<dyn JobExecutor>) -> Self {
+        Self {
+            store: RwLock::new(store),
+            executor: RwLock::new(executor),
+            running: Arc::new(Mutex::new(HashSet::new())),
+        }
+    }
+
+    /// استبدال المنفذ (عند تغيير أنظمة MH-OS التي يعمل عليها)
+    pub fn set_executor(&self, executor: Arc<dyn JobExecutor>) {
+        if let Ok(mut current) = self.executor.write() {
+            *current = executor;
+        }
+    }
+
+    /// تنفيذ عملية على المخزن بعد مزامنته مع الملف
+    fn with_store<T>(&self, f: impl FnOnce(&mut JobStore) -> Result<T, SchedulerError>) -> Result<T, SchedulerError> {
+        let mut store = match self.store.write() {
+            Ok(store) => store,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Err(e) = store.reload_if_changed() {
+            log::error!("❌ تعذر إعادة تحميل المهام: {}", e);
+        }
+        f(&mut store)
+    }
+
+    fn lock_running(&self) -> MutexGuard<'_, HashSet<String>> {
+        match self.running.lock() {
+            Ok(running) => running,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    /// المهام مع حالة تشغيلها
+    pub fn list(&self) -> Vec<JobStatus> {
+        let jobs = self.with_store(|store| Ok(store.list().to_vec())).unwrap_or_default();
+        let running = self.lock_running();
+        jobs.into_iter()
+            .map(|job| JobStatus { running: running.contains(&job.id), job })
+            .collect()
+    }
+
+    pub fn add(&self, draft: JobDraft) -> Result<ScheduledJob, SchedulerError> {
+        let job = self.with_store(|store| store.add(draft))?;
+        log::info!("🗓️  مهمة مجدولة جديدة: {} ({}) - {}", job.name, job.kind.display_name(), job.schedule);
+        Ok(job)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<ScheduledJob, SchedulerError> {
+        self.with_store(|store| store.remove(id))
+    }
+
+    pub fn set_paused(&self, id: &str, paused: bool) -> Result<ScheduledJob, SchedulerError> {
+        self.with_store(|store| store.set_paused(id, paused))
+    }
+
+    /// سجل التشغيل (الأحدث أولاً)
+    pub fn runs(&self, job_id: Option<&str>, limit: usize) -> Vec<JobRun> {
+        self.with_store(|store| Ok(store.runs(job_id, limit))).unwrap_or_default()
+    }
+
+    /// حجز المهمة للتشغيل، أو رفضه إن كان تشغيل سابق جارياً
+    fn claim(&self, job_id: &str) -> Option<RunningGuard> {
+        self.lock_running().insert(job_id.to_string()).then(|| RunningGuard {
+            running: self.running.clone(),
+            job_id: job_id.to_string(),
+        })
+    }
+
+    /// تشغيل مهمة يدوياً في الخلفية؛ يعيد معرف التشغيل
+    pub fn trigger(self: &Arc<Self>, id: &str) -> Result<String, SchedulerError> {
+        let job = self.with_store(|store| store.get(id).cloned().ok_or_else(|| SchedulerError::NotFound(id.to_string())))?;
+        let guard = self.claim(&job.id).ok_or_else(|| SchedulerError::AlreadyRunning(job.id.clone()))?;
+
+        let run_id = new_run_id();
+        tokio::spawn(self.clone().execute(job, JobTrigger::Manual, run_id.clone(), guard));
+        Ok(run_id)
+    }
+
+    /// تشغيل مهمة يدوياً وانتظار نتيجتها
+    pub async fn run_now(self: &Arc<Self>, id: &str) -> Result<JobRun, SchedulerError> {
+        let job = self.with_store(|store| store.get(id).cloned().ok_or_else(|| SchedulerError::NotFound(id.to_string())))?;
+        let guard = self.claim(&job.id).ok_or_else(|| SchedulerError::AlreadyRunning(job.id.clone()))?;
+        Ok(self.clone().execute(job, JobTrigger::Manual, new_run_id(), guard).await)
+    }
+
+    /// تشغيل المهام المستحقة في الخلفية (المهمة التي ما زالت تعمل تُسجل كمتخطاة)
+    pub fn run_due(self: &Arc<Self>, now: DateTime<Utc>) -> Vec<String> {
+        let due = self.with_store(|store| {
+            let due = store.take_due(now);
+            if !due.is_empty() {
+                store.save()?;
+            }
+            Ok(due)
+        }).unwrap_or_else(|e| {
+            log::error!("❌ {}", e);
+            Vec::new()
+        });
+
+        let mut started = Vec::new();
+        for job in due {
+            let run_id = new_run_id();
+            match self.claim(&job.id) {
+                Some(guard) => {
+                    tokio::spawn(self.clone().execute(job, JobTrigger::Schedule, run_id.clone(), guard));
+                    started.push(run_id);
+                }
+                None => {
+                    log::warn!("⏭️  تخطي {}: التشغيل السابق ما زال جارياً", job.name);
+                    let run = JobRun {
+                        id: run_id,
+                        job_id: job.id.clone(),
+                        job_name: job.name.clone(),
+                        trigger: JobTrigger::Schedule,
+                        outcome: JobOutcome::Skipped,
+                        message: "التشغيل السابق ما زال جارياً".to_string(),
+                        output: None,
+                        started_at: now,
+                        finished_at: now,
+                        duration_ms: 0,
+                    };
+                    self.record(run);
+                }
+            }
+        }
+        started
+    }
+
+    /// حلقة المجدول طوال عمر الخادم
+    pub async fn run(self: Arc<Self>) {
+        log::info!("🗓️  بدء المجدول ({} مهمة)", self.list().len());
+        loop {
+            self.run_due(Utc::now());
+
+            let next = self.with_store(|store| Ok(store.next_due())).ok().flatten();
+            let wait = next
+                .and_then(|at| (at - Utc::now()).to_std().ok())
+                .unwrap_or(MAX_TICK)
+                .clamp(std::time::Duration::from_secs(1), MAX_TICK);
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    async fn execute(self: Arc<Self>, job: ScheduledJob, trigger: JobTrigger, run_id: String, guard: RunningGuard) -> JobRun {
+        let executor = match self.executor.read() {
+            Ok(executor) => executor.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+
+        log::info!("▶️  تشغيل المهمة {} ({})", job.name, job.kind.display_name());
+        let started_at = Utc::now();
+        let start = std::time::Instant::now();
+        let result = executor.execute(&job).await;
+        drop(guard);
+
+        let (outcome, output) = match result {
+            Ok(output) => {
+                log::info!("✅ انتهت المهمة {}: {}", job.name, output.message);
+                (JobOutcome::Succeeded, output)
+            }
+            Err(e) => {
+                log::error!("❌ فشلت المهمة {}: {}", job.name, e);
+                (JobOutcome::Failed, JobOutput::new(e))
+            }
+        };
+
+        let run = JobRun {
+            id: run_id,
+            job_id: job.id.clone(),
+            job_name: job.name.clone(),
+            trigger,
+            outcome,
+            message: output.message,
+            output: output.output.map(|path| path.display().to_string()),
+            started_at,
+            finished_at: Utc::now(),
+            duration_ms: start.elapsed().as_millis() as u64,
+        };
+        self.record(run.clone());
+        run
+    }
+
+    fn record(&self, run: JobRun) {
+        if let Err(e) = self.with_store(|store| store.record_run(run).map_err(SchedulerError::from)) {
+            log::error!("❌ تعذر تسجيل تشغيل المهمة: {}", e);
+        }
+    }
+}
+
+fn new_run_id() -> String {
+    format!("run_{}", crate::core::telemetry::random_hex(8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// منفذ ينتظر إشارة قبل الانتهاء لاختبار منع التداخل
+    struct GatedExecutor {
+        release: tokio::sync::Semaphore,
+    }
+
+    impl JobExecutor for GatedExecutor {
+        fn execute<'a>(&'a self, job: &'a ScheduledJob) -> JobFuture<'a> {
+            Box::pin(async move {
+                self.release.acquire().await.map_err(|e| e.to_string())?.forget();
+                match job.kind {
+                    JobKind::Backup => Err("القرص ممتلئ".to_string()),
+                    _ => Ok(JobOutput::new("تم")),
+                }
+            })
+        }
+    }
+
+    fn scheduler(store: JobStore) -> (Arc<Scheduler>, Arc<GatedExecutor>) {
+        let executor = Arc::new(GatedExecutor { release: tokio::sync::Semaphore::new(0) });
+        (Arc::new(Scheduler::new(store, executor.clone())), executor)
+    }
+
+    /// انتظار عدد معين من سجلات تشغيل المهمة
+    async fn wait_for_runs(scheduler: &Scheduler, job_id: &str, count: usize) -> Vec<JobRun> {
+        for _ in 0..100 {
+            let runs = scheduler.runs(Some(job_id), 10);
+            if runs.len() >= count {
+                return runs;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        scheduler.runs(Some(job_id), 10)
+    }
+
+    fn run_record(job_id: &str, index: usize) -> JobRun {
+        let now = Utc::now();
+        JobRun {
+            id: format!("run_{}", index),
+            job_id: job_id.to_string(),
+            job_name: "تدقيق".to_string(),
+            trigger: JobTrigger::Schedule,
+            outcome: JobOutcome::Succeeded,
+            message: "تم".to_string(),
+            output: None,
+            started_at: now,
+            finished_at: now,
+            duration_ms: 0,
+        }
+    }
+
+    #[test]
+    fn test_add_rejects_invalid_drafts() {
+        let (scheduler, _) = scheduler(JobStore::in_memory());
+        assert!(matches!(scheduler.add(JobDraft::new("", "* * * * *", JobKind::Optimize)), Err(SchedulerError::Invalid(_))));
+        assert!(matches!(scheduler.add(JobDraft::new("x", "61 * * * *", JobKind::Optimize)), Err(SchedulerError::Invalid(_))));
+        assert!(matches!(scheduler.add(JobDraft::new("x", "* * *", JobKind::Optimize)), Err(SchedulerError::Invalid(_))));
+        assert!(scheduler.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_running_job_is_not_started_again() {
+        let (scheduler, executor) = scheduler(JobStore::in_memory());
+        let audit = scheduler.add(JobDraft::new("تدقيق", "*/5 * * * *", JobKind::QualityAudit)).unwrap();
+
+        let due = audit.next_run.unwrap();
+        assert_eq!(scheduler.run_due(due).len(), 1);
+        assert!(scheduler.list().iter().any(|s| s.job.id == audit.id && s.running));
+
+        // التشغيل اليدوي والموعد التالي أثناء التشغيل لا يبدآن نسخة ثانية
+        assert!(matches!(scheduler.trigger(&audit.id), Err(SchedulerError::AlreadyRunning(_))));
+        assert!(scheduler.run_due(due + chrono::Duration::minutes(5)).is_empty());
+        assert_eq!(scheduler.runs(Some(&audit.id), 10)[0].outcome, JobOutcome::Skipped);
+
+        executor.release.add_permits(1);
+        let runs = wait_for_runs(&scheduler, &audit.id, 2).await;
+        assert_eq!(runs[0].outcome, JobOutcome::Succeeded);
+        assert!(!scheduler.list().iter().any(|s| s.running));
+    }
+
+    #[tokio::test]
+    async fn test_finished_job_can_run_again() {
+        let (scheduler, executor) = scheduler(JobStore::in_memory());
+        let audit = scheduler.add(JobDraft::new("تدقيق", "*/5 * * * *", JobKind::QualityAudit)).unwrap();
+
+        executor.release.add_permits(2);
+        scheduler.run_now(&audit.id).await.unwrap();
+        let run = scheduler.run_now(&audit.id).await.unwrap();
+        assert_eq!(run.outcome, JobOutcome::Succeeded);
+        assert_eq!(scheduler.runs(Some(&audit.id), 10).len(), 2);
+    }
+
+    #[test]
+    fn test_paused_job_has_no_next_run() {
+        let (scheduler, _) = scheduler(JobStore::in_memory());
+        let backup = scheduler.add(JobDraft::new("نسخ", "0 3 * * *", JobKind::Backup)).unwrap();
+
+        assert!(scheduler.set_paused(&backup.id, true).unwrap().next_run.is_none());
+        assert!(scheduler.set_paused(&backup.id, false).unwrap().next_run.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_manual_run_records_failure() {
+        let (scheduler, executor) = scheduler(JobStore::in_memory());
+        let backup = scheduler.add(JobDraft::new("نسخ", "0 3 * * *", JobKind::Backup)).unwrap();
+
+        executor.release.add_permits(1);
+        let run = scheduler.run_now(&backup.id).await.unwrap();
+        assert_eq!((run.outcome, run.trigger), (JobOutcome::Failed, JobTrigger::Manual));
+        assert_eq!(run.message, "القرص ممتلئ");
+        assert!(matches!(scheduler.run_now("missing").await, Err(SchedulerError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_store_reloads_jobs_and_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        let (scheduler, executor) = scheduler(JobStore::open(&path).unwrap());
+        scheduler.add(JobDraft::new("تدقيق", "*/5 * * * *", JobKind::QualityAudit)).unwrap();
+        let backup = scheduler.add(JobDraft::new("نسخ", "0 3 * * *", JobKind::Backup)).unwrap();
+        scheduler.set_paused(&backup.id, true).unwrap();
+        executor.release.add_permits(1);
+        scheduler.run_now(&backup.id).await.unwrap();
+
+        let reloaded = JobStore::open(&path).unwrap();
+        assert_eq!(reloaded.list().len(), 2);
+        assert!(reloaded.get(&backup.id).unwrap().paused);
+        assert_eq!(reloaded.get(&backup.id).unwrap().last_outcome, Some(JobOutcome::Failed));
+        assert_eq!(reloaded.runs(None, 10).len(), 1);
+    }
+
+    #[test]
+    fn test_run_history_truncated_to_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jobs.json");
+        let mut store = JobStore::open(&path).unwrap();
+        let audit = store.add(JobDraft::new("تدقيق", "*/5 * * * *", JobKind::QualityAudit)).unwrap();
+
+        for index in 0..MAX_JOB_RUNS + 5 {
+            store.record_run(run_record(&audit.id, index)).unwrap();
+        }
+
+        let runs = store.runs(None, usize::MAX);
+        assert_eq!(runs.len(), MAX_JOB_RUNS);
+        assert_eq!(runs[0].id, format!("run_{}", MAX_JOB_RUNS + 4));
+        assert_eq!(runs.last().unwrap().id, "run_5");
+        assert_eq!(JobStore::open(&path).unwrap().runs(None, usize::MAX).len(), MAX_JOB_RUNS);
+    }
+}
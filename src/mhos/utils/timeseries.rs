@@ -0,0 +1,259 @@
+//! تحليل السلاسل الزمنية: المتوسطات المتحركة، التمهيد الأسي، المئينات،
+//! الاتجاه الخطي واكتشاف القيم الشاذة
+//!
+//! تعمل الدوال على قيم مرتبة زمنياً بفواصل متساوية، ويُعامل ترتيب القيمة
+//! في السلسلة كمحور الزمن.
+
+use serde::{Deserialize, Serialize};
+
+/// المتوسط الحسابي
+pub fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// الانحراف المعياري للمجتمع
+pub fn std_dev(values: &[f64]) -> Option<f64> {
+    let mean = mean(values)?;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    Some(variance.sqrt())
+}
+
+/// المتوسط المتحرك الخلفي (النقاط الأولى تُحسب على النافذة المتاحة)
+pub fn moving_average(values: &[f64], window: usize) -> Vec<f64> {
+    let window = window.max(1);
+    let mut sum = 0.0;
+    values.iter().enumerate().map(|(i, value)| {
+        sum += value;
+        if i >= window {
+            sum -= values[i - window];
+        }
+        sum / (i + 1).min(window) as f64
+    }).collect()
+}
+
+/// التمهيد الأسي؛ `alpha` في (0, 1] وكلما كبر زاد وزن القيم الحديثة
+pub fn ewma(values: &[f64], alpha: f64) -> Vec<f64> {
+    let alpha = alpha.clamp(f64::EPSILON, 1.0);
+    let mut smoothed: Vec<f64> = Vec::with_capacity(values.len());
+    for value in values {
+        let next = match smoothed.last() {
+            Some(previous) => alpha * value + (1.0 - alpha) * previous,
+            None => *value,
+        };
+        smoothed.push(next);
+    }
+    smoothed
+}
+
+/// المئين `p` (من 0 إلى 100) بالاستيفاء الخطي بين أقرب رتبتين
+pub fn percentile(values: &[f64], p: f64) -> Option<f64> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    percentile_sorted(&sorted, p)
+}
+
+fn percentile_sorted(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    Some(sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64))
+}
+
+/// الربيعيات
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quartiles {
+    pub q1: f64,
+    pub median: f64,
+    pub q3: f64,
+}
+
+impl Quartiles {
+    /// المدى الربيعي
+    pub fn iqr(&self) -> f64 {
+        self.q3 - self.q1
+    }
+}
+
+pub fn quartiles(values: &[f64]) -> Option<Quartiles> {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    Some(Quartiles {
+        q1: percentile_sorted(&sorted, 25.0)?,
+        median: percentile_sorted(&sorted, 50.0)?,
+        q3: percentile_sorted(&sorted, 75.0)?,
+    })
+}
+
+/// خط الاتجاه بطريقة المربعات الصغرى
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LinearTrend {
+    /// التغير لكل نقطة
+    pub slope: f64,
+    pub intercept: f64,
+    /// جودة الملاءمة (1 = السلسلة على الخط تماماً)
+    pub r_squared: f64,
+}
+
+impl LinearTrend {
+    /// القيمة المتوقعة عند الترتيب `x`
+    pub fn predict(&self, x: f64) -> f64 {
+        self.intercept + self.slope * x
+    }
+}
+
+/// ملاءمة خط مستقيم للسلسلة (تحتاج نقطتين على الأقل)
+pub fn linear_trend(values: &[f64]) -> Option<LinearTrend> {
+    if values.len() < 2 {
+        return None;
+    }
+    let n = values.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = mean(values)?;
+
+    let (sxy, sxx, syy) = values.iter().enumerate().fold((0.0, 0.0, 0.0), |(sxy, sxx, syy), (i, y)| {
+        let dx = i as f64 - mean_x;
+        let dy = y - mean_y;
+        (sxy + dx * dy, sxx + dx * dx, syy + dy * dy)
+    });
+
+    let slope = sxy / sxx;
+    let r_squared = if syy == 0.0 { 1.0 } else { (sxy * sxy) / (sxx * syy) };
+
+    Some(LinearTrend {
+        slope,
+        intercept: mean_y - slope * mean_x,
+        r_squared,
+    })
+}
+
+/// طريقة اكتشاف القيم الشاذة
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyMethod {
+    /// البعد عن المتوسط بوحدات الانحراف المعياري
+    ZScore,
+    /// البعد عن الربيعيين بوحدات المدى الربيعي
+    Iqr,
+}
+
+/// النطاق المعتاد للسلسلة: [المتوسط ± k·σ] أو [Q1 - k·IQR، Q3 + k·IQR]
+///
+/// يعيد `None` إذا كانت السلسلة أقصر من نقطتين أو بلا تشتت.
+pub fn normal_range(values: &[f64], method: AnomalyMethod, sensitivity: f64) -> Option<(f64, f64)> {
+    if values.len() < 2 {
+        return None;
+    }
+    let (center_low, center_high, spread) = match method {
+        AnomalyMethod::ZScore => {
+            let mean = mean(values)?;
+            (mean, mean, std_dev(values)?)
+        }
+        AnomalyMethod::Iqr => {
+            let q = quartiles(values)?;
+            (q.q1, q.q3, q.iqr())
+        }
+    };
+    if spread <= f64::EPSILON {
+        return None;
+    }
+    Some((center_low - sensitivity * spread, center_high + sensitivity * spread))
+}
+
+/// قيمة شاذة في السلسلة
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Anomaly {
+    pub index: usize,
+    pub value: f64,
+    /// البعد عن النطاق المعتاد بوحدات التشتت (سالب تحت النطاق)
+    pub score: f64,
+}
+
+/// القيم التي يتجاوز بعدها عن المتوسط `threshold` انحرافاً معيارياً
+pub fn zscore_anomalies(values: &[f64], threshold: f64) -> Vec<Anomaly> {
+    let (mean, std_dev) = match (mean(values), std_dev(values)) {
+        (Some(mean), Some(std_dev)) if std_dev > f64::EPSILON => (mean, std_dev),
+        _ => return Vec::new(),
+    };
+    values.iter().enumerate()
+        .map(|(index, value)| Anomaly { index, value: *value, score: (value - mean) / std_dev })
+        .filter(|a| a.score.abs() > threshold)
+        .collect()
+}
+
+/// القيم الواقعة خارج [Q1 - k·IQR، Q3 + k·IQR] (k = 1.5 هو سياج توكي المعتاد)
+pub fn iqr_anomalies(values: &[f64], k: f64) -> Vec<Anomaly> {
+    let q = match quartiles(values) {
+        Some(q) if q.iqr() > f64::EPSILON => q,
+        _ => return Vec::new(),
+    };
+    let iqr = q.iqr();
+    values.iter().enumerate()
+        .filter_map(|(index, value)| {
+            let score = if *value > q.q3 {
+                (value - q.q3) / iqr
+            } else if *value < q.q1 {
+                (value - q.q1) / iqr
+            } else {
+                0.0
+            };
+            (score.abs() > k).then_some(Anomaly { index, value: *value, score })
+        })
+        .collect()
+}
+
+/// اكتشاف القيم الشاذة بالطريقة المحددة
+pub fn anomalies(values: &[f64], method: AnomalyMethod, sensitivity: f64) -> Vec<Anomaly> {
+    match method {
+        AnomalyMethod::ZScore => zscore_anomalies(values, sensitivity),
+        AnomalyMethod::Iqr => iqr_anomalies(values, sensitivity),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn test_timeseries_toolkit() {
+        let values = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+        assert_eq!(moving_average(&values, 3), vec![1.0, 1.5, 2.0, 3.0, 4.0]);
+        let smoothed = ewma(&values, 0.5);
+        assert!(close(smoothed[1], 1.5) && close(smoothed[4], 4.0625));
+
+        assert_eq!(percentile(&[5.0, 1.0, 3.0], 50.0), Some(3.0));
+        assert!(close(percentile(&values, 90.0).unwrap(), 4.6));
+        assert_eq!(percentile(&[], 50.0), None);
+        assert!(close(quartiles(&values).unwrap().iqr(), 2.0));
+
+        let trend = linear_trend(&[3.0, 5.0, 7.0, 9.0]).unwrap();
+        assert!(close(trend.slope, 2.0) && close(trend.intercept, 3.0) && close(trend.r_squared, 1.0));
+        assert!(close(trend.predict(5.0), 13.0));
+        assert!(linear_trend(&[1.0]).is_none());
+
+        // قفزة واحدة وسط سلسلة مستقرة
+        let mut series = vec![10.0, 11.0, 9.0, 10.0, 10.5, 9.5, 10.0, 11.0, 9.0, 10.0];
+        series.push(30.0);
+        let spikes = zscore_anomalies(&series, 2.5);
+        assert_eq!(spikes.len(), 1);
+        assert_eq!(spikes[0].index, 10);
+        let spikes = iqr_anomalies(&series, 1.5);
+        assert_eq!(spikes.iter().map(|a| a.index).collect::<Vec<_>>(), vec![10]);
+        assert!(spikes[0].score > 1.5);
+
+        let (low, high) = normal_range(&series[..10], AnomalyMethod::ZScore, 3.0).unwrap();
+        assert!(low < 9.0 && high > 11.0 && high < 30.0);
+        assert!(normal_range(&[4.0, 4.0, 4.0], AnomalyMethod::Iqr, 1.5).is_none());
+        assert!(zscore_anomalies(&[4.0, 4.0], 1.0).is_empty());
+    }
+}
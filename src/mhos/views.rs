@@ -0,0 +1,252 @@
+//! عرض لوحات التحكم بقوالب Tera
+//!
+//! القوالب في `templates/dashboard` ومضمنة في الملف التنفيذي، فيقدّم
+//! الخادم اللوحة على `/dashboard` دون ملفات HTML ثابتة بجانبه. صفحات
+//! الجذر (`dashboard_live.html` وأخواتها) واجهات الخادم الثابت في Python
+//! وتستدعي مسارات `/api/*` الخاصة به، فتبقى حتى يُستغنى عن ذلك الخادم.
+
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+use serde::Serialize;
+use tera::{Context, Tera};
+use crate::core::quality::MiniDashboard;
+use crate::mhos::dashboard::{DashboardData, DashboardWidget};
+
+const TEMPLATES: [(&str, &str); 4] = [
+    ("base.html", include_str!("../../templates/dashboard/base.html")),
+    ("dashboard.html", include_str!("../../templates/dashboard/dashboard.html")),
+    ("widget.html", include_str!("../../templates/dashboard/widget.html")),
+    ("mini_dashboard.html", include_str!("../../templates/dashboard/mini_dashboard.html")),
+];
+
+/// أسماء مفاتيح بيانات العناصر بالعربية
+const LABELS: [(&str, &str); 12] = [
+    ("cpu_usage", "المعالج"),
+    ("memory_usage", "الذاكرة"),
+    ("disk_usage", "القرص"),
+    ("network_usage", "الشبكة"),
+    ("total", "الإجمالي"),
+    ("today", "اليوم"),
+    ("this_week", "هذا الأسبوع"),
+    ("this_month", "هذا الشهر"),
+    ("average_daily", "المتوسط اليومي"),
+    ("growth_rate", "معدل النمو"),
+    ("top_products", "أكثر المنتجات"),
+    ("trend", "الاتجاه"),
+];
+
+/// اللوحة المصغرة بقيم جاهزة للعرض
+#[derive(Serialize)]
+struct MiniDashboardView<'a> {
+    active_tasks: u32,
+    processing_percent: u32,
+    quality_out_of_ten: u32,
+    ai_assistance: bool,
+    direction: &'static str,
+    regressions: Vec<RegressionView<'a>>,
+}
+
+#[derive(Serialize)]
+struct RegressionView<'a> {
+    factory: &'a str,
+    gate_name: &'a str,
+    current_percent: String,
+    baseline_percent: String,
+}
+
+impl<'a> From<&'a MiniDashboard> for MiniDashboardView<'a> {
+    fn from(mini: &'a MiniDashboard) -> Self {
+        Self {
+            active_tasks: mini.active_tasks,
+            processing_percent: (mini.processing_speed * 100.0) as u32,
+            quality_out_of_ten: (mini.output_quality * 10.0) as u32,
+            ai_assistance: mini.ai_assistance,
+            direction: mini.quality_direction.map(|d| d.display_name()).unwrap_or("لا توجد بيانات"),
+            regressions: mini.regressions.iter().map(|r| RegressionView {
+                factory: &r.factory,
+                gate_name: &r.gate_name,
+                current_percent: format!("{:.0}", r.current * 100.0),
+                baseline_percent: format!("{:.0}", r.baseline * 100.0),
+            }).collect(),
+        }
+    }
+}
+
+/// عارض لوحات التحكم
+pub struct DashboardRenderer {
+    tera: Tera,
+}
+
+impl DashboardRenderer {
+    pub fn new() -> tera::Result<Self> {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(TEMPLATES.to_vec())?;
+        Ok(Self { tera })
+    }
+
+    fn base_context() -> Context {
+        let mut context = Context::new();
+        context.insert("labels", &LABELS.iter().copied().collect::<BTreeMap<_, _>>());
+        context
+    }
+
+    /// الصفحة الكاملة: شريط حالة النظام وشبكة العناصر حسب التخطيط واللوحة المصغرة،
+    /// و`live_token` توكن البث الحي الذي تمرره الصفحة إلى EventSource
+    pub fn render_dashboard(
+        &self,
+        data: &DashboardData,
+        mini: Option<&MiniDashboard>,
+        live_token: Option<&str>
+    ) -> tera::Result<String> {
+        let widgets: BTreeMap<&str, &DashboardWidget> = data.widgets.iter().map(|w| (w.id.as_str(), w)).collect();
+
+        let mut context = Self::base_context();
+        context.insert("dashboard", data);
+        context.insert("widgets", &widgets);
+        if let Some(mini) = mini {
+            context.insert("mini", &MiniDashboardView::from(mini));
+        }
+        if let Some(token) = live_token {
+            context.insert("live_token", token);
+        }
+        self.tera.render("dashboard.html", &context)
+    }
+
+    /// محتوى عنصر واحد (للتحديث الجزئي من البث الحي)
+    pub fn render_widget(&self, widget: &DashboardWidget) -> tera::Result<String> {
+        let mut context = Self::base_context();
+        context.insert("widget", widget);
+        self.tera.render("widget.html", &context)
+    }
+
+    pub fn render_mini_dashboard(&self, mini: &MiniDashboard) -> tera::Result<String> {
+        let mut context = Self::base_context();
+        context.insert("mini", &MiniDashboardView::from(mini));
+        self.tera.render("mini_dashboard.html", &context)
+    }
+}
+
+/// العارض المشترك (القوالب مضمنة فيُحلَّل مرة واحدة)
+pub fn renderer() -> &'static DashboardRenderer {
+    static RENDERER: OnceLock<DashboardRenderer> = OnceLock::new();
+    RENDERER.get_or_init(|| DashboardRenderer::new().expect("قوالب لوحة التحكم المضمنة غير صالحة"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::core::factory_manager::FactoryManager;
+    use crate::mhos::dashboard::{Dashboard, WidgetType};
+
+    fn widget(id: &str, widget_type: WidgetType, data: serde_json::Value) -> DashboardWidget {
+        DashboardWidget {
+            id: id.to_string(),
+            widget_type,
+            title: format!("عنوان {}", id),
+            data,
+            position: (0, 0),
+            size: (4, 4),
+            refresh_interval: 30,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_render_dashboard_rtl_grid() {
+        let mut dashboard = Dashboard::new(Arc::new(FactoryManager::new()));
+        let data = dashboard.get_dashboard_data().await;
+        let html = renderer().render_dashboard(&data, Some(&MiniDashboard::new()), Some("abc.def")).unwrap();
+
+        assert!(html.contains(r#"<html lang="ar" dir="rtl">"#));
+        assert!(html.contains("const token = 'abc.def';"));
+        assert!(html.contains("/api/v1/mhos/live?topics=widget,factory_status&token=${token}"));
+        assert!(html.contains("grid-template-columns: repeat(12, 1fr)"));
+        assert!(html.contains(r#"id="widget-factory_status""#));
+        assert!(html.contains("grid-column: 1 / span 4; grid-row: 5 / span 4;"));
+        assert!(html.contains("Mini Dashboard"));
+    }
+
+    #[tokio::test]
+    async fn test_render_dashboard_without_live_token() {
+        let mut dashboard = Dashboard::new(Arc::new(FactoryManager::new()));
+        let data = dashboard.get_dashboard_data().await;
+        let html = renderer().render_dashboard(&data, None, None).unwrap();
+
+        assert!(!html.contains("EventSource"));
+        assert!(html.contains(r#"id="widget-factory_status""#));
+    }
+
+    #[tokio::test]
+    async fn test_render_every_default_widget() {
+        let mut dashboard = Dashboard::new(Arc::new(FactoryManager::new()));
+        let data = dashboard.get_dashboard_data().await;
+        assert!(!data.widgets.is_empty());
+
+        for widget in &data.widgets {
+            let html = renderer().render_widget(widget).unwrap();
+            assert!(html.contains(&format!("<h3>{}</h3>", widget.title)), "{}", widget.id);
+        }
+    }
+
+    #[test]
+    fn test_render_factory_status_widget() {
+        let html = renderer().render_widget(&widget("factory_status", WidgetType::FactoryStatus, serde_json::json!({
+            "total": 2, "active": 1, "idle": 0, "error": 1,
+            "details": [
+                {"name": "مصنع التعليم", "status": "active", "production": 450},
+                {"name": "المصنع الإبداعي", "status": "error", "production": 3}
+            ]
+        }))).unwrap();
+
+        assert!(html.contains("الإجمالي 2 · نشط 1 · خامل 0 · خطأ 1"));
+        assert!(html.contains("<td>مصنع التعليم</td>"));
+        assert!(html.contains(r#"<span class="badge error">error</span>"#));
+    }
+
+    #[test]
+    fn test_render_performance_widget() {
+        let html = renderer().render_widget(&widget("performance_chart", WidgetType::PerformanceChart, serde_json::json!({
+            "cpu_usage": 35, "memory_usage": 45.5, "trend": "stable"
+        }))).unwrap();
+
+        assert!(html.contains("المعالج:"));
+        assert!(html.contains(r#"<span style="width: 35%">"#));
+        assert!(html.contains("الذاكرة:"));
+        // المقاييس الغائبة لا تُعرض
+        assert!(!html.contains("القرص"));
+        assert!(!html.contains("زمن الاستجابة"));
+    }
+
+    #[test]
+    fn test_render_quality_gauge_widget() {
+        let html = renderer().render_widget(&widget("quality_gauge", WidgetType::QualityGauge, serde_json::json!({
+            "overall_quality": 0.9,
+            "by_factory": {"education": 0.95}
+        }))).unwrap();
+
+        assert!(html.contains("<td>education</td>"));
+        assert!(html.contains(r#"<div class="value">90"#));
+    }
+
+    #[test]
+    fn test_render_recent_activity_widget() {
+        let html = renderer().render_widget(&widget("recent_activity", WidgetType::RecentActivity, serde_json::json!({
+            "activities": [{"time": "10:30", "action": "بدء تشغيل النظام", "user": "النظام"}]
+        }))).unwrap();
+
+        assert!(html.contains(r#"<td class="muted">10:30</td><td>بدء تشغيل النظام</td>"#));
+    }
+
+    #[test]
+    fn test_render_generic_widget_labels_and_lists() {
+        let html = renderer().render_widget(&widget("production_stats", WidgetType::Custom("stats".to_string()), serde_json::json!({
+            "today": 125,
+            "top_products": ["دورة تعليمية", "تصميم موقع"],
+            "nested": {"skipped": true}
+        }))).unwrap();
+
+        assert!(html.contains(r#"<td class="muted">اليوم</td><td>125</td>"#));
+        assert!(html.contains("دورة تعليمية، تصميم موقع"));
+        assert!(!html.contains("skipped"));
+    }
+}
@@ -0,0 +1,385 @@
+//! مزودو بيانات عناصر لوحة التحكم
+//!
+//! لكل نوع عنصر مزود مسجل باسمه يستعلم عن بياناته ويحدد فترة تحديثه
+//! الافتراضية. الأنواع المضمنة مسجلة مسبقاً، وتُضاف أنواع جديدة بتسجيل
+//! مزود واستخدام `WidgetType::Custom(اسم المزود)`.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+use serde_json::{json, Value};
+use crate::core::factory_manager::{FactoryManager, LATENCY_BUCKETS_MS};
+use crate::core::quality::QualityManager;
+use crate::core::tuning::knobs;
+use crate::mhos::monitor::SystemMonitor;
+
+/// مصادر البيانات المتاحة للمزودين
+#[derive(Clone)]
+pub struct WidgetContext {
+    pub factory_manager: Arc<FactoryManager>,
+    pub quality: Option<Arc<RwLock<QualityManager>>>,
+    pub monitor: Option<Arc<SystemMonitor>>,
+}
+
+impl WidgetContext {
+    pub fn new(factory_manager: Arc<FactoryManager>) -> Self {
+        Self { factory_manager, quality: None, monitor: None }
+    }
+
+    pub fn with_quality(mut self, quality: Arc<RwLock<QualityManager>>) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    pub fn with_monitor(mut self, monitor: Arc<SystemMonitor>) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+}
+
+/// مزود بيانات عنصر
+pub trait WidgetProvider: Send + Sync {
+    /// اسم المزود (يطابق `WidgetType::provider_name`)
+    fn name(&self) -> &str;
+
+    /// العنوان الافتراضي للعنصر
+    fn title(&self) -> &str;
+
+    /// فترة التحديث الافتراضية بالثواني
+    fn refresh_interval(&self) -> u64 {
+        60
+    }
+
+    /// بيانات العنصر الحالية
+    fn fetch(&self, context: &WidgetContext) -> Value;
+}
+
+/// سجل المزودين
+pub struct WidgetRegistry {
+    providers: RwLock<BTreeMap<String, Arc<dyn WidgetProvider>>>,
+}
+
+impl WidgetRegistry {
+    /// سجل فارغ
+    pub fn new() -> Self {
+        Self { providers: RwLock::new(BTreeMap::new()) }
+    }
+
+    /// سجل بالمزودين المضمنين
+    pub fn with_builtin() -> Self {
+        let registry = Self::new();
+        registry.register(Arc::new(PerformanceProvider));
+        registry.register(Arc::new(QualityGaugeProvider));
+        registry.register(Arc::new(FactoryStatusProvider));
+        registry.register(Arc::new(ProductionStatsProvider));
+        registry.register(Arc::new(QueueDepthProvider));
+        registry.register(Arc::new(FactoryLatencyProvider));
+        registry.register(Arc::new(QualityFailuresProvider));
+        registry.register(Arc::new(TopTemplatesProvider));
+        registry
+    }
+
+    /// تسجيل مزود (يحل محل مزود بنفس الاسم)
+    pub fn register(&self, provider: Arc<dyn WidgetProvider>) {
+        let name = provider.name().to_string();
+        match self.providers.write() {
+            Ok(mut providers) => {
+                providers.insert(name, provider);
+            }
+            Err(poisoned) => {
+                poisoned.into_inner().insert(name, provider);
+            }
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn WidgetProvider>> {
+        self.providers.read().ok()?.get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// أسماء المزودين المسجلين
+    pub fn names(&self) -> Vec<String> {
+        self.providers.read().map(|p| p.keys().cloned().collect()).unwrap_or_default()
+    }
+}
+
+impl Default for WidgetRegistry {
+    fn default() -> Self {
+        Self::with_builtin()
+    }
+}
+
+/// السجل المشترك للعملية
+pub fn widget_registry() -> Arc<WidgetRegistry> {
+    static REGISTRY: OnceLock<Arc<WidgetRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Arc::new(WidgetRegistry::with_builtin())).clone()
+}
+
+/// أداء النظام: آخر عينة من المراقب، أو عينة تقديرية إن لم يعمل المراقب
+struct PerformanceProvider;
+
+impl WidgetProvider for PerformanceProvider {
+    fn name(&self) -> &str { "performance_chart" }
+    fn title(&self) -> &str { "أداء النظام" }
+    fn refresh_interval(&self) -> u64 { 30 }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        if let Some(metrics) = context.monitor.as_ref().and_then(|m| m.latest_metrics()) {
+            return json!({
+                "cpu_usage": metrics.cpu_usage * 100.0,
+                "memory_usage": metrics.memory_usage * 100.0,
+                "disk_usage": metrics.disk_usage * 100.0,
+                "network_usage": metrics.network_usage * 100.0,
+                "response_time": metrics.response_time,
+                "throughput": metrics.throughput,
+                "timestamp": metrics.timestamp.to_rfc3339()
+            });
+        }
+
+        // محاكاة بيانات الأداء
+        json!({
+            "cpu_usage": rand::random::<u8>() % 30 + 20,
+            "memory_usage": rand::random::<u8>() % 30 + 30,
+            "disk_usage": rand::random::<u8>() % 20 + 10,
+            "network_usage": rand::random::<u8>() % 10 + 5,
+            "response_time": rand::random::<u16>() % 50 + 50,
+            "throughput": rand::random::<u16>() % 100 + 100,
+            "trend": if rand::random::<bool>() { "improving" } else { "stable" }
+        })
+    }
+}
+
+/// جودة المصانع
+struct QualityGaugeProvider;
+
+impl WidgetProvider for QualityGaugeProvider {
+    fn name(&self) -> &str { "quality_gauge" }
+    fn title(&self) -> &str { "جودة المصانع" }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        let factories = context.factory_manager.list_factories();
+        let mut by_factory = serde_json::Map::new();
+
+        for factory in &factories {
+            by_factory.insert(
+                factory.factory_type().to_string(),
+                Value::from(factory.quality_score() as f64)
+            );
+        }
+
+        let quality_scores: Vec<f32> = factories.iter()
+            .map(|f| f.quality_score())
+            .collect();
+
+        let overall_quality = if !quality_scores.is_empty() {
+            quality_scores.iter().sum::<f32>() / quality_scores.len() as f32
+        } else { 0.0 };
+
+        json!({
+            "overall_quality": overall_quality,
+            "by_factory": by_factory,
+            "threshold": 0.85,
+            "status": if overall_quality > 0.9 { "excellent" } else if overall_quality > 0.8 { "good" } else { "needs_improvement" }
+        })
+    }
+}
+
+/// حالة المصانع
+struct FactoryStatusProvider;
+
+impl WidgetProvider for FactoryStatusProvider {
+    fn name(&self) -> &str { "factory_status" }
+    fn title(&self) -> &str { "حالة المصانع" }
+    fn refresh_interval(&self) -> u64 { 15 }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        let details: Vec<Value> = context.factory_manager.list_factories().iter().map(|factory| json!({
+            "name": factory.name(),
+            "type": factory.factory_type(),
+            "status": factory.status(),
+            "production": factory.production_count(),
+            "quality": factory.quality_score()
+        })).collect();
+
+        json!({
+            "total": details.len(),
+            "active": details.iter().filter(|d| d["status"] == "active").count(),
+            "idle": details.iter().filter(|d| d["status"] == "idle").count(),
+            "error": details.iter().filter(|d| d["status"] == "error").count(),
+            "details": details
+        })
+    }
+}
+
+/// إحصائيات الإنتاج
+struct ProductionStatsProvider;
+
+impl WidgetProvider for ProductionStatsProvider {
+    fn name(&self) -> &str { "production_stats" }
+    fn title(&self) -> &str { "إحصائيات الإنتاج" }
+    fn refresh_interval(&self) -> u64 { 300 }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        let total_production: u64 = context.factory_manager.list_factories().iter()
+            .map(|f| f.production_count())
+            .sum();
+
+        json!({
+            "total": total_production,
+            "today": (total_production as f32 * 0.1) as u64, // محاكاة
+            "this_week": (total_production as f32 * 0.7) as u64,
+            "this_month": total_production,
+            "average_daily": total_production / 30,
+            "growth_rate": 0.15
+        })
+    }
+}
+
+/// عمق الطوابير: الطلبات قيد التنفيذ مقابل حد التزامن لكل مصنع
+struct QueueDepthProvider;
+
+impl WidgetProvider for QueueDepthProvider {
+    fn name(&self) -> &str { "queue_depth" }
+    fn title(&self) -> &str { "عمق الطوابير" }
+    fn refresh_interval(&self) -> u64 { 5 }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        let knobs = knobs();
+        let metrics: BTreeMap<String, _> = context.factory_manager.factory_metrics().into_iter().collect();
+        let rows: Vec<Value> = metrics.iter().map(|(factory_type, m)| {
+            let limit = knobs.factory_concurrency(factory_type);
+            json!([factory_type, m.current_queue_size, limit, format!("{:.0}%", m.current_queue_size as f64 / limit.max(1) as f64 * 100.0)])
+        }).collect();
+
+        json!({
+            "columns": ["المصنع", "قيد التنفيذ", "حد التزامن", "الاستخدام"],
+            "rows": rows,
+            "total": metrics.values().map(|m| m.current_queue_size).sum::<usize>()
+        })
+    }
+}
+
+/// خريطة حرارية لزمن التصنيع: المصانع × فئات الزمن
+struct FactoryLatencyProvider;
+
+impl WidgetProvider for FactoryLatencyProvider {
+    fn name(&self) -> &str { "factory_latency" }
+    fn title(&self) -> &str { "زمن التصنيع لكل مصنع" }
+    fn refresh_interval(&self) -> u64 { 30 }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        let mut columns = vec!["المصنع".to_string()];
+        columns.extend(LATENCY_BUCKETS_MS.iter().map(|bound| format!("≤{}ms", bound)));
+        columns.push(format!(">{}ms", LATENCY_BUCKETS_MS[LATENCY_BUCKETS_MS.len() - 1]));
+
+        let histogram: BTreeMap<String, Vec<u64>> = context.factory_manager.latency_histogram().into_iter().collect();
+        let rows: Vec<Value> = histogram.iter().map(|(factory_type, buckets)| {
+            let mut row = vec![json!(factory_type)];
+            row.extend(buckets.iter().map(|count| json!(count)));
+            Value::Array(row)
+        }).collect();
+
+        json!({
+            "columns": columns,
+            "rows": rows,
+            "heatmap": histogram
+        })
+    }
+}
+
+/// آخر تقارير الجودة الفاشلة
+struct QualityFailuresProvider;
+
+impl WidgetProvider for QualityFailuresProvider {
+    fn name(&self) -> &str { "quality_failures" }
+    fn title(&self) -> &str { "إخفاقات الجودة الأخيرة" }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        let manager = match context.quality.as_ref().and_then(|q| q.read().ok()) {
+            Some(manager) => manager,
+            None => return json!({ "columns": [], "rows": [], "total": 0 }),
+        };
+
+        let reports = manager.history().all();
+        let failures: Vec<_> = reports.iter().rev().filter(|r| !r.passed).take(10).collect();
+        let rows: Vec<Value> = failures.iter().map(|report| {
+            let mut failed_gates: Vec<&str> = report.gate_results.values()
+                .filter(|g| !g.passed)
+                .map(|g| g.gate_name.as_str())
+                .collect();
+            failed_gates.sort();
+            json!([
+                report.generated_at.format("%H:%M").to_string(),
+                report.factory.as_deref().unwrap_or(&report.entity_type),
+                format!("{:.0}%", report.overall_score * 100.0),
+                failed_gates.join("، ")
+            ])
+        }).collect();
+
+        json!({
+            "columns": ["الوقت", "المصنع", "النتيجة", "البوابات الفاشلة"],
+            "rows": rows,
+            "total": reports.iter().filter(|r| !r.passed).count()
+        })
+    }
+}
+
+/// القوالب الأكثر استخداماً في التصنيع
+struct TopTemplatesProvider;
+
+impl WidgetProvider for TopTemplatesProvider {
+    fn name(&self) -> &str { "top_templates" }
+    fn title(&self) -> &str { "القوالب الأكثر استخداماً" }
+    fn refresh_interval(&self) -> u64 { 120 }
+
+    fn fetch(&self, context: &WidgetContext) -> Value {
+        let rows: Vec<Value> = context.factory_manager.template_usage().into_iter()
+            .take(10)
+            .map(|(template, count)| json!([template, count]))
+            .collect();
+
+        json!({
+            "columns": ["القالب", "مرات الاستخدام"],
+            "rows": rows
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct StaticProvider;
+
+    impl WidgetProvider for StaticProvider {
+        fn name(&self) -> &str { "static" }
+        fn title(&self) -> &str { "ثابت" }
+        fn refresh_interval(&self) -> u64 { 7 }
+        fn fetch(&self, _context: &WidgetContext) -> Value { json!({ "value": 42 }) }
+    }
+
+    #[test]
+    fn test_registry_builtin_and_custom_providers() {
+        let registry = WidgetRegistry::with_builtin();
+        let factory_manager = Arc::new(FactoryManager::new());
+        let context = WidgetContext::new(factory_manager.clone());
+
+        for name in ["queue_depth", "factory_latency", "quality_failures", "top_templates"] {
+            assert!(registry.contains(name), "{}", name);
+        }
+
+        factory_manager.manufacture("education", json!("درس"), json!({ "template": "basic_course" })).unwrap();
+        let templates = registry.get("top_templates").unwrap().fetch(&context);
+        assert_eq!(templates["rows"][0], json!(["basic_course", 1]));
+        let latency = registry.get("factory_latency").unwrap().fetch(&context);
+        assert_eq!(latency["rows"][0][0], "education");
+        assert_eq!(latency["rows"][0].as_array().unwrap().len(), LATENCY_BUCKETS_MS.len() + 2);
+
+        registry.register(Arc::new(StaticProvider));
+        let provider = registry.get("static").unwrap();
+        assert_eq!(provider.refresh_interval(), 7);
+        assert_eq!(provider.fetch(&context)["value"], 42);
+    }
+}